@@ -9,10 +9,10 @@ use chrono::{DateTime, Utc};
 use cosmos::{
     messages::{MsgExecHelper, MsgGrantHelper},
     proto::cosmwasm::wasm::v1::MsgExecuteContract,
-    Address, Cosmos, HasAddress, HasAddressHrp, TxBuilder, TxMessage,
+    Address, Cosmos, HasAddress, HasAddressHrp, ParsedCoin, TxBuilder, TxMessage,
 };
 
-use crate::{my_duration::MyDuration, parsed_coin::ParsedCoin, TxOpt};
+use crate::{my_duration::MyDuration, TxOpt};
 
 #[derive(clap::Parser)]
 pub(crate) struct Opt {
@@ -49,6 +49,8 @@ enum Subcommand {
     },
     /// Query grants by the granter
     GranterGrants { granter: Address },
+    /// Query grants received by the grantee
+    GranteeGrants { grantee: Address },
     /// Exec a store-code via a grant
     StoreCode {
         /// Filepath containing the code
@@ -97,6 +99,7 @@ pub(crate) async fn go(cosmos: Cosmos, Opt { sub }: Opt) -> Result<()> {
             cw3_grant(granter, grantee, expiration, grant_type)?;
         }
         Subcommand::GranterGrants { granter } => granter_grants(cosmos, granter).await?,
+        Subcommand::GranteeGrants { grantee } => grantee_grants(cosmos, grantee).await?,
         Subcommand::StoreCode {
             path,
             granter,
@@ -209,6 +212,13 @@ async fn granter_grants(cosmos: Cosmos, granter: Address) -> Result<()> {
     Ok(())
 }
 
+async fn grantee_grants(cosmos: Cosmos, grantee: Address) -> Result<()> {
+    for x in cosmos.query_grantee_grants(grantee).await? {
+        tracing::info!("{x:?}");
+    }
+    Ok(())
+}
+
 async fn store_code(cosmos: Cosmos, tx_opt: TxOpt, path: &Path, granter: Address) -> Result<()> {
     let wallet = tx_opt.get_wallet(cosmos.get_address_hrp())?;
     let (res, code_id) = cosmos.store_code_path_authz(&wallet, path, granter).await?;
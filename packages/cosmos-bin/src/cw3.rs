@@ -1,14 +1,14 @@
 use anyhow::{Context, Result};
 use cosmos::{
     proto::cosmos::bank::v1beta1::MsgSend, Address, ContractAdmin, Cosmos, HasAddress,
-    HasAddressHrp, TxBuilder,
+    HasAddressHrp, ParsedCoin, TxBuilder,
 };
 use cosmwasm_std::{to_binary, CosmosMsg, Decimal, Empty, WasmMsg};
 use cw3::{ProposalListResponse, ProposalResponse};
 use cw4::Member;
 use cw_utils::Threshold;
 
-use crate::{my_duration::MyDuration, parsed_coin::ParsedCoin, TxOpt};
+use crate::{my_duration::MyDuration, TxOpt};
 
 #[derive(Clone, Copy, Debug)]
 enum ContractType {
@@ -476,7 +476,13 @@ async fn send_coins_message(
 ) -> Result<()> {
     let msg = CosmosMsg::<Empty>::Bank(cosmwasm_std::BankMsg::Send {
         to_address: recipient.get_address_string(),
-        amount: coins.iter().cloned().map(|x| x.into()).collect(),
+        amount: coins
+            .iter()
+            .map(|x| cosmwasm_std::Coin {
+                denom: x.denom().to_owned(),
+                amount: x.amount().into(),
+            })
+            .collect(),
     });
     println!("{}", serde_json::to_string(&msg)?);
 
@@ -5,7 +5,6 @@ mod contract;
 mod cw3;
 mod my_duration;
 mod nft;
-mod parsed_coin;
 mod tokenfactory;
 
 use std::{io::Write, path::PathBuf, str::FromStr};
@@ -23,10 +22,9 @@ use cosmos::{
         },
         traits::Message,
     },
-    Address, AddressHrp, BlockInfo, Coin, ContractAdmin, HasAddress, HasAddressHrp, RawAddress,
-    SeedPhrase, TxBuilder, Wallet,
+    Address, AddressHrp, BlockInfo, Coin, ContractAdmin, HasAddress, HasAddressHrp, ParsedCoin,
+    RawAddress, SeedPhrase, TxBuilder, Wallet,
 };
-use parsed_coin::ParsedCoin;
 use tracing::Level;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, EnvFilter, Layer};
 
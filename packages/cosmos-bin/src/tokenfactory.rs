@@ -1,5 +1,5 @@
 use anyhow::Result;
-use cosmos::{Cosmos, HasAddressHrp, SeedPhrase};
+use cosmos::{Address, Cosmos, HasAddressHrp, SeedPhrase};
 
 #[derive(clap::Parser)]
 pub enum Command {
@@ -10,6 +10,8 @@ pub enum Command {
     Burn { denom: String, amount: u128 },
 
     ChangeAdmin { denom: String, addr: String },
+
+    DenomsFromCreator { creator: Address },
 }
 
 pub(crate) async fn go(cosmos: Cosmos, raw_wallet: SeedPhrase, cmd: Command) -> Result<()> {
@@ -41,6 +43,12 @@ pub(crate) async fn go(cosmos: Cosmos, raw_wallet: SeedPhrase, cmd: Command) ->
                 resp.txhash
             );
         }
+
+        Command::DenomsFromCreator { creator } => {
+            for denom in tokenfactory.denoms_from_creator(creator).await? {
+                println!("{denom}");
+            }
+        }
     }
     Ok(())
 }
@@ -0,0 +1,176 @@
+use cosmos_sdk_proto::cosmos::{
+    auth::v1beta1::{BaseAccount, ModuleAccount},
+    vesting::v1beta1::{
+        BaseVestingAccount, ContinuousVestingAccount, DelayedVestingAccount,
+        PeriodicVestingAccount, PermanentLockedAccount,
+    },
+};
+use prost::Message;
+use prost_types::Any;
+
+use crate::injective::EthAccount;
+
+/// An on-chain account, decoded from the account query's type URL.
+///
+/// Use [Account::base_account] to get the sequence and account number
+/// regardless of the account type.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Account {
+    /// A regular account
+    Base(BaseAccount),
+    /// An account owned by a module, such as the distribution module
+    Module(ModuleAccount),
+    /// Vesting account which vests linearly between the start and end times
+    ContinuousVesting(ContinuousVestingAccount),
+    /// Vesting account which vests everything at the end time
+    DelayedVesting(DelayedVestingAccount),
+    /// Vesting account which vests according to a schedule of periods
+    PeriodicVesting(PeriodicVestingAccount),
+    /// Vesting account which never vests, but can still delegate
+    PermanentLocked(PermanentLockedAccount),
+    /// Injective and Ethermint account, see [Account::base_account]
+    Eth {
+        /// The embedded base account
+        base_account: Option<BaseAccount>,
+        /// Hash of the EVM code, empty for externally owned accounts
+        code_hash: Vec<u8>,
+    },
+}
+
+impl Account {
+    /// Decode an account from the protobuf returned by the auth module.
+    ///
+    /// Unrecognized type URLs are decoded as [BaseAccount].
+    pub fn decode(any: &Any) -> Result<Self, prost::DecodeError> {
+        let value = any.value.as_slice();
+        Ok(match any.type_url.as_str() {
+            "/cosmos.auth.v1beta1.ModuleAccount" => Account::Module(ModuleAccount::decode(value)?),
+            "/cosmos.vesting.v1beta1.ContinuousVestingAccount" => {
+                Account::ContinuousVesting(ContinuousVestingAccount::decode(value)?)
+            }
+            "/cosmos.vesting.v1beta1.DelayedVestingAccount" => {
+                Account::DelayedVesting(DelayedVestingAccount::decode(value)?)
+            }
+            "/cosmos.vesting.v1beta1.PeriodicVestingAccount" => {
+                Account::PeriodicVesting(PeriodicVestingAccount::decode(value)?)
+            }
+            "/cosmos.vesting.v1beta1.PermanentLockedAccount" => {
+                Account::PermanentLocked(PermanentLockedAccount::decode(value)?)
+            }
+            "/injective.types.v1beta1.EthAccount" | "/ethermint.types.v1.EthAccount" => {
+                let EthAccount {
+                    base_account,
+                    code_hash,
+                } = EthAccount::decode(value)?;
+                Account::Eth {
+                    base_account,
+                    code_hash,
+                }
+            }
+            _ => Account::Base(BaseAccount::decode(value)?),
+        })
+    }
+
+    /// The vesting details, for vesting accounts.
+    pub fn base_vesting_account(&self) -> Option<&BaseVestingAccount> {
+        match self {
+            Account::ContinuousVesting(account) => account.base_vesting_account.as_ref(),
+            Account::DelayedVesting(account) => account.base_vesting_account.as_ref(),
+            Account::PeriodicVesting(account) => account.base_vesting_account.as_ref(),
+            Account::PermanentLocked(account) => account.base_vesting_account.as_ref(),
+            Account::Base(_) | Account::Module(_) | Account::Eth { .. } => None,
+        }
+    }
+
+    /// The embedded base account, containing the address, sequence and account number.
+    pub fn base_account(&self) -> Option<&BaseAccount> {
+        match self {
+            Account::Base(account) => Some(account),
+            Account::Module(account) => account.base_account.as_ref(),
+            Account::Eth { base_account, .. } => base_account.as_ref(),
+            Account::ContinuousVesting(_)
+            | Account::DelayedVesting(_)
+            | Account::PeriodicVesting(_)
+            | Account::PermanentLocked(_) => self
+                .base_vesting_account()
+                .and_then(|account| account.base_account.as_ref()),
+        }
+    }
+
+    /// Like [Account::base_account], but takes ownership.
+    pub fn into_base_account(self) -> Option<BaseAccount> {
+        match self {
+            Account::Base(account) => Some(account),
+            Account::Module(account) => account.base_account,
+            Account::Eth { base_account, .. } => base_account,
+            Account::ContinuousVesting(account) => account.base_vesting_account?.base_account,
+            Account::DelayedVesting(account) => account.base_vesting_account?.base_account,
+            Account::PeriodicVesting(account) => account.base_vesting_account?.base_account,
+            Account::PermanentLocked(account) => account.base_vesting_account?.base_account,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn base() -> BaseAccount {
+        BaseAccount {
+            address: "osmo168gdk6r58jdwfv49kuesq2rs747jawnn4ryvyk".to_owned(),
+            pub_key: None,
+            account_number: 42,
+            sequence: 7,
+        }
+    }
+
+    #[test]
+    fn vesting_accounts() {
+        let any = Any {
+            type_url: "/cosmos.vesting.v1beta1.DelayedVestingAccount".to_owned(),
+            value: DelayedVestingAccount {
+                base_vesting_account: Some(BaseVestingAccount {
+                    base_account: Some(base()),
+                    original_vesting: vec![],
+                    delegated_free: vec![],
+                    delegated_vesting: vec![],
+                    end_time: 1_700_000_000,
+                }),
+            }
+            .encode_to_vec(),
+        };
+        let account = Account::decode(&any).unwrap();
+        assert!(matches!(account, Account::DelayedVesting(_)));
+        assert_eq!(account.base_account(), Some(&base()));
+        assert_eq!(
+            account
+                .base_vesting_account()
+                .map(|account| account.end_time),
+            Some(1_700_000_000)
+        );
+        assert_eq!(account.into_base_account(), Some(base()));
+    }
+
+    #[test]
+    fn module_and_base_accounts() {
+        let any = Any {
+            type_url: "/cosmos.auth.v1beta1.ModuleAccount".to_owned(),
+            value: ModuleAccount {
+                base_account: Some(base()),
+                name: "distribution".to_owned(),
+                permissions: vec![],
+            }
+            .encode_to_vec(),
+        };
+        assert_eq!(
+            Account::decode(&any).unwrap().into_base_account(),
+            Some(base())
+        );
+
+        let any = Any {
+            type_url: "/cosmos.auth.v1beta1.BaseAccount".to_owned(),
+            value: base().encode_to_vec(),
+        };
+        assert_eq!(Account::decode(&any).unwrap(), Account::Base(base()));
+    }
+}
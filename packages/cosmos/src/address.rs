@@ -254,17 +254,21 @@ impl FromStr for AddressHrp {
 impl AddressHrp {
     /// The default [DerivationPath] for this HRP.
     ///
-    /// Some chains follow Ethereum rules, notably Injective. For all other
-    /// chains we default to Cosmos defaults.
+    /// Some chains follow Ethereum rules, notably Injective and Evmos, and
+    /// Terra uses its own coin type. For all other chains we default to
+    /// Cosmos defaults.
     pub fn default_derivation_path(self) -> Arc<DerivationPath> {
         self.default_derivation_path_with_index(0)
     }
 
     /// Same as [Self::default_derivation_path], but includes an index.
     pub fn default_derivation_path_with_index(self, index: u64) -> Arc<DerivationPath> {
-        match self.as_str() {
-            "inj" => DerivationPathConfig::ethereum_numbered(index).as_derivation_path(),
-            _ => DerivationPathConfig::cosmos_numbered(index).as_derivation_path(),
+        if self.uses_ethereum_keys() {
+            DerivationPathConfig::ethereum_numbered(index).as_derivation_path()
+        } else if self.as_str() == "terra" {
+            DerivationPathConfig::terra_numbered(index).as_derivation_path()
+        } else {
+            DerivationPathConfig::cosmos_numbered(index).as_derivation_path()
         }
     }
 
@@ -274,11 +278,17 @@ impl AddressHrp {
     /// represents the strategy used. Some chains, notably Injective, use
     /// Ethereum's method. The default is to use Cosmos's method.
     pub fn default_public_key_method(self) -> PublicKeyMethod {
-        match self.as_str() {
-            "inj" => PublicKeyMethod::Ethereum,
-            _ => PublicKeyMethod::Cosmos,
+        if self.uses_ethereum_keys() {
+            PublicKeyMethod::Ethereum
+        } else {
+            PublicKeyMethod::Cosmos
         }
     }
+
+    /// Does this chain use Ethereum-style eth_secp256k1 keys, with coin type 60?
+    fn uses_ethereum_keys(self) -> bool {
+        matches!(self.as_str(), "inj" | "evmos")
+    }
 }
 
 impl Display for AddressHrp {
@@ -411,6 +421,10 @@ impl HasAddressHrp for CosmosNetwork {
             CosmosNetwork::SeiMainnet | CosmosNetwork::SeiTestnet => "sei",
             CosmosNetwork::StargazeTestnet | CosmosNetwork::StargazeMainnet => "stars",
             CosmosNetwork::InjectiveTestnet | CosmosNetwork::InjectiveMainnet => "inj",
+            CosmosNetwork::NeutronMainnet | CosmosNetwork::NeutronTestnet => "neutron",
+            CosmosNetwork::KujiraMainnet | CosmosNetwork::KujiraTestnet => "kujira",
+            CosmosNetwork::TerraMainnet | CosmosNetwork::TerraTestnet => "terra",
+            CosmosNetwork::ArchwayMainnet | CosmosNetwork::ArchwayTestnet => "archway",
         })
     }
 }
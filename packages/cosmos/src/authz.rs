@@ -1,12 +1,506 @@
+use std::fmt::Display;
+
+use chrono::{DateTime, Utc};
 use cosmos_sdk_proto::cosmos::{
     authz::v1beta1::{
-        GrantAuthorization, MsgGrant, QueryGranterGrantsRequest, QueryGranterGrantsResponse,
+        GenericAuthorization, Grant, GrantAuthorization, MsgGrant, MsgRevoke,
+        QueryGranteeGrantsRequest, QueryGranterGrantsRequest, QueryGrantsRequest,
+    },
+    bank::v1beta1::SendAuthorization,
+    base::{abci::v1beta1::TxResponse, v1beta1::Coin},
+    staking::v1beta1::{
+        stake_authorization::{Policy, Validators},
+        AuthorizationType, StakeAuthorization,
     },
-    base::query::v1beta1::{PageRequest, PageResponse},
 };
+use futures_util::{Stream, TryStreamExt};
 use prost::Message;
+use prost_types::{Any, Timestamp};
+
+use crate::{
+    error::{Action, ChainParseError, QueryError, QueryErrorDetails},
+    messages::MsgRevokeHelper,
+    Address, Cosmos, HasAddress, TxMessage, Wallet,
+};
+
+/// Permissions granted to a grantee via authz.
+///
+/// Converts to and from the protobuf [Any] stored in a grant. Authorization
+/// types this library doesn't know about are kept as [Authorization::Unknown].
+#[derive(Clone, Debug, PartialEq)]
+pub enum Authorization {
+    /// Unrestricted permission to send a single message type
+    Generic {
+        /// Type URL of the message, e.g. `/cosmos.bank.v1beta1.MsgSend`
+        msg_type_url: String,
+    },
+    /// Send coins, up to a spend limit
+    Send {
+        /// Coins which may still be sent
+        spend_limit: Vec<Coin>,
+    },
+    /// Delegate, undelegate or redelegate tokens
+    Stake {
+        /// Which staking message is allowed
+        authorization_type: AuthorizationType,
+        /// Maximum tokens which may be staked, [None] for no limit
+        max_tokens: Option<Coin>,
+        /// Validators which may or may not be used, [None] for any validator
+        validators: Option<StakeValidators>,
+    },
+    /// Execute CosmWasm contracts
+    ContractExecution(Vec<ContractGrant>),
+    /// Migrate CosmWasm contracts
+    ContractMigration(Vec<ContractGrant>),
+    /// An authorization type not supported by this library
+    Unknown(Any),
+}
+
+/// Validator restrictions for [Authorization::Stake].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum StakeValidators {
+    /// Only these validator addresses may be used
+    AllowList(Vec<String>),
+    /// These validator addresses may not be used
+    DenyList(Vec<String>),
+}
+
+/// Permission to execute or migrate a single contract, see [Authorization::ContractExecution].
+#[derive(Clone, Debug, PartialEq)]
+pub struct ContractGrant {
+    /// Contract address
+    pub contract: Address,
+    /// How often or with how many funds the contract may be called
+    pub limit: ContractGrantLimit,
+    /// Which messages may be sent to the contract
+    pub filter: ContractGrantFilter,
+}
+
+/// Limit on a [ContractGrant].
+#[derive(Clone, Debug, PartialEq)]
+pub enum ContractGrantLimit {
+    /// Maximum number of calls
+    MaxCalls(u64),
+    /// Maximum funds which may be sent with calls
+    MaxFunds(Vec<Coin>),
+    /// Both a maximum number of calls and maximum funds
+    Combined {
+        /// Maximum number of calls
+        calls_remaining: u64,
+        /// Maximum funds which may be sent with calls
+        amounts: Vec<Coin>,
+    },
+    /// A limit type not supported by this library
+    Unknown(Any),
+}
+
+/// Message filter on a [ContractGrant].
+#[derive(Clone, Debug, PartialEq)]
+pub enum ContractGrantFilter {
+    /// Any message is allowed
+    AllowAll,
+    /// Only messages with one of these top level JSON keys are allowed
+    AcceptedMessageKeys(Vec<String>),
+    /// Only these exact JSON messages are allowed
+    AcceptedMessages(Vec<Vec<u8>>),
+    /// A filter type not supported by this library
+    Unknown(Any),
+}
+
+/// A decoded authz grant, as returned by [Cosmos::query_granter_grants] and
+/// [Cosmos::query_grantee_grants].
+#[derive(Clone, Debug, PartialEq)]
+pub struct GrantInfo {
+    /// Address which granted the permissions
+    pub granter: Address,
+    /// Address which received the permissions
+    pub grantee: Address,
+    /// Permissions granted
+    pub authorization: Authorization,
+    /// When the grant expires, [None] for no expiration
+    pub expiration: Option<DateTime<Utc>>,
+}
+
+/// Whether the grants required by a grantee are in place, see [Cosmos::check_grants].
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GrantStatus {
+    /// Required message types without an unexpired grant
+    pub missing: Vec<String>,
+    /// Grants for required message types which expire within the
+    /// [crate::CosmosBuilder::grant_expiry_window]
+    pub expiring: Vec<GrantInfo>,
+    /// All grants from the granter to the grantee
+    pub grants: Vec<GrantInfo>,
+}
+
+impl GrantStatus {
+    fn new(
+        grants: Vec<GrantInfo>,
+        required: &[&str],
+        now: DateTime<Utc>,
+        window: chrono::Duration,
+    ) -> Self {
+        let mut missing = vec![];
+        let mut expiring = vec![];
+        for msg_type_url in required {
+            let matching = grants
+                .iter()
+                .filter(|grant| grant.authorization.msg_type_url() == Some(*msg_type_url))
+                .filter(|grant| grant.expiration.map_or(true, |expiration| expiration > now))
+                .collect::<Vec<_>>();
+            if matching.is_empty() {
+                missing.push((*msg_type_url).to_owned());
+            } else if matching.iter().all(|grant| {
+                grant
+                    .expiration
+                    .map_or(false, |expiration| expiration <= now + window)
+            }) {
+                expiring.extend(matching.into_iter().cloned());
+            }
+        }
+        GrantStatus {
+            missing,
+            expiring,
+            grants,
+        }
+    }
+
+    /// Are all required grants in place and not expiring soon?
+    pub fn is_ok(&self) -> bool {
+        self.missing.is_empty() && self.expiring.is_empty()
+    }
+}
+
+impl Authorization {
+    /// Unrestricted permission to send the given message type.
+    pub fn generic(msg_type_url: impl Into<String>) -> Self {
+        Authorization::Generic {
+            msg_type_url: msg_type_url.into(),
+        }
+    }
+
+    /// Permission to send up to the given coins.
+    pub fn send(spend_limit: Vec<Coin>) -> Self {
+        Authorization::Send { spend_limit }
+    }
+
+    /// The message type this authorization allows the grantee to send.
+    ///
+    /// Returns [None] for [Authorization::Unknown] and unspecified staking authorizations.
+    pub fn msg_type_url(&self) -> Option<&str> {
+        match self {
+            Authorization::Generic { msg_type_url } => Some(msg_type_url),
+            Authorization::Send { .. } => Some("/cosmos.bank.v1beta1.MsgSend"),
+            Authorization::Stake {
+                authorization_type, ..
+            } => match authorization_type {
+                AuthorizationType::Unspecified => None,
+                AuthorizationType::Delegate => Some("/cosmos.staking.v1beta1.MsgDelegate"),
+                AuthorizationType::Undelegate => Some("/cosmos.staking.v1beta1.MsgUndelegate"),
+                AuthorizationType::Redelegate => Some("/cosmos.staking.v1beta1.MsgBeginRedelegate"),
+            },
+            Authorization::ContractExecution(_) => Some("/cosmwasm.wasm.v1.MsgExecuteContract"),
+            Authorization::ContractMigration(_) => Some("/cosmwasm.wasm.v1.MsgMigrateContract"),
+            Authorization::Unknown(_) => None,
+        }
+    }
+}
 
-use crate::{error::Action, Cosmos, HasAddress, TxMessage};
+fn to_any(type_url: &str, msg: impl Message) -> Any {
+    Any {
+        type_url: type_url.to_owned(),
+        value: msg.encode_to_vec(),
+    }
+}
+
+impl From<Authorization> for Any {
+    fn from(authorization: Authorization) -> Self {
+        match authorization {
+            Authorization::Generic { msg_type_url } => to_any(
+                "/cosmos.authz.v1beta1.GenericAuthorization",
+                GenericAuthorization { msg: msg_type_url },
+            ),
+            Authorization::Send { spend_limit } => to_any(
+                "/cosmos.bank.v1beta1.SendAuthorization",
+                SendAuthorization { spend_limit },
+            ),
+            Authorization::Stake {
+                authorization_type,
+                max_tokens,
+                validators,
+            } => to_any(
+                "/cosmos.staking.v1beta1.StakeAuthorization",
+                StakeAuthorization {
+                    max_tokens,
+                    authorization_type: authorization_type.into(),
+                    validators: validators.map(|validators| match validators {
+                        StakeValidators::AllowList(address) => {
+                            Policy::AllowList(Validators { address })
+                        }
+                        StakeValidators::DenyList(address) => {
+                            Policy::DenyList(Validators { address })
+                        }
+                    }),
+                },
+            ),
+            Authorization::ContractExecution(grants) => to_any(
+                "/cosmwasm.wasm.v1.ContractExecutionAuthorization",
+                wasm_proto::ContractExecutionAuthorization {
+                    grants: grants.into_iter().map(Into::into).collect(),
+                },
+            ),
+            Authorization::ContractMigration(grants) => to_any(
+                "/cosmwasm.wasm.v1.ContractMigrationAuthorization",
+                wasm_proto::ContractMigrationAuthorization {
+                    grants: grants.into_iter().map(Into::into).collect(),
+                },
+            ),
+            Authorization::Unknown(any) => any,
+        }
+    }
+}
+
+impl From<Any> for Authorization {
+    fn from(any: Any) -> Self {
+        fn decode(any: &Any) -> Option<Authorization> {
+            Some(match any.type_url.as_str() {
+                "/cosmos.authz.v1beta1.GenericAuthorization" => {
+                    Authorization::generic(GenericAuthorization::decode(&*any.value).ok()?.msg)
+                }
+                "/cosmos.bank.v1beta1.SendAuthorization" => {
+                    Authorization::send(SendAuthorization::decode(&*any.value).ok()?.spend_limit)
+                }
+                "/cosmos.staking.v1beta1.StakeAuthorization" => {
+                    let StakeAuthorization {
+                        max_tokens,
+                        authorization_type,
+                        validators,
+                    } = StakeAuthorization::decode(&*any.value).ok()?;
+                    Authorization::Stake {
+                        authorization_type: AuthorizationType::from_i32(authorization_type)?,
+                        max_tokens,
+                        validators: validators.map(|validators| match validators {
+                            Policy::AllowList(Validators { address }) => {
+                                StakeValidators::AllowList(address)
+                            }
+                            Policy::DenyList(Validators { address }) => {
+                                StakeValidators::DenyList(address)
+                            }
+                        }),
+                    }
+                }
+                "/cosmwasm.wasm.v1.ContractExecutionAuthorization" => {
+                    Authorization::ContractExecution(
+                        wasm_proto::ContractExecutionAuthorization::decode(&*any.value)
+                            .ok()?
+                            .grants
+                            .into_iter()
+                            .map(ContractGrant::from_proto)
+                            .collect::<Option<_>>()?,
+                    )
+                }
+                "/cosmwasm.wasm.v1.ContractMigrationAuthorization" => {
+                    Authorization::ContractMigration(
+                        wasm_proto::ContractMigrationAuthorization::decode(&*any.value)
+                            .ok()?
+                            .grants
+                            .into_iter()
+                            .map(ContractGrant::from_proto)
+                            .collect::<Option<_>>()?,
+                    )
+                }
+                _ => return None,
+            })
+        }
+        decode(&any).unwrap_or(Authorization::Unknown(any))
+    }
+}
+
+impl From<ContractGrant> for wasm_proto::ContractGrant {
+    fn from(
+        ContractGrant {
+            contract,
+            limit,
+            filter,
+        }: ContractGrant,
+    ) -> Self {
+        let limit = match limit {
+            ContractGrantLimit::MaxCalls(remaining) => to_any(
+                "/cosmwasm.wasm.v1.MaxCallsLimit",
+                wasm_proto::MaxCallsLimit { remaining },
+            ),
+            ContractGrantLimit::MaxFunds(amounts) => to_any(
+                "/cosmwasm.wasm.v1.MaxFundsLimit",
+                wasm_proto::MaxFundsLimit { amounts },
+            ),
+            ContractGrantLimit::Combined {
+                calls_remaining,
+                amounts,
+            } => to_any(
+                "/cosmwasm.wasm.v1.CombinedLimit",
+                wasm_proto::CombinedLimit {
+                    calls_remaining,
+                    amounts,
+                },
+            ),
+            ContractGrantLimit::Unknown(any) => any,
+        };
+        let filter = match filter {
+            ContractGrantFilter::AllowAll => to_any(
+                "/cosmwasm.wasm.v1.AllowAllMessagesFilter",
+                wasm_proto::AllowAllMessagesFilter {},
+            ),
+            ContractGrantFilter::AcceptedMessageKeys(keys) => to_any(
+                "/cosmwasm.wasm.v1.AcceptedMessageKeysFilter",
+                wasm_proto::AcceptedMessageKeysFilter { keys },
+            ),
+            ContractGrantFilter::AcceptedMessages(messages) => to_any(
+                "/cosmwasm.wasm.v1.AcceptedMessagesFilter",
+                wasm_proto::AcceptedMessagesFilter { messages },
+            ),
+            ContractGrantFilter::Unknown(any) => any,
+        };
+        wasm_proto::ContractGrant {
+            contract: contract.get_address_string(),
+            limit: Some(limit),
+            filter: Some(filter),
+        }
+    }
+}
+
+impl ContractGrant {
+    fn from_proto(grant: wasm_proto::ContractGrant) -> Option<Self> {
+        let limit = grant.limit?;
+        let limit = match limit.type_url.as_str() {
+            "/cosmwasm.wasm.v1.MaxCallsLimit" => wasm_proto::MaxCallsLimit::decode(&*limit.value)
+                .ok()
+                .map(|limit| ContractGrantLimit::MaxCalls(limit.remaining)),
+            "/cosmwasm.wasm.v1.MaxFundsLimit" => wasm_proto::MaxFundsLimit::decode(&*limit.value)
+                .ok()
+                .map(|limit| ContractGrantLimit::MaxFunds(limit.amounts)),
+            "/cosmwasm.wasm.v1.CombinedLimit" => wasm_proto::CombinedLimit::decode(&*limit.value)
+                .ok()
+                .map(|limit| ContractGrantLimit::Combined {
+                    calls_remaining: limit.calls_remaining,
+                    amounts: limit.amounts,
+                }),
+            _ => None,
+        }
+        .unwrap_or(ContractGrantLimit::Unknown(limit));
+        let filter = grant.filter?;
+        let filter = match filter.type_url.as_str() {
+            "/cosmwasm.wasm.v1.AllowAllMessagesFilter" => Some(ContractGrantFilter::AllowAll),
+            "/cosmwasm.wasm.v1.AcceptedMessageKeysFilter" => {
+                wasm_proto::AcceptedMessageKeysFilter::decode(&*filter.value)
+                    .ok()
+                    .map(|filter| ContractGrantFilter::AcceptedMessageKeys(filter.keys))
+            }
+            "/cosmwasm.wasm.v1.AcceptedMessagesFilter" => {
+                wasm_proto::AcceptedMessagesFilter::decode(&*filter.value)
+                    .ok()
+                    .map(|filter| ContractGrantFilter::AcceptedMessages(filter.messages))
+            }
+            _ => None,
+        }
+        .unwrap_or(ContractGrantFilter::Unknown(filter));
+        Some(ContractGrant {
+            contract: grant.contract.parse().ok()?,
+            limit,
+            filter,
+        })
+    }
+}
+
+impl Display for Authorization {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Authorization::Generic { msg_type_url } => write!(f, "{msg_type_url}"),
+            Authorization::Send { spend_limit } => {
+                write!(f, "sending up to ")?;
+                write_coins(f, spend_limit)
+            }
+            Authorization::Stake {
+                authorization_type,
+                max_tokens,
+                validators,
+            } => {
+                write!(f, "{}", authorization_type.as_str_name())?;
+                if let Some(Coin { denom, amount }) = max_tokens {
+                    write!(f, " up to {amount}{denom}")?;
+                }
+                match validators {
+                    Some(StakeValidators::AllowList(validators)) => {
+                        write!(f, " with validators {validators:?}")
+                    }
+                    Some(StakeValidators::DenyList(validators)) => {
+                        write!(f, " excluding validators {validators:?}")
+                    }
+                    None => Ok(()),
+                }
+            }
+            Authorization::ContractExecution(grants) => {
+                write!(f, "executing contracts ")?;
+                write_contracts(f, grants)
+            }
+            Authorization::ContractMigration(grants) => {
+                write!(f, "migrating contracts ")?;
+                write_contracts(f, grants)
+            }
+            Authorization::Unknown(any) => write!(f, "unknown authorization {}", any.type_url),
+        }
+    }
+}
+
+fn write_coins(f: &mut std::fmt::Formatter<'_>, coins: &[Coin]) -> std::fmt::Result {
+    for (idx, Coin { denom, amount }) in coins.iter().enumerate() {
+        if idx > 0 {
+            write!(f, ", ")?;
+        }
+        write!(f, "{amount}{denom}")?;
+    }
+    Ok(())
+}
+
+fn write_contracts(f: &mut std::fmt::Formatter<'_>, grants: &[ContractGrant]) -> std::fmt::Result {
+    for (idx, grant) in grants.iter().enumerate() {
+        if idx > 0 {
+            write!(f, ", ")?;
+        }
+        write!(f, "{}", grant.contract)?;
+    }
+    Ok(())
+}
+
+impl GrantInfo {
+    pub(crate) fn from_grant_authorization(
+        GrantAuthorization {
+            granter,
+            grantee,
+            authorization,
+            expiration,
+        }: GrantAuthorization,
+        action: &Action,
+    ) -> Result<Self, crate::Error> {
+        let parse_address = |address: String| {
+            address.parse().map_err(|source| crate::Error::ChainParse {
+                source: Box::new(ChainParseError::InvalidAddress { address, source }),
+                action: action.clone(),
+            })
+        };
+        Ok(GrantInfo {
+            granter: parse_address(granter)?,
+            grantee: parse_address(grantee)?,
+            authorization: authorization.unwrap_or_default().into(),
+            expiration: expiration.and_then(timestamp_to_datetime),
+        })
+    }
+}
+
+pub(crate) fn timestamp_to_datetime(
+    Timestamp { seconds, nanos }: Timestamp,
+) -> Option<DateTime<Utc>> {
+    DateTime::from_timestamp(seconds, u32::try_from(nanos).ok().unwrap_or_default())
+}
 
 impl From<MsgGrant> for TxMessage {
     fn from(msg: MsgGrant) -> Self {
@@ -21,44 +515,360 @@ impl From<MsgGrant> for TxMessage {
     }
 }
 
+impl From<MsgRevoke> for TxMessage {
+    fn from(msg: MsgRevoke) -> Self {
+        TxMessage::new(
+            "/cosmos.authz.v1beta1.MsgRevoke",
+            msg.encode_to_vec(),
+            format!(
+                "{} revokes {} authorization for {}",
+                msg.granter, msg.grantee, msg.msg_type_url
+            ),
+        )
+    }
+}
+
 impl Cosmos {
+    /// Revoke the authorization for `msg_type_url` which `wallet` granted to `grantee`.
+    pub async fn revoke_grant(
+        &self,
+        wallet: &Wallet,
+        grantee: impl HasAddress,
+        msg_type_url: impl Into<String>,
+    ) -> Result<TxResponse, crate::Error> {
+        wallet
+            .broadcast_message(
+                self,
+                MsgRevokeHelper {
+                    granter: wallet.get_address(),
+                    grantee: grantee.get_address(),
+                    msg_type_url: msg_type_url.into(),
+                },
+            )
+            .await
+    }
+
+    /// Lazily load the grants the given address has authorized, one page at a time.
+    pub fn granter_grants_stream(
+        &self,
+        granter: impl HasAddress,
+    ) -> impl Stream<Item = Result<GrantAuthorization, crate::Error>> + Send + 'static {
+        let granter = granter.get_address();
+        self.paginate(
+            Action::QueryGranterGrants(granter),
+            move |pagination| QueryGranterGrantsRequest {
+                granter: granter.get_address_string(),
+                pagination,
+            },
+            |res| (res.grants, res.pagination),
+        )
+    }
+
+    /// Lazily load the grants the given address has received, one page at a time.
+    pub fn grantee_grants_stream(
+        &self,
+        grantee: impl HasAddress,
+    ) -> impl Stream<Item = Result<GrantAuthorization, crate::Error>> + Send + 'static {
+        let grantee = grantee.get_address();
+        self.paginate(
+            Action::QueryGranteeGrants(grantee),
+            move |pagination| QueryGranteeGrantsRequest {
+                grantee: grantee.get_address_string(),
+                pagination,
+            },
+            |res| (res.grants, res.pagination),
+        )
+    }
+
+    /// Check which grants the given address has received.
+    pub async fn query_grantee_grants(
+        &self,
+        grantee: impl HasAddress,
+    ) -> Result<Vec<GrantInfo>, crate::Error> {
+        let action = Action::QueryGranteeGrants(grantee.get_address());
+        self.grantee_grants_stream(grantee)
+            .try_collect::<Vec<_>>()
+            .await?
+            .into_iter()
+            .map(|grant| GrantInfo::from_grant_authorization(grant, &action))
+            .collect()
+    }
+
+    /// Check the grants from `granter` to `grantee`.
+    ///
+    /// When `msg_type_url` is provided, only grants for that message type are
+    /// returned. An empty list is returned if no matching grant exists.
+    pub async fn query_grants(
+        &self,
+        granter: impl HasAddress,
+        grantee: impl HasAddress,
+        msg_type_url: Option<&str>,
+    ) -> Result<Vec<GrantInfo>, crate::Error> {
+        let granter = granter.get_address();
+        let grantee = grantee.get_address();
+        let msg_type_url = msg_type_url.unwrap_or_default().to_owned();
+        let res = self
+            .paginate(
+                Action::QueryGrants { granter, grantee },
+                move |pagination| QueryGrantsRequest {
+                    granter: granter.get_address_string(),
+                    grantee: grantee.get_address_string(),
+                    msg_type_url: msg_type_url.clone(),
+                    pagination,
+                },
+                |res| (res.grants, res.pagination),
+            )
+            .try_collect::<Vec<_>>()
+            .await;
+        let grants = match res {
+            Ok(grants) => grants,
+            Err(crate::Error::Query(QueryError {
+                query: QueryErrorDetails::NotFound(_),
+                ..
+            })) => vec![],
+            Err(e) => return Err(e),
+        };
+        Ok(grants
+            .into_iter()
+            .map(
+                |Grant {
+                     authorization,
+                     expiration,
+                 }| GrantInfo {
+                    granter,
+                    grantee,
+                    authorization: authorization.unwrap_or_default().into(),
+                    expiration: expiration.and_then(timestamp_to_datetime),
+                },
+            )
+            .collect())
+    }
+
+    /// Check that `grantee` holds grants from `granter` for each of the `required` message types.
+    ///
+    /// Reports required message types with no unexpired grant, and grants
+    /// which expire within [crate::CosmosBuilder::grant_expiry_window], so
+    /// long running services can alert before their grants lapse.
+    pub async fn check_grants(
+        &self,
+        granter: impl HasAddress,
+        grantee: impl HasAddress,
+        required: &[&str],
+    ) -> Result<GrantStatus, crate::Error> {
+        let grants = self.query_grants(granter, grantee, None).await?;
+        let window = chrono::Duration::from_std(self.get_cosmos_builder().grant_expiry_window())
+            .unwrap_or(chrono::Duration::max_value());
+        let status = GrantStatus::new(grants, required, Utc::now(), window);
+        for msg_type_url in &status.missing {
+            tracing::warn!("Missing authz grant for {msg_type_url}");
+        }
+        for grant in &status.expiring {
+            tracing::warn!(
+                "Authz grant {} from {} to {} expires at {:?}",
+                grant.authorization,
+                grant.granter,
+                grant.grantee,
+                grant.expiration
+            );
+        }
+        Ok(status)
+    }
+
     /// Check which grants the given address has authorized.
     pub async fn query_granter_grants(
         &self,
         granter: impl HasAddress,
-    ) -> Result<Vec<GrantAuthorization>, crate::Error> {
-        let mut res = vec![];
-        let mut pagination = None;
+    ) -> Result<Vec<GrantInfo>, crate::Error> {
+        let action = Action::QueryGranterGrants(granter.get_address());
+        self.granter_grants_stream(granter)
+            .try_collect::<Vec<_>>()
+            .await?
+            .into_iter()
+            .map(|grant| GrantInfo::from_grant_authorization(grant, &action))
+            .collect()
+    }
+}
 
-        loop {
-            let req = QueryGranterGrantsRequest {
-                granter: granter.get_address_string(),
-                pagination: pagination.take(),
-            };
-
-            let QueryGranterGrantsResponse {
-                mut grants,
-                pagination: pag_res,
-            } = self
-                .perform_query(req, Action::QueryGranterGrants(granter.get_address()), true)
-                .await?
-                .into_inner();
-            println!("{grants:?}");
-            if grants.is_empty() {
-                break Ok(res);
-            }
+//////////// GENERATED, COPY/PASTED, AND PATCHED FROM PROST-BUILD ////////////////
+
+/// CosmWasm authz types, not included in our version of the protobuf definitions.
+mod wasm_proto {
+    use cosmos_sdk_proto::cosmos::base::v1beta1::Coin;
+
+    /// ContractExecutionAuthorization defines authorization for wasm execute.
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub(super) struct ContractExecutionAuthorization {
+        /// Grants for contract executions
+        #[prost(message, repeated, tag = "1")]
+        pub(super) grants: ::prost::alloc::vec::Vec<ContractGrant>,
+    }
+    /// ContractMigrationAuthorization defines authorization for wasm contract migration.
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub(super) struct ContractMigrationAuthorization {
+        /// Grants for contract migrations
+        #[prost(message, repeated, tag = "1")]
+        pub(super) grants: ::prost::alloc::vec::Vec<ContractGrant>,
+    }
+    /// ContractGrant a granted permission for a single contract
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub(super) struct ContractGrant {
+        /// Contract is the bech32 address of the smart contract
+        #[prost(string, tag = "1")]
+        pub(super) contract: ::prost::alloc::string::String,
+        /// Limit defines execution limits that are enforced and updated when the grant
+        /// is applied.
+        #[prost(message, optional, tag = "2")]
+        pub(super) limit: ::core::option::Option<::prost_types::Any>,
+        /// Filter define more fine-grained control on the message payload passed
+        /// to the contract in the operation.
+        #[prost(message, optional, tag = "3")]
+        pub(super) filter: ::core::option::Option<::prost_types::Any>,
+    }
+    /// MaxCallsLimit limited number of calls to the contract.
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub(super) struct MaxCallsLimit {
+        /// Remaining number that is decremented on each execution
+        #[prost(uint64, tag = "1")]
+        pub(super) remaining: u64,
+    }
+    /// MaxFundsLimit defines the maximal amounts that can be sent to the contract.
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub(super) struct MaxFundsLimit {
+        /// Amounts is the maximal amount of tokens transferable to the contract.
+        #[prost(message, repeated, tag = "1")]
+        pub(super) amounts: ::prost::alloc::vec::Vec<Coin>,
+    }
+    /// CombinedLimit defines the maximal amounts that can be sent to a contract and
+    /// the maximal number of calls executable.
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub(super) struct CombinedLimit {
+        /// Remaining number that is decremented on each execution
+        #[prost(uint64, tag = "1")]
+        pub(super) calls_remaining: u64,
+        /// Amounts is the maximal amount of tokens transferable to the contract.
+        #[prost(message, repeated, tag = "2")]
+        pub(super) amounts: ::prost::alloc::vec::Vec<Coin>,
+    }
+    /// AllowAllMessagesFilter is a wildcard to allow any type of contract payload
+    /// message.
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub(super) struct AllowAllMessagesFilter {}
+    /// AcceptedMessageKeysFilter accept only the specific contract message keys in
+    /// the json object to be executed.
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub(super) struct AcceptedMessageKeysFilter {
+        /// Messages is the list of unique keys
+        #[prost(string, repeated, tag = "1")]
+        pub(super) keys: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    }
+    /// AcceptedMessagesFilter accept only the specific raw contract messages to be
+    /// executed.
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub(super) struct AcceptedMessagesFilter {
+        /// Messages is the list of raw contract messages
+        #[prost(bytes = "vec", repeated, tag = "1")]
+        pub(super) messages: ::prost::alloc::vec::Vec<::prost::alloc::vec::Vec<u8>>,
+    }
+}
 
-            res.append(&mut grants);
-
-            pagination = pag_res.map(|PageResponse { next_key, total: _ }| PageRequest {
-                key: next_key,
-                // Ideally we'd just leave this out so we use next_key
-                // instead, but the Rust types don't allow this
-                offset: res.len().try_into().unwrap_or(u64::MAX),
-                limit: 10,
-                count_total: false,
-                reverse: false,
-            });
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn authorization_round_trip() {
+        let contract: Address = "osmo1qg5ega6dykkxc307y25pecuufrjkxkaggkkxh7nad0vhyhtuhw3sqaa3c5"
+            .parse()
+            .unwrap();
+        let coin = |amount: &str| Coin {
+            denom: "uosmo".to_owned(),
+            amount: amount.to_owned(),
+        };
+        let authorizations = [
+            Authorization::generic("/cosmos.bank.v1beta1.MsgSend"),
+            Authorization::send(vec![coin("1000")]),
+            Authorization::Stake {
+                authorization_type: AuthorizationType::Delegate,
+                max_tokens: Some(coin("500")),
+                validators: Some(StakeValidators::AllowList(vec![
+                    "osmovaloper1clpqr4nrk4khgkxj78fcwwh6dl3uw4ep88n0y4".to_owned(),
+                ])),
+            },
+            Authorization::ContractExecution(vec![ContractGrant {
+                contract,
+                limit: ContractGrantLimit::Combined {
+                    calls_remaining: 3,
+                    amounts: vec![coin("10")],
+                },
+                filter: ContractGrantFilter::AcceptedMessageKeys(vec!["crank".to_owned()]),
+            }]),
+            Authorization::ContractMigration(vec![ContractGrant {
+                contract,
+                limit: ContractGrantLimit::MaxCalls(1),
+                filter: ContractGrantFilter::AllowAll,
+            }]),
+        ];
+        for authorization in authorizations {
+            let any = Any::from(authorization.clone());
+            assert_eq!(Authorization::from(any), authorization);
         }
     }
+
+    #[test]
+    fn grant_status() {
+        let granter: Address = "osmo168gdk6r58jdwfv49kuesq2rs747jawnn4ryvyk"
+            .parse()
+            .unwrap();
+        let now = Utc::now();
+        let grant = |msg_type_url: &str, expires_in_days| GrantInfo {
+            granter,
+            grantee: granter,
+            authorization: Authorization::generic(msg_type_url),
+            expiration: expires_in_days.map(|days| now + chrono::Duration::days(days)),
+        };
+        let execute = "/cosmwasm.wasm.v1.MsgExecuteContract";
+        let send = "/cosmos.bank.v1beta1.MsgSend";
+        let store = "/cosmwasm.wasm.v1.MsgStoreCode";
+        let status = GrantStatus::new(
+            vec![
+                grant(execute, None),
+                grant(send, Some(2)),
+                grant(store, Some(-1)),
+            ],
+            &[execute, send, store],
+            now,
+            chrono::Duration::days(7),
+        );
+        assert_eq!(status.missing, vec![store.to_owned()]);
+        assert_eq!(status.expiring, vec![grant(send, Some(2))]);
+        assert!(!status.is_ok());
+
+        let status = GrantStatus::new(
+            vec![grant(execute, None), grant(send, Some(30))],
+            &[execute, send],
+            now,
+            chrono::Duration::days(7),
+        );
+        assert!(status.is_ok());
+    }
+
+    #[test]
+    fn unknown_authorization() {
+        let any = Any {
+            type_url: "/example.v1.CustomAuthorization".to_owned(),
+            value: vec![1, 2, 3],
+        };
+        let authorization = Authorization::from(any.clone());
+        assert_eq!(authorization, Authorization::Unknown(any));
+        assert_eq!(authorization.msg_type_url(), None);
+    }
 }
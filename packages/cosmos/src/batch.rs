@@ -0,0 +1,118 @@
+use cosmos_sdk_proto::{
+    cosmos::{auth::v1beta1::BaseAccount, base::v1beta1::Coin},
+    cosmwasm::wasm::v1::ContractInfo,
+};
+use futures_util::future::BoxFuture;
+
+use crate::{Cosmos, HasAddress};
+
+/// A set of queries executed concurrently, see [Cosmos::batch].
+#[must_use = "Queries are only performed when calling execute"]
+pub struct QueryBatch {
+    cosmos: Cosmos,
+    queries: Vec<BoxFuture<'static, Result<BatchResult, crate::Error>>>,
+}
+
+/// The result of a single query in a [QueryBatch].
+#[derive(Debug)]
+pub enum BatchResult {
+    /// From [QueryBatch::all_balances]
+    Balances(Vec<Coin>),
+    /// From [QueryBatch::base_account]
+    BaseAccount(BaseAccount),
+    /// From [QueryBatch::contract_info]
+    ContractInfo(ContractInfo),
+    /// Raw JSON response from [QueryBatch::smart_query]
+    SmartQuery(Vec<u8>),
+    /// From [QueryBatch::raw_query]
+    RawQuery(Vec<u8>),
+}
+
+impl Cosmos {
+    /// Start a batch of queries to be executed concurrently.
+    ///
+    /// Each query is performed independently over the connection pool, so
+    /// the total time is roughly that of the slowest query instead of the
+    /// sum of all of them.
+    pub fn batch(&self) -> QueryBatch {
+        QueryBatch {
+            cosmos: self.clone(),
+            queries: vec![],
+        }
+    }
+}
+
+impl QueryBatch {
+    fn push(
+        mut self,
+        query: impl std::future::Future<Output = Result<BatchResult, crate::Error>> + Send + 'static,
+    ) -> Self {
+        self.queries.push(Box::pin(query));
+        self
+    }
+
+    /// Query all balances of an address.
+    pub fn all_balances(self, address: impl HasAddress) -> Self {
+        let cosmos = self.cosmos.clone();
+        let address = address.get_address();
+        self.push(async move {
+            cosmos
+                .all_balances(address)
+                .await
+                .map(BatchResult::Balances)
+        })
+    }
+
+    /// Query the base account of an address.
+    pub fn base_account(self, address: impl HasAddress) -> Self {
+        let cosmos = self.cosmos.clone();
+        let address = address.get_address();
+        self.push(async move {
+            cosmos
+                .get_base_account(address)
+                .await
+                .map(BatchResult::BaseAccount)
+        })
+    }
+
+    /// Query contract info metadata.
+    pub fn contract_info(self, contract: impl HasAddress) -> Self {
+        let contract = self.cosmos.make_contract(contract.get_address());
+        self.push(async move { contract.info().await.map(BatchResult::ContractInfo) })
+    }
+
+    /// Perform a smart query, returning the raw JSON response.
+    pub fn smart_query(self, contract: impl HasAddress, msg: impl serde::Serialize) -> Self {
+        let contract = self.cosmos.make_contract(contract.get_address());
+        let msg = serde_json::to_vec(&msg);
+        self.push(async move {
+            contract
+                .query_rendered_bytes(msg?)
+                .await
+                .map(BatchResult::SmartQuery)
+                .map_err(crate::Error::from)
+        })
+    }
+
+    /// Perform a raw query on contract storage.
+    pub fn raw_query(self, contract: impl HasAddress, key: impl Into<Vec<u8>>) -> Self {
+        let contract = self.cosmos.make_contract(contract.get_address());
+        let key = key.into();
+        self.push(async move { contract.query_raw(key).await.map(BatchResult::RawQuery) })
+    }
+
+    /// Number of queries in the batch
+    pub fn len(&self) -> usize {
+        self.queries.len()
+    }
+
+    /// Does the batch contain no queries?
+    pub fn is_empty(&self) -> bool {
+        self.queries.is_empty()
+    }
+
+    /// Run all queries concurrently, returning their results in the order they were added.
+    pub async fn execute(self) -> Vec<Result<BatchResult, crate::Error>> {
+        futures_util::future::join_all(self.queries).await
+    }
+}
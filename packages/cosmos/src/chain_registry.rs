@@ -0,0 +1,284 @@
+//! Load network configuration from the [Cosmos chain registry](https://github.com/cosmos/chain-registry).
+//!
+//! Chains are identified by their directory name in the registry, e.g.
+//! `osmosis`. Testnets live in a subdirectory, e.g. `testnets/osmosistestnet`.
+
+use cosmos_sdk_proto::cosmos::bank::v1beta1::{DenomUnit, Metadata};
+use serde::Deserialize;
+
+use crate::{
+    cosmos_network::load_json, error::BuilderError, AddressHrp, CosmosBuilder, DenomDisplay,
+};
+
+const REGISTRY_BASE: &str = "https://raw.githubusercontent.com/cosmos/chain-registry/master";
+
+/// Network details from a chain's `chain.json`.
+#[derive(Clone, Debug)]
+pub struct ChainInfo {
+    /// Chain ID, e.g. `osmosis-1`
+    pub chain_id: String,
+    /// Address prefix, e.g. `osmo`
+    pub bech32_prefix: String,
+    /// Denoms accepted for fees, the first one is the default
+    pub fee_tokens: Vec<FeeToken>,
+    /// gRPC endpoints, with a scheme added where the registry omits it
+    pub grpc_urls: Vec<String>,
+    /// Tendermint RPC endpoints
+    pub rpc_urls: Vec<String>,
+}
+
+/// A fee denom with its gas prices, from [ChainInfo::fee_tokens].
+#[derive(Clone, Debug)]
+pub struct FeeToken {
+    /// Denom used for fees
+    pub denom: String,
+    /// Lowest gas price to attempt
+    pub low_gas_price: f64,
+    /// Highest gas price to attempt
+    pub high_gas_price: f64,
+}
+
+#[derive(Deserialize)]
+struct RawChain {
+    chain_id: String,
+    bech32_prefix: String,
+    #[serde(default)]
+    fees: Option<RawFees>,
+    #[serde(default)]
+    apis: Option<RawApis>,
+}
+
+#[derive(Deserialize)]
+struct RawFees {
+    #[serde(default)]
+    fee_tokens: Vec<RawFeeToken>,
+}
+
+#[derive(Deserialize)]
+struct RawFeeToken {
+    denom: String,
+    fixed_min_gas_price: Option<f64>,
+    low_gas_price: Option<f64>,
+    high_gas_price: Option<f64>,
+}
+
+#[derive(Deserialize)]
+struct RawApis {
+    #[serde(default)]
+    grpc: Vec<RawApi>,
+    #[serde(default)]
+    rpc: Vec<RawApi>,
+}
+
+#[derive(Deserialize)]
+struct RawApi {
+    address: String,
+}
+
+#[derive(Deserialize)]
+struct RawAssetList {
+    assets: Vec<RawAsset>,
+}
+
+#[derive(Deserialize)]
+struct RawAsset {
+    base: String,
+    display: String,
+    #[serde(default)]
+    symbol: String,
+    denom_units: Vec<RawDenomUnit>,
+}
+
+#[derive(Deserialize)]
+struct RawDenomUnit {
+    denom: String,
+    exponent: u32,
+    #[serde(default)]
+    aliases: Vec<String>,
+}
+
+impl From<RawChain> for ChainInfo {
+    fn from(raw: RawChain) -> Self {
+        let apis = raw.apis.unwrap_or(RawApis {
+            grpc: vec![],
+            rpc: vec![],
+        });
+        ChainInfo {
+            chain_id: raw.chain_id,
+            bech32_prefix: raw.bech32_prefix,
+            fee_tokens: raw
+                .fees
+                .map(|fees| fees.fee_tokens)
+                .unwrap_or_default()
+                .into_iter()
+                .filter_map(|token| {
+                    let low = token.low_gas_price.or(token.fixed_min_gas_price)?;
+                    Some(FeeToken {
+                        denom: token.denom,
+                        low_gas_price: low,
+                        high_gas_price: token.high_gas_price.unwrap_or(low * 2.0).max(low),
+                    })
+                })
+                .collect(),
+            grpc_urls: apis
+                .grpc
+                .into_iter()
+                .map(|api| normalize_grpc_url(&api.address))
+                .collect(),
+            rpc_urls: apis.rpc.into_iter().map(|api| api.address).collect(),
+        }
+    }
+}
+
+/// Registry gRPC addresses are often `host:port` without a scheme.
+fn normalize_grpc_url(address: &str) -> String {
+    if address.contains("://") {
+        address.to_owned()
+    } else if address.ends_with(":443") {
+        format!("https://{}", address.trim_end_matches(":443"))
+    } else {
+        format!("http://{address}")
+    }
+}
+
+/// Load a chain's `chain.json` from the registry.
+pub async fn load_chain(
+    client: &reqwest::Client,
+    chain_name: &str,
+) -> Result<ChainInfo, BuilderError> {
+    load_json::<RawChain>(&format!("{REGISTRY_BASE}/{chain_name}/chain.json"), client)
+        .await
+        .map(ChainInfo::from)
+}
+
+/// Load display information for a chain's assets from its `assetlist.json`.
+pub async fn load_assets(
+    client: &reqwest::Client,
+    chain_name: &str,
+) -> Result<Vec<DenomDisplay>, BuilderError> {
+    let assets = load_json::<RawAssetList>(
+        &format!("{REGISTRY_BASE}/{chain_name}/assetlist.json"),
+        client,
+    )
+    .await?;
+    Ok(assets
+        .assets
+        .into_iter()
+        .filter_map(|asset| {
+            DenomDisplay::from_metadata(&Metadata {
+                denom_units: asset
+                    .denom_units
+                    .into_iter()
+                    .map(|unit| DenomUnit {
+                        denom: unit.denom,
+                        exponent: unit.exponent,
+                        aliases: unit.aliases,
+                    })
+                    .collect(),
+                base: asset.base,
+                display: asset.display,
+                symbol: asset.symbol,
+                ..Metadata::default()
+            })
+        })
+        .collect())
+}
+
+/// Use the registry gas prices for the builder's gas coin, and add all other
+/// fee tokens as alternative fee denoms.
+pub(crate) fn apply_fee_tokens(builder: &mut CosmosBuilder, fee_tokens: &[FeeToken]) {
+    for token in fee_tokens {
+        if token.denom == builder.gas_coin() {
+            builder.set_gas_price(token.low_gas_price, token.high_gas_price);
+        } else {
+            builder.add_alternative_fee_denom(
+                &token.denom,
+                token.low_gas_price,
+                token.high_gas_price,
+            );
+        }
+    }
+}
+
+impl CosmosBuilder {
+    /// Construct a builder from the chain registry entry for the given chain.
+    ///
+    /// The first listed gRPC endpoint is used as the primary, with the rest
+    /// as fallbacks, and the first RPC endpoint is used for
+    /// [Self::rpc_url]. The first fee token becomes the gas coin.
+    pub async fn from_chain_registry(chain_name: &str) -> Result<CosmosBuilder, BuilderError> {
+        Self::from_chain_registry_with(&crate::client::env_http_client()?, chain_name).await
+    }
+
+    /// Same as [Self::from_chain_registry] but takes an existing [reqwest::Client]
+    pub async fn from_chain_registry_with(
+        client: &reqwest::Client,
+        chain_name: &str,
+    ) -> Result<CosmosBuilder, BuilderError> {
+        let chain = load_chain(client, chain_name).await?;
+        let invalid = |message: String| BuilderError::InvalidChainRegistry {
+            chain_name: chain_name.to_owned(),
+            message,
+        };
+        let hrp = AddressHrp::new(&chain.bech32_prefix).map_err(|e| invalid(e.to_string()))?;
+        let fee_token = chain
+            .fee_tokens
+            .first()
+            .ok_or_else(|| invalid("no fee tokens with gas prices listed".to_owned()))?;
+        let (grpc_url, fallbacks) = chain
+            .grpc_urls
+            .split_first()
+            .ok_or_else(|| invalid("no gRPC endpoints listed".to_owned()))?;
+
+        let mut builder = CosmosBuilder::new(&chain.chain_id, &fee_token.denom, hrp, grpc_url);
+        for fallback in fallbacks {
+            builder.add_grpc_fallback_url(fallback);
+        }
+        builder.set_rpc_url(chain.rpc_urls.first().cloned());
+        apply_fee_tokens(&mut builder, &chain.fee_tokens);
+        Ok(builder)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_chain() {
+        let raw: RawChain = serde_json::from_str(
+            r#"{
+                "chain_name": "kujira",
+                "chain_id": "kaiyo-1",
+                "bech32_prefix": "kujira",
+                "fees": {"fee_tokens": [
+                    {"denom": "ukuji", "fixed_min_gas_price": 0.00119, "low_gas_price": 0.0034, "high_gas_price": 0.0068},
+                    {"denom": "factory/kujira1/uusk", "fixed_min_gas_price": 0.0015},
+                    {"denom": "unpriced"}
+                ]},
+                "apis": {
+                    "grpc": [
+                        {"address": "kujira-grpc.polkachu.com:11890"},
+                        {"address": "grpc-kujira.example.com:443"},
+                        {"address": "https://grpc.kujira.example.com"}
+                    ],
+                    "rpc": [{"address": "https://rpc.kujira.example.com"}]
+                }
+            }"#,
+        )
+        .unwrap();
+        let chain = ChainInfo::from(raw);
+        assert_eq!(chain.chain_id, "kaiyo-1");
+        assert_eq!(chain.fee_tokens.len(), 2);
+        assert_eq!(chain.fee_tokens[1].low_gas_price, 0.0015);
+        assert_eq!(chain.fee_tokens[1].high_gas_price, 0.003);
+        assert_eq!(
+            chain.grpc_urls,
+            vec![
+                "http://kujira-grpc.polkachu.com:11890",
+                "https://grpc-kujira.example.com",
+                "https://grpc.kujira.example.com"
+            ]
+        );
+    }
+}
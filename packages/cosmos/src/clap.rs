@@ -1,6 +1,11 @@
 //! Provides helpers for generating Cosmos values from command line parameters.
 
-use crate::{error::BuilderError, Cosmos, CosmosBuilder, CosmosNetwork};
+use std::path::PathBuf;
+
+use crate::{
+    error::{BuilderError, WalletError},
+    AddressHrp, Cosmos, CosmosBuilder, CosmosNetwork, HasAddressHrp, SeedPhrase, Wallet,
+};
 
 /// Command line options for connecting to a Cosmos network
 #[derive(clap::Parser, Clone, Debug)]
@@ -28,6 +33,27 @@ pub struct CosmosOpt {
     /// Referer header
     #[clap(long, short, global = true, env = "COSMOS_REFERER_HEADER")]
     referer_header: Option<String>,
+    /// Optional lower bound of the gas price, requires --gas-price-high
+    #[clap(long, env = "COSMOS_GAS_PRICE_LOW", global = true)]
+    pub gas_price_low: Option<f64>,
+    /// Optional upper bound of the gas price, requires --gas-price-low
+    #[clap(long, env = "COSMOS_GAS_PRICE_HIGH", global = true)]
+    pub gas_price_high: Option<f64>,
+    /// Optional denom to pay fees in instead of the network's gas coin
+    ///
+    /// With --gas-price-low and --gas-price-high, those prices are used for this
+    /// denom. Otherwise it must be one of the network's alternative fee denoms.
+    #[clap(long, env = "COSMOS_FEE_DENOM", global = true)]
+    pub fee_denom: Option<String>,
+    /// Optional timeout for each query attempt, in seconds
+    #[clap(long, env = "COSMOS_QUERY_TIMEOUT", global = true)]
+    pub query_timeout: Option<u32>,
+    /// Optional number of attempts to make when waiting for a transaction
+    #[clap(long, env = "COSMOS_TRANSACTION_ATTEMPTS", global = true)]
+    pub transaction_attempts: Option<usize>,
+    /// Optional maximum number of concurrent requests to the nodes
+    #[clap(long, env = "COSMOS_CONNECTION_COUNT", global = true)]
+    pub connection_count: Option<usize>,
 }
 
 /// Errors for working with [CosmosOpt]
@@ -38,11 +64,28 @@ pub enum CosmosOptError {
     NoNetworkProvided,
     #[error("{source}")]
     CosmosBuilderError { source: BuilderError },
+    #[error("Gas prices must be set together, provide both --gas-price-low and --gas-price-high")]
+    IncompleteGasPrice,
+    #[error("--gas-price-low ({low}) must not be greater than --gas-price-high ({high})")]
+    InvalidGasPrice { low: f64, high: f64 },
 }
 
 impl CosmosOpt {
+    /// The gas price bounds, if provided.
+    fn gas_price(&self) -> Result<Option<(f64, f64)>, CosmosOptError> {
+        match (self.gas_price_low, self.gas_price_high) {
+            (Some(low), Some(high)) if low > high => {
+                Err(CosmosOptError::InvalidGasPrice { low, high })
+            }
+            (Some(low), Some(high)) => Ok(Some((low, high))),
+            (None, None) => Ok(None),
+            _ => Err(CosmosOptError::IncompleteGasPrice),
+        }
+    }
+
     /// Convert these options into a new [CosmosBuilder].
     pub async fn into_builder(self) -> Result<CosmosBuilder, CosmosOptError> {
+        let gas_price = self.gas_price()?;
         let CosmosOpt {
             network,
             cosmos_grpc,
@@ -50,6 +93,12 @@ impl CosmosOpt {
             chain_id,
             gas_multiplier,
             referer_header,
+            gas_price_low: _,
+            gas_price_high: _,
+            fee_denom,
+            query_timeout,
+            transaction_attempts,
+            connection_count,
         } = self;
 
         // Do the error checking here instead of in clap so that the field can
@@ -74,6 +123,35 @@ impl CosmosOpt {
         }
         builder.set_referer_header(referer_header);
 
+        match (fee_denom, gas_price) {
+            (Some(fee_denom), Some((low, high))) if fee_denom != builder.gas_coin() => {
+                // The gas prices are for the fee denom, not the network's gas coin
+                builder.add_alternative_fee_denom(fee_denom.clone(), low, high);
+                builder
+                    .use_fee_denom(&fee_denom)
+                    .map_err(|source| CosmosOptError::CosmosBuilderError { source })?;
+            }
+            (Some(fee_denom), gas_price) => {
+                builder
+                    .use_fee_denom(&fee_denom)
+                    .map_err(|source| CosmosOptError::CosmosBuilderError { source })?;
+                if let Some((low, high)) = gas_price {
+                    builder.set_gas_price(low, high);
+                }
+            }
+            (None, Some((low, high))) => builder.set_gas_price(low, high),
+            (None, None) => (),
+        }
+        if query_timeout.is_some() {
+            builder.set_query_timeout_seconds(query_timeout);
+        }
+        if transaction_attempts.is_some() {
+            builder.set_transaction_attempts(transaction_attempts);
+        }
+        if connection_count.is_some() {
+            builder.set_request_count(connection_count);
+        }
+
         Ok(builder)
     }
 
@@ -85,3 +163,148 @@ impl CosmosOpt {
             .map_err(|source| CosmosOptError::CosmosBuilderError { source })
     }
 }
+
+/// Command line options for loading a [Wallet]
+///
+/// Intentionally does not implement [Debug] to avoid leaking the seed phrase.
+#[derive(clap::Parser, Clone)]
+pub struct WalletOpt {
+    /// Mnemonic seed phrase for the wallet
+    #[clap(long, env = "COSMOS_MNEMONIC", global = true, hide_env_values = true)]
+    pub mnemonic: Option<SeedPhrase>,
+    /// File containing the mnemonic seed phrase for the wallet
+    ///
+    /// Takes precedence over --mnemonic, so a file can override a mnemonic
+    /// from the environment.
+    #[clap(long, env = "COSMOS_MNEMONIC_FILE", global = true)]
+    pub mnemonic_file: Option<PathBuf>,
+    /// Optional index for the network's default derivation path
+    ///
+    /// Can't be combined with a mnemonic that includes its own derivation path.
+    #[clap(long, env = "COSMOS_DERIVATION_INDEX", global = true)]
+    pub derivation_index: Option<u64>,
+}
+
+/// Errors for working with [WalletOpt]
+#[derive(thiserror::Error, Debug)]
+#[allow(missing_docs)]
+pub enum WalletOptError {
+    #[error("No wallet specified, either provide the COSMOS_MNEMONIC env var or --mnemonic or --mnemonic-file option")]
+    NoWalletProvided,
+    #[error("Unable to read mnemonic file: {source}")]
+    ReadMnemonicFile { source: std::io::Error },
+    #[error("{source}")]
+    WalletError { source: WalletError },
+    #[error(
+        "--derivation-index {index} conflicts with the derivation path {path} in the mnemonic"
+    )]
+    DerivationIndexConflict { index: u64, path: String },
+}
+
+impl WalletOpt {
+    /// Load the seed phrase, applying the derivation index if provided.
+    pub fn into_seed_phrase(self, hrp: AddressHrp) -> Result<SeedPhrase, WalletOptError> {
+        let WalletOpt {
+            mnemonic,
+            mnemonic_file,
+            derivation_index,
+        } = self;
+
+        let mut seed_phrase = match (mnemonic_file, mnemonic) {
+            (Some(path), _) => fs_err::read_to_string(path)
+                .map_err(|source| WalletOptError::ReadMnemonicFile { source })?
+                .trim()
+                .parse()
+                .map_err(|source| WalletOptError::WalletError { source })?,
+            (None, Some(mnemonic)) => mnemonic,
+            (None, None) => return Err(WalletOptError::NoWalletProvided),
+        };
+        if let Some(index) = derivation_index {
+            if let Some(path) = &seed_phrase.derivation_path {
+                return Err(WalletOptError::DerivationIndexConflict {
+                    index,
+                    path: path.to_string(),
+                });
+            }
+            seed_phrase.derivation_path = Some(hrp.default_derivation_path_with_index(index));
+        }
+        Ok(seed_phrase)
+    }
+
+    /// Generate a [Wallet] with the address prefix of the given network or connection.
+    ///
+    /// This works with both a [CosmosNetwork] and a [Cosmos].
+    pub fn into_wallet(self, network: impl HasAddressHrp) -> Result<Wallet, WalletOptError> {
+        let hrp = network.get_address_hrp();
+        self.into_seed_phrase(hrp)?
+            .with_hrp(hrp)
+            .map_err(|source| WalletOptError::WalletError { source })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+
+    use super::*;
+
+    #[test]
+    fn parse_gas_price() {
+        let parse = |args: &[&str]| {
+            CosmosOpt::try_parse_from(std::iter::once("cosmos").chain(args.iter().copied()))
+                .unwrap()
+        };
+        let opt = parse(&[
+            "--gas-price-low",
+            "0.01",
+            "--gas-price-high",
+            "0.03",
+            "--fee-denom",
+            "uusdc",
+        ]);
+        assert_eq!(opt.gas_price().unwrap(), Some((0.01, 0.03)));
+        assert_eq!(opt.fee_denom.as_deref(), Some("uusdc"));
+        assert!(matches!(
+            parse(&["--gas-price-low", "0.03", "--gas-price-high", "0.01"]).gas_price(),
+            Err(CosmosOptError::InvalidGasPrice { .. })
+        ));
+        assert!(matches!(
+            parse(&["--gas-price-low", "0.01"]).gas_price(),
+            Err(CosmosOptError::IncompleteGasPrice)
+        ));
+    }
+
+    #[test]
+    fn wallet_mnemonic_sources() {
+        let hrp = AddressHrp::from_static("osmo");
+        let osmo: SeedPhrase = "osmo-local".parse().unwrap();
+        let juno: SeedPhrase = "juno-local".parse().unwrap();
+
+        let path = std::env::temp_dir().join(format!("cosmos-mnemonic-{}", std::process::id()));
+        std::fs::write(&path, "juno-local\n").unwrap();
+        let seed_phrase = WalletOpt {
+            mnemonic: Some(osmo.clone()),
+            mnemonic_file: Some(path.clone()),
+            derivation_index: None,
+        }
+        .into_seed_phrase(hrp)
+        .unwrap();
+        std::fs::remove_file(&path).unwrap();
+        // The file wins over the mnemonic, e.g. from COSMOS_MNEMONIC
+        assert_eq!(seed_phrase.mnemonic.to_string(), juno.mnemonic.to_string());
+
+        let with_path = SeedPhrase {
+            derivation_path: Some(hrp.default_derivation_path_with_index(3)),
+            ..osmo
+        };
+        assert!(matches!(
+            WalletOpt {
+                mnemonic: Some(with_path),
+                mnemonic_file: None,
+                derivation_index: Some(1),
+            }
+            .into_seed_phrase(hrp),
+            Err(WalletOptError::DerivationIndexConflict { index: 1, .. })
+        ));
+    }
+}
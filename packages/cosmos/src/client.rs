@@ -1,7 +1,21 @@
+mod abci;
+mod cache;
+mod chain_params;
+mod fee_market;
+mod fixture;
+mod json_number;
+mod lcd;
+#[cfg(feature = "light-client")]
+mod light_client;
+mod module_account;
 mod node;
 mod node_chooser;
+mod node_status;
 mod pool;
+mod proxy;
 mod query;
+mod rpc;
+mod wasm_query;
 
 use std::{
     str::FromStr,
@@ -15,38 +29,57 @@ use cosmos_sdk_proto::{
         bank::v1beta1::QueryAllBalancesRequest,
         base::{
             abci::v1beta1::TxResponse,
-            query::v1beta1::PageRequest,
+            query::v1beta1::{PageRequest, PageResponse},
             tendermint::v1beta1::{GetBlockByHeightRequest, GetLatestBlockRequest},
             v1beta1::Coin,
         },
         tx::v1beta1::{
-            AuthInfo, BroadcastMode, BroadcastTxRequest, Fee, GetTxRequest, GetTxResponse,
-            GetTxsEventRequest, ModeInfo, OrderBy, SignDoc, SignerInfo, SimulateRequest,
-            SimulateResponse, Tx, TxBody,
+            AuthInfo, BroadcastMode, BroadcastTxRequest, BroadcastTxResponse, Fee, GetTxRequest,
+            GetTxResponse, ModeInfo, SignDoc, SignerInfo, SimulateRequest, SimulateResponse, Tx,
+            TxBody,
         },
     },
     cosmwasm::wasm::v1::QueryCodeRequest,
     traits::Message,
 };
+use futures_util::{Stream, StreamExt, TryStreamExt};
 use parking_lot::Mutex;
 use tokio::time::Instant;
-use tonic::{service::Interceptor, Status};
+use tokio_util::sync::CancellationToken;
+use tonic::{
+    metadata::{Ascii, MetadataKey, MetadataValue},
+    service::Interceptor,
+    Status,
+};
 
 use crate::{
-    address::HasAddressHrp,
+    address::{AddressHrp, HasAddressHrp},
+    decode::{txhash_for_bytes, DecodedTx},
     error::{
-        Action, BuilderError, ConnectionError, CosmosSdkError, NodeHealthReport, QueryError,
-        QueryErrorCategory, QueryErrorDetails,
+        Action, BuilderError, ChainError, ConnectionError, ErrorKind, NodeHealthReport, QueryError,
+        QueryErrorCategory, QueryErrorDetails, RetryReason, SingleNodeHealthReport, TxDecodeError,
     },
     gas_multiplier::{GasMultiplier, GasMultiplierConfig},
     gas_price::CurrentGasPrice,
     osmosis::ChainPausedStatus,
     wallet::WalletPublicKey,
-    Address, CosmosBuilder, DynamicGasMultiplier, Error, HasAddress, TxBuilder,
+    Account, Address, CosmosBuilder, DynamicGasMultiplier, Error, HasAddress, QueryLog, TxBuilder,
+    TxCostReport,
 };
 
 use self::{node::Node, node_chooser::QueryResult, pool::Pool, query::GrpcRequest};
 
+#[cfg(feature = "osmosis")]
+pub(crate) use self::chain_params::parse_dec;
+#[cfg(feature = "light-client")]
+pub use self::light_client::{LightClient, VerifiedHeader};
+pub(crate) use self::proxy::env_http_client;
+pub use self::{
+    abci::AbciQueryResponse,
+    chain_params::{MinGasPrice, MinGasPriceSource},
+    rpc::{BlockResults, BlockTxResult, UnconfirmedTxs},
+};
+
 use super::Wallet;
 
 /// A connection to a gRPC endpoint to communicate with a Cosmos chain.
@@ -65,6 +98,8 @@ pub struct Cosmos {
     gas_multiplier: GasMultiplier,
     /// Maximum gas price
     max_price: f64,
+    /// Number of nodes to race each query against, 1 or less to disable
+    hedged_queries: usize,
 }
 
 pub(crate) struct WeakCosmos {
@@ -74,6 +109,7 @@ pub(crate) struct WeakCosmos {
     chain_paused_status: ChainPausedStatus,
     gas_multiplier: GasMultiplier,
     max_price: f64,
+    hedged_queries: usize,
 }
 
 /// Type encapsulating both the [TxResponse] as well the actual [Tx]
@@ -84,6 +120,8 @@ pub struct CosmosTxResponse {
     /// Transaction representing it's body, signature and other
     /// information.
     pub tx: Tx,
+    /// Gas and fees for the transaction
+    pub cost: TxCostReport,
 }
 
 impl From<&Cosmos> for WeakCosmos {
@@ -95,6 +133,7 @@ impl From<&Cosmos> for WeakCosmos {
             chain_paused_status,
             gas_multiplier,
             max_price,
+            hedged_queries,
         }: &Cosmos,
     ) -> Self {
         WeakCosmos {
@@ -104,6 +143,7 @@ impl From<&Cosmos> for WeakCosmos {
             chain_paused_status: chain_paused_status.clone(),
             gas_multiplier: gas_multiplier.clone(),
             max_price: *max_price,
+            hedged_queries: *hedged_queries,
         }
     }
 }
@@ -117,6 +157,7 @@ impl WeakCosmos {
             chain_paused_status,
             gas_multiplier,
             max_price,
+            hedged_queries,
         } = self;
         block_height_tracking
             .upgrade()
@@ -127,6 +168,7 @@ impl WeakCosmos {
                 chain_paused_status: chain_paused_status.clone(),
                 gas_multiplier: gas_multiplier.clone(),
                 max_price: *max_price,
+                hedged_queries: *hedged_queries,
             })
     }
 }
@@ -162,8 +204,8 @@ impl Cosmos {
         &self,
         address: Address,
     ) -> Result<BaseAccount, Error> {
-        let mut guard = self.pool.get().await?;
-        let cosmos = guard.get_inner_mut();
+        let guard = self.pool.get().await?;
+        let cosmos = guard.get_inner();
         let sequence = {
             let guard = cosmos.simulate_sequences().read();
             let result = guard.get(&address);
@@ -213,8 +255,8 @@ impl Cosmos {
         tx: &Tx,
         hash: &str,
     ) -> Result<(), Error> {
-        let mut guard = self.pool.get().await?;
-        let cosmos = guard.get_inner_mut();
+        let guard = self.pool.get().await?;
+        let cosmos = guard.get_inner();
         let auth_info = &tx.auth_info;
         if let Some(auth_info) = auth_info {
             // This only works since we allow a single signer per
@@ -247,8 +289,8 @@ impl Cosmos {
         &self,
         address: Address,
     ) -> Result<BaseAccount, Error> {
-        let mut guard = self.pool.get().await?;
-        let cosmos = guard.get_inner_mut();
+        let guard = self.pool.get().await?;
+        let cosmos = guard.get_inner();
         let sequence = {
             let guard = cosmos.broadcast_sequences().read();
             let result = guard.get(&address);
@@ -298,19 +340,160 @@ impl Cosmos {
         action: Action,
         should_retry: bool,
     ) -> Result<PerformQueryWrapper<Request::Response>, QueryError> {
+        let start = Instant::now();
+        let request_size = prost::Message::encoded_len(&req);
+        let res = self
+            .perform_query_cached(req, action.clone(), should_retry)
+            .await;
+        let (grpc_url, response_size, error) = match &res {
+            Ok(res) => (
+                &res.grpc_url,
+                Some(prost::Message::encoded_len(res.tonic.get_ref())),
+                None,
+            ),
+            Err(e) => (&e.grpc_url, None, Some(e)),
+        };
+        let log = QueryLog {
+            request_type: std::any::type_name::<Request>(),
+            action: &action,
+            height: self.height,
+            request_size,
+            response_size,
+            grpc_url,
+            elapsed: start.elapsed(),
+            error,
+        };
+        tracing::debug!(
+            target: "cosmos::query",
+            "{} on {}: {} bytes sent, {} bytes received in {:?}{}",
+            log.action,
+            log.grpc_url,
+            log.request_size,
+            log.response_size.unwrap_or_default(),
+            log.elapsed,
+            if log.error.is_some() { ", failed" } else { "" }
+        );
+        if let Some(logger) = self.pool.builder.query_logger() {
+            logger.call(&log);
+        }
+        res
+    }
+
+    async fn perform_query_cached<Request: GrpcRequest>(
+        &self,
+        req: Request,
+        action: Action,
+        should_retry: bool,
+    ) -> Result<PerformQueryWrapper<Request::Response>, QueryError> {
+        if self.pool.fixture.is_some() {
+            if let Some(res) = self.replay_fixture(&req, &action) {
+                return res;
+            }
+            let res = self
+                .perform_query_uncached(req.clone(), action, should_retry)
+                .await;
+            self.record_fixture(&req, &res);
+            return res;
+        }
+        let cache = match &self.pool.cache {
+            None => return self.perform_query_uncached(req, action, should_retry).await,
+            Some(cache) => cache,
+        };
+        if let Some((grpc_url, res)) = cache.get(&req, self.height) {
+            return Ok(PerformQueryWrapper {
+                grpc_url,
+                tonic: tonic::Response::new(res),
+            });
+        }
+        let res = self
+            .perform_query_uncached(req.clone(), action, should_retry)
+            .await?;
+        cache.insert(&req, self.height, &res.grpc_url, res.tonic.get_ref());
+        Ok(res)
+    }
+
+    /// Answer a query from the fixture, if replaying one.
+    fn replay_fixture<Request: GrpcRequest>(
+        &self,
+        req: &Request,
+        action: &Action,
+    ) -> Option<Result<PerformQueryWrapper<Request::Response>, QueryError>> {
+        let res = self.pool.fixture.as_ref()?.replay(req, self.height)?;
+        let grpc_url = self.get_cosmos_builder().grpc_url_arc().clone();
+        Some(match res {
+            Ok(res) => Ok(PerformQueryWrapper {
+                grpc_url,
+                tonic: tonic::Response::new(res),
+            }),
+            Err(query) => Err(QueryError {
+                action: action.clone(),
+                builder: self.pool.builder.clone(),
+                height: self.height,
+                query,
+                grpc_url,
+                node_health: self.pool.node_chooser.health_report(),
+            }),
+        })
+    }
+
+    /// Save the outcome of a query to the fixture, if recording one.
+    fn record_fixture<Request: GrpcRequest>(
+        &self,
+        req: &Request,
+        res: &Result<PerformQueryWrapper<Request::Response>, QueryError>,
+    ) {
+        if let Some(fixture) = &self.pool.fixture {
+            let res = match res {
+                Ok(res) => Ok(res.tonic.get_ref()),
+                Err(err) => Err(&err.query),
+            };
+            fixture.record(req, self.height, res);
+        }
+    }
+
+    /// Write the queries recorded so far to the fixture file.
+    ///
+    /// Recorded queries are also written when the last clone of this
+    /// connection is dropped. Does nothing unless recording, see
+    /// [CosmosBuilder::set_fixture].
+    pub fn flush_fixture(&self) -> Result<(), crate::Error> {
+        match &self.pool.fixture {
+            Some(fixture) => fixture.flush(),
+            None => Ok(()),
+        }
+    }
+
+    async fn perform_query_uncached<Request: GrpcRequest>(
+        &self,
+        req: Request,
+        action: Action,
+        should_retry: bool,
+    ) -> Result<PerformQueryWrapper<Request::Response>, QueryError> {
+        if self.hedged_queries > 1 && self.height.is_none() {
+            return self.perform_query_hedged(&req, action).await;
+        }
+        let retry_policy = self.pool.builder.retry_policy();
         let mut attempt = 0;
         loop {
-            let (err, can_retry, grpc_url) = match self.pool.get().await {
+            let guard = if self.height.is_some() {
+                self.pool.get_historical().await
+            } else {
+                self.pool.get().await
+            };
+            let (err, can_retry, grpc_url) = match guard {
                 Err(err) => (
                     QueryErrorDetails::ConnectionError(err),
                     true,
                     self.get_cosmos_builder().grpc_url_arc().clone(),
                 ),
-                Ok(mut guard) => {
-                    let cosmos_inner = guard.get_inner_mut();
+                Ok(guard) => {
+                    let cosmos_inner = guard.get_inner();
+                    let start = Instant::now();
                     match self.perform_query_inner(req.clone(), cosmos_inner).await {
                         Ok(x) => {
-                            cosmos_inner.log_query_result(QueryResult::Success);
+                            cosmos_inner.log_query_result(QueryResult::Success {
+                                latency: start.elapsed(),
+                            });
                             break Ok(PerformQueryWrapper {
                                 grpc_url: cosmos_inner.grpc_url().clone(),
                                 tonic: x,
@@ -330,7 +513,13 @@ impl Cosmos {
                     }
                 }
             };
-            if attempt >= self.pool.builder.query_retries() || !should_retry || !can_retry {
+            let can_retry = can_retry || retry_policy.is_retryable(&err);
+            if attempt + 1 >= retry_policy.max_attempts || !should_retry || !can_retry {
+                if can_retry {
+                    if let Some(res) = self.perform_query_lcd(&req).await {
+                        break Ok(res);
+                    }
+                }
                 break Err(QueryError {
                     action,
                     builder: self.pool.builder.clone(),
@@ -341,19 +530,365 @@ impl Cosmos {
                 });
             } else {
                 attempt += 1;
+                let backoff = retry_policy.backoff(attempt);
                 tracing::debug!(
-                    "Error performing a query, retrying. Attempt {attempt} of {}. {err:?}",
-                    self.pool.builder.query_retries()
+                    "Error performing a query, retrying in {backoff:?}. Retry {attempt} of {}. {err:?}",
+                    retry_policy.max_attempts.saturating_sub(1)
                 );
+                tokio::time::sleep(backoff).await;
+            }
+        }
+    }
+
+    /// Try the request over the LCD fallback, if one is configured and supports it.
+    async fn perform_query_lcd<Request: GrpcRequest>(
+        &self,
+        req: &Request,
+    ) -> Option<PerformQueryWrapper<Request::Response>> {
+        let lcd = self.pool.lcd.as_ref()?;
+        match req.perform_lcd(lcd, self.height).await? {
+            Ok(res) => Some(PerformQueryWrapper {
+                grpc_url: lcd.url().clone(),
+                tonic: tonic::Response::new(res),
+            }),
+            Err(err) => {
+                tracing::warn!("LCD fallback to {} failed: {err}", lcd.url());
+                None
+            }
+        }
+    }
+
+    /// Query a service which may not be present on the chain.
+    ///
+    /// Returns [None] if the node doesn't implement the service or the query
+    /// route, and remembers this so the method isn't queried again. Only a
+    /// single node is queried: failures aren't retried and don't affect node
+    /// health, since a missing service says nothing about the node.
+    pub(crate) async fn probe_query<Request: GrpcRequest>(
+        &self,
+        req: Request,
+        action: Action,
+    ) -> Result<Option<Request::Response>, QueryError> {
+        let service = Request::METHOD;
+        if self.pool.absent_services.lock().contains(service) {
+            return Ok(None);
+        }
+        let res = match self.replay_fixture(&req, &action) {
+            Some(res) => res,
+            None => {
+                let res = self.probe_query_once(req.clone(), action).await;
+                self.record_fixture(&req, &res);
+                res
+            }
+        };
+        match res {
+            Ok(res) => Ok(Some(res.tonic.into_inner())),
+            Err(QueryError {
+                query: QueryErrorDetails::Unimplemented { .. } | QueryErrorDetails::NotFound(_),
+                ..
+            }) => {
+                self.pool.absent_services.lock().insert(service);
+                Ok(None)
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Send a [Self::probe_query] request to a single node, without retries.
+    async fn probe_query_once<Request: GrpcRequest>(
+        &self,
+        req: Request,
+        action: Action,
+    ) -> Result<PerformQueryWrapper<Request::Response>, QueryError> {
+        let make_error = |query, grpc_url| QueryError {
+            action,
+            builder: self.pool.builder.clone(),
+            height: self.height,
+            query,
+            grpc_url,
+            node_health: self.pool.node_chooser.health_report(),
+        };
+        let guard = match self.pool.get().await {
+            Ok(guard) => guard,
+            Err(err) => {
+                return Err(make_error(
+                    QueryErrorDetails::ConnectionError(err),
+                    self.get_cosmos_builder().grpc_url_arc().clone(),
+                ))
+            }
+        };
+        let node = guard.get_inner();
+        let timeout =
+            std::time::Duration::from_secs(self.pool.builder.query_timeout_seconds().into());
+        let mut req = tonic::Request::new(req);
+        req.set_timeout(timeout);
+        let res = match tokio::time::timeout(timeout, GrpcRequest::perform(req, node)).await {
+            Ok(res) => res.map_err(QueryErrorDetails::from_tonic_status),
+            Err(_) => Err(QueryErrorDetails::QueryTimeout(timeout)),
+        };
+        match res {
+            Ok(tonic) => Ok(PerformQueryWrapper {
+                grpc_url: node.grpc_url().clone(),
+                tonic,
+            }),
+            Err(query) => Err(make_error(query, node.grpc_url().clone())),
+        }
+    }
+
+    /// Send the query to multiple nodes at once and return the first success.
+    ///
+    /// If every node fails, the hedged nodes are not queried again. The error
+    /// from a node which answered is preferred over connection errors, since
+    /// it's more likely to describe a problem with the query itself.
+    async fn perform_query_hedged<Request: GrpcRequest>(
+        &self,
+        req: &Request,
+        action: Action,
+    ) -> Result<PerformQueryWrapper<Request::Response>, QueryError> {
+        let action = &action;
+        let mut queries = self
+            .pool
+            .node_chooser
+            .choose_nodes(self.hedged_queries)
+            .into_iter()
+            .map(|node| async move {
+                let guard = match self.pool.get_with_node(node).await {
+                    Ok(guard) => guard,
+                    Err(err) => {
+                        return Err((
+                            QueryErrorDetails::ConnectionError(err),
+                            true,
+                            node.grpc_url().clone(),
+                        ))
+                    }
+                };
+                let node = guard.get_inner();
+                let start = Instant::now();
+                match self.perform_query_inner(req.clone(), node).await {
+                    Ok(tonic) => {
+                        node.log_query_result(QueryResult::Success {
+                            latency: start.elapsed(),
+                        });
+                        Ok(PerformQueryWrapper {
+                            grpc_url: node.grpc_url().clone(),
+                            tonic,
+                        })
+                    }
+                    Err((err, can_retry)) => {
+                        tracing::debug!("Hedged query to {} failed: {err:?}", node.grpc_url());
+                        node.log_query_result(if can_retry {
+                            QueryResult::NetworkError {
+                                err: err.clone(),
+                                action: action.clone(),
+                            }
+                        } else {
+                            QueryResult::OtherError
+                        });
+                        Err((err, can_retry, node.grpc_url().clone()))
+                    }
+                }
+            })
+            .collect::<futures_util::stream::FuturesUnordered<_>>();
+        let mut best: Option<(QueryErrorDetails, bool, Arc<String>)> = None;
+        // Returning early drops the remaining futures, cancelling the slower requests.
+        while let Some(res) = queries.next().await {
+            match res {
+                Ok(res) => return Ok(res),
+                Err(err) => {
+                    if best.as_ref().map_or(true, |(_, can_retry, _)| *can_retry) {
+                        best = Some(err);
+                    }
+                }
+            }
+        }
+        let (err, can_retry, grpc_url) = best.expect("choose_nodes always returns a node");
+        if can_retry {
+            if let Some(res) = self.perform_query_lcd(req).await {
+                return Ok(res);
             }
         }
+        Err(QueryError {
+            action: action.clone(),
+            builder: self.pool.builder.clone(),
+            height: self.height,
+            query: err,
+            grpc_url,
+            node_health: self.pool.node_chooser.health_report(),
+        })
+    }
+
+    /// Broadcast a fully signed transaction and wait for it to be included in a block.
+    ///
+    /// Unless the builder skips code checks, fails if the transaction fails
+    /// at either stage.
+    pub(crate) async fn broadcast_tx_and_wait(
+        &self,
+        tx_bytes: Vec<u8>,
+        txbuilder: &TxBuilder,
+    ) -> Result<TxResponse, crate::Error> {
+        let PerformQueryWrapper { grpc_url, tonic } = self
+            .broadcast_tx_bytes(tx_bytes, Action::Broadcast(txbuilder.clone()))
+            .await?;
+        let res =
+            tonic
+                .into_inner()
+                .tx_response
+                .ok_or_else(|| crate::Error::InvalidChainResponse {
+                    message: "Missing inner tx_response".to_owned(),
+                    action: Action::Broadcast(txbuilder.clone()),
+                })?;
+
+        if !txbuilder.skip_code_check && res.code != 0 {
+            return Err(crate::Error::TransactionFailed {
+                code: ChainError::new(res.codespace, res.code),
+                raw_log: res.raw_log,
+                action: Action::Broadcast(txbuilder.clone()).into(),
+                grpc_url,
+                stage: crate::error::TransactionStage::Broadcast,
+            });
+        };
+
+        tracing::debug!("Initial BroadcastTxResponse: {res:?}");
+
+        let (_, res) = self
+            .wait_for_transaction_with_action(
+                res.txhash,
+                Some(Action::Broadcast(txbuilder.clone())),
+            )
+            .await?;
+        if !txbuilder.skip_code_check && res.code != 0 {
+            return Err(crate::Error::TransactionFailed {
+                code: ChainError::new(res.codespace, res.code),
+                raw_log: res.raw_log,
+                action: Action::Broadcast(txbuilder.clone()).into(),
+                grpc_url,
+                stage: crate::error::TransactionStage::Wait,
+            });
+        };
+
+        tracing::debug!("TxResponse: {res:?}");
+        Ok(res)
+    }
+
+    /// Broadcast signed transaction bytes in sync mode.
+    ///
+    /// If [CosmosBuilder::broadcast_to_all_nodes] is enabled, the transaction
+    /// is sent to every node concurrently and the first accepted response is
+    /// returned. If no node accepts it, one of the failures is returned.
+    async fn broadcast_tx_bytes(
+        &self,
+        tx_bytes: Vec<u8>,
+        action: Action,
+    ) -> Result<PerformQueryWrapper<BroadcastTxResponse>, QueryError> {
+        let req = BroadcastTxRequest {
+            tx_bytes,
+            mode: BroadcastMode::Sync as i32,
+        };
+        if !self.pool.builder.broadcast_to_all_nodes() {
+            return self.perform_query(req, action, true).await;
+        }
+        if let Some(res) = self.replay_fixture(&req, &action) {
+            return res;
+        }
+        let res = self.broadcast_tx_all_nodes(req.clone(), action).await;
+        self.record_fixture(&req, &res);
+        res
+    }
+
+    /// Broadcast to every node at once, see [CosmosBuilder::set_broadcast_to_all_nodes].
+    async fn broadcast_tx_all_nodes(
+        &self,
+        req: BroadcastTxRequest,
+        action: Action,
+    ) -> Result<PerformQueryWrapper<BroadcastTxResponse>, QueryError> {
+        let mut broadcasts = self
+            .pool
+            .node_chooser
+            .all_nodes()
+            .map(|node| {
+                let req = req.clone();
+                let action = action.clone();
+                Box::pin(async move {
+                    let make_err = |query, grpc_url| QueryError {
+                        action,
+                        builder: self.pool.builder.clone(),
+                        height: self.height,
+                        query,
+                        grpc_url,
+                        node_health: self.pool.node_chooser.health_report(),
+                    };
+                    let guard = match self.pool.get_with_node(node).await {
+                        Ok(guard) => guard,
+                        Err(err) => {
+                            return Err(Err(make_err(
+                                QueryErrorDetails::ConnectionError(err),
+                                node.grpc_url().clone(),
+                            )))
+                        }
+                    };
+                    let node = guard.get_inner();
+                    let start = Instant::now();
+                    match self.perform_query_inner(req, node).await {
+                        Ok(tonic) => {
+                            node.log_query_result(QueryResult::Success {
+                                latency: start.elapsed(),
+                            });
+                            let res = PerformQueryWrapper {
+                                grpc_url: node.grpc_url().clone(),
+                                tonic,
+                            };
+                            // Rejected by this node, e.g. due to a full mempool.
+                            // Keep waiting on the other nodes.
+                            let rejected = res
+                                .tonic
+                                .get_ref()
+                                .tx_response
+                                .as_ref()
+                                .map_or(false, |txres| txres.code != 0);
+                            if rejected {
+                                Err(Ok(res))
+                            } else {
+                                Ok(res)
+                            }
+                        }
+                        Err((err, _)) => Err(Err(make_err(err, node.grpc_url().clone()))),
+                    }
+                })
+            })
+            .collect::<futures_util::stream::FuturesUnordered<_>>();
+
+        // If every node fails, prefer a rejection from the chain itself over
+        // connection problems with individual nodes: the rejection is
+        // deterministic, and hiding it would invite pointless retries.
+        fn rank(res: &Result<PerformQueryWrapper<BroadcastTxResponse>, QueryError>) -> u8 {
+            match res {
+                Ok(_) => 2,
+                Err(err) if err.query.kind() == ErrorKind::Rejected => 1,
+                Err(_) => 0,
+            }
+        }
+        let mut failure = None;
+        while let Some(res) = broadcasts.next().await {
+            match res {
+                Ok(res) => return Ok(res),
+                Err(res) => {
+                    if failure
+                        .as_ref()
+                        .map_or(true, |failure| rank(&res) > rank(failure))
+                    {
+                        failure = Some(res);
+                    }
+                }
+            }
+        }
+        failure.expect("all_nodes always includes the primary node")
     }
 
     /// Error return: the details itself, and whether a retry can be attempted.
     async fn perform_query_inner<Request: GrpcRequest>(
         &self,
         req: Request,
-        cosmos_inner: &mut Node,
+        cosmos_inner: &Node,
     ) -> Result<tonic::Response<Request::Response>, (QueryErrorDetails, bool)> {
         let mut req = tonic::Request::new(req.clone());
         if let Some(height) = self.height {
@@ -361,13 +896,27 @@ impl Cosmos {
             let metadata = req.metadata_mut();
             metadata.insert("x-cosmos-block-height", height.into());
         }
-        let res = GrpcRequest::perform(req, cosmos_inner).await;
+        let timeout = std::time::Duration::from_secs(
+            if Request::IS_BROADCAST {
+                self.pool.builder.broadcast_timeout_seconds()
+            } else {
+                self.pool.builder.query_timeout_seconds()
+            }
+            .into(),
+        );
+        // Let the node know as well, so it can stop working on the request.
+        req.set_timeout(timeout);
+        let res = match tokio::time::timeout(timeout, GrpcRequest::perform(req, cosmos_inner)).await
+        {
+            Ok(res) => res,
+            Err(_) => {
+                cosmos_inner.set_broken(|grpc_url| ConnectionError::TimeoutQuery { grpc_url });
+                return Err((QueryErrorDetails::QueryTimeout(timeout), true));
+            }
+        };
         match res {
             Ok(res) => {
-                self.check_block_height(
-                    res.metadata().get("x-cosmos-block-height"),
-                    cosmos_inner.grpc_url(),
-                )?;
+                self.check_block_height(res.metadata().get("x-cosmos-block-height"), cosmos_inner)?;
                 Ok(res)
             }
             Err(status) => {
@@ -422,8 +971,9 @@ impl Cosmos {
     fn check_block_height(
         &self,
         new_height: Option<&tonic::metadata::MetadataValue<tonic::metadata::Ascii>>,
-        grpc_url: &Arc<String>,
+        node: &Node,
     ) -> Result<(), (QueryErrorDetails, bool)> {
+        let grpc_url = node.grpc_url();
         if self.height.is_some() {
             // Don't do a height check, we're specifically querying historical data.
             return Ok(());
@@ -456,6 +1006,7 @@ impl Cosmos {
                 return Ok(());
             }
         };
+        node.record_block_height(new_height);
         let now = Instant::now();
 
         let mut guard = self.block_height_tracking.lock();
@@ -512,18 +1063,100 @@ impl Cosmos {
 }
 
 #[derive(Clone)]
-pub struct CosmosInterceptor(Option<Arc<String>>);
+pub struct CosmosInterceptor {
+    referer: Option<Arc<String>>,
+    headers: Arc<[(MetadataKey<Ascii>, MetadataValue<Ascii>)]>,
+    user: Option<RequestInterceptor>,
+}
+
+impl CosmosBuilder {
+    /// Proxy used when connecting to the given URL, if any.
+    ///
+    /// An explicitly configured [Self::proxy] is always used. Otherwise the
+    /// proxy comes from the environment: `HTTPS_PROXY` or `HTTP_PROXY`
+    /// depending on the scheme, falling back to `ALL_PROXY`. Loopback hosts
+    /// and hosts listed in `NO_PROXY` are connected to directly.
+    pub fn proxy_for_url(&self, url: &str) -> Option<String> {
+        match self.proxy() {
+            Some(proxy) => Some(proxy.to_owned()),
+            None => proxy::env_proxy_for_url(url, |var| std::env::var(var).ok()),
+        }
+    }
+
+    /// HTTP client for the RPC and LCD endpoints, using the same proxy and headers as gRPC.
+    pub(crate) fn make_http_client(&self, url: &str) -> Result<reqwest::Client, BuilderError> {
+        let mut headers = reqwest::header::HeaderMap::new();
+        if let Some(referer) = self.referer_header() {
+            if let Ok(value) = reqwest::header::HeaderValue::from_str(referer) {
+                headers.insert(reqwest::header::REFERER, value);
+            }
+        }
+        for (name, value) in self.grpc_headers() {
+            let invalid = |message: String| BuilderError::InvalidGrpcHeader {
+                name: name.clone(),
+                message,
+            };
+            let key =
+                reqwest::header::HeaderName::from_str(name).map_err(|e| invalid(e.to_string()))?;
+            let value = reqwest::header::HeaderValue::from_str(value)
+                .map_err(|e| invalid(e.to_string()))?;
+            headers.insert(key, value);
+        }
+        // Environment variables are already handled by proxy_for_url, don't
+        // let reqwest apply them a second time.
+        let mut client = reqwest::Client::builder()
+            .default_headers(headers)
+            .no_proxy();
+        if let Some(proxy) = self.proxy_for_url(url) {
+            let proxy = reqwest::Proxy::all(&proxy).map_err(|e| BuilderError::InvalidProxy {
+                proxy: proxy.clone(),
+                message: e.to_string(),
+            })?;
+            client = client.proxy(proxy);
+        }
+        client
+            .build()
+            .map_err(|source| BuilderError::HttpClient { source })
+    }
+
+    pub(crate) fn make_interceptor(&self) -> Result<CosmosInterceptor, BuilderError> {
+        let headers = self
+            .grpc_headers()
+            .iter()
+            .map(|(name, value)| {
+                let invalid = |message: String| BuilderError::InvalidGrpcHeader {
+                    name: name.clone(),
+                    message,
+                };
+                let key = MetadataKey::from_str(name).map_err(|e| invalid(e.to_string()))?;
+                let value = MetadataValue::from_str(value).map_err(|e| invalid(e.to_string()))?;
+                Ok((key, value))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(CosmosInterceptor {
+            referer: self.referer_header().map(|x| Arc::new(x.to_owned())),
+            headers: headers.into(),
+            user: self.request_interceptor().cloned(),
+        })
+    }
+}
 
 impl Interceptor for CosmosInterceptor {
     fn call(&mut self, mut request: tonic::Request<()>) -> Result<tonic::Request<()>, Status> {
         let req = request.metadata_mut();
-        if let Some(value) = &self.0 {
+        if let Some(value) = &self.referer {
             let value = FromStr::from_str(value);
             if let Ok(header_value) = value {
                 req.insert("referer", header_value);
             }
         }
-        Ok(request)
+        for (key, value) in self.headers.iter() {
+            req.insert(key.clone(), value.clone());
+        }
+        match &self.user {
+            Some(user) => user.call(request),
+            None => Ok(request),
+        }
     }
 }
 
@@ -550,15 +1183,18 @@ impl CosmosBuilder {
             .map(|header| header.chain_id);
 
         let expected = cosmos.get_cosmos_builder().chain_id();
-        if actual.as_deref() == Some(expected) {
-            Ok(cosmos)
-        } else {
-            Err(BuilderError::MismatchedChainIds {
+        if actual.as_deref() != Some(expected) {
+            return Err(BuilderError::MismatchedChainIds {
                 grpc_url: cosmos.get_cosmos_builder().grpc_url().to_owned(),
                 expected: expected.to_owned(),
                 actual,
-            })
+            });
         }
+
+        if cosmos.get_cosmos_builder().verify_chain_params() {
+            cosmos.verify_chain_params().await?;
+        }
+        Ok(cosmos)
     }
 
     /// Create a new [Cosmos] but do not perform any sanity checks.
@@ -579,19 +1215,45 @@ impl CosmosBuilder {
             chain_paused_status,
             gas_multiplier,
             max_price,
+            hedged_queries: 1,
         };
         // cosmos.launch_chain_paused_tracker();
+        cosmos.pool.builder.launch_gas_price_refresh(&cosmos);
         Ok(cosmos)
     }
 }
 
 impl Cosmos {
     /// Return a modified version of this [Cosmos] that queries at the given height.
+    ///
+    /// Every query made through the returned value, including balances,
+    /// accounts and contract queries, sends the `x-cosmos-block-height`
+    /// header and is routed to the archive node if one is configured.
+    /// Pass [None] to return to querying the latest state.
     pub fn at_height(mut self, height: Option<u64>) -> Self {
         self.height = height;
         self
     }
 
+    /// The height this [Cosmos] is pinned to, if any.
+    pub fn get_height(&self) -> Option<u64> {
+        self.height
+    }
+
+    /// Return a modified version of this [Cosmos] that races each query against multiple nodes.
+    ///
+    /// Queries are sent to up to `count` healthy nodes concurrently, and the
+    /// first successful response is used. The remaining requests are
+    /// cancelled. This reduces tail latency when a node is slow, at the cost
+    /// of extra load. If every node fails, the error is returned without
+    /// retrying, since the healthiest nodes were already tried. A `count` of 1
+    /// or less disables hedging. Queries
+    /// pinned to a height are never hedged.
+    pub fn with_hedged_queries(mut self, count: usize) -> Self {
+        self.hedged_queries = count;
+        self
+    }
+
     /// Return a modified version of this [Cosmos] that sets the maximum gas price to this value.
     ///
     /// Only has an impact on Osmosis mainnet.
@@ -621,8 +1283,8 @@ impl Cosmos {
         }
     }
 
-    /// Get the base account information for the given address.
-    pub async fn get_base_account(&self, address: Address) -> Result<BaseAccount, crate::Error> {
+    /// Get the account information for the given address.
+    pub async fn get_account(&self, address: Address) -> Result<Account, crate::Error> {
         let action = Action::GetBaseAccount(address);
         let res = self
             .perform_query(
@@ -635,74 +1297,115 @@ impl Cosmos {
             .await?
             .into_inner();
 
-        let base_account = if self.get_address_hrp().as_str() == "inj" {
-            let eth_account: crate::injective::EthAccount = prost::Message::decode(
-                res.account
-                    .ok_or_else(|| crate::Error::InvalidChainResponse {
-                        message: "no eth account found".to_owned(),
-                        action: action.clone(),
-                    })?
-                    .value
-                    .as_ref(),
-            )
-            .map_err(|source| crate::Error::InvalidChainResponse {
-                message: format!("Unable to parse eth_account: {source}"),
+        let account = res
+            .account
+            .ok_or_else(|| crate::Error::InvalidChainResponse {
+                message: "no account found".to_owned(),
                 action: action.clone(),
             })?;
-            eth_account
-                .base_account
-                .ok_or_else(|| crate::Error::InvalidChainResponse {
-                    message: "no base account found".to_owned(),
-                    action: action.clone(),
-                })?
-        } else {
-            prost::Message::decode(
-                res.account
-                    .ok_or_else(|| crate::Error::InvalidChainResponse {
-                        message: "no account found".to_owned(),
-                        action: action.clone(),
-                    })?
-                    .value
-                    .as_ref(),
-            )
-            .map_err(|source| crate::Error::InvalidChainResponse {
-                message: format!("Unable to parse account: {source}"),
-                action,
-            })?
-        };
-        Ok(base_account)
+        Account::decode(&account).map_err(|source| crate::Error::InvalidChainResponse {
+            message: format!("Unable to parse {}: {source}", account.type_url),
+            action,
+        })
+    }
+
+    /// Get the base account information for the given address.
+    ///
+    /// Works for all account types supported by [Account], such as vesting
+    /// and module accounts.
+    pub async fn get_base_account(&self, address: Address) -> Result<BaseAccount, crate::Error> {
+        self.get_account(address)
+            .await?
+            .into_base_account()
+            .ok_or_else(|| crate::Error::InvalidChainResponse {
+                message: "no base account found".to_owned(),
+                action: Action::GetBaseAccount(address),
+            })
     }
 
     /// Get the coin balances for the given address.
     pub async fn all_balances(&self, address: Address) -> Result<Vec<Coin>, crate::Error> {
-        let mut coins = Vec::new();
-        let mut pagination = None;
-        loop {
-            let mut res = self
-                .perform_query(
-                    QueryAllBalancesRequest {
-                        address: address.get_address_string(),
-                        pagination: pagination.take(),
-                    },
-                    Action::QueryAllBalances(address),
-                    true,
-                )
-                .await?
-                .into_inner();
-            coins.append(&mut res.balances);
-            match res.pagination {
-                Some(x) if !x.next_key.is_empty() => {
-                    pagination = Some(PageRequest {
+        self.all_balances_stream(address).try_collect().await
+    }
+
+    /// Lazily load all balances for the given address, one page at a time.
+    pub fn all_balances_stream(
+        &self,
+        address: Address,
+    ) -> impl Stream<Item = Result<Coin, crate::Error>> + Send + 'static {
+        self.paginate(
+            Action::QueryAllBalances(address),
+            move |pagination| QueryAllBalancesRequest {
+                address: address.get_address_string(),
+                pagination,
+            },
+            |res| (res.balances, res.pagination),
+        )
+    }
+
+    /// Lazily load every item from a paginated query.
+    ///
+    /// `make_request` builds the request for each page from the pagination
+    /// parameters, and `split_response` extracts the items and pagination
+    /// info from the response. Pages are loaded by key, and only a single
+    /// page is held in memory at a time. The stream ends after the first
+    /// error.
+    pub(crate) fn paginate<Request, Item>(
+        &self,
+        action: Action,
+        make_request: impl Fn(Option<PageRequest>) -> Request + Send + 'static,
+        split_response: impl Fn(Request::Response) -> (Vec<Item>, Option<PageResponse>) + Send + 'static,
+    ) -> impl Stream<Item = Result<Item, crate::Error>> + Send + 'static
+    where
+        Request: GrpcRequest + Send + 'static,
+        Item: Send + 'static,
+    {
+        struct State<Item, MakeRequest, SplitResponse> {
+            cosmos: Cosmos,
+            action: Action,
+            make_request: MakeRequest,
+            split_response: SplitResponse,
+            buffer: std::collections::VecDeque<Item>,
+            /// Pagination for the next page, or [None] once the last page is loaded
+            next: Option<Option<PageRequest>>,
+        }
+        let state = State {
+            cosmos: self.clone(),
+            action,
+            make_request,
+            split_response,
+            buffer: std::collections::VecDeque::new(),
+            next: Some(None),
+        };
+        futures_util::stream::unfold(state, |mut state| async move {
+            loop {
+                if let Some(item) = state.buffer.pop_front() {
+                    return Some((Ok(item), state));
+                }
+                let pagination = state.next.take()?;
+                let req = (state.make_request)(pagination);
+                let res = match state
+                    .cosmos
+                    .perform_query(req, state.action.clone(), true)
+                    .await
+                {
+                    Ok(res) => res.into_inner(),
+                    Err(e) => return Some((Err(e.into()), state)),
+                };
+                let (items, pagination) = (state.split_response)(res);
+                state.buffer.extend(items);
+                state.next = match pagination {
+                    Some(x) if !x.next_key.is_empty() => Some(Some(PageRequest {
                         key: x.next_key,
                         offset: 0,
                         limit: 0,
                         count_total: false,
                         reverse: false,
-                    })
-                }
-                _ => break Ok(coins),
+                    })),
+                    _ => None,
+                };
             }
-        }
+        })
     }
 
     pub(crate) async fn code_info(&self, code_id: u64) -> Result<Vec<u8>, crate::Error> {
@@ -716,6 +1419,21 @@ impl Cosmos {
         Ok(res.into_inner().data)
     }
 
+    pub(crate) async fn code_checksum(&self, code_id: u64) -> Result<Vec<u8>, crate::Error> {
+        let action = Action::CodeInfo(code_id);
+        let res = self
+            .perform_query(QueryCodeRequest { code_id }, action.clone(), true)
+            .await?
+            .into_inner();
+        match res.code_info {
+            Some(code_info) => Ok(code_info.data_hash),
+            None => Err(crate::Error::InvalidChainResponse {
+                message: format!("No code info returned for code ID {code_id}"),
+                action,
+            }),
+        }
+    }
+
     fn txres_to_pair(
         txres: GetTxResponse,
         action: Action,
@@ -788,13 +1506,13 @@ impl Cosmos {
             Ok(txres) => Self::txres_to_pair(txres.into_inner(), action),
             Err(e) => {
                 for node in self.pool.node_chooser.all_nodes() {
-                    if let Ok(mut node_guard) = self.pool.get_with_node(node).await {
+                    if let Ok(node_guard) = self.pool.get_with_node(node).await {
                         if let Ok(txres) = self
                             .perform_query_inner(
                                 GetTxRequest {
                                     hash: txhash.clone(),
                                 },
-                                node_guard.get_inner_mut(),
+                                node_guard.get_inner(),
                             )
                             .await
                         {
@@ -867,42 +1585,209 @@ impl Cosmos {
         })
     }
 
+    /// Wait for a transaction with an overall deadline and cancellation.
+    ///
+    /// Unlike [Self::wait_for_transaction], running out of time is not an
+    /// error. Instead the outcome says whether the transaction can no longer
+    /// land, making it safe to re-broadcast, or whether its fate is unknown.
+    pub async fn wait_for_transaction_with_config(
+        &self,
+        txhash: impl Into<String>,
+        config: WaitForTransactionConfig,
+    ) -> Result<WaitForTransactionOutcome, crate::Error> {
+        let txhash = txhash.into();
+        let cancel = config.cancel.clone().unwrap_or_default();
+        let deadline = async {
+            match config.deadline {
+                Some(deadline) => tokio::time::sleep_until(deadline).await,
+                None => futures_util::future::pending().await,
+            }
+        };
+        let stop = futures_util::future::select(Box::pin(cancel.cancelled()), Box::pin(deadline));
+        let poll = Box::pin(self.poll_for_transaction(&txhash, &config));
+        match futures_util::future::select(poll, stop).await {
+            futures_util::future::Either::Left((res, _)) => res,
+            futures_util::future::Either::Right(_) => {
+                tracing::debug!("Stopped waiting for transaction {txhash}");
+                Ok(WaitForTransactionOutcome::Unknown)
+            }
+        }
+    }
+
+    async fn poll_for_transaction(
+        &self,
+        txhash: &str,
+        config: &WaitForTransactionConfig,
+    ) -> Result<WaitForTransactionOutcome, crate::Error> {
+        let expires_after = config.timeout_height.map(|timeout_height| {
+            i64::try_from(timeout_height.saturating_add(config.expiry_margin_blocks))
+                .unwrap_or(i64::MAX)
+        });
+        let mut seen_pending = false;
+        let mut misses = 0;
+        loop {
+            if let Some((body, response)) = self.try_get_transaction(txhash).await? {
+                return Ok(WaitForTransactionOutcome::Found(body, response));
+            }
+            if let Some(expires_after) = expires_after {
+                let height = self.get_latest_block_info().await?.height;
+                if height > expires_after {
+                    // Check once more in case the transaction was indexed since the last check.
+                    return Ok(match self.try_get_transaction(txhash).await? {
+                        Some((body, response)) => WaitForTransactionOutcome::Found(body, response),
+                        None => WaitForTransactionOutcome::Expired { height },
+                    });
+                }
+            }
+            if config.check_mempool {
+                match self.is_tx_pending(txhash).await? {
+                    Some(true) => {
+                        seen_pending = true;
+                        misses = 0;
+                    }
+                    Some(false) if seen_pending => {
+                        misses += 1;
+                        // A single miss can be a node that hasn't indexed the
+                        // block yet, or a brief mempool recheck. Only give up
+                        // after several consecutive misses, with the
+                        // transaction still not found on chain.
+                        if misses >= MEMPOOL_MISSES_FOR_DROPPED {
+                            return Ok(WaitForTransactionOutcome::Dropped);
+                        }
+                    }
+                    // Absence can't be confirmed, or it was never seen
+                    Some(false) | None => (),
+                }
+            }
+            tracing::debug!("Transaction {txhash} not ready");
+            tokio::time::sleep(config.poll_interval).await;
+        }
+    }
+
+    async fn try_get_transaction(
+        &self,
+        txhash: &str,
+    ) -> Result<Option<(TxBody, TxResponse)>, crate::Error> {
+        let action = Action::WaitForTransaction(txhash.to_owned());
+        match self
+            .perform_query(
+                GetTxRequest {
+                    hash: txhash.to_owned(),
+                },
+                action.clone(),
+                false,
+            )
+            .await
+        {
+            Ok(txres) => Self::txres_to_pair(txres.into_inner(), action).map(Some),
+            Err(QueryError {
+                query: QueryErrorDetails::NotFound(_),
+                ..
+            }) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
     /// Get a list of txhashes for transactions send by the given address.
+    ///
+    /// See [crate::TxSearchBuilder] for more flexible searches.
     pub async fn list_transactions_for(
         &self,
         address: Address,
         limit: Option<u64>,
         offset: Option<u64>,
     ) -> Result<Vec<String>, QueryError> {
-        self.perform_query(
-            GetTxsEventRequest {
-                events: vec![format!("message.sender='{address}'")],
-                pagination: Some(PageRequest {
-                    key: vec![],
-                    offset: offset.unwrap_or_default(),
-                    limit: limit.unwrap_or(10),
-                    count_total: false,
-                    reverse: false,
-                }),
-                order_by: OrderBy::Asc as i32,
-            },
-            Action::ListTransactionsFor(address),
-            true,
+        let page = crate::TxSearchBuilder::new()
+            .sender(address)
+            .limit(limit.unwrap_or(10))
+            .search(self, offset.map(crate::TxSearchToken::from_offset))
+            .await?;
+        Ok(page.txs.into_iter().map(|x| x.txhash).collect())
+    }
+
+    /// Gas to request for a transaction, given the gas used while simulating it.
+    ///
+    /// Pads the estimate by the gas multiplier and applies any
+    /// [CosmosBuilder::message_gas_overrides] for the transaction's messages.
+    pub(crate) fn padded_gas(&self, body: &TxBody, gas_used: u64) -> u64 {
+        // Gas estimation is not perfect, so we need to adjust it by a multiplier to account for drift
+        // Since we're already estimating and padding, the loss of precision from f64 to u64 is negligible
+        let padded = (gas_used as f64 * self.gas_multiplier.get_current()) as u64;
+        self.pool.builder.apply_gas_overrides(
+            body.messages.iter().map(|msg| msg.type_url.as_str()),
+            padded,
         )
-        .await
-        .map(|x| {
-            x.into_inner()
-                .tx_responses
-                .into_iter()
-                .map(|x| x.txhash)
-                .collect()
-        })
     }
 
     /// attempt_number starts at 0
-    fn gas_to_coins(&self, gas: u64, attempt_number: u64) -> u64 {
+    pub(crate) fn gas_to_coins(&self, gas: u64, attempt_number: u64) -> u64 {
         let CurrentGasPrice { low, high, base: _ } =
             self.pool.builder.current_gas_price(self.max_price);
+        self.price_gas(gas, attempt_number, low, high)
+    }
+
+    /// Fee for a transaction, in the transaction's fee denom if set.
+    ///
+    /// attempt_number starts at 0
+    pub(crate) fn gas_to_fee(
+        &self,
+        txbuilder: &TxBuilder,
+        gas: u64,
+        attempt_number: u64,
+    ) -> Result<Coin, crate::Error> {
+        let gas_coin = self.pool.builder.gas_coin();
+        let (denom, amount) = match txbuilder.fee_denom.as_deref() {
+            None => (gas_coin, self.gas_to_coins(gas, attempt_number)),
+            Some(denom) if denom == gas_coin => (gas_coin, self.gas_to_coins(gas, attempt_number)),
+            Some(denom) => {
+                let (_, low, high) = self
+                    .pool
+                    .builder
+                    .alternative_fee_denoms()
+                    .iter()
+                    .find(|(x, _, _)| x == denom)
+                    .ok_or_else(|| crate::Error::UnknownFeeDenom {
+                        denom: denom.to_owned(),
+                    })?;
+                (denom, self.price_gas(gas, attempt_number, *low, *high))
+            }
+        };
+        self.check_max_fee(denom, amount, gas)?;
+        Ok(Coin {
+            denom: denom.to_owned(),
+            amount: amount.to_string(),
+        })
+    }
+
+    /// Fail if the fee is above the configured maximum for its denom.
+    fn check_max_fee(&self, denom: &str, fee: u64, gas: u64) -> Result<(), crate::Error> {
+        let builder = &self.pool.builder;
+        let max_fee = if denom == builder.gas_coin() {
+            builder.max_fee()
+        } else {
+            match (builder.alternative_max_fee(denom), builder.max_fee()) {
+                (Some(max_fee), _) => Some(max_fee),
+                (None, Some(_)) => {
+                    return Err(crate::Error::NoMaxFeeForDenom {
+                        denom: denom.to_owned(),
+                    })
+                }
+                (None, None) => None,
+            }
+        };
+        match max_fee {
+            Some(max_fee) if fee > max_fee => Err(crate::Error::FeeTooHigh {
+                fee,
+                max_fee,
+                denom: denom.to_owned(),
+                gas,
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    /// Step from the low to the high gas price over the configured attempts.
+    fn price_gas(&self, gas: u64, attempt_number: u64, low: f64, high: f64) -> u64 {
         let attempts = self.pool.builder.gas_price_retry_attempts();
 
         let gas_price = if attempt_number >= attempts {
@@ -926,6 +1811,36 @@ impl Cosmos {
         BlockInfo::new(action, res.block_id, res.block, Some(height))
     }
 
+    /// Get all transactions in the given block, decoded, along with their hashes.
+    ///
+    /// This avoids a separate [Self::get_transaction_body] query for each
+    /// hash in [BlockInfo::txhashes]. Each transaction is decoded separately,
+    /// so one transaction which can't be decoded doesn't hide the rest of the
+    /// block.
+    pub async fn get_block_txs(
+        &self,
+        height: i64,
+    ) -> Result<Vec<(String, Result<DecodedTx, TxDecodeError>)>, crate::Error> {
+        let action = Action::GetBlock(height);
+        let res = self
+            .perform_query(GetBlockByHeightRequest { height }, action.clone(), true)
+            .await?
+            .into_inner();
+        let txs = res
+            .block
+            .and_then(|block| block.data)
+            .ok_or_else(|| crate::Error::InvalidChainResponse {
+                message: "get_block_txs: block data is None".to_owned(),
+                action,
+            })?
+            .txs;
+        let hrp = self.get_address_hrp();
+        Ok(txs
+            .iter()
+            .map(|raw| (txhash_for_bytes(raw), DecodedTx::from_bytes(raw, hrp)))
+            .collect())
+    }
+
     /// Same as [Self::get_transaction_with_fallbacks] but for [Self::get_block_info]
     pub async fn get_block_info_with_fallbacks(
         &self,
@@ -940,11 +1855,11 @@ impl Cosmos {
             Ok(res) => BlockInfo::new(action, res.block_id, res.block, Some(height)),
             Err(e) => {
                 for node in self.pool.node_chooser.all_nodes() {
-                    if let Ok(mut node_guard) = self.pool.get_with_node(node).await {
+                    if let Ok(node_guard) = self.pool.get_with_node(node).await {
                         if let Ok(res) = self
                             .perform_query_inner(
                                 GetBlockByHeightRequest { height },
-                                node_guard.get_inner_mut(),
+                                node_guard.get_inner(),
                             )
                             .await
                         {
@@ -959,7 +1874,19 @@ impl Cosmos {
     }
 
     /// Get information on the earliest block available from this node
+    ///
+    /// Uses the earliest height reported by the node's status endpoint when
+    /// available. Older nodes don't provide this, in which case we request
+    /// the first block and parse the lowest available height from the error.
     pub async fn get_earliest_block_info(&self) -> Result<BlockInfo, crate::Error> {
+        if let Some(height) = self.get_earliest_store_height().await {
+            match self.get_block_info(height).await {
+                Ok(info) => return Ok(info),
+                Err(e) => tracing::debug!(
+                    "Unable to load earliest block {height} reported by node status: {e}"
+                ),
+            }
+        }
         match self.get_block_info(1).await {
             Err(crate::Error::Query(QueryError {
                 query:
@@ -973,6 +1900,81 @@ impl Cosmos {
         }
     }
 
+    /// Query the node status service for the earliest height in its store.
+    ///
+    /// Goes through [Self::probe_query], since nodes predating Cosmos SDK
+    /// 0.50 don't implement the service and that shouldn't count against
+    /// their health.
+    async fn get_earliest_store_height(&self) -> Option<i64> {
+        match self
+            .probe_query(node_status::StatusRequest {}, Action::GetEarliestBlock)
+            .await
+        {
+            Ok(res) => res
+                .and_then(|res| i64::try_from(res.earliest_store_height).ok())
+                .filter(|height| *height > 0),
+            Err(e) => {
+                tracing::debug!("Node status query unavailable: {e}");
+                None
+            }
+        }
+    }
+
+    /// Find the first block with a timestamp at or after the given time.
+    ///
+    /// Performs a binary search between the earliest and latest available blocks.
+    pub async fn first_block_after(
+        &self,
+        timestamp: DateTime<Utc>,
+    ) -> Result<BlockInfo, crate::Error> {
+        self.first_block_after_with_earliest(timestamp, None).await
+    }
+
+    /// Same as [Self::first_block_after], but search from the given height instead of the earliest available block.
+    pub async fn first_block_after_with_earliest(
+        &self,
+        timestamp: DateTime<Utc>,
+        earliest: Option<i64>,
+    ) -> Result<BlockInfo, crate::Error> {
+        let earliest = match earliest {
+            None => self.get_earliest_block_info().await?,
+            Some(height) => self.get_block_info(height).await?,
+        };
+        let latest = self.get_latest_block_info().await?;
+        // If the earliest block is already past the timestamp, older blocks
+        // may have been pruned, so we cannot know which block came first.
+        if earliest.timestamp >= timestamp || latest.timestamp < timestamp {
+            return Err(crate::Error::TimestampOutOfRange {
+                timestamp,
+                earliest_height: earliest.height,
+                earliest_timestamp: earliest.timestamp,
+                latest_height: latest.height,
+                latest_timestamp: latest.timestamp,
+            });
+        }
+
+        // Invariant: low is before the timestamp, high is at or after it.
+        let mut low = earliest.height;
+        let mut high = latest;
+        tracing::debug!("Earliest height {low} at {}", earliest.timestamp);
+        tracing::debug!("Latest height {} at {}", high.height, high.timestamp);
+        while low + 1 < high.height {
+            let mid = low + (high.height - low) / 2;
+            let info = self.get_block_info(mid).await?;
+            tracing::debug!(
+                "Block #{} occurred at timestamp {}",
+                info.height,
+                info.timestamp
+            );
+            if info.timestamp < timestamp {
+                low = mid;
+            } else {
+                high = info;
+            }
+        }
+        Ok(high)
+    }
+
     /// Get the latest block available
     pub async fn get_latest_block_info(&self) -> Result<BlockInfo, crate::Error> {
         let action = Action::GetLatestBlock;
@@ -1005,14 +2007,92 @@ impl Cosmos {
         self.pool.builder.current_gas_price(self.max_price).base
     }
 
+    /// Remove all cached query responses, see [CosmosBuilder::set_query_cache].
+    pub fn clear_query_cache(&self) {
+        if let Some(cache) = &self.pool.cache {
+            cache.clear();
+        }
+    }
+
     /// Get a node health report
     pub fn node_health_report(&self) -> NodeHealthReport {
         self.pool.node_chooser.health_report()
     }
+
+    /// Per-node health statistics: consecutive errors, query latency, block lag and quarantine status.
+    ///
+    /// The primary node comes first, followed by fallbacks and archive nodes.
+    pub fn node_health(&self) -> Vec<SingleNodeHealthReport> {
+        self.pool.node_chooser.health_report().nodes
+    }
+}
+
+/// Settings for [Cosmos::wait_for_transaction_with_config].
+#[derive(Clone, Debug)]
+pub struct WaitForTransactionConfig {
+    /// Stop waiting once this instant is reached.
+    pub deadline: Option<Instant>,
+    /// Stop waiting once this token is cancelled.
+    pub cancel: Option<CancellationToken>,
+    /// The timeout height set on the transaction body, if any.
+    ///
+    /// Once the chain passes this height, the transaction can no longer be included.
+    pub timeout_height: Option<u64>,
+    /// Blocks past the timeout height to wait before reporting the transaction as expired.
+    ///
+    /// Gives nodes a chance to finish indexing recent blocks.
+    pub expiry_margin_blocks: u64,
+    /// Delay between checks for the transaction.
+    pub poll_interval: std::time::Duration,
+    /// Watch the RPC node's mempool to detect dropped transactions.
+    ///
+    /// Requires [CosmosBuilder::set_rpc_url]. Defaults to `false`.
+    pub check_mempool: bool,
+}
+
+impl Default for WaitForTransactionConfig {
+    fn default() -> Self {
+        WaitForTransactionConfig {
+            deadline: None,
+            cancel: None,
+            timeout_height: None,
+            expiry_margin_blocks: 2,
+            poll_interval: std::time::Duration::from_secs(2),
+            check_mempool: false,
+        }
+    }
+}
+
+/// Consecutive polls a transaction must be missing from the mempool, after
+/// being seen there, before it's considered dropped.
+const MEMPOOL_MISSES_FOR_DROPPED: u32 = 3;
+
+/// Result of [Cosmos::wait_for_transaction_with_config].
+#[derive(Debug, Clone)]
+#[allow(clippy::large_enum_variant)]
+pub enum WaitForTransactionOutcome {
+    /// The transaction was included in a block.
+    Found(TxBody, TxResponse),
+    /// The chain advanced past the timeout height without including the transaction.
+    ///
+    /// The transaction can never be included, so it is safe to re-broadcast.
+    Expired {
+        /// Latest block height when expiry was detected
+        height: i64,
+    },
+    /// The deadline passed or waiting was cancelled. The transaction may still be included.
+    Unknown,
+    /// The transaction was seen in the mempool but left it without being included.
+    ///
+    /// Only reported with [WaitForTransactionConfig::check_mempool], once the
+    /// transaction has been absent from the mempool and the chain for several
+    /// consecutive polls. The transaction was most likely evicted, so
+    /// re-broadcasting is usually safe.
+    Dropped,
 }
 
 /// Information on a block.
-#[derive(Debug)]
+#[derive(Debug, Clone, serde::Serialize)]
 pub struct BlockInfo {
     /// Block height
     pub height: i64,
@@ -1053,14 +2133,7 @@ impl BlockInfo {
                     ));
                 }
             }
-            let mut txhashes = vec![];
-            for tx in data.txs {
-                use sha2::{Digest, Sha256};
-                let mut hasher = Sha256::new();
-                hasher.update(tx);
-                let digest = hasher.finalize();
-                txhashes.push(hex::encode_upper(digest));
-            }
+            let txhashes = data.txs.iter().map(|tx| txhash_for_bytes(tx)).collect();
             Ok(BlockInfo {
                 height: header.height,
                 block_hash: hex::encode_upper(block_id.hash),
@@ -1151,16 +2224,14 @@ impl TxBuilder {
         let mut attempts = 0;
         loop {
             let simres = self.simulate(cosmos, &[wallet.get_address()]).await?;
+            let gas_to_request = cosmos.padded_gas(&simres.body, simres.gas_used);
             let res = self
-                .inner_sign_and_broadcast_cosmos(
-                    cosmos,
-                    wallet,
-                    simres.body,
-                    // Gas estimation is not perfect, so we need to adjust it by a multiplier to account for drift
-                    // Since we're already estimating and padding, the loss of precision from f64 to u64 is negligible
-                    (simres.gas_used as f64 * cosmos.gas_multiplier.get_current()) as u64,
-                )
-                .await;
+                .inner_sign_and_broadcast_cosmos(cosmos, wallet, simres.body, gas_to_request)
+                .await
+                .map(|mut res| {
+                    res.cost.simulated_gas = Some(simres.gas_used);
+                    res
+                });
             let did_update = cosmos.gas_multiplier.update(&res);
             if !did_update {
                 break res;
@@ -1240,9 +2311,13 @@ impl TxBuilder {
         .await
     }
 
-    fn make_signer_info(&self, sequence: u64, wallet: Option<&Wallet>) -> SignerInfo {
+    pub(crate) fn make_signer_info(
+        &self,
+        sequence: u64,
+        public_key: Option<(&WalletPublicKey, AddressHrp)>,
+    ) -> SignerInfo {
         SignerInfo {
-            public_key: match wallet {
+            public_key: match public_key {
                 // No wallet/base account. We're simulating. Fill in a dummy value.
                 None => Some(cosmos_sdk_proto::Any {
                     type_url: "/cosmos.crypto.secp256k1.PubKey".to_owned(),
@@ -1253,33 +2328,35 @@ impl TxBuilder {
                     }
                     .encode_to_vec(),
                 }),
-                Some(wallet) => {
-                    match wallet.public_key {
+                Some((public_key, hrp)) => {
+                    let (type_url, key) = match public_key {
                         // Use the Cosmos method of public key
-                        WalletPublicKey::Cosmos(public_key) => Some(cosmos_sdk_proto::Any {
-                            type_url: "/cosmos.crypto.secp256k1.PubKey".to_owned(),
-                            value: cosmos_sdk_proto::tendermint::crypto::PublicKey {
-                                sum: Some(
-                                    cosmos_sdk_proto::tendermint::crypto::public_key::Sum::Ed25519(
-                                        public_key.to_vec(),
-                                    ),
-                                ),
-                            }
-                            .encode_to_vec(),
-                        }),
+                        WalletPublicKey::Cosmos(public_key) => {
+                            ("/cosmos.crypto.secp256k1.PubKey", public_key.to_vec())
+                        }
                         // Use the Injective method of public key
-                        WalletPublicKey::Ethereum(public_key) => Some(cosmos_sdk_proto::Any {
-                            type_url: "/injective.crypto.v1beta1.ethsecp256k1.PubKey".to_owned(),
-                            value: cosmos_sdk_proto::tendermint::crypto::PublicKey {
-                                sum: Some(
-                                    cosmos_sdk_proto::tendermint::crypto::public_key::Sum::Ed25519(
-                                        public_key.to_vec(),
-                                    ),
-                                ),
-                            }
-                            .encode_to_vec(),
-                        }),
-                    }
+                        WalletPublicKey::Ethereum(public_key) if hrp.as_str() == "inj" => (
+                            "/injective.crypto.v1beta1.ethsecp256k1.PubKey",
+                            public_key.to_vec(),
+                        ),
+                        // Ethermint chains expect the compressed public key
+                        WalletPublicKey::Ethereum(public_key) => (
+                            "/ethermint.crypto.v1.ethsecp256k1.PubKey",
+                            bitcoin::secp256k1::PublicKey::from_slice(public_key)
+                                .expect("Wallet always holds a valid public key")
+                                .serialize()
+                                .to_vec(),
+                        ),
+                    };
+                    Some(cosmos_sdk_proto::Any {
+                        type_url: type_url.to_owned(),
+                        value: cosmos_sdk_proto::tendermint::crypto::PublicKey {
+                            sum: Some(
+                                cosmos_sdk_proto::tendermint::crypto::public_key::Sum::Ed25519(key),
+                            ),
+                        }
+                        .encode_to_vec(),
+                    })
                 }
             },
             mode_info: Some(ModeInfo {
@@ -1294,7 +2371,7 @@ impl TxBuilder {
     }
 
     /// Make a [TxBody] for this builder
-    fn make_tx_body(&self) -> TxBody {
+    pub(crate) fn make_tx_body(&self) -> TxBody {
         TxBody {
             messages: self.messages.iter().map(|msg| msg.get_protobuf()).collect(),
             memo: self.memo.as_deref().unwrap_or_default().to_owned(),
@@ -1318,8 +2395,8 @@ impl TxBuilder {
                 fee: Some(Fee {
                     amount: vec![],
                     gas_limit: 0,
-                    payer: "".to_owned(),
-                    granter: "".to_owned(),
+                    payer: self.fee_payer_string(),
+                    granter: self.fee_granter_string(),
                 }),
                 signer_infos: sequences
                     .iter()
@@ -1378,7 +2455,7 @@ impl TxBuilder {
         .await
     }
 
-    async fn sign_and_broadcast_with_inner(
+    pub(crate) async fn sign_and_broadcast_with_inner(
         &self,
         cosmos: &Cosmos,
         wallet: &Wallet,
@@ -1387,27 +2464,18 @@ impl TxBuilder {
         body: TxBody,
         gas_to_request: u64,
     ) -> Result<CosmosTxResponse, crate::Error> {
-        // enum AttemptError {
-        //     Inner(Infallible),
-        //     InsufficientGas(Infallible),
-        // }
-        // impl From<anyhow::Error> for AttemptError {
-        //     fn from(e: anyhow::Error) -> Self {
-        //         AttemptError::Inner(e)
-        //     }
-        // }
         let body_ref = &body;
-        let retry_with_price = |amount| async move {
+        let retry_with_price = |fee: Coin, attempt_number: u64| async move {
             let auth_info = AuthInfo {
-                signer_infos: vec![self.make_signer_info(sequence, Some(wallet))],
+                signer_infos: vec![self.make_signer_info(
+                    sequence,
+                    Some((&wallet.public_key, wallet.get_address_hrp())),
+                )],
                 fee: Some(Fee {
-                    amount: vec![Coin {
-                        denom: cosmos.pool.builder.gas_coin().to_owned(),
-                        amount,
-                    }],
+                    amount: vec![fee],
                     gas_limit: gas_to_request,
-                    payer: "".to_owned(),
-                    granter: "".to_owned(),
+                    payer: self.fee_payer_string(),
+                    granter: self.fee_granter_string(),
                 }),
             };
 
@@ -1426,71 +2494,30 @@ impl TxBuilder {
                 signatures: vec![signature.serialize_compact().to_vec()],
             };
 
-            let PerformQueryWrapper { grpc_url, tonic } = cosmos
-                .perform_query(
-                    BroadcastTxRequest {
-                        tx_bytes: tx.encode_to_vec(),
-                        mode: BroadcastMode::Sync as i32,
-                    },
-                    Action::Broadcast(self.clone()),
-                    true,
-                )
+            let res = cosmos
+                .broadcast_tx_and_wait(tx.encode_to_vec(), self)
                 .await?;
-            let res = tonic.into_inner().tx_response.ok_or_else(|| {
-                crate::Error::InvalidChainResponse {
-                    message: "Missing inner tx_response".to_owned(),
-                    action: Action::Broadcast(self.clone()),
-                }
-            })?;
-
-            if !self.skip_code_check && res.code != 0 {
-                return Err(crate::Error::TransactionFailed {
-                    code: res.code.into(),
-                    raw_log: res.raw_log,
-                    action: Action::Broadcast(self.clone()).into(),
-                    grpc_url,
-                    stage: crate::error::TransactionStage::Broadcast,
-                });
-            };
-
-            tracing::debug!("Initial BroadcastTxResponse: {res:?}");
-
-            let (_, res) = cosmos
-                .wait_for_transaction_with_action(res.txhash, Some(Action::Broadcast(self.clone())))
-                .await?;
-            if !self.skip_code_check && res.code != 0 {
-                return Err(crate::Error::TransactionFailed {
-                    code: res.code.into(),
-                    raw_log: res.raw_log,
-                    action: Action::Broadcast(self.clone()).into(),
-                    grpc_url,
-                    stage: crate::error::TransactionStage::Wait,
-                });
-            };
-
-            tracing::debug!("TxResponse: {res:?}");
             cosmos
                 .update_broadcast_sequence(wallet.get_address(), &tx, &res.txhash)
                 .await?;
 
-            Ok(CosmosTxResponse { response: res, tx })
+            let mut cost = TxCostReport::new(&tx, &res);
+            cost.price_attempt = Some(attempt_number);
+            Ok(CosmosTxResponse {
+                response: res,
+                tx,
+                cost,
+            })
         };
 
+        cosmos.get_cosmos_builder().refresh_gas_price(cosmos).await;
         let attempts = cosmos.get_cosmos_builder().gas_price_retry_attempts();
         for attempt_number in 0..attempts {
-            let amount = cosmos
-                .gas_to_coins(gas_to_request, attempt_number)
-                .to_string();
-            match retry_with_price(amount).await {
-                Err(crate::Error::TransactionFailed {
-                    code: CosmosSdkError::InsufficientFee,
-                    raw_log,
-                    action: _,
-                    grpc_url: _,
-                    stage: _,
-                }) => {
+            let fee = cosmos.gas_to_fee(self, gas_to_request, attempt_number)?;
+            match retry_with_price(fee, attempt_number).await {
+                Err(e) if e.retry_reason() == Some(RetryReason::InsufficientFee) => {
                     tracing::debug!(
-                        "Insufficient gas in attempt #{}, retrying. Raw log: {raw_log}",
+                        "Insufficient gas in attempt #{}, retrying: {e}",
                         attempt_number + 1
                     );
                 }
@@ -1498,8 +2525,8 @@ impl TxBuilder {
             }
         }
 
-        let amount = cosmos.gas_to_coins(gas_to_request, attempts).to_string();
-        retry_with_price(amount).await
+        let fee = cosmos.gas_to_fee(self, gas_to_request, attempts)?;
+        retry_with_price(fee, attempts).await
     }
 
     /// Does this transaction have any messages already?
@@ -1540,7 +2567,7 @@ impl Cosmos {
     }
 }
 
-fn get_expected_sequence_inner(message: &str) -> Option<u64> {
+pub(crate) fn get_expected_sequence_inner(message: &str) -> Option<u64> {
     for line in message.lines() {
         if let Some(x) = get_expected_sequence_single(line) {
             return Some(x);
@@ -1607,6 +2634,105 @@ mod tests {
         cosmos.get_latest_block_info().await.unwrap();
     }
 
+    #[tokio::test]
+    async fn hedged_failure_does_not_retry() {
+        let mut builder = CosmosBuilder::new(
+            "osmosis-1",
+            "uosmo",
+            AddressHrp::from_static("osmo"),
+            "http://127.0.0.1:1",
+        );
+        builder.add_grpc_fallback_url("http://127.0.0.1:2");
+        builder.set_allowed_error_count(Some(100));
+        builder.set_query_retries(Some(3));
+        let cosmos = builder.build_lazy().unwrap().with_hedged_queries(2);
+        let err = cosmos.get_latest_block_info().await.unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::Connection);
+        // Each hedged node is queried exactly once.
+        let error_counts = cosmos
+            .node_health()
+            .into_iter()
+            .map(|node| node.error_count)
+            .collect::<Vec<_>>();
+        assert_eq!(error_counts, [1, 1]);
+    }
+
+    #[tokio::test]
+    async fn fee_denom_prices() {
+        let mut builder = CosmosNetwork::KujiraMainnet.builder_local();
+        builder.set_gas_price(0.01, 0.02);
+        builder.set_gas_price_retry_attempts(Some(2));
+        builder.add_alternative_fee_denom("uusk", 0.1, 0.3);
+        let cosmos = builder.build_lazy().unwrap();
+        let fee = |tx: &TxBuilder, attempt| {
+            cosmos
+                .gas_to_fee(tx, 1000, attempt)
+                .map(|coin| (coin.denom, coin.amount))
+        };
+
+        let mut tx = TxBuilder::default();
+        assert_eq!(fee(&tx, 0).unwrap(), ("ukuji".to_owned(), "10".to_owned()));
+        tx.set_fee_denom("uusk");
+        assert_eq!(fee(&tx, 0).unwrap(), ("uusk".to_owned(), "100".to_owned()));
+        assert_eq!(fee(&tx, 1).unwrap(), ("uusk".to_owned(), "200".to_owned()));
+        assert_eq!(fee(&tx, 2).unwrap(), ("uusk".to_owned(), "300".to_owned()));
+        tx.set_fee_denom("uatom");
+        fee(&tx, 0).unwrap_err();
+    }
+
+    #[tokio::test]
+    async fn max_fee() {
+        let mut builder = CosmosNetwork::KujiraMainnet.builder_local();
+        builder.set_gas_price(0.01, 0.02);
+        builder.set_gas_price_retry_attempts(Some(2));
+        builder.set_max_fee(Some(15));
+        let cosmos = builder.build_lazy().unwrap();
+        let tx = TxBuilder::default();
+        cosmos.gas_to_fee(&tx, 1000, 0).unwrap();
+        match cosmos.gas_to_fee(&tx, 1000, 2) {
+            Err(crate::Error::FeeTooHigh { fee, max_fee, .. }) => {
+                assert_eq!((fee, max_fee), (20, 15))
+            }
+            res => panic!("Unexpected result: {res:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn max_fee_alternative_denom() {
+        let mut builder = CosmosNetwork::KujiraMainnet.builder_local();
+        builder.set_gas_price(0.01, 0.02);
+        builder.set_gas_price_retry_attempts(Some(2));
+        builder.add_alternative_fee_denom("uusk", 0.1, 0.3);
+        builder.set_max_fee(Some(15));
+        let mut tx = TxBuilder::default();
+        tx.set_fee_denom("uusk");
+
+        let cosmos = builder.clone().build_lazy().unwrap();
+        match cosmos.gas_to_fee(&tx, 1000, 0) {
+            Err(crate::Error::NoMaxFeeForDenom { denom }) => assert_eq!(denom, "uusk"),
+            res => panic!("Unexpected result: {res:?}"),
+        }
+        builder.use_fee_denom("uusk").unwrap_err();
+
+        builder.set_alternative_max_fee("uusk", Some(250));
+        let cosmos = builder.clone().build_lazy().unwrap();
+        cosmos.gas_to_fee(&tx, 1000, 1).unwrap();
+        match cosmos.gas_to_fee(&tx, 1000, 2) {
+            Err(crate::Error::FeeTooHigh {
+                fee,
+                max_fee,
+                denom,
+                ..
+            }) => {
+                assert_eq!((fee, max_fee, denom.as_str()), (300, 250, "uusk"))
+            }
+            res => panic!("Unexpected result: {res:?}"),
+        }
+
+        builder.use_fee_denom("uusk").unwrap();
+        assert_eq!(builder.max_fee(), Some(250));
+    }
+
     #[test]
     fn get_expected_sequence_good() {
         assert_eq!(
@@ -0,0 +1,433 @@
+//! Raw ABCI queries with optional Merkle proof verification.
+//!
+//! Store queries (paths of the form `/store/<store name>/key`) return ICS23
+//! proofs: one from the key to the store's IAVL root, and one from the store
+//! root to the application hash. The application hash for height `H` is
+//! found in the header of block `H + 1`.
+//!
+//! A verified proof only shows that the response matches the application hash
+//! it was checked against. That hash must itself come from a verified header
+//! for the response to be trustworthy.
+
+use base64::Engine;
+use cosmos_sdk_proto::cosmos::base::tendermint::v1beta1::GetBlockByHeightRequest;
+use ics23::{commitment_proof::Proof, CommitmentProof, HostFunctionsManager};
+use prost::Message;
+use serde::Deserialize;
+
+use crate::{error::Action, Cosmos};
+
+/// Response from [Cosmos::abci_query].
+#[derive(Clone, Debug)]
+pub struct AbciQueryResponse {
+    /// Result code, 0 for success
+    pub code: u32,
+    /// Module namespace for a non-zero code
+    pub codespace: String,
+    /// Log output, contains the error message for failed queries
+    pub log: String,
+    /// Key of the returned value, for store queries
+    pub key: Vec<u8>,
+    /// Returned value, empty if a store key does not exist
+    pub value: Vec<u8>,
+    /// Height the query was performed at
+    pub height: i64,
+    /// Was the response verified against the application hash?
+    pub verified: bool,
+}
+
+#[derive(Deserialize)]
+struct RawAbciQuery {
+    response: RawAbciResponse,
+}
+
+#[derive(Deserialize)]
+struct RawAbciResponse {
+    #[serde(default)]
+    code: u32,
+    #[serde(default)]
+    codespace: String,
+    #[serde(default)]
+    log: String,
+    key: Option<String>,
+    value: Option<String>,
+    #[serde(rename = "proofOps")]
+    proof_ops: Option<RawProofOps>,
+    height: String,
+}
+
+#[derive(Deserialize)]
+struct RawProofOps {
+    ops: Vec<RawProofOp>,
+}
+
+#[derive(Deserialize)]
+pub(super) struct RawProofOp {
+    #[serde(rename = "type")]
+    kind: String,
+    key: Option<String>,
+    data: Option<String>,
+}
+
+fn decode_base64(s: Option<String>) -> Result<Vec<u8>, String> {
+    match s {
+        None => Ok(vec![]),
+        Some(s) => base64::engine::general_purpose::STANDARD
+            .decode(&s)
+            .map_err(|e| format!("Invalid base64 value {s:?}: {e}")),
+    }
+}
+
+/// Get the store name from a store query path such as `/store/wasm/key`.
+fn store_name(path: &str) -> Option<&str> {
+    let mut parts = path.strip_prefix("/store/")?.split('/');
+    let name = parts.next()?;
+    (parts.next() == Some("key") && parts.next().is_none()).then_some(name)
+}
+
+/// Root hash implied by a proof, used to chain the store proof into the multistore proof.
+fn proof_root(proof: &CommitmentProof) -> Result<Vec<u8>, String> {
+    let exist = match &proof.proof {
+        Some(Proof::Exist(exist)) => Some(exist),
+        Some(Proof::Nonexist(nonexist)) => nonexist.left.as_ref().or(nonexist.right.as_ref()),
+        _ => None,
+    }
+    .ok_or("Unsupported commitment proof type")?;
+    ics23::calculate_existence_root::<HostFunctionsManager>(exist)
+        .map_err(|e| format!("Unable to calculate root from proof: {e}"))
+}
+
+/// Verify a store query response against the given application hash.
+///
+/// The caller is responsible for `app_hash` coming from a verified header.
+fn verify_store_proof(
+    path: &str,
+    key: &[u8],
+    value: &[u8],
+    ops: &[RawProofOp],
+    app_hash: &[u8],
+) -> Result<(), String> {
+    let store = store_name(path).ok_or_else(|| {
+        format!("Proofs can only be verified for /store/<name>/key queries, not {path}")
+    })?;
+    let (iavl_op, multistore_op) = match ops {
+        [iavl_op, multistore_op] => (iavl_op, multistore_op),
+        _ => return Err(format!("Expected 2 proof ops, received {}", ops.len())),
+    };
+    if iavl_op.kind != "ics23:iavl" || multistore_op.kind != "ics23:simple" {
+        return Err(format!(
+            "Unexpected proof op types {} and {}",
+            iavl_op.kind, multistore_op.kind
+        ));
+    }
+    if decode_base64(multistore_op.key.clone())? != store.as_bytes() {
+        return Err(format!("Multistore proof is not for store {store}"));
+    }
+    let decode_proof = |op: &RawProofOp| {
+        CommitmentProof::decode(decode_base64(op.data.clone())?.as_slice())
+            .map_err(|e| format!("Invalid {} proof: {e}", op.kind))
+    };
+    let iavl_proof = decode_proof(iavl_op)?;
+    let multistore_proof = decode_proof(multistore_op)?;
+
+    let store_root = proof_root(&iavl_proof)?;
+    let store_verified = if value.is_empty() {
+        ics23::verify_non_membership::<HostFunctionsManager>(
+            &iavl_proof,
+            &ics23::iavl_spec(),
+            &store_root,
+            key,
+        )
+    } else {
+        ics23::verify_membership::<HostFunctionsManager>(
+            &iavl_proof,
+            &ics23::iavl_spec(),
+            &store_root,
+            key,
+            value,
+        )
+    };
+    if !store_verified {
+        return Err(format!(
+            "Invalid proof for key {} in store {store}",
+            hex::encode(key)
+        ));
+    }
+    if !ics23::verify_membership::<HostFunctionsManager>(
+        &multistore_proof,
+        &ics23::tendermint_spec(),
+        &app_hash.to_vec(),
+        store.as_bytes(),
+        &store_root,
+    ) {
+        return Err(format!(
+            "Store {store} root does not match application hash {}",
+            hex::encode_upper(app_hash)
+        ));
+    }
+    Ok(())
+}
+
+impl AbciQueryResponse {
+    /// Verify the response against the given application hash.
+    ///
+    /// `app_hash` must come from a verified header, not from the node being queried.
+    pub(super) fn verify(
+        &mut self,
+        path: &str,
+        data: &[u8],
+        ops: &[RawProofOp],
+        app_hash: &[u8],
+    ) -> Result<(), crate::Error> {
+        verify_store_proof(path, data, &self.value, ops, app_hash).map_err(|message| {
+            crate::Error::ProofVerification {
+                message,
+                action: Action::AbciQuery(path.to_owned()),
+            }
+        })?;
+        self.verified = true;
+        Ok(())
+    }
+}
+
+impl Cosmos {
+    /// Perform a raw ABCI query over Tendermint RPC.
+    ///
+    /// With `prove` set, the response is verified against the application
+    /// hash from the next block's header, returning an error if verification
+    /// fails. Only store queries such as `/store/wasm/key` can be proven.
+    /// Without a height, the query is performed one block behind the latest
+    /// block, so that the next header is available.
+    ///
+    /// The header is fetched from a gRPC node and is not itself verified, so
+    /// a proof only shows that the RPC and gRPC nodes agree. For a response
+    /// verified against a light client header, use `LightClient::abci_query`
+    /// from the `light-client` feature instead.
+    ///
+    /// Requires an RPC endpoint, see [crate::CosmosBuilder::set_rpc_url].
+    pub async fn abci_query(
+        &self,
+        path: &str,
+        data: impl AsRef<[u8]>,
+        height: Option<u64>,
+        prove: bool,
+    ) -> Result<AbciQueryResponse, crate::Error> {
+        let data = data.as_ref();
+        let height = match height.or(self.height) {
+            Some(height) => height,
+            None if prove => self
+                .get_latest_block_info()
+                .await?
+                .height
+                .saturating_sub(1)
+                .try_into()
+                .unwrap_or_default(),
+            None => 0,
+        };
+        let (mut res, ops) = self.abci_query_raw(path, data, height, prove).await?;
+        if prove && res.code == 0 {
+            let action = Action::GetBlock(res.height + 1);
+            let app_hash = self
+                .perform_query(
+                    GetBlockByHeightRequest {
+                        height: res.height + 1,
+                    },
+                    action.clone(),
+                    true,
+                )
+                .await?
+                .into_inner()
+                .block
+                .and_then(|block| block.header)
+                .ok_or_else(|| crate::Error::InvalidChainResponse {
+                    message: "abci_query: block header is None".to_owned(),
+                    action,
+                })?
+                .app_hash;
+            res.verify(path, data, &ops, &app_hash)?;
+        }
+        Ok(res)
+    }
+
+    /// Perform an ABCI query without verifying any returned proofs.
+    ///
+    /// A height of 0 queries the latest state.
+    pub(super) async fn abci_query_raw(
+        &self,
+        path: &str,
+        data: &[u8],
+        height: u64,
+        prove: bool,
+    ) -> Result<(AbciQueryResponse, Vec<RawProofOp>), crate::Error> {
+        let action = Action::AbciQuery(path.to_owned());
+        let mut params = vec![
+            ("path", format!("{path:?}")),
+            ("data", format!("0x{}", hex::encode(data))),
+            ("prove", prove.to_string()),
+        ];
+        if height > 0 {
+            params.push(("height", height.to_string()));
+        }
+        let RawAbciQuery { response } =
+            self.rpc_call("abci_query", &params, action.clone()).await?;
+        let invalid = |message| crate::Error::InvalidChainResponse {
+            message,
+            action: action.clone(),
+        };
+        let key = decode_base64(response.key).map_err(invalid)?;
+        let value = decode_base64(response.value).map_err(invalid)?;
+        let height = response
+            .height
+            .parse::<i64>()
+            .map_err(|e| invalid(format!("Invalid height value {:?}: {e}", response.height)))?;
+        let ops = response
+            .proof_ops
+            .map(|proof_ops| proof_ops.ops)
+            .unwrap_or_default();
+        Ok((
+            AbciQueryResponse {
+                code: response.code,
+                codespace: response.codespace,
+                log: response.log,
+                key,
+                value,
+                height,
+                verified: false,
+            },
+            ops,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ics23::{ExistenceProof, HashOp, InnerOp, LeafOp, LengthOp};
+    use sha2::{Digest, Sha256};
+
+    use super::*;
+
+    #[test]
+    fn store_names() {
+        assert_eq!(store_name("/store/wasm/key"), Some("wasm"));
+        assert_eq!(store_name("/store/bank/subspace"), None);
+        assert_eq!(store_name("/store/wasm/key/extra"), None);
+        assert_eq!(store_name("/cosmos.bank.v1beta1.Query/AllBalances"), None);
+    }
+
+    #[test]
+    fn rejects_missing_proofs() {
+        let err = verify_store_proof("/store/wasm/key", b"key", b"value", &[], &[0; 32]);
+        assert_eq!(err.unwrap_err(), "Expected 2 proof ops, received 0");
+    }
+
+    const KEY: &[u8] = b"\x03config";
+    const VALUE: &[u8] = br#"{"owner":"osmo1"}"#;
+    /// Hash of the IAVL leaf for `\x03state` = `{"count":1}` at version 1.
+    const SIBLING_LEAF: &str = "fe3174443101cdbaf0672f3c87ed76add89aed50f5cd63bff17cf16d3436b9ae";
+    /// Multistore leaf for a `bank` store with root `sha256("bank root")`.
+    const BANK_LEAF: &str = "93866f8705c5796ab03196a0d291c9445af97913f36689b077ed1830c4aae99c";
+    /// Application hash of a multistore containing `bank` and `wasm`.
+    const APP_HASH: &str = "ba9f1884d106af1c289552fb2c5b31f14824e87cf68f0f426515fa2d9c999191";
+
+    fn leaf_op(prefix: Vec<u8>) -> LeafOp {
+        LeafOp {
+            hash: HashOp::Sha256.into(),
+            prehash_key: HashOp::NoHash.into(),
+            prehash_value: HashOp::Sha256.into(),
+            length: LengthOp::VarProto.into(),
+            prefix,
+        }
+    }
+
+    fn proof_op(kind: &str, key: &[u8], exist: ExistenceProof) -> RawProofOp {
+        let proof = CommitmentProof {
+            proof: Some(Proof::Exist(exist)),
+        };
+        let encode = |bytes: &[u8]| base64::engine::general_purpose::STANDARD.encode(bytes);
+        RawProofOp {
+            kind: kind.to_owned(),
+            key: Some(encode(key)),
+            data: Some(encode(&proof.encode_to_vec())),
+        }
+    }
+
+    /// Proof ops for [KEY] as the left leaf of a two leaf IAVL tree, stored
+    /// as the right leaf of a two store multistore.
+    fn wasm_proof_ops(value: &[u8]) -> Vec<RawProofOp> {
+        let mut iavl_suffix = vec![32];
+        iavl_suffix.extend(hex::decode(SIBLING_LEAF).unwrap());
+        let iavl = ExistenceProof {
+            key: KEY.to_vec(),
+            value: value.to_vec(),
+            // height 0, size 1, version 1
+            leaf: Some(leaf_op(vec![0, 2, 2])),
+            path: vec![InnerOp {
+                hash: HashOp::Sha256.into(),
+                // height 1, size 2, version 1, left child length
+                prefix: vec![2, 4, 2, 32],
+                suffix: iavl_suffix,
+            }],
+        };
+        let store_root = ics23::calculate_existence_root::<HostFunctionsManager>(&iavl).unwrap();
+
+        let mut multistore_prefix = vec![1];
+        multistore_prefix.extend(hex::decode(BANK_LEAF).unwrap());
+        let multistore = ExistenceProof {
+            key: b"wasm".to_vec(),
+            value: store_root,
+            leaf: Some(leaf_op(vec![0])),
+            path: vec![InnerOp {
+                hash: HashOp::Sha256.into(),
+                prefix: multistore_prefix,
+                suffix: vec![],
+            }],
+        };
+        vec![
+            proof_op("ics23:iavl", KEY, iavl),
+            proof_op("ics23:simple", b"wasm", multistore),
+        ]
+    }
+
+    #[test]
+    fn verifies_store_proof() {
+        let app_hash = hex::decode(APP_HASH).unwrap();
+        let ops = wasm_proof_ops(VALUE);
+        verify_store_proof("/store/wasm/key", KEY, VALUE, &ops, &app_hash).unwrap();
+
+        // Intermediate hashes match an independent computation of the trees.
+        let store_root = proof_root(
+            &CommitmentProof::decode(decode_base64(ops[0].data.clone()).unwrap().as_slice())
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            hex::encode(store_root),
+            "21a3a5f238243c920bda7f9d889437912ab716d23aa367ad2d41191d66edb270"
+        );
+        assert_eq!(
+            hex::encode(Sha256::digest(b"bank root")),
+            "148f7290e6684e8ec1bc383f77bce8e2326a710a396c75185447a74bb17b3def"
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_store_proof() {
+        let app_hash = hex::decode(APP_HASH).unwrap();
+        let ops = wasm_proof_ops(VALUE);
+
+        // Value does not match the proof
+        verify_store_proof("/store/wasm/key", KEY, b"{}", &ops, &app_hash).unwrap_err();
+        // Key does not match the proof
+        verify_store_proof("/store/wasm/key", b"\x03state", VALUE, &ops, &app_hash).unwrap_err();
+        // Proof for a different value, consistent within itself but not with the app hash
+        let forged = wasm_proof_ops(b"{}");
+        let err = verify_store_proof("/store/wasm/key", KEY, b"{}", &forged, &app_hash);
+        assert!(err
+            .unwrap_err()
+            .starts_with("Store wasm root does not match"));
+        // Wrong store
+        verify_store_proof("/store/bank/key", KEY, VALUE, &ops, &app_hash).unwrap_err();
+        // Wrong application hash
+        verify_store_proof("/store/wasm/key", KEY, VALUE, &ops, &[0; 32]).unwrap_err();
+    }
+}
@@ -0,0 +1,173 @@
+use std::{collections::HashMap, sync::Arc, time::Duration};
+
+use parking_lot::Mutex;
+use prost::Message;
+use tokio::time::Instant;
+
+use crate::QueryCacheConfig;
+
+use super::query::GrpcRequest;
+
+/// How long a cached response remains valid.
+pub(crate) enum CacheLifetime {
+    /// The response can never change, e.g. a block at a given height.
+    Forever,
+    /// The response may change, so it expires after the given duration.
+    Ttl(Duration),
+}
+
+#[derive(Clone, PartialEq, Eq, Hash)]
+struct CacheKey {
+    /// Type name of the request
+    request: &'static str,
+    /// Protobuf encoded request
+    body: Vec<u8>,
+    height: Option<u64>,
+}
+
+struct CacheEntry {
+    response: Vec<u8>,
+    grpc_url: Arc<String>,
+    inserted: Instant,
+    expires: Option<Instant>,
+}
+
+/// Cache of protobuf encoded query responses, shared by all clones of a [crate::Cosmos].
+pub(crate) struct QueryCache {
+    config: QueryCacheConfig,
+    entries: Mutex<HashMap<CacheKey, CacheEntry>>,
+}
+
+impl QueryCache {
+    pub(super) fn new(config: QueryCacheConfig) -> Self {
+        QueryCache {
+            config,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn key<Request: GrpcRequest>(req: &Request, height: Option<u64>) -> CacheKey {
+        CacheKey {
+            request: Request::METHOD,
+            body: req.encode_to_vec(),
+            height,
+        }
+    }
+
+    /// Look up a cached response, if the request is cacheable.
+    pub(super) fn get<Request: GrpcRequest>(
+        &self,
+        req: &Request,
+        height: Option<u64>,
+    ) -> Option<(Arc<String>, Request::Response)> {
+        Request::cache_lifetime(&self.config)?;
+        let key = Self::key(req, height);
+        let mut entries = self.entries.lock();
+        let entry = entries.get(&key)?;
+        if entry
+            .expires
+            .map_or(false, |expires| expires <= Instant::now())
+        {
+            entries.remove(&key);
+            return None;
+        }
+        // Only fails if the cache contains garbage, treat it as a miss.
+        let response = Request::Response::decode(&*entry.response).ok()?;
+        Some((entry.grpc_url.clone(), response))
+    }
+
+    /// Store a response, if the request is cacheable.
+    pub(super) fn insert<Request: GrpcRequest>(
+        &self,
+        req: &Request,
+        height: Option<u64>,
+        grpc_url: &Arc<String>,
+        response: &Request::Response,
+    ) {
+        let now = Instant::now();
+        let expires = match Request::cache_lifetime(&self.config) {
+            None => return,
+            Some(CacheLifetime::Forever) => None,
+            Some(CacheLifetime::Ttl(ttl)) => Some(now + ttl),
+        };
+        let mut entries = self.entries.lock();
+        if entries.len() >= self.config.max_entries {
+            entries.retain(|_, entry| entry.expires.map_or(true, |expires| expires > now));
+        }
+        if entries.len() >= self.config.max_entries {
+            let oldest = entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.inserted)
+                .map(|(key, _)| key.clone());
+            if let Some(oldest) = oldest {
+                entries.remove(&oldest);
+            }
+        }
+        if self.config.max_entries > 0 {
+            entries.insert(
+                Self::key(req, height),
+                CacheEntry {
+                    response: response.encode_to_vec(),
+                    grpc_url: grpc_url.clone(),
+                    inserted: now,
+                    expires,
+                },
+            );
+        }
+    }
+
+    pub(super) fn clear(&self) {
+        self.entries.lock().clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cosmos_sdk_proto::cosmos::{
+        auth::v1beta1::{QueryAccountRequest, QueryAccountResponse},
+        bank::v1beta1::{QueryAllBalancesRequest, QueryAllBalancesResponse},
+        base::tendermint::v1beta1::{GetBlockByHeightRequest, GetBlockByHeightResponse},
+    };
+
+    use super::*;
+
+    #[test]
+    fn cache_lifetimes() {
+        let cache = QueryCache::new(QueryCacheConfig {
+            account_ttl: Some(Duration::ZERO),
+            ..QueryCacheConfig::default()
+        });
+        let grpc_url = Arc::new("http://localhost:9090".to_owned());
+
+        let block = GetBlockByHeightRequest { height: 5 };
+        cache.insert(
+            &block,
+            None,
+            &grpc_url,
+            &GetBlockByHeightResponse::default(),
+        );
+        assert!(cache.get(&block, None).is_some());
+        assert!(cache.get(&block, Some(5)).is_none());
+
+        let account = QueryAccountRequest {
+            address: "osmo1".to_owned(),
+        };
+        cache.insert(&account, None, &grpc_url, &QueryAccountResponse::default());
+        // Expires immediately
+        assert!(cache.get(&account, None).is_none());
+        assert!(cache.get(&block, None).is_some());
+
+        // Balances are never cached
+        let balances = QueryAllBalancesRequest {
+            address: "osmo1".to_owned(),
+            pagination: None,
+        };
+        cache.insert(
+            &balances,
+            None,
+            &grpc_url,
+            &QueryAllBalancesResponse::default(),
+        );
+        assert!(cache.get(&balances, None).is_none());
+    }
+}
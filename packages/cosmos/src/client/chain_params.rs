@@ -0,0 +1,377 @@
+//! Verify chain parameters reported by the node against the builder's configuration.
+
+use cosmos_sdk_proto::cosmos::{
+    auth::v1beta1::QueryAccountsRequest, base::query::v1beta1::PageRequest,
+};
+use prost_types::Any;
+
+use crate::{
+    error::{Action, BuilderError, QueryError},
+    Account, Address, Cosmos,
+};
+
+use super::{
+    fee_market::{GasPricesRequest, QueryEipBaseFeeRequest},
+    node_status::ConfigRequest,
+};
+
+/// Where a [MinGasPrice] was loaded from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MinGasPriceSource {
+    /// The node's `minimum-gas-prices` configuration
+    Node,
+    /// Current prices from the `feemarket` module
+    FeeMarket,
+    /// Osmosis's EIP-1559 base fee
+    OsmosisEipBaseFee,
+}
+
+/// Lowest gas price accepted for a denom, see [Cosmos::min_gas_prices].
+#[derive(Clone, Debug, PartialEq)]
+pub struct MinGasPrice {
+    /// Fee denom
+    pub denom: String,
+    /// Minimum price per unit of gas
+    pub price: f64,
+    /// Where the price was loaded from
+    pub source: MinGasPriceSource,
+}
+
+/// Find the address of an account of any type supported by [Account].
+fn account_address(account: &Any) -> Option<Address> {
+    Account::decode(account)
+        .ok()?
+        .base_account()?
+        .address
+        .parse()
+        .ok()
+}
+
+/// Parse a decimal from a gRPC response.
+///
+/// Decimal fields are sent as integers scaled by 10^18, unless they are
+/// plain string fields.
+pub(crate) fn parse_dec(s: &str) -> Option<f64> {
+    if s.contains('.') {
+        s.parse().ok()
+    } else {
+        s.parse::<f64>().ok().map(|x| x / 1e18)
+    }
+}
+
+/// Add a price, keeping the highest price per denom.
+fn add_min_gas_price(prices: &mut Vec<MinGasPrice>, new: MinGasPrice) {
+    match prices.iter_mut().find(|price| price.denom == new.denom) {
+        Some(existing) => {
+            if new.price > existing.price {
+                *existing = new;
+            }
+        }
+        None => prices.push(new),
+    }
+}
+
+/// Parse a node's minimum gas prices, e.g. `0.0025uosmo,0.1ibc/27394FB0`.
+fn parse_min_gas_prices(s: &str) -> Vec<(String, f64)> {
+    s.split(',')
+        .filter_map(|coin| {
+            let coin = coin.trim();
+            let split = coin.find(|c: char| !c.is_ascii_digit() && c != '.')?;
+            let (amount, denom) = coin.split_at(split);
+            Some((denom.to_owned(), amount.parse().ok()?))
+        })
+        .collect()
+}
+
+impl Cosmos {
+    /// Minimum gas prices the chain and node will currently accept, by denom.
+    ///
+    /// Combines the node's `minimum-gas-prices` configuration with the
+    /// `feemarket` module's current prices and Osmosis's EIP-1559 base fee,
+    /// where available, keeping the highest price for each denom. Sources
+    /// not supported by the chain are skipped, and an error is returned if
+    /// every remaining source fails. A source the node reports as
+    /// unimplemented or not found is only queried once per connection, and
+    /// doesn't mark the node as unhealthy. Useful for checking the configured
+    /// gas prices before spending broadcast attempts on them.
+    pub async fn min_gas_prices(&self) -> Result<Vec<MinGasPrice>, crate::Error> {
+        let mut prices = vec![];
+        let mut any_loaded = false;
+        let mut first_error = None;
+
+        match self
+            .probe_query(GasPricesRequest {}, Action::QueryFeeMarket)
+            .await
+        {
+            Ok(Some(res)) => {
+                any_loaded = true;
+                for coin in res.prices {
+                    if let Some(price) = parse_dec(&coin.amount) {
+                        add_min_gas_price(
+                            &mut prices,
+                            MinGasPrice {
+                                denom: coin.denom,
+                                price,
+                                source: MinGasPriceSource::FeeMarket,
+                            },
+                        );
+                    }
+                }
+            }
+            Ok(None) => (),
+            Err(e) => {
+                tracing::debug!("Fee market gas prices unavailable: {e}");
+                first_error.get_or_insert(e);
+            }
+        }
+
+        match self
+            .probe_query(QueryEipBaseFeeRequest {}, Action::QueryFeeMarket)
+            .await
+        {
+            Ok(Some(res)) => {
+                any_loaded = true;
+                if let Some(price) = parse_dec(&res.base_fee) {
+                    add_min_gas_price(
+                        &mut prices,
+                        MinGasPrice {
+                            denom: self.get_cosmos_builder().gas_coin().to_owned(),
+                            price,
+                            source: MinGasPriceSource::OsmosisEipBaseFee,
+                        },
+                    );
+                }
+            }
+            Ok(None) => (),
+            Err(e) => {
+                tracing::debug!("Osmosis EIP base fee unavailable: {e}");
+                first_error.get_or_insert(e);
+            }
+        }
+
+        match self.node_min_gas_prices().await {
+            Ok(Some(node_prices)) => {
+                any_loaded = true;
+                for (denom, price) in node_prices {
+                    add_min_gas_price(
+                        &mut prices,
+                        MinGasPrice {
+                            denom,
+                            price,
+                            source: MinGasPriceSource::Node,
+                        },
+                    );
+                }
+            }
+            Ok(None) => (),
+            Err(e) => {
+                tracing::debug!("Node minimum gas prices unavailable: {e}");
+                first_error.get_or_insert(e);
+            }
+        }
+
+        match first_error {
+            Some(e) if !any_loaded => Err(e.into()),
+            _ => Ok(prices),
+        }
+    }
+
+    /// Minimum gas prices configured on the node, by denom.
+    ///
+    /// Returns [None] if the node doesn't implement the node config service,
+    /// which older SDK versions lack.
+    pub(crate) async fn node_min_gas_prices(
+        &self,
+    ) -> Result<Option<Vec<(String, f64)>>, QueryError> {
+        Ok(self
+            .probe_query(ConfigRequest {}, Action::QueryMinimumGasPrices)
+            .await?
+            .map(|config| parse_min_gas_prices(&config.minimum_gas_price)))
+    }
+
+    /// Check the bech32 prefix and minimum gas prices reported by the node.
+    ///
+    /// Nodes which don't support the necessary queries are skipped, since
+    /// older SDK versions don't implement the node config service.
+    pub(super) async fn verify_chain_params(&self) -> Result<(), BuilderError> {
+        let builder = self.get_cosmos_builder();
+        let grpc_url = builder.grpc_url().to_owned();
+
+        let accounts = self
+            .perform_query(
+                QueryAccountsRequest {
+                    pagination: Some(PageRequest {
+                        key: vec![],
+                        offset: 0,
+                        limit: 1,
+                        count_total: false,
+                        reverse: false,
+                    }),
+                },
+                Action::SanityCheck,
+                false,
+            )
+            .await
+            .map_err(|source| BuilderError::SanityQueryFailed { source })?
+            .into_inner()
+            .accounts;
+        if let Some(address) = accounts.first().and_then(account_address) {
+            if address.hrp() != builder.hrp() {
+                return Err(BuilderError::MismatchedBech32Prefix {
+                    grpc_url,
+                    expected: builder.hrp().as_str().to_owned(),
+                    actual: address.hrp().as_str().to_owned(),
+                });
+            }
+        }
+
+        let min_gas_prices = match self.node_min_gas_prices().await {
+            Ok(Some(min_gas_prices)) => min_gas_prices,
+            Ok(None) => {
+                tracing::debug!("Node config service not implemented on {grpc_url}");
+                return Ok(());
+            }
+            Err(e) => {
+                tracing::debug!("Node config query unavailable on {grpc_url}: {e}");
+                return Ok(());
+            }
+        };
+        if min_gas_prices.is_empty() {
+            return Ok(());
+        }
+        let gas_coin = builder.gas_coin();
+        let Some(&(_, minimum)) = min_gas_prices.iter().find(|(denom, _)| denom == gas_coin)
+        else {
+            return Err(BuilderError::GasCoinNotAccepted {
+                grpc_url,
+                gas_coin: gas_coin.to_owned(),
+                minimum_gas_price: min_gas_prices
+                    .iter()
+                    .map(|(denom, price)| format!("{price}{denom}"))
+                    .collect::<Vec<_>>()
+                    .join(","),
+            });
+        };
+        let price = builder.current_gas_price(self.max_price);
+        if price.high < minimum {
+            return Err(BuilderError::GasPriceBelowMinimum {
+                grpc_url,
+                gas_coin: gas_coin.to_owned(),
+                configured: price.high,
+                minimum,
+            });
+        }
+        if price.low < minimum {
+            tracing::warn!(
+                "Low gas price {} for {gas_coin} is below the minimum {minimum} of {grpc_url}",
+                price.low
+            );
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cosmos_sdk_proto::cosmos::auth::v1beta1::{BaseAccount, ModuleAccount};
+    use prost::Message;
+
+    use super::*;
+
+    #[test]
+    fn min_gas_prices() {
+        assert_eq!(
+            parse_min_gas_prices("0.002500000000000000uosmo,0.1ibc/27394FB0"),
+            vec![
+                ("uosmo".to_owned(), 0.0025),
+                ("ibc/27394FB0".to_owned(), 0.1)
+            ]
+        );
+        assert_eq!(parse_min_gas_prices(""), vec![]);
+    }
+
+    #[tokio::test]
+    async fn min_gas_prices_all_sources_failed() {
+        let make_cosmos = |name: &str, interactions: &str| {
+            let path = std::env::temp_dir()
+                .join(format!("cosmos-min-gas-{name}-{}.json", std::process::id()));
+            std::fs::write(&path, format!(r#"{{"interactions":[{interactions}]}}"#)).unwrap();
+            let mut builder = crate::CosmosBuilder::new(
+                "osmosis-1",
+                "uosmo",
+                crate::AddressHrp::from_static("osmo"),
+                "http://127.0.0.1:1",
+            );
+            builder.set_fixture(Some(crate::FixtureMode::Replay(path.clone())));
+            let cosmos = builder.build_lazy().unwrap();
+            std::fs::remove_file(&path).unwrap();
+            cosmos
+        };
+        let unimplemented = |method: &str| {
+            format!(
+                r#"{{"method":"{method}","height":null,"body":"","error":{{"code":12,"message":"unknown service"}}}}"#
+            )
+        };
+
+        // The node config service is missing and the other sources fail
+        let cosmos = make_cosmos(
+            "failed",
+            &unimplemented("/cosmos.base.node.v1beta1.Service/Config"),
+        );
+        cosmos.min_gas_prices().await.unwrap_err();
+
+        // Sources the chain doesn't support are skipped
+        let cosmos = make_cosmos(
+            "unsupported",
+            &[
+                "/cosmos.base.node.v1beta1.Service/Config",
+                "/osmosis.txfees.v1beta1.Query/GetEipBaseFee",
+                "/feemarket.feemarket.v1.Query/GasPrices",
+            ]
+            .map(unimplemented)
+            .join(","),
+        );
+        assert_eq!(cosmos.min_gas_prices().await.unwrap(), vec![]);
+    }
+
+    #[test]
+    fn dec_values() {
+        assert_eq!(parse_dec("0.0025"), Some(0.0025));
+        assert_eq!(parse_dec("2500000000000000"), Some(0.0025));
+        assert_eq!(parse_dec("abc"), None);
+    }
+
+    #[test]
+    fn highest_price_wins() {
+        let mut prices = vec![];
+        let price = |price, source| MinGasPrice {
+            denom: "untrn".to_owned(),
+            price,
+            source,
+        };
+        add_min_gas_price(&mut prices, price(0.01, MinGasPriceSource::FeeMarket));
+        add_min_gas_price(&mut prices, price(0.005, MinGasPriceSource::Node));
+        assert_eq!(prices, vec![price(0.01, MinGasPriceSource::FeeMarket)]);
+    }
+
+    #[test]
+    fn embedded_account_address() {
+        let address = "osmo168gdk6r58jdwfv49kuesq2rs747jawnn4ryvyk";
+        let base = BaseAccount {
+            address: address.to_owned(),
+            pub_key: None,
+            account_number: 5,
+            sequence: 2,
+        };
+        let module = ModuleAccount {
+            base_account: Some(base),
+            name: "distribution".to_owned(),
+            permissions: vec![],
+        };
+        let any = Any {
+            type_url: "/cosmos.auth.v1beta1.ModuleAccount".to_owned(),
+            value: module.encode_to_vec(),
+        };
+        assert_eq!(account_address(&any).unwrap().to_string(), address);
+    }
+}
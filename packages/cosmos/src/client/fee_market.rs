@@ -0,0 +1,79 @@
+//! Fee market queries: Osmosis's `txfees` EIP base fee and the `feemarket`
+//! module used by chains such as Neutron.
+//!
+//! Not included in our version of the protobuf definitions.
+
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct QueryEipBaseFeeRequest {}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct QueryEipBaseFeeResponse {
+    /// current base fee in the chain's base denom
+    #[prost(string, tag = "1")]
+    pub base_fee: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GasPricesRequest {}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GasPricesResponse {
+    /// current gas prices for all accepted denoms
+    #[prost(message, repeated, tag = "1")]
+    pub prices: ::prost::alloc::vec::Vec<cosmos_sdk_proto::cosmos::base::v1beta1::DecCoin>,
+}
+
+/// Client for the fee market query services.
+pub(crate) mod query_client {
+    #![allow(unused_variables, dead_code, missing_docs, clippy::let_unit_value)]
+    use tonic::codegen::*;
+    #[derive(Debug, Clone)]
+    pub(crate) struct QueryClient<T> {
+        inner: tonic::client::Grpc<T>,
+    }
+    impl<T> QueryClient<T>
+    where
+        T: tonic::client::GrpcService<tonic::body::BoxBody>,
+        T::Error: Into<StdError>,
+        T::ResponseBody: Body<Data = Bytes> + Send + 'static,
+        <T::ResponseBody as Body>::Error: Into<StdError> + Send,
+    {
+        pub(crate) fn new(inner: T) -> Self {
+            let inner = tonic::client::Grpc::new(inner);
+            Self { inner }
+        }
+        /// Returns the current Osmosis EIP-1559 base fee
+        pub(crate) async fn get_eip_base_fee(
+            &mut self,
+            request: impl tonic::IntoRequest<super::QueryEipBaseFeeRequest>,
+        ) -> Result<tonic::Response<super::QueryEipBaseFeeResponse>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(
+                    tonic::Code::Unknown,
+                    format!("Service was not ready: {}", e.into()),
+                )
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path =
+                http::uri::PathAndQuery::from_static("/osmosis.txfees.v1beta1.Query/GetEipBaseFee");
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+        /// Returns the current feemarket gas prices for all accepted denoms
+        pub(crate) async fn gas_prices(
+            &mut self,
+            request: impl tonic::IntoRequest<super::GasPricesRequest>,
+        ) -> Result<tonic::Response<super::GasPricesResponse>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(
+                    tonic::Code::Unknown,
+                    format!("Service was not ready: {}", e.into()),
+                )
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path =
+                http::uri::PathAndQuery::from_static("/feemarket.feemarket.v1.Query/GasPrices");
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+    }
+}
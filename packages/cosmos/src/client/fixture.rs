@@ -0,0 +1,378 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    path::PathBuf,
+};
+
+use parking_lot::Mutex;
+use prost::Message;
+
+use crate::{error::BuilderError, error::QueryErrorDetails, FixtureMode};
+
+use super::query::GrpcRequest;
+
+/// One recorded query.
+///
+/// gRPC requests and responses are hex encoded protobuf, RPC responses are
+/// JSON. Exactly one of `response` and `error` is set.
+#[derive(serde::Serialize, serde::Deserialize, Clone)]
+struct Interaction {
+    method: String,
+    height: Option<u64>,
+    body: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    response: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    error: Option<RecordedError>,
+}
+
+/// A gRPC status returned by the chain, such as NotFound or a contract error.
+#[derive(serde::Serialize, serde::Deserialize, Clone)]
+struct RecordedError {
+    code: i32,
+    message: String,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Default)]
+struct FixtureFile {
+    interactions: Vec<Interaction>,
+}
+
+/// Method path, height and request body.
+type Key = (String, Option<u64>, String);
+
+type Outcome = Result<String, RecordedError>;
+
+struct Recording {
+    file: FixtureFile,
+    /// Are there interactions which haven't been written yet?
+    dirty: bool,
+}
+
+/// Recorded query responses, see [FixtureMode].
+pub(crate) enum Fixture {
+    Record {
+        path: PathBuf,
+        recording: Mutex<Recording>,
+    },
+    Replay {
+        responses: Mutex<HashMap<Key, VecDeque<Outcome>>>,
+    },
+}
+
+impl Fixture {
+    pub(super) fn new(mode: &FixtureMode) -> Result<Self, BuilderError> {
+        match mode {
+            FixtureMode::Record(path) => Ok(Fixture::Record {
+                path: path.clone(),
+                recording: Mutex::new(Recording {
+                    file: FixtureFile::default(),
+                    dirty: false,
+                }),
+            }),
+            FixtureMode::Replay(path) => {
+                let contents = fs_err::read(path).map_err(|source| BuilderError::ReadFixture {
+                    path: path.clone(),
+                    source,
+                })?;
+                let file: FixtureFile = serde_json::from_slice(&contents).map_err(|source| {
+                    BuilderError::ParseFixture {
+                        path: path.clone(),
+                        source,
+                    }
+                })?;
+                let mut responses = HashMap::<_, VecDeque<_>>::new();
+                for Interaction {
+                    method,
+                    height,
+                    body,
+                    response,
+                    error,
+                } in file.interactions
+                {
+                    let outcome = match (response, error) {
+                        (_, Some(error)) => Err(error),
+                        (response, None) => Ok(response.unwrap_or_default()),
+                    };
+                    responses
+                        .entry((method, height, body))
+                        .or_default()
+                        .push_back(outcome);
+                }
+                Ok(Fixture::Replay {
+                    responses: Mutex::new(responses),
+                })
+            }
+        }
+    }
+
+    /// Look up the next recorded outcome for a key, or [None] when recording.
+    ///
+    /// The inner [None] means nothing was recorded for the key.
+    fn replay_outcome(&self, key: Key) -> Option<Option<Outcome>> {
+        let responses = match self {
+            Fixture::Record { .. } => return None,
+            Fixture::Replay { responses } => responses,
+        };
+        let mut responses = responses.lock();
+        let Some(queue) = responses.get_mut(&key) else {
+            return Some(None);
+        };
+        // Keep the last response around for any further identical queries.
+        Some(if queue.len() > 1 {
+            queue.pop_front()
+        } else {
+            queue.front().cloned()
+        })
+    }
+
+    /// Save an outcome, if recording.
+    fn record_outcome(&self, (method, height, body): Key, outcome: Outcome) {
+        let recording = match self {
+            Fixture::Record { recording, .. } => recording,
+            Fixture::Replay { .. } => return,
+        };
+        let (response, error) = match outcome {
+            Ok(response) => (Some(response), None),
+            Err(error) => (None, Some(error)),
+        };
+        let mut recording = recording.lock();
+        recording.file.interactions.push(Interaction {
+            method,
+            height,
+            body,
+            response,
+            error,
+        });
+        recording.dirty = true;
+    }
+
+    fn key<Request: GrpcRequest>(req: &Request, height: Option<u64>) -> Key {
+        (
+            Request::METHOD.to_owned(),
+            height,
+            hex::encode(req.encode_to_vec()),
+        )
+    }
+
+    fn rpc_key(method: &str, params: &[(&str, String)]) -> Key {
+        let body = params
+            .iter()
+            .map(|(name, value)| format!("{name}={value}"))
+            .collect::<Vec<_>>()
+            .join("&");
+        (format!("rpc/{method}"), None, body)
+    }
+
+    /// Look up the recorded response, or [None] when recording.
+    pub(super) fn replay<Request: GrpcRequest>(
+        &self,
+        req: &Request,
+        height: Option<u64>,
+    ) -> Option<Result<Request::Response, QueryErrorDetails>> {
+        let missing = || QueryErrorDetails::FixtureMissing {
+            request: Request::METHOD,
+        };
+        Some(match self.replay_outcome(Self::key(req, height))? {
+            None => Err(missing()),
+            Some(Ok(response)) => hex::decode(response)
+                .ok()
+                .and_then(|response| Request::Response::decode(response.as_slice()).ok())
+                .ok_or_else(missing),
+            Some(Err(RecordedError { code, message })) => {
+                Err(QueryErrorDetails::from_tonic_status(tonic::Status::new(
+                    tonic::Code::from(code),
+                    message,
+                )))
+            }
+        })
+    }
+
+    /// Save a response or error, if recording.
+    ///
+    /// Only errors returned by the chain itself are saved. Connection
+    /// problems are not, since they wouldn't happen again on replay.
+    pub(super) fn record<Request: GrpcRequest>(
+        &self,
+        req: &Request,
+        height: Option<u64>,
+        res: Result<&Request::Response, &QueryErrorDetails>,
+    ) {
+        let outcome = match res {
+            Ok(response) => Ok(hex::encode(response.encode_to_vec())),
+            Err(err) => match recorded_error(err) {
+                Some(error) => Err(error),
+                None => return,
+            },
+        };
+        self.record_outcome(Self::key(req, height), outcome);
+    }
+
+    /// Look up a recorded RPC response, or [None] when recording.
+    pub(super) fn replay_rpc(
+        &self,
+        method: &str,
+        params: &[(&str, String)],
+    ) -> Option<Result<serde_json::Value, String>> {
+        let missing =
+            || format!("No recorded response for RPC method {method} in the replay fixture");
+        Some(match self.replay_outcome(Self::rpc_key(method, params))? {
+            Some(Ok(response)) => serde_json::from_str(&response).map_err(|_| missing()),
+            Some(Err(_)) | None => Err(missing()),
+        })
+    }
+
+    /// Save an RPC response, if recording.
+    pub(super) fn record_rpc(
+        &self,
+        method: &str,
+        params: &[(&str, String)],
+        response: &serde_json::Value,
+    ) {
+        self.record_outcome(Self::rpc_key(method, params), Ok(response.to_string()));
+    }
+
+    /// Write any recorded interactions to the fixture file.
+    ///
+    /// The whole file is rewritten, so it always contains every interaction
+    /// recorded so far.
+    pub(super) fn flush(&self) -> Result<(), crate::Error> {
+        let (path, recording) = match self {
+            Fixture::Record { path, recording } => (path, recording),
+            Fixture::Replay { .. } => return Ok(()),
+        };
+        let mut recording = recording.lock();
+        if !recording.dirty {
+            return Ok(());
+        }
+        serde_json::to_vec_pretty(&recording.file)
+            .map_err(std::io::Error::from)
+            .and_then(|contents| fs_err::write(path, contents))
+            .map_err(|source| crate::Error::WriteFixture {
+                path: path.clone(),
+                source,
+            })?;
+        recording.dirty = false;
+        Ok(())
+    }
+}
+
+impl Drop for Fixture {
+    fn drop(&mut self) {
+        if let Err(e) = self.flush() {
+            tracing::warn!("{e}");
+        }
+    }
+}
+
+/// The gRPC status to record for an error returned by the chain.
+fn recorded_error(err: &QueryErrorDetails) -> Option<RecordedError> {
+    let (code, message) = match err {
+        QueryErrorDetails::NotFound(message) => (tonic::Code::NotFound, message.as_str()),
+        QueryErrorDetails::Unknown(source)
+        | QueryErrorDetails::CosmosSdk { source, .. }
+        | QueryErrorDetails::JsonParseError(source)
+        | QueryErrorDetails::FailedToExecute(source)
+        | QueryErrorDetails::HeightNotAvailable { source, .. }
+        | QueryErrorDetails::Unimplemented { source }
+        | QueryErrorDetails::AccountSequenceMismatch(source) => (source.code(), source.message()),
+        QueryErrorDetails::QueryTimeout(_)
+        | QueryErrorDetails::ConnectionError(_)
+        | QueryErrorDetails::Unavailable { .. }
+        | QueryErrorDetails::TransportError { .. }
+        | QueryErrorDetails::BlocksLagDetected { .. }
+        | QueryErrorDetails::NoNewBlockFound { .. }
+        | QueryErrorDetails::FixtureMissing { .. } => return None,
+    };
+    Some(RecordedError {
+        code: code as i32,
+        message: message.to_owned(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use cosmos_sdk_proto::cosmos::bank::v1beta1::{
+        QueryAllBalancesRequest, QueryAllBalancesResponse,
+    };
+
+    use super::*;
+
+    #[test]
+    fn record_then_replay() {
+        let path = std::env::temp_dir().join(format!("cosmos-fixture-{}.json", std::process::id()));
+        let req = QueryAllBalancesRequest {
+            address: "osmo168gdk6r58jdwfv49kuesq2rs747jawnn4ryvyk".to_owned(),
+            pagination: None,
+        };
+        let other = QueryAllBalancesRequest {
+            address: "osmo1qg5ega6dykkxc307y25pecuufrjkxkaggkkxh7nad0vhyhtuhw3sqaa3c5".to_owned(),
+            pagination: None,
+        };
+        let response = |amount: &str| QueryAllBalancesResponse {
+            balances: vec![crate::Coin {
+                denom: "uosmo".to_owned(),
+                amount: amount.to_owned(),
+            }],
+            pagination: None,
+        };
+
+        let recorder = Fixture::new(&FixtureMode::Record(path.clone())).unwrap();
+        assert!(recorder.replay(&req, None).is_none());
+        recorder.record(&req, None, Ok(&response("1")));
+        recorder.record(&req, None, Ok(&response("2")));
+        let not_found = QueryErrorDetails::NotFound("account not found".to_owned());
+        recorder.record(&other, None, Err(&not_found));
+        // Connection problems aren't recorded
+        let timeout = QueryErrorDetails::QueryTimeout(std::time::Duration::from_secs(1));
+        recorder.record(&other, Some(5), Err(&timeout));
+        recorder.record_rpc(
+            "block_results",
+            &[("height", "5".to_owned())],
+            &serde_json::json!({"height": "5"}),
+        );
+        // Nothing is written until the recording is flushed
+        assert!(!path.exists());
+        drop(recorder);
+
+        let replayer = Fixture::new(&FixtureMode::Replay(path.clone())).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(replayer.replay(&req, None).unwrap().unwrap(), response("1"));
+        assert_eq!(replayer.replay(&req, None).unwrap().unwrap(), response("2"));
+        assert_eq!(replayer.replay(&req, None).unwrap().unwrap(), response("2"));
+        assert!(matches!(
+            replayer.replay(&other, None),
+            Some(Err(QueryErrorDetails::NotFound(message))) if message == "account not found"
+        ));
+        assert!(matches!(
+            replayer.replay(&other, Some(5)),
+            Some(Err(QueryErrorDetails::FixtureMissing {
+                request: "/cosmos.bank.v1beta1.Query/AllBalances"
+            }))
+        ));
+        assert_eq!(
+            replayer
+                .replay_rpc("block_results", &[("height", "5".to_owned())])
+                .unwrap()
+                .unwrap(),
+            serde_json::json!({"height": "5"})
+        );
+        replayer
+            .replay_rpc("block_results", &[("height", "6".to_owned())])
+            .unwrap()
+            .unwrap_err();
+    }
+
+    #[test]
+    fn explicit_flush() {
+        let path =
+            std::env::temp_dir().join(format!("cosmos-fixture-flush-{}.json", std::process::id()));
+        let recorder = Fixture::new(&FixtureMode::Record(path.clone())).unwrap();
+        recorder.record_rpc("status", &[], &serde_json::json!({}));
+        recorder.flush().unwrap();
+        assert!(path.exists());
+        std::fs::remove_file(&path).unwrap();
+        // Already flushed, so dropping doesn't write the file again
+        drop(recorder);
+        assert!(!path.exists());
+    }
+}
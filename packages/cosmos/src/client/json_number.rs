@@ -0,0 +1,81 @@
+//! Numbers in JSON responses from the RPC and LCD endpoints.
+//!
+//! Cosmos nodes normally encode 64-bit integers as strings, but some versions
+//! and proxies use plain JSON numbers instead. Both are accepted.
+
+use std::{fmt::Display, str::FromStr};
+
+use serde::Deserialize;
+
+#[derive(Deserialize, Debug)]
+#[serde(untagged)]
+pub(super) enum JsonNumber {
+    String(String),
+    Number(serde_json::Number),
+}
+
+impl JsonNumber {
+    pub(super) fn parse<T: FromStr>(&self, field: &str) -> Result<T, String>
+    where
+        T::Err: Display,
+    {
+        match self {
+            JsonNumber::String(s) => parse_str(s, field),
+            JsonNumber::Number(n) => parse_str(&n.to_string(), field),
+        }
+    }
+}
+
+/// Parse an optional field, treating a missing value or empty string as zero.
+pub(super) fn parse_or_zero<T: FromStr + Default>(
+    value: Option<&JsonNumber>,
+    field: &str,
+) -> Result<T, String>
+where
+    T::Err: Display,
+{
+    match value {
+        None => Ok(T::default()),
+        Some(JsonNumber::String(s)) if s.is_empty() => Ok(T::default()),
+        Some(value) => value.parse(field),
+    }
+}
+
+fn parse_str<T: FromStr>(s: &str, field: &str) -> Result<T, String>
+where
+    T::Err: Display,
+{
+    s.parse()
+        .map_err(|e| format!("Invalid {field} value {s:?}: {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn string_or_number() {
+        let parse = |json: &str| {
+            serde_json::from_str::<JsonNumber>(json)
+                .unwrap()
+                .parse::<u64>("field")
+        };
+        assert_eq!(parse(r#""42""#), Ok(42));
+        assert_eq!(parse("42"), Ok(42));
+        assert_eq!(parse(r#""18446744073709551615""#), Ok(u64::MAX));
+        assert_eq!(parse("18446744073709551615"), Ok(u64::MAX));
+        parse(r#""-1""#).unwrap_err();
+        parse("1.5").unwrap_err();
+        parse(r#""""#).unwrap_err();
+        serde_json::from_str::<JsonNumber>("null").unwrap_err();
+    }
+
+    #[test]
+    fn optional_defaults_to_zero() {
+        let empty = JsonNumber::String(String::new());
+        assert_eq!(parse_or_zero::<i64>(None, "field"), Ok(0));
+        assert_eq!(parse_or_zero::<i64>(Some(&empty), "field"), Ok(0));
+        let value = serde_json::from_str::<JsonNumber>("7").unwrap();
+        assert_eq!(parse_or_zero::<i64>(Some(&value), "field"), Ok(7));
+    }
+}
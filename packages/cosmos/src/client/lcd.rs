@@ -0,0 +1,483 @@
+//! Fallback transport using a node's LCD (REST) API.
+//!
+//! Only a subset of queries is supported, see [super::query::GrpcRequest::perform_lcd].
+
+use std::sync::Arc;
+
+use base64::Engine;
+use cosmos_sdk_proto::{
+    cosmos::{
+        auth::v1beta1::{BaseAccount, QueryAccountRequest, QueryAccountResponse},
+        bank::v1beta1::{QueryAllBalancesRequest, QueryAllBalancesResponse},
+        base::{
+            abci::v1beta1::TxResponse,
+            query::v1beta1::{PageRequest, PageResponse},
+            v1beta1::Coin,
+        },
+        tx::v1beta1::{BroadcastMode, BroadcastTxRequest, BroadcastTxResponse},
+    },
+    cosmwasm::wasm::v1::{
+        QueryRawContractStateRequest, QueryRawContractStateResponse,
+        QuerySmartContractStateRequest, QuerySmartContractStateResponse,
+    },
+};
+use prost::Message;
+use reqwest::Url;
+use serde::{de::DeserializeOwned, Deserialize};
+
+use crate::{error::BuilderError, CosmosBuilder};
+
+use super::json_number::{parse_or_zero, JsonNumber};
+
+/// Client for an LCD endpoint.
+///
+/// Errors are plain strings, since they are only logged before falling back
+/// to the original gRPC error.
+pub(crate) struct Lcd {
+    url: Arc<String>,
+    base: Url,
+    client: reqwest::Client,
+}
+
+impl Lcd {
+    pub(super) fn new(builder: &CosmosBuilder, url: &str) -> Result<Self, BuilderError> {
+        let base = url.parse().map_err(|source| BuilderError::InvalidLcdUrl {
+            lcd_url: url.to_owned(),
+            source,
+        })?;
+        Ok(Lcd {
+            url: Arc::new(url.to_owned()),
+            base,
+            client: builder.make_http_client(url)?,
+        })
+    }
+
+    pub(super) fn url(&self) -> &Arc<String> {
+        &self.url
+    }
+
+    fn make_url(&self, segments: &[&str], params: &[(&str, String)]) -> Result<Url, String> {
+        let mut url = self.base.clone();
+        url.path_segments_mut()
+            .map_err(|()| format!("LCD URL {} cannot be a base", self.url))?
+            .pop_if_empty()
+            .extend(segments);
+        if !params.is_empty() {
+            url.query_pairs_mut().extend_pairs(params);
+        }
+        Ok(url)
+    }
+
+    async fn get<T: DeserializeOwned>(
+        &self,
+        segments: &[&str],
+        params: &[(&str, String)],
+        height: Option<u64>,
+    ) -> Result<T, String> {
+        let url = self.make_url(segments, params)?;
+        let mut req = self.client.get(url.clone());
+        if let Some(height) = height {
+            req = req.header("x-cosmos-block-height", height);
+        }
+        Self::handle(url, req).await
+    }
+
+    async fn handle<T: DeserializeOwned>(
+        url: Url,
+        req: reqwest::RequestBuilder,
+    ) -> Result<T, String> {
+        let res = req
+            .send()
+            .await
+            .map_err(|e| format!("Request to {url} failed: {e}"))?;
+        let status = res.status();
+        if !status.is_success() {
+            let body = res.text().await.unwrap_or_default();
+            return Err(format!("{url} returned HTTP status {status}: {body}"));
+        }
+        res.json()
+            .await
+            .map_err(|e| format!("Unable to parse response from {url}: {e}"))
+    }
+
+    pub(super) async fn account(
+        &self,
+        req: &QueryAccountRequest,
+        height: Option<u64>,
+    ) -> Result<QueryAccountResponse, String> {
+        #[derive(Deserialize)]
+        struct Response {
+            account: LcdAccount,
+        }
+        let Response { account } = self
+            .get(
+                &["cosmos", "auth", "v1beta1", "accounts", &req.address],
+                &[],
+                height,
+            )
+            .await?;
+        account.into_proto()
+    }
+
+    pub(super) async fn all_balances(
+        &self,
+        req: &QueryAllBalancesRequest,
+        height: Option<u64>,
+    ) -> Result<QueryAllBalancesResponse, String> {
+        #[derive(Deserialize)]
+        struct Response {
+            balances: Vec<LcdCoin>,
+            pagination: Option<LcdPagination>,
+        }
+        let Response {
+            balances,
+            pagination,
+        } = self
+            .get(
+                &["cosmos", "bank", "v1beta1", "balances", &req.address],
+                &pagination_params(req.pagination.as_ref()),
+                height,
+            )
+            .await?;
+        Ok(QueryAllBalancesResponse {
+            balances: balances.into_iter().map(Coin::from).collect(),
+            pagination: pagination.map(LcdPagination::into_proto).transpose()?,
+        })
+    }
+
+    pub(super) async fn smart_contract_state(
+        &self,
+        req: &QuerySmartContractStateRequest,
+        height: Option<u64>,
+    ) -> Result<QuerySmartContractStateResponse, String> {
+        #[derive(Deserialize)]
+        struct Response {
+            data: serde_json::Value,
+        }
+        let query = base64::engine::general_purpose::STANDARD.encode(&req.query_data);
+        let Response { data } = self
+            .get(
+                &[
+                    "cosmwasm",
+                    "wasm",
+                    "v1",
+                    "contract",
+                    &req.address,
+                    "smart",
+                    &query,
+                ],
+                &[],
+                height,
+            )
+            .await?;
+        Ok(QuerySmartContractStateResponse {
+            data: serde_json::to_vec(&data).map_err(|e| e.to_string())?,
+        })
+    }
+
+    pub(super) async fn raw_contract_state(
+        &self,
+        req: &QueryRawContractStateRequest,
+        height: Option<u64>,
+    ) -> Result<QueryRawContractStateResponse, String> {
+        #[derive(Deserialize)]
+        struct Response {
+            data: Option<String>,
+        }
+        let key = base64::engine::general_purpose::STANDARD.encode(&req.query_data);
+        let Response { data } = self
+            .get(
+                &[
+                    "cosmwasm",
+                    "wasm",
+                    "v1",
+                    "contract",
+                    &req.address,
+                    "raw",
+                    &key,
+                ],
+                &[],
+                height,
+            )
+            .await?;
+        Ok(QueryRawContractStateResponse {
+            data: data
+                .as_deref()
+                .map(decode_base64)
+                .transpose()?
+                .unwrap_or_default(),
+        })
+    }
+
+    pub(super) async fn broadcast_tx(
+        &self,
+        req: &BroadcastTxRequest,
+    ) -> Result<BroadcastTxResponse, String> {
+        #[derive(Deserialize)]
+        struct Response {
+            tx_response: LcdTxResponse,
+        }
+        let mode = match BroadcastMode::from_i32(req.mode) {
+            Some(BroadcastMode::Block) => "BROADCAST_MODE_BLOCK",
+            Some(BroadcastMode::Async) => "BROADCAST_MODE_ASYNC",
+            _ => "BROADCAST_MODE_SYNC",
+        };
+        let url = self.make_url(&["cosmos", "tx", "v1beta1", "txs"], &[])?;
+        let body = serde_json::json!({
+            "tx_bytes": base64::engine::general_purpose::STANDARD.encode(&req.tx_bytes),
+            "mode": mode,
+        });
+        let Response { tx_response } =
+            Self::handle(url.clone(), self.client.post(url).json(&body)).await?;
+        Ok(BroadcastTxResponse {
+            tx_response: Some(tx_response.into_proto()?),
+        })
+    }
+}
+
+#[derive(Deserialize)]
+struct LcdAccount {
+    #[serde(rename = "@type")]
+    type_url: String,
+    address: String,
+    account_number: JsonNumber,
+    sequence: JsonNumber,
+}
+
+impl LcdAccount {
+    fn into_proto(self) -> Result<QueryAccountResponse, String> {
+        const BASE_ACCOUNT: &str = "/cosmos.auth.v1beta1.BaseAccount";
+        if self.type_url != BASE_ACCOUNT {
+            return Err(format!(
+                "Unsupported account type {} over LCD",
+                self.type_url
+            ));
+        }
+        let base_account = BaseAccount {
+            address: self.address,
+            pub_key: None,
+            account_number: self.account_number.parse("account_number")?,
+            sequence: self.sequence.parse("sequence")?,
+        };
+        Ok(QueryAccountResponse {
+            account: Some(prost_types::Any {
+                type_url: BASE_ACCOUNT.to_owned(),
+                value: base_account.encode_to_vec(),
+            }),
+        })
+    }
+}
+
+#[derive(Deserialize)]
+struct LcdTxResponse {
+    #[serde(default)]
+    height: Option<JsonNumber>,
+    txhash: String,
+    #[serde(default)]
+    codespace: String,
+    #[serde(default)]
+    code: u32,
+    #[serde(default)]
+    data: String,
+    #[serde(default)]
+    raw_log: String,
+    #[serde(default)]
+    info: String,
+    #[serde(default)]
+    gas_wanted: Option<JsonNumber>,
+    #[serde(default)]
+    gas_used: Option<JsonNumber>,
+}
+
+impl LcdTxResponse {
+    fn into_proto(self) -> Result<TxResponse, String> {
+        Ok(TxResponse {
+            height: parse_or_zero(self.height.as_ref(), "height")?,
+            txhash: self.txhash,
+            codespace: self.codespace,
+            code: self.code,
+            data: self.data,
+            raw_log: self.raw_log,
+            logs: vec![],
+            info: self.info,
+            gas_wanted: parse_or_zero(self.gas_wanted.as_ref(), "gas_wanted")?,
+            gas_used: parse_or_zero(self.gas_used.as_ref(), "gas_used")?,
+            tx: None,
+            timestamp: String::new(),
+            events: vec![],
+        })
+    }
+}
+
+#[derive(Deserialize)]
+struct LcdCoin {
+    denom: String,
+    amount: String,
+}
+
+impl From<LcdCoin> for Coin {
+    fn from(LcdCoin { denom, amount }: LcdCoin) -> Self {
+        Coin { denom, amount }
+    }
+}
+
+#[derive(Deserialize)]
+struct LcdPagination {
+    next_key: Option<String>,
+    total: Option<JsonNumber>,
+}
+
+impl LcdPagination {
+    fn into_proto(self) -> Result<PageResponse, String> {
+        Ok(PageResponse {
+            next_key: self
+                .next_key
+                .as_deref()
+                .map(decode_base64)
+                .transpose()?
+                .unwrap_or_default(),
+            total: parse_or_zero(self.total.as_ref(), "pagination.total")?,
+        })
+    }
+}
+
+fn pagination_params(pagination: Option<&PageRequest>) -> Vec<(&'static str, String)> {
+    let mut params = vec![];
+    if let Some(pagination) = pagination {
+        if !pagination.key.is_empty() {
+            params.push((
+                "pagination.key",
+                base64::engine::general_purpose::STANDARD.encode(&pagination.key),
+            ));
+        }
+        if pagination.offset != 0 {
+            params.push(("pagination.offset", pagination.offset.to_string()));
+        }
+        if pagination.limit != 0 {
+            params.push(("pagination.limit", pagination.limit.to_string()));
+        }
+        if pagination.count_total {
+            params.push(("pagination.count_total", "true".to_owned()));
+        }
+        if pagination.reverse {
+            params.push(("pagination.reverse", "true".to_owned()));
+        }
+    }
+    params
+}
+
+fn decode_base64(s: &str) -> Result<Vec<u8>, String> {
+    base64::engine::general_purpose::STANDARD
+        .decode(s)
+        .map_err(|e| format!("Invalid base64 in LCD response: {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::AddressHrp;
+
+    use super::*;
+
+    #[test]
+    fn lcd_urls() {
+        let builder = CosmosBuilder::new(
+            "osmosis-1",
+            "uosmo",
+            AddressHrp::from_static("osmo"),
+            "http://127.0.0.1:1",
+        );
+        let lcd = Lcd::new(&builder, "https://lcd.example.com/api/").unwrap();
+        let url = lcd
+            .make_url(
+                &[
+                    "cosmwasm", "wasm", "v1", "contract", "osmo1abc", "smart", "e30+/w==",
+                ],
+                &[],
+            )
+            .unwrap();
+        assert_eq!(
+            url.as_str(),
+            "https://lcd.example.com/api/cosmwasm/wasm/v1/contract/osmo1abc/smart/e30+%2Fw=="
+        );
+        let url = lcd
+            .make_url(
+                &["cosmos", "bank", "v1beta1", "balances", "osmo1abc"],
+                &pagination_params(Some(&PageRequest {
+                    key: vec![],
+                    offset: 0,
+                    limit: 100,
+                    count_total: false,
+                    reverse: false,
+                })),
+            )
+            .unwrap();
+        assert_eq!(
+            url.as_str(),
+            "https://lcd.example.com/api/cosmos/bank/v1beta1/balances/osmo1abc?pagination.limit=100"
+        );
+    }
+
+    #[test]
+    fn parse_account() {
+        for json in [
+            r#"{"@type": "/cosmos.auth.v1beta1.BaseAccount", "address": "osmo1abc", "account_number": "12", "sequence": "3"}"#,
+            r#"{"@type": "/cosmos.auth.v1beta1.BaseAccount", "address": "osmo1abc", "account_number": 12, "sequence": 3}"#,
+        ] {
+            let account: LcdAccount = serde_json::from_str(json).unwrap();
+            let res = account.into_proto().unwrap();
+            let base_account = BaseAccount::decode(res.account.unwrap().value.as_slice()).unwrap();
+            assert_eq!(base_account.address, "osmo1abc");
+            assert_eq!(base_account.account_number, 12);
+            assert_eq!(base_account.sequence, 3);
+        }
+
+        let account: LcdAccount = serde_json::from_str(
+            r#"{"@type": "/cosmos.vesting.v1beta1.DelayedVestingAccount", "address": "osmo1abc", "account_number": "12", "sequence": "3"}"#,
+        )
+        .unwrap();
+        account.into_proto().unwrap_err();
+    }
+
+    #[test]
+    fn parse_tx_response() {
+        let res: LcdTxResponse = serde_json::from_str(
+            r#"{"height": "0", "txhash": "ABC", "code": 5, "codespace": "sdk", "raw_log": "insufficient funds", "gas_wanted": "200000", "gas_used": ""}"#,
+        )
+        .unwrap();
+        let res = res.into_proto().unwrap();
+        assert_eq!(res.txhash, "ABC");
+        assert_eq!(res.code, 5);
+        assert_eq!(res.gas_wanted, 200000);
+        assert_eq!(res.gas_used, 0);
+
+        let res: LcdTxResponse =
+            serde_json::from_str(r#"{"height": 15, "txhash": "ABC", "gas_wanted": 200000}"#)
+                .unwrap();
+        let res = res.into_proto().unwrap();
+        assert_eq!(res.height, 15);
+        assert_eq!(res.gas_wanted, 200000);
+        assert_eq!(res.gas_used, 0);
+
+        let res: LcdTxResponse =
+            serde_json::from_str(r#"{"txhash": "ABC", "gas_used": "lots"}"#).unwrap();
+        res.into_proto().unwrap_err();
+    }
+
+    #[test]
+    fn parse_pagination() {
+        let pagination: LcdPagination =
+            serde_json::from_str(r#"{"next_key": "AQI=", "total": "7"}"#).unwrap();
+        let pagination = pagination.into_proto().unwrap();
+        assert_eq!(pagination.next_key, vec![1, 2]);
+        assert_eq!(pagination.total, 7);
+
+        let pagination: LcdPagination =
+            serde_json::from_str(r#"{"next_key": null, "total": 7}"#).unwrap();
+        let pagination = pagination.into_proto().unwrap();
+        assert!(pagination.next_key.is_empty());
+        assert_eq!(pagination.total, 7);
+
+        let pagination: LcdPagination = serde_json::from_str(r#"{"total": ""}"#).unwrap();
+        assert_eq!(pagination.into_proto().unwrap().total, 0);
+    }
+}
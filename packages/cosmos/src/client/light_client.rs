@@ -0,0 +1,404 @@
+//! Light client verification of headers, enabled by the `light-client` feature.
+//!
+//! Starting from a trusted block, headers are verified using the validator
+//! signatures on their commits, following the Tendermint light client
+//! protocol with bisection. Verified headers are then used to check block
+//! contents and ABCI query proofs.
+
+use std::{collections::BTreeMap, time::Duration};
+
+use chrono::{DateTime, Utc};
+use cosmos_sdk_proto::cosmos::base::tendermint::v1beta1::GetBlockByHeightRequest;
+use parking_lot::Mutex;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use tendermint::{block::signed_header::SignedHeader, validator};
+use tendermint_light_client_verifier::{
+    options::Options,
+    types::{LightBlock, TrustThreshold},
+    ProdVerifier, Verdict, Verifier,
+};
+
+use crate::{error::Action, AbciQueryResponse, Address, BlockInfo, Cosmos, HasAddress};
+
+/// Verifies headers from a chain, starting from a trusted block.
+///
+/// Requires an RPC endpoint, see [crate::CosmosBuilder::set_rpc_url].
+/// Only heights at or after the trusted block can be verified. Each height
+/// is verified from the nearest previously verified block at or below it.
+pub struct LightClient {
+    cosmos: Cosmos,
+    options: Options,
+    /// Verified blocks by height. The initial trusted block is never evicted.
+    verified: Mutex<BTreeMap<u64, LightBlock>>,
+}
+
+/// Maximum number of verified blocks kept as starting points for verification.
+const MAX_VERIFIED_BLOCKS: usize = 256;
+
+/// A block header verified by a [LightClient].
+#[derive(Clone, Debug)]
+pub struct VerifiedHeader {
+    /// Block height
+    pub height: u64,
+    /// Hash of the block
+    pub block_hash: String,
+    /// Timestamp of the block
+    pub timestamp: DateTime<Utc>,
+    /// Application state hash after executing the previous block
+    pub app_hash: Vec<u8>,
+    /// Merkle root of the transactions in this block
+    pub data_hash: Option<Vec<u8>>,
+}
+
+#[derive(Deserialize)]
+struct CommitResponse {
+    signed_header: SignedHeader,
+}
+
+#[derive(Deserialize)]
+struct ValidatorsResponse {
+    validators: Vec<validator::Info>,
+    total: String,
+}
+
+impl LightClient {
+    /// Create a light client from a block height and hash obtained out of band.
+    ///
+    /// The trusting period should be less than the chain's unbonding period,
+    /// typically two thirds of it.
+    pub async fn new(
+        cosmos: Cosmos,
+        trusted_height: u64,
+        trusted_hash: &str,
+        trusting_period: Duration,
+    ) -> Result<Self, crate::Error> {
+        let block = fetch_light_block(&cosmos, trusted_height).await?;
+        let actual = block.signed_header.header.hash().to_string();
+        if !actual.eq_ignore_ascii_case(trusted_hash) {
+            return Err(crate::Error::LightClientVerification {
+                height: trusted_height,
+                message: format!("Trusted hash {trusted_hash} does not match block hash {actual}"),
+            });
+        }
+        Ok(LightClient {
+            cosmos,
+            options: Options {
+                trust_threshold: TrustThreshold::ONE_THIRD,
+                trusting_period,
+                clock_drift: Duration::from_secs(5),
+            },
+            verified: Mutex::new(BTreeMap::from([(trusted_height, block)])),
+        })
+    }
+
+    /// Height of the latest verified block.
+    pub fn trusted_height(&self) -> u64 {
+        self.verified
+            .lock()
+            .last_key_value()
+            .map_or(0, |(height, _)| *height)
+    }
+
+    /// Verify the header at the given height.
+    pub async fn verify_header(&self, height: u64) -> Result<VerifiedHeader, crate::Error> {
+        let header = self.verify_to(height).await?.signed_header.header;
+        let timestamp = DateTime::parse_from_rfc3339(&header.time.to_rfc3339())
+            .map(|time| time.with_timezone(&Utc))
+            .map_err(|e| crate::Error::LightClientVerification {
+                height,
+                message: format!("Invalid header time: {e}"),
+            })?;
+        Ok(VerifiedHeader {
+            height,
+            block_hash: header.hash().to_string(),
+            timestamp,
+            app_hash: header.app_hash.as_bytes().to_vec(),
+            data_hash: header.data_hash.map(|hash| hash.as_bytes().to_vec()),
+        })
+    }
+
+    /// Get block information, verifying the block hash and transactions against a verified header.
+    pub async fn get_block_info(&self, height: u64) -> Result<BlockInfo, crate::Error> {
+        let header = self.verify_header(height).await?;
+        let action = Action::GetBlock(height as i64);
+        let res = self
+            .cosmos
+            .perform_query(
+                GetBlockByHeightRequest {
+                    height: height as i64,
+                },
+                action.clone(),
+                true,
+            )
+            .await?
+            .into_inner();
+        let block_hash = res
+            .block_id
+            .as_ref()
+            .map(|block_id| hex::encode_upper(&block_id.hash))
+            .unwrap_or_default();
+        if block_hash != header.block_hash {
+            return Err(crate::Error::LightClientVerification {
+                height,
+                message: format!(
+                    "Block hash {block_hash} does not match verified hash {}",
+                    header.block_hash
+                ),
+            });
+        }
+        let txs = res
+            .block
+            .as_ref()
+            .and_then(|block| block.data.as_ref())
+            .map(|data| data.txs.as_slice())
+            .unwrap_or_default();
+        let data_hash = header.data_hash.unwrap_or_else(|| txs_root(&[]));
+        if txs_root(txs) != data_hash {
+            return Err(crate::Error::LightClientVerification {
+                height,
+                message: "Block transactions do not match verified data hash".to_owned(),
+            });
+        }
+        BlockInfo::new(action, res.block_id, res.block, Some(height as i64))
+    }
+
+    /// Perform an ABCI store query, verifying the proof against a verified header.
+    ///
+    /// Only store queries such as `/store/wasm/key` can be verified. The
+    /// query is performed at the given height, which must be below the
+    /// latest block height.
+    pub async fn abci_query(
+        &self,
+        path: &str,
+        data: impl AsRef<[u8]>,
+        height: u64,
+    ) -> Result<AbciQueryResponse, crate::Error> {
+        let data = data.as_ref();
+        let (mut res, ops) = self.cosmos.abci_query_raw(path, data, height, true).await?;
+        if res.code != 0 {
+            return Ok(res);
+        }
+        let next = self.verify_header(res.height as u64 + 1).await?;
+        res.verify(path, data, &ops, &next.app_hash)?;
+        Ok(res)
+    }
+
+    /// Read a raw key from a contract's storage, verified against a verified header.
+    ///
+    /// Smart queries execute contract code and cannot be proven, so raw
+    /// storage reads are the verifiable alternative.
+    pub async fn wasm_raw_query(
+        &self,
+        contract: impl HasAddress,
+        key: impl AsRef<[u8]>,
+        height: u64,
+    ) -> Result<Vec<u8>, crate::Error> {
+        let contract: Address = contract.get_address();
+        let mut data = vec![0x03];
+        data.extend_from_slice(contract.raw().as_ref());
+        data.extend_from_slice(key.as_ref());
+        Ok(self
+            .abci_query("/store/wasm/key", data, height)
+            .await?
+            .value)
+    }
+
+    /// Verify the block at the given height, starting from the nearest
+    /// verified block at or below it and using bisection when the validator
+    /// set changed too much since that block.
+    async fn verify_to(&self, height: u64) -> Result<LightBlock, crate::Error> {
+        let mut trusted = {
+            let verified = self.verified.lock();
+            match verified.range(..=height).next_back() {
+                Some((_, block)) => block.clone(),
+                None => {
+                    let anchor = verified.keys().next().copied().unwrap_or_default();
+                    return Err(crate::Error::LightClientVerification {
+                        height,
+                        message: format!("Cannot verify blocks before trusted height {anchor}"),
+                    });
+                }
+            }
+        };
+        if trusted.signed_header.header.height.value() == height {
+            return Ok(trusted);
+        }
+
+        let verifier = ProdVerifier::default();
+        let mut pending = vec![height];
+        let mut verified = None;
+        while let Some(&target) = pending.last() {
+            let untrusted = fetch_light_block(&self.cosmos, target).await?;
+            let now = Utc::now();
+            let now = tendermint::Time::from_unix_timestamp(
+                now.timestamp(),
+                now.timestamp_subsec_nanos(),
+            )
+            .map_err(|e| crate::Error::LightClientVerification {
+                height: target,
+                message: format!("Invalid current time: {e}"),
+            })?;
+            match verifier.verify(
+                untrusted.as_untrusted_state(),
+                trusted.as_trusted_state(),
+                &self.options,
+                now,
+            ) {
+                Verdict::Success => {
+                    pending.pop();
+                    self.remember(target, untrusted.clone());
+                    trusted = untrusted.clone();
+                    verified = Some(untrusted);
+                }
+                Verdict::NotEnoughTrust(tally) => {
+                    let from = trusted.signed_header.header.height.value();
+                    let pivot = from + (target - from) / 2;
+                    if pivot == from {
+                        return Err(crate::Error::LightClientVerification {
+                            height: target,
+                            message: format!("Not enough trust: {tally:?}"),
+                        });
+                    }
+                    pending.push(pivot);
+                }
+                Verdict::Invalid(detail) => {
+                    return Err(crate::Error::LightClientVerification {
+                        height: target,
+                        message: detail.to_string(),
+                    })
+                }
+            }
+        }
+
+        Ok(verified.expect("verify_to: pending heights always end with a verified block"))
+    }
+
+    /// Store a verified block, evicting the oldest blocks after the initial trusted block.
+    fn remember(&self, height: u64, block: LightBlock) {
+        let mut verified = self.verified.lock();
+        verified.insert(height, block);
+        while verified.len() > MAX_VERIFIED_BLOCKS {
+            let evict = verified
+                .keys()
+                .nth(1)
+                .copied()
+                .expect("remember: more than one verified block");
+            verified.remove(&evict);
+        }
+    }
+}
+
+async fn fetch_light_block(cosmos: &Cosmos, height: u64) -> Result<LightBlock, crate::Error> {
+    let action = Action::LightClientFetch(height);
+    let CommitResponse { signed_header } = cosmos
+        .rpc_call("commit", &[("height", height.to_string())], action.clone())
+        .await?;
+    let validators = fetch_validators(cosmos, height, &action).await?;
+    let next_validators = fetch_validators(cosmos, height + 1, &action).await?;
+    Ok(LightBlock::new(
+        signed_header,
+        validators,
+        next_validators,
+        tendermint::node::Id::new([0; 20]),
+    ))
+}
+
+async fn fetch_validators(
+    cosmos: &Cosmos,
+    height: u64,
+    action: &Action,
+) -> Result<validator::Set, crate::Error> {
+    let mut validators = vec![];
+    for page in 1.. {
+        let res: ValidatorsResponse = cosmos
+            .rpc_call(
+                "validators",
+                &[
+                    ("height", height.to_string()),
+                    ("page", page.to_string()),
+                    ("per_page", "100".to_owned()),
+                ],
+                action.clone(),
+            )
+            .await?;
+        let total = res
+            .total
+            .parse::<usize>()
+            .map_err(|e| crate::Error::InvalidChainResponse {
+                message: format!("Invalid validator total {:?}: {e}", res.total),
+                action: action.clone(),
+            })?;
+        let done = res.validators.is_empty();
+        validators.extend(res.validators);
+        if done || validators.len() >= total {
+            break;
+        }
+    }
+    Ok(validator::Set::without_proposer(validators))
+}
+
+/// Merkle root of a block's transactions, as used for the header's data hash.
+///
+/// Follows RFC 6962: leaves and inner nodes are hashed with distinct
+/// prefixes, and the tree is split at the largest power of two below the
+/// number of leaves.
+fn txs_root(txs: &[Vec<u8>]) -> Vec<u8> {
+    fn hash_leaf(tx: &[u8]) -> Vec<u8> {
+        let tx_hash = Sha256::digest(tx);
+        Sha256::new()
+            .chain_update([0u8])
+            .chain_update(tx_hash)
+            .finalize()
+            .to_vec()
+    }
+    fn root(leaves: &[Vec<u8>]) -> Vec<u8> {
+        match leaves {
+            [] => Sha256::digest(b"").to_vec(),
+            [leaf] => leaf.clone(),
+            _ => {
+                let split = leaves.len().next_power_of_two() / 2;
+                let (left, right) = leaves.split_at(split);
+                Sha256::new()
+                    .chain_update([1u8])
+                    .chain_update(root(left))
+                    .chain_update(root(right))
+                    .finalize()
+                    .to_vec()
+            }
+        }
+    }
+    root(&txs.iter().map(|tx| hash_leaf(tx)).collect::<Vec<_>>())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn txs_root_shape() {
+        assert_eq!(
+            hex::encode(txs_root(&[])),
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+        let txs = vec![b"a".to_vec(), b"b".to_vec(), b"c".to_vec()];
+        let leaves = txs
+            .iter()
+            .map(|tx| {
+                let mut leaf = vec![0];
+                leaf.extend(Sha256::digest(tx));
+                Sha256::digest(leaf).to_vec()
+            })
+            .collect::<Vec<_>>();
+        let inner = |l: &[u8], r: &[u8]| {
+            let mut node = vec![1];
+            node.extend(l);
+            node.extend(r);
+            Sha256::digest(node).to_vec()
+        };
+        assert_eq!(
+            txs_root(&txs),
+            inner(&inner(&leaves[0], &leaves[1]), &leaves[2])
+        );
+    }
+}
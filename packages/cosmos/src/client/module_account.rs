@@ -0,0 +1,137 @@
+//! Module account queries from the auth module.
+//!
+//! Not included in our version of the protobuf definitions, requires Cosmos SDK 0.46 or later.
+
+use cosmos_sdk_proto::cosmos::auth::v1beta1::ModuleAccount;
+
+use crate::{error::Action, Account, Cosmos};
+
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct QueryModuleAccountsRequest {}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct QueryModuleAccountsResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub accounts: ::prost::alloc::vec::Vec<::prost_types::Any>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct QueryModuleAccountByNameRequest {
+    #[prost(string, tag = "1")]
+    pub name: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct QueryModuleAccountByNameResponse {
+    #[prost(message, optional, tag = "1")]
+    pub account: ::core::option::Option<::prost_types::Any>,
+}
+
+/// Client for the auth module's module account queries.
+pub(crate) mod query_client {
+    #![allow(unused_variables, dead_code, missing_docs, clippy::let_unit_value)]
+    use tonic::codegen::*;
+    #[derive(Debug, Clone)]
+    pub(crate) struct QueryClient<T> {
+        inner: tonic::client::Grpc<T>,
+    }
+    impl<T> QueryClient<T>
+    where
+        T: tonic::client::GrpcService<tonic::body::BoxBody>,
+        T::Error: Into<StdError>,
+        T::ResponseBody: Body<Data = Bytes> + Send + 'static,
+        <T::ResponseBody as Body>::Error: Into<StdError> + Send,
+    {
+        pub(crate) fn new(inner: T) -> Self {
+            let inner = tonic::client::Grpc::new(inner);
+            Self { inner }
+        }
+        /// Returns all the existing module accounts
+        pub(crate) async fn module_accounts(
+            &mut self,
+            request: impl tonic::IntoRequest<super::QueryModuleAccountsRequest>,
+        ) -> Result<tonic::Response<super::QueryModuleAccountsResponse>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(
+                    tonic::Code::Unknown,
+                    format!("Service was not ready: {}", e.into()),
+                )
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path =
+                http::uri::PathAndQuery::from_static("/cosmos.auth.v1beta1.Query/ModuleAccounts");
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+        /// Returns the module account info by module name
+        pub(crate) async fn module_account_by_name(
+            &mut self,
+            request: impl tonic::IntoRequest<super::QueryModuleAccountByNameRequest>,
+        ) -> Result<tonic::Response<super::QueryModuleAccountByNameResponse>, tonic::Status>
+        {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(
+                    tonic::Code::Unknown,
+                    format!("Service was not ready: {}", e.into()),
+                )
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/cosmos.auth.v1beta1.Query/ModuleAccountByName",
+            );
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+    }
+}
+
+fn decode_module_account(
+    any: &prost_types::Any,
+    action: &Action,
+) -> Result<ModuleAccount, crate::Error> {
+    match Account::decode(any) {
+        Ok(Account::Module(account)) => Ok(account),
+        Ok(_) => Err(crate::Error::InvalidChainResponse {
+            message: format!("Expected a module account, received {}", any.type_url),
+            action: action.clone(),
+        }),
+        Err(source) => Err(crate::Error::InvalidChainResponse {
+            message: format!("Unable to parse {}: {source}", any.type_url),
+            action: action.clone(),
+        }),
+    }
+}
+
+impl Cosmos {
+    /// Get all module accounts, such as the distribution and fee collector accounts.
+    pub async fn module_accounts(&self) -> Result<Vec<ModuleAccount>, crate::Error> {
+        let action = Action::QueryModuleAccounts;
+        self.perform_query(QueryModuleAccountsRequest {}, action.clone(), true)
+            .await?
+            .into_inner()
+            .accounts
+            .iter()
+            .map(|any| decode_module_account(any, &action))
+            .collect()
+    }
+
+    /// Get the module account with the given name, e.g. `distribution`.
+    pub async fn module_account(&self, name: &str) -> Result<ModuleAccount, crate::Error> {
+        let action = Action::QueryModuleAccount(name.to_owned());
+        let account = self
+            .perform_query(
+                QueryModuleAccountByNameRequest {
+                    name: name.to_owned(),
+                },
+                action.clone(),
+                true,
+            )
+            .await?
+            .into_inner()
+            .account
+            .ok_or_else(|| crate::Error::InvalidChainResponse {
+                message: format!("No module account named {name}"),
+                action: action.clone(),
+            })?;
+        decode_module_account(&account, &action)
+    }
+}
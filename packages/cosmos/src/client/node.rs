@@ -17,7 +17,9 @@ use crate::{
     Address, CosmosBuilder,
 };
 
-use super::{node_chooser::QueryResult, CosmosInterceptor, SequenceInformation};
+use super::{
+    node_chooser::QueryResult, proxy::ProxyConnector, CosmosInterceptor, SequenceInformation,
+};
 
 /// Internal data structure containing gRPC clients.
 #[derive(Clone)]
@@ -32,6 +34,12 @@ struct NodeInner {
     channel: InterceptedService<Channel, CosmosInterceptor>,
     simulate_sequences: RwLock<HashMap<Address, SequenceInformation>>,
     broadcast_sequences: RwLock<HashMap<Address, SequenceInformation>>,
+    /// Moving average of successful query latency
+    latency: RwLock<Option<Duration>>,
+    /// Most recent block height reported by this node
+    latest_height: RwLock<Option<i64>>,
+    /// When the block height was last reported
+    height_checked: RwLock<Option<Instant>>,
 }
 
 #[derive(Debug)]
@@ -47,10 +55,23 @@ struct LastError {
 }
 
 impl LastError {
+    /// How long the node is kept out of rotation after its most recent error.
+    ///
+    /// Starts at 30 seconds once the allowed error count is exceeded, and
+    /// doubles for each further consecutive error, up to 8 minutes.
+    fn quarantine(&self, allowed_error_count: usize) -> Duration {
+        const NODE_ERROR_TIMEOUT: Duration = Duration::from_secs(30);
+        const MAX_DOUBLINGS: usize = 4;
+        let excess = self
+            .error_count
+            .saturating_sub(allowed_error_count + 1)
+            .min(MAX_DOUBLINGS);
+        NODE_ERROR_TIMEOUT * (1 << excess)
+    }
+
     fn is_healthy(&self, allowed_error_count: usize) -> bool {
-        const NODE_ERROR_TIMEOUT: u64 = 30;
-        self.instant.elapsed().as_secs() > NODE_ERROR_TIMEOUT
-            || self.error_count <= allowed_error_count
+        self.error_count <= allowed_error_count
+            || self.instant.elapsed() > self.quarantine(allowed_error_count)
     }
 }
 
@@ -76,6 +97,24 @@ impl CosmosBuilder {
         // https://github.com/hyperium/tonic/issues/1033#issuecomment-1537239811
         let grpc_endpoint = grpc_endpoint.origin(uri);
 
+        let grpc_endpoint = grpc_endpoint
+            .connect_timeout(self.connection_timeout())
+            .tcp_keepalive(self.tcp_keepalive())
+            .keep_alive_timeout(self.keep_alive_timeout())
+            .keep_alive_while_idle(self.keep_alive_while_idle())
+            .initial_stream_window_size(self.initial_stream_window_size())
+            .initial_connection_window_size(self.initial_connection_window_size());
+
+        let grpc_endpoint = match self.http2_keep_alive_interval() {
+            Some(interval) => grpc_endpoint.http2_keep_alive_interval(interval),
+            None => grpc_endpoint,
+        };
+
+        let grpc_endpoint = match self.concurrency_limit() {
+            Some(limit) => grpc_endpoint.concurrency_limit(limit),
+            None => grpc_endpoint,
+        };
+
         let grpc_endpoint = if let Some(rate_limit) = self.rate_limit() {
             grpc_endpoint.rate_limit(rate_limit, Duration::from_secs(rate_limit))
         } else {
@@ -93,11 +132,12 @@ impl CosmosBuilder {
             grpc_endpoint
         };
 
-        let grpc_channel = grpc_endpoint.connect_lazy();
-
-        let referer_header = self.referer_header().map(|x| x.to_owned());
+        let grpc_channel = match self.proxy_for_url(grpc_url) {
+            Some(proxy) => grpc_endpoint.connect_with_connector_lazy(ProxyConnector::new(&proxy)?),
+            None => grpc_endpoint.connect_lazy(),
+        };
 
-        let interceptor = CosmosInterceptor(referer_header.map(Arc::new));
+        let interceptor = self.make_interceptor()?;
         let channel = InterceptedService::new(grpc_channel, interceptor);
 
         Ok(Node {
@@ -108,6 +148,9 @@ impl CosmosBuilder {
                 broadcast_sequences: RwLock::new(HashMap::new()),
                 grpc_url: grpc_url.clone(),
                 last_error: RwLock::new(None),
+                latency: RwLock::new(None),
+                latest_height: RwLock::new(None),
+                height_checked: RwLock::new(None),
             }),
         })
     }
@@ -128,25 +171,41 @@ impl Node {
         &self.node_inner.broadcast_sequences
     }
 
-    pub(crate) fn set_broken(&mut self, err: impl FnOnce(Arc<String>) -> ConnectionError) {
+    pub(crate) fn set_broken(&self, err: impl FnOnce(Arc<String>) -> ConnectionError) {
         let err = err(self.node_inner.grpc_url.clone());
         self.log_connection_error(err);
     }
 
     pub(super) fn log_connection_error(&self, error: ConnectionError) {
-        *self.node_inner.last_error.write() = Some(LastError {
+        let mut guard = self.node_inner.last_error.write();
+        // Keep the count of consecutive errors so that the quarantine keeps
+        // growing for a node which never recovers.
+        let error_count = guard.as_ref().map_or(1, |x| x.error_count.max(1));
+        *guard = Some(LastError {
             error: error.to_string().into(),
             instant: Instant::now(),
             timestamp: Utc::now(),
             action: None,
-            error_count: 1,
+            error_count,
         });
     }
 
     pub(super) fn log_query_result(&self, res: QueryResult) {
         let mut guard = self.node_inner.last_error.write();
         match res {
-            QueryResult::Success | QueryResult::OtherError => {
+            QueryResult::Success { latency } => {
+                if let Some(error) = guard.as_mut() {
+                    error.error_count = 0;
+                }
+                let mut average = self.node_inner.latency.write();
+                *average = Some(match *average {
+                    None => latency,
+                    // Exponentially weighted, so a few slow queries don't
+                    // knock out an otherwise fast node.
+                    Some(average) => (average * 4 + latency) / 5,
+                });
+            }
+            QueryResult::OtherError => {
                 if let Some(error) = guard.as_mut() {
                     error.error_count = 0;
                 }
@@ -171,16 +230,68 @@ impl Node {
         }
     }
 
-    pub(crate) fn health_report(&self, allowed_error_count: usize) -> SingleNodeHealthReport {
+    pub(super) fn record_block_height(&self, height: i64) {
+        let mut guard = self.node_inner.latest_height.write();
+        if guard.map_or(true, |old| height > old) {
+            *guard = Some(height);
+        }
+        *self.node_inner.height_checked.write() = Some(Instant::now());
+    }
+
+    /// How long since this node last reported its block height.
+    pub(super) fn height_age(&self) -> Option<Duration> {
+        self.node_inner
+            .height_checked
+            .read()
+            .map(|instant| instant.elapsed())
+    }
+
+    #[cfg(test)]
+    pub(super) fn backdate_height_check(&self, age: Duration) {
+        *self.node_inner.height_checked.write() = Some(Instant::now() - age);
+    }
+
+    pub(super) fn latest_height(&self) -> Option<i64> {
+        *self.node_inner.latest_height.read()
+    }
+
+    pub(super) fn latency(&self) -> Option<Duration> {
+        *self.node_inner.latency.read()
+    }
+
+    /// How many blocks is this node behind the given height?
+    pub(super) fn block_lag(&self, max_height: Option<i64>) -> Option<i64> {
+        Some(max_height? - self.latest_height()?)
+    }
+
+    pub(crate) fn health_report(
+        &self,
+        allowed_error_count: usize,
+        max_height: Option<i64>,
+        block_lag_allowed: u32,
+    ) -> SingleNodeHealthReport {
         let guard = self.node_inner.last_error.read();
         let last_error = guard.as_ref();
+        let block_lag = self.block_lag(max_height);
+        let quarantined_for = last_error.and_then(|last_error| {
+            if last_error.is_healthy(allowed_error_count) {
+                None
+            } else {
+                last_error
+                    .quarantine(allowed_error_count)
+                    .checked_sub(last_error.instant.elapsed())
+            }
+        });
         SingleNodeHealthReport {
             grpc_url: self.node_inner.grpc_url.clone(),
             is_fallback: self.node_inner.is_fallback,
-            is_healthy: last_error.as_ref().map_or(true, |last_error| {
-                last_error.is_healthy(allowed_error_count)
-            }),
+            is_healthy: quarantined_for.is_none()
+                && block_lag.map_or(true, |lag| lag <= block_lag_allowed.into()),
             error_count: last_error.map_or(0, |last_error| last_error.error_count),
+            latency: self.latency(),
+            latest_height: self.latest_height(),
+            block_lag,
+            quarantined_for,
             last_error: last_error.map(|last_error| {
                 let error = match &last_error.action {
                     Some(action) => Arc::new(format!(
@@ -241,6 +352,31 @@ impl Node {
         )
     }
 
+    pub(crate) fn distribution_query_client(
+        &self,
+    ) -> cosmos_sdk_proto::cosmos::distribution::v1beta1::query_client::QueryClient<CosmosChannel>
+    {
+        cosmos_sdk_proto::cosmos::distribution::v1beta1::query_client::QueryClient::new(
+            self.node_inner.channel.clone(),
+        )
+    }
+
+    pub(crate) fn feegrant_query_client(
+        &self,
+    ) -> cosmos_sdk_proto::cosmos::feegrant::v1beta1::query_client::QueryClient<CosmosChannel> {
+        cosmos_sdk_proto::cosmos::feegrant::v1beta1::query_client::QueryClient::new(
+            self.node_inner.channel.clone(),
+        )
+    }
+
+    pub(crate) fn gov_query_client(
+        &self,
+    ) -> cosmos_sdk_proto::cosmos::gov::v1beta1::query_client::QueryClient<CosmosChannel> {
+        cosmos_sdk_proto::cosmos::gov::v1beta1::query_client::QueryClient::new(
+            self.node_inner.channel.clone(),
+        )
+    }
+
     pub(crate) fn authz_query_client(
         &self,
     ) -> cosmos_sdk_proto::cosmos::authz::v1beta1::query_client::QueryClient<CosmosChannel> {
@@ -254,4 +390,105 @@ impl Node {
     ) -> crate::osmosis::epochs::query_client::QueryClient<CosmosChannel> {
         crate::osmosis::epochs::query_client::QueryClient::new(self.node_inner.channel.clone())
     }
+
+    #[cfg(feature = "osmosis")]
+    pub(crate) fn poolmanager_query_client(
+        &self,
+    ) -> crate::osmosis::poolmanager::query_client::QueryClient<CosmosChannel> {
+        crate::osmosis::poolmanager::query_client::QueryClient::new(self.node_inner.channel.clone())
+    }
+
+    #[cfg(feature = "osmosis")]
+    pub(crate) fn twap_query_client(
+        &self,
+    ) -> crate::osmosis::twap::query_client::QueryClient<CosmosChannel> {
+        crate::osmosis::twap::query_client::QueryClient::new(self.node_inner.channel.clone())
+    }
+
+    #[cfg(feature = "osmosis")]
+    pub(crate) fn concentrated_liquidity_query_client(
+        &self,
+    ) -> crate::osmosis::concentrated_liquidity::query_client::QueryClient<CosmosChannel> {
+        crate::osmosis::concentrated_liquidity::query_client::QueryClient::new(
+            self.node_inner.channel.clone(),
+        )
+    }
+
+    #[cfg(feature = "osmosis")]
+    pub(crate) fn lockup_query_client(
+        &self,
+    ) -> crate::osmosis::lockup::query_client::QueryClient<CosmosChannel> {
+        crate::osmosis::lockup::query_client::QueryClient::new(self.node_inner.channel.clone())
+    }
+
+    pub(crate) fn ica_controller_query_client(
+        &self,
+    ) -> crate::ica::query_client::QueryClient<CosmosChannel> {
+        crate::ica::query_client::QueryClient::new(self.node_inner.channel.clone())
+    }
+
+    pub(crate) fn wasm_creator_query_client(
+        &self,
+    ) -> super::wasm_query::query_client::QueryClient<CosmosChannel> {
+        super::wasm_query::query_client::QueryClient::new(self.node_inner.channel.clone())
+    }
+
+    pub(crate) fn fee_market_query_client(
+        &self,
+    ) -> super::fee_market::query_client::QueryClient<CosmosChannel> {
+        super::fee_market::query_client::QueryClient::new(self.node_inner.channel.clone())
+    }
+
+    pub(crate) fn module_account_query_client(
+        &self,
+    ) -> super::module_account::query_client::QueryClient<CosmosChannel> {
+        super::module_account::query_client::QueryClient::new(self.node_inner.channel.clone())
+    }
+
+    pub(crate) fn tokenfactory_query_client(
+        &self,
+    ) -> crate::tokenfactory::query_client::QueryClient<CosmosChannel> {
+        crate::tokenfactory::query_client::QueryClient::new(self.node_inner.channel.clone())
+    }
+
+    pub(crate) fn node_status_client(
+        &self,
+    ) -> super::node_status::service_client::ServiceClient<CosmosChannel> {
+        super::node_status::service_client::ServiceClient::new(self.node_inner.channel.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn last_error(error_count: usize, age: Duration) -> LastError {
+        LastError {
+            error: Arc::new("test".to_owned()),
+            instant: Instant::now() - age,
+            timestamp: Utc::now(),
+            action: None,
+            error_count,
+        }
+    }
+
+    #[test]
+    fn quarantine_backoff() {
+        let allowed = 3;
+        assert!(last_error(3, Duration::ZERO).is_healthy(allowed));
+        assert_eq!(
+            last_error(4, Duration::ZERO).quarantine(allowed),
+            Duration::from_secs(30)
+        );
+        assert_eq!(
+            last_error(6, Duration::ZERO).quarantine(allowed),
+            Duration::from_secs(120)
+        );
+        assert_eq!(
+            last_error(100, Duration::ZERO).quarantine(allowed),
+            Duration::from_secs(480)
+        );
+        assert!(!last_error(5, Duration::from_secs(45)).is_healthy(allowed));
+        assert!(last_error(5, Duration::from_secs(61)).is_healthy(allowed));
+    }
 }
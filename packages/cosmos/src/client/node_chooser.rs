@@ -1,4 +1,4 @@
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 
 use rand::seq::SliceRandom;
 
@@ -13,8 +13,12 @@ use super::node::Node;
 pub(super) struct NodeChooser {
     primary: Arc<Node>,
     fallbacks: Arc<[Node]>,
+    /// Nodes with full history, preferred for height-pinned queries.
+    archives: Arc<[Node]>,
     /// How many errors in a row are allowed before we call a node unhealthy?
     allowed_error_count: usize,
+    /// How many blocks behind the most up to date node before we avoid a node?
+    block_lag_allowed: u32,
 }
 
 impl NodeChooser {
@@ -27,50 +31,175 @@ impl NodeChooser {
                 .map(|fallback| builder.make_node(fallback, true))
                 .collect::<Result<Vec<_>, _>>()?
                 .into(),
+            archives: builder
+                .grpc_archive_urls()
+                .iter()
+                .map(|archive| builder.make_node(archive, true))
+                .collect::<Result<Vec<_>, _>>()?
+                .into(),
             allowed_error_count: builder.get_allowed_error_count(),
+            block_lag_allowed: builder.block_lag_allowed(),
         })
     }
 
+    /// Choose the primary if it's usable, otherwise the fastest usable fallback.
+    ///
+    /// Nodes without a latency measurement yet are tried first, so that every
+    /// fallback gets measured. If nothing is usable, the primary is returned.
     pub(super) fn choose_node(&self) -> &Node {
-        if self.primary.is_healthy(self.allowed_error_count) {
+        let max_height = self.max_height();
+        if self.is_usable(&self.primary, max_height) {
             &self.primary
         } else {
-            let fallbacks = self
-                .fallbacks
+            self.fallbacks
                 .iter()
-                .filter(|node| node.is_healthy(self.allowed_error_count))
-                .collect::<Vec<_>>();
-            let mut rng = rand::thread_rng();
-            fallbacks
-                .as_slice()
-                .choose(&mut rng)
-                .copied()
+                .filter(|node| self.is_usable(node, max_height))
+                .min_by_key(|node| node.latency().unwrap_or(Duration::ZERO))
                 .unwrap_or(&self.primary)
         }
     }
 
+    /// Choose up to `count` distinct usable nodes, starting with [Self::choose_node].
+    ///
+    /// Additional nodes are ordered by latency. Always returns at least one node.
+    pub(super) fn choose_nodes(&self, count: usize) -> Vec<&Node> {
+        let first = self.choose_node();
+        let max_height = self.max_height();
+        let mut rest = std::iter::once(&*self.primary)
+            .chain(self.fallbacks.iter())
+            .filter(|node| node.grpc_url() != first.grpc_url() && self.is_usable(node, max_height))
+            .collect::<Vec<_>>();
+        rest.sort_by_key(|node| node.latency().unwrap_or(Duration::ZERO));
+        std::iter::once(first)
+            .chain(rest)
+            .take(count.max(1))
+            .collect()
+    }
+
+    /// Not quarantined due to errors, and not too far behind the other nodes
+    ///
+    /// A node's height is only refreshed when it's queried, so a lagging node
+    /// is given another try once its height is older than [LAG_RECHECK].
+    /// Otherwise it would stay excluded even after catching up.
+    fn is_usable(&self, node: &Node, max_height: Option<i64>) -> bool {
+        node.is_healthy(self.allowed_error_count)
+            && match node.block_lag(max_height) {
+                Some(lag) if lag > self.block_lag_allowed.into() => {
+                    node.height_age().map_or(true, |age| age > LAG_RECHECK)
+                }
+                _ => true,
+            }
+    }
+
+    /// Highest block height seen from any node
+    fn max_height(&self) -> Option<i64> {
+        self.all_nodes_with_archives()
+            .filter_map(Node::latest_height)
+            .max()
+    }
+
+    /// Choose a healthy archive node, if any are configured.
+    pub(super) fn choose_archive_node(&self) -> Option<&Node> {
+        let archives = self
+            .archives
+            .iter()
+            .filter(|node| node.is_healthy(self.allowed_error_count))
+            .collect::<Vec<_>>();
+        let mut rng = rand::thread_rng();
+        archives.as_slice().choose(&mut rng).copied()
+    }
+
     pub(super) fn health_report(&self) -> NodeHealthReport {
+        let max_height = self.max_height();
         NodeHealthReport {
-            nodes: std::iter::once(self.primary.health_report(self.allowed_error_count))
-                .chain(
-                    self.fallbacks
-                        .iter()
-                        .map(|node| node.health_report(self.allowed_error_count)),
-                )
+            nodes: self
+                .all_nodes_with_archives()
+                .map(|node| {
+                    node.health_report(self.allowed_error_count, max_height, self.block_lag_allowed)
+                })
                 .collect(),
         }
     }
 
+    /// The primary and fallback nodes.
+    ///
+    /// Archive nodes are reserved for historical queries, see [Self::choose_archive_node].
     pub(super) fn all_nodes(&self) -> impl Iterator<Item = &Node> {
         std::iter::once(&*self.primary).chain(self.fallbacks.iter())
     }
+
+    /// Every configured node, including archives, for health reporting.
+    fn all_nodes_with_archives(&self) -> impl Iterator<Item = &Node> {
+        self.all_nodes().chain(self.archives.iter())
+    }
 }
 
+/// How long a node excluded for lagging is skipped before it's tried again.
+const LAG_RECHECK: Duration = Duration::from_secs(30);
+
 pub(crate) enum QueryResult {
-    Success,
+    Success {
+        latency: Duration,
+    },
     NetworkError {
         err: QueryErrorDetails,
         action: Action,
     },
     OtherError,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::AddressHrp;
+
+    #[tokio::test]
+    async fn lagging_node_is_rechecked() {
+        let mut builder = CosmosBuilder::new(
+            "osmosis-1",
+            "uosmo",
+            AddressHrp::from_static("osmo"),
+            "http://127.0.0.1:1",
+        );
+        builder.add_grpc_fallback_url("http://127.0.0.1:2");
+        builder.set_block_lag_allowed(Some(10));
+        let chooser = NodeChooser::new(&builder).unwrap();
+        let primary = chooser.primary.clone();
+        let fallback = &chooser.fallbacks[0];
+
+        primary.record_block_height(100);
+        fallback.record_block_height(200);
+        assert_eq!(chooser.choose_node().grpc_url(), fallback.grpc_url());
+
+        // Not queried for a while, so give it another try
+        primary.backdate_height_check(LAG_RECHECK * 2);
+        assert_eq!(chooser.choose_node().grpc_url(), primary.grpc_url());
+
+        // Still lagging when queried, excluded again
+        primary.record_block_height(150);
+        assert_eq!(chooser.choose_node().grpc_url(), fallback.grpc_url());
+
+        // Caught up
+        primary.record_block_height(200);
+        assert_eq!(chooser.choose_node().grpc_url(), primary.grpc_url());
+    }
+
+    #[tokio::test]
+    async fn archives_not_in_all_nodes() {
+        let mut builder = CosmosBuilder::new(
+            "osmosis-1",
+            "uosmo",
+            AddressHrp::from_static("osmo"),
+            "http://127.0.0.1:1",
+        );
+        builder.add_grpc_fallback_url("http://127.0.0.1:2");
+        builder.add_grpc_archive_url("http://127.0.0.1:3");
+        let chooser = NodeChooser::new(&builder).unwrap();
+        assert_eq!(chooser.all_nodes().count(), 2);
+        assert_eq!(chooser.health_report().nodes.len(), 3);
+        assert_eq!(
+            chooser.choose_archive_node().unwrap().grpc_url().as_str(),
+            "http://127.0.0.1:3"
+        );
+    }
+}
@@ -0,0 +1,92 @@
+//! The `cosmos.base.node.v1beta1.Service` queries: `Config`, added in Cosmos
+//! SDK 0.46, and `Status`, added in Cosmos SDK 0.50.
+//!
+//! Not included in our version of the protobuf definitions.
+
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ConfigRequest {}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ConfigResponse {
+    /// minimum gas prices the node accepts, e.g. `0.0025uosmo`
+    #[prost(string, tag = "1")]
+    pub minimum_gas_price: ::prost::alloc::string::String,
+}
+
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct StatusRequest {}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct StatusResponse {
+    /// earliest block height available in the store
+    #[prost(uint64, tag = "1")]
+    pub earliest_store_height: u64,
+    /// current block height
+    #[prost(uint64, tag = "2")]
+    pub height: u64,
+    /// block height timestamp
+    #[prost(message, optional, tag = "3")]
+    pub timestamp: ::core::option::Option<::prost_types::Timestamp>,
+    /// app hash of the current block
+    #[prost(bytes = "vec", tag = "4")]
+    pub app_hash: ::prost::alloc::vec::Vec<u8>,
+    /// validator hash provided by the consensus header
+    #[prost(bytes = "vec", tag = "5")]
+    pub validator_hash: ::prost::alloc::vec::Vec<u8>,
+}
+/// Generated client implementations.
+pub mod service_client {
+    #![allow(unused_variables, dead_code, missing_docs, clippy::let_unit_value)]
+    use tonic::codegen::*;
+    /// Service defines the gRPC querier service for node related queries.
+    #[derive(Debug, Clone)]
+    pub struct ServiceClient<T> {
+        inner: tonic::client::Grpc<T>,
+    }
+    impl<T> ServiceClient<T>
+    where
+        T: tonic::client::GrpcService<tonic::body::BoxBody>,
+        T::Error: Into<StdError>,
+        T::ResponseBody: Body<Data = Bytes> + Send + 'static,
+        <T::ResponseBody as Body>::Error: Into<StdError> + Send,
+    {
+        pub fn new(inner: T) -> Self {
+            let inner = tonic::client::Grpc::new(inner);
+            Self { inner }
+        }
+        /// Config queries for the operator configuration.
+        pub async fn config(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ConfigRequest>,
+        ) -> Result<tonic::Response<super::ConfigResponse>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(
+                    tonic::Code::Unknown,
+                    format!("Service was not ready: {}", e.into()),
+                )
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path =
+                http::uri::PathAndQuery::from_static("/cosmos.base.node.v1beta1.Service/Config");
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+        /// Status queries for the node status.
+        pub async fn status(
+            &mut self,
+            request: impl tonic::IntoRequest<super::StatusRequest>,
+        ) -> Result<tonic::Response<super::StatusResponse>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(
+                    tonic::Code::Unknown,
+                    format!("Service was not ready: {}", e.into()),
+                )
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path =
+                http::uri::PathAndQuery::from_static("/cosmos.base.node.v1beta1.Service/Status");
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+    }
+}
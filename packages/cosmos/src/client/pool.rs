@@ -1,4 +1,6 @@
-use std::sync::Arc;
+use std::{collections::HashSet, sync::Arc};
+
+use parking_lot::Mutex;
 
 use tokio::sync::{OwnedSemaphorePermit, Semaphore};
 
@@ -7,7 +9,9 @@ use crate::{
     CosmosBuilder,
 };
 
-use super::{node::Node, node_chooser::NodeChooser};
+use super::{
+    cache::QueryCache, fixture::Fixture, lcd::Lcd, node::Node, node_chooser::NodeChooser, rpc::Rpc,
+};
 
 #[derive(Clone)]
 pub(super) struct Pool {
@@ -15,6 +19,16 @@ pub(super) struct Pool {
     pub(super) node_chooser: NodeChooser,
     /// Permits for enforcing global concurrent request count.
     semaphore: Arc<Semaphore>,
+    /// REST fallback used when no gRPC node can serve a request.
+    pub(super) lcd: Option<Arc<Lcd>>,
+    /// Tendermint RPC endpoint for data not available over gRPC.
+    pub(super) rpc: Option<Arc<Rpc>>,
+    /// Cache of query responses, if enabled.
+    pub(super) cache: Option<Arc<QueryCache>>,
+    /// Recorded responses, if recording or replaying.
+    pub(super) fixture: Option<Arc<Fixture>>,
+    /// Services found to be missing by [crate::Cosmos::probe_query].
+    pub(super) absent_services: Arc<Mutex<HashSet<&'static str>>>,
 }
 
 pub(super) struct NodeGuard {
@@ -23,8 +37,8 @@ pub(super) struct NodeGuard {
 }
 
 impl NodeGuard {
-    pub(crate) fn get_inner_mut(&mut self) -> &mut Node {
-        &mut self.inner
+    pub(crate) fn get_inner(&self) -> &Node {
+        &self.inner
     }
 }
 
@@ -32,10 +46,33 @@ impl Pool {
     pub(super) fn new(builder: Arc<CosmosBuilder>) -> Result<Self, BuilderError> {
         let node_chooser = NodeChooser::new(&builder)?;
         let semaphore = Arc::new(Semaphore::new(builder.request_count()));
+        let lcd = builder
+            .lcd_url()
+            .map(|url| Lcd::new(&builder, url))
+            .transpose()?
+            .map(Arc::new);
+        let rpc = builder
+            .rpc_url()
+            .map(|url| Rpc::new(&builder, url))
+            .transpose()?
+            .map(Arc::new);
+        let cache = builder
+            .query_cache()
+            .map(|config| Arc::new(QueryCache::new(config.clone())));
+        let fixture = builder
+            .fixture()
+            .map(Fixture::new)
+            .transpose()?
+            .map(Arc::new);
         Ok(Pool {
             builder,
             node_chooser,
             semaphore,
+            lcd,
+            rpc,
+            cache,
+            fixture,
+            absent_services: Arc::default(),
         })
     }
 
@@ -54,6 +91,14 @@ impl Pool {
         })
     }
 
+    /// Like [Self::get], but prefers an archive node for historical queries.
+    pub(super) async fn get_historical(&self) -> Result<NodeGuard, ConnectionError> {
+        match self.node_chooser.choose_archive_node() {
+            Some(node) => self.get_with_node(node).await,
+            None => self.get().await,
+        }
+    }
+
     pub(crate) async fn get_with_node(&self, node: &Node) -> Result<NodeGuard, ConnectionError> {
         let permit = self
             .semaphore
@@ -1,104 +1,413 @@
 use cosmos_sdk_proto::{
     cosmos::{
-        auth::v1beta1::{QueryAccountRequest, QueryAccountResponse},
+        auth::v1beta1::{
+            QueryAccountRequest, QueryAccountResponse, QueryAccountsRequest, QueryAccountsResponse,
+        },
         authz::v1beta1::{
             QueryGranteeGrantsRequest, QueryGranteeGrantsResponse, QueryGranterGrantsRequest,
-            QueryGranterGrantsResponse,
+            QueryGranterGrantsResponse, QueryGrantsRequest, QueryGrantsResponse,
+        },
+        bank::v1beta1::{
+            QueryAllBalancesRequest, QueryAllBalancesResponse, QueryDenomMetadataRequest,
+            QueryDenomMetadataResponse,
         },
-        bank::v1beta1::{QueryAllBalancesRequest, QueryAllBalancesResponse},
         base::tendermint::v1beta1::{
             GetBlockByHeightRequest, GetBlockByHeightResponse, GetLatestBlockRequest,
             GetLatestBlockResponse,
         },
+        distribution::v1beta1::{
+            QueryCommunityPoolRequest, QueryCommunityPoolResponse, QueryDelegationRewardsRequest,
+            QueryDelegationRewardsResponse, QueryDelegationTotalRewardsRequest,
+            QueryDelegationTotalRewardsResponse, QueryDelegatorWithdrawAddressRequest,
+            QueryDelegatorWithdrawAddressResponse,
+        },
+        feegrant::v1beta1::{
+            QueryAllowanceRequest, QueryAllowanceResponse, QueryAllowancesRequest,
+            QueryAllowancesResponse,
+        },
+        gov::v1beta1::{
+            QueryProposalRequest, QueryProposalResponse, QueryProposalsRequest,
+            QueryProposalsResponse, QueryTallyResultRequest, QueryTallyResultResponse,
+            QueryVotesRequest, QueryVotesResponse,
+        },
         tx::v1beta1::{
             BroadcastTxRequest, BroadcastTxResponse, GetTxRequest, GetTxResponse,
             GetTxsEventRequest, GetTxsEventResponse, SimulateRequest, SimulateResponse,
         },
     },
     cosmwasm::wasm::v1::{
-        QueryCodeRequest, QueryCodeResponse, QueryContractHistoryRequest,
+        QueryAllContractStateRequest, QueryAllContractStateResponse, QueryCodeRequest,
+        QueryCodeResponse, QueryCodesRequest, QueryCodesResponse, QueryContractHistoryRequest,
         QueryContractHistoryResponse, QueryContractInfoRequest, QueryContractInfoResponse,
-        QueryRawContractStateRequest, QueryRawContractStateResponse,
-        QuerySmartContractStateRequest, QuerySmartContractStateResponse,
+        QueryContractsByCodeRequest, QueryContractsByCodeResponse,
+        QueryParamsRequest as WasmQueryParamsRequest,
+        QueryParamsResponse as WasmQueryParamsResponse, QueryRawContractStateRequest,
+        QueryRawContractStateResponse, QuerySmartContractStateRequest,
+        QuerySmartContractStateResponse,
     },
 };
 use tonic::async_trait;
 
-use crate::osmosis::epochs::{QueryEpochsInfoRequest, QueryEpochsInfoResponse};
+use crate::{
+    ica::{QueryInterchainAccountRequest, QueryInterchainAccountResponse},
+    osmosis::epochs::{
+        QueryCurrentEpochRequest, QueryCurrentEpochResponse, QueryEpochsInfoRequest,
+        QueryEpochsInfoResponse,
+    },
+    tokenfactory::{
+        QueryDenomsFromCreatorRequest, QueryDenomsFromCreatorResponse,
+        SeiQueryDenomsFromCreatorRequest,
+    },
+    QueryCacheConfig,
+};
 
-use super::node::Node;
+use super::{
+    cache::CacheLifetime,
+    fee_market::{
+        GasPricesRequest, GasPricesResponse, QueryEipBaseFeeRequest, QueryEipBaseFeeResponse,
+    },
+    lcd::Lcd,
+    module_account::{
+        QueryModuleAccountByNameRequest, QueryModuleAccountByNameResponse,
+        QueryModuleAccountsRequest, QueryModuleAccountsResponse,
+    },
+    node::Node,
+    node_status::{ConfigRequest, ConfigResponse, StatusRequest, StatusResponse},
+    wasm_query::{QueryContractsByCreatorRequest, QueryContractsByCreatorResponse},
+};
 
 #[async_trait]
-pub(crate) trait GrpcRequest: Clone + Sized {
-    type Response;
+pub(crate) trait GrpcRequest: Clone + Sized + prost::Message {
+    type Response: prost::Message + Default;
+
+    /// Full gRPC method path, such as `/cosmos.bank.v1beta1.Query/AllBalances`.
+    const METHOD: &'static str;
+
+    /// Does this request broadcast a transaction? Used to pick the timeout.
+    const IS_BROADCAST: bool = false;
 
     async fn perform(
         req: tonic::Request<Self>,
-        inner: &mut Node,
+        inner: &Node,
     ) -> Result<tonic::Response<Self::Response>, tonic::Status>;
+
+    /// How long responses can be cached for, [None] if they shouldn't be.
+    fn cache_lifetime(_config: &QueryCacheConfig) -> Option<CacheLifetime> {
+        None
+    }
+
+    /// Perform the same request over the LCD API, if supported.
+    async fn perform_lcd(
+        &self,
+        _lcd: &Lcd,
+        _height: Option<u64>,
+    ) -> Option<Result<Self::Response, String>> {
+        None
+    }
 }
 
 #[async_trait]
 impl GrpcRequest for QueryAccountRequest {
     type Response = QueryAccountResponse;
+    const METHOD: &'static str = "/cosmos.auth.v1beta1.Query/Account";
     async fn perform(
         req: tonic::Request<Self>,
-        inner: &mut Node,
+        inner: &Node,
     ) -> Result<tonic::Response<Self::Response>, tonic::Status> {
         inner.auth_query_client().account(req).await
     }
+
+    async fn perform_lcd(
+        &self,
+        lcd: &Lcd,
+        height: Option<u64>,
+    ) -> Option<Result<Self::Response, String>> {
+        Some(lcd.account(self, height).await)
+    }
+
+    fn cache_lifetime(config: &QueryCacheConfig) -> Option<CacheLifetime> {
+        config.account_ttl.map(CacheLifetime::Ttl)
+    }
+}
+
+#[async_trait]
+impl GrpcRequest for QueryAccountsRequest {
+    type Response = QueryAccountsResponse;
+    const METHOD: &'static str = "/cosmos.auth.v1beta1.Query/Accounts";
+    async fn perform(
+        req: tonic::Request<Self>,
+        inner: &Node,
+    ) -> Result<tonic::Response<Self::Response>, tonic::Status> {
+        inner.auth_query_client().accounts(req).await
+    }
+}
+
+#[async_trait]
+impl GrpcRequest for ConfigRequest {
+    type Response = ConfigResponse;
+    const METHOD: &'static str = "/cosmos.base.node.v1beta1.Service/Config";
+    async fn perform(
+        req: tonic::Request<Self>,
+        inner: &Node,
+    ) -> Result<tonic::Response<Self::Response>, tonic::Status> {
+        inner.node_status_client().config(req).await
+    }
+}
+
+#[async_trait]
+impl GrpcRequest for StatusRequest {
+    type Response = StatusResponse;
+    const METHOD: &'static str = "/cosmos.base.node.v1beta1.Service/Status";
+    async fn perform(
+        req: tonic::Request<Self>,
+        inner: &Node,
+    ) -> Result<tonic::Response<Self::Response>, tonic::Status> {
+        inner.node_status_client().status(req).await
+    }
+}
+
+#[async_trait]
+impl GrpcRequest for QueryEipBaseFeeRequest {
+    type Response = QueryEipBaseFeeResponse;
+    const METHOD: &'static str = "/osmosis.txfees.v1beta1.Query/GetEipBaseFee";
+    async fn perform(
+        req: tonic::Request<Self>,
+        inner: &Node,
+    ) -> Result<tonic::Response<Self::Response>, tonic::Status> {
+        inner.fee_market_query_client().get_eip_base_fee(req).await
+    }
+}
+
+#[async_trait]
+impl GrpcRequest for GasPricesRequest {
+    type Response = GasPricesResponse;
+    const METHOD: &'static str = "/feemarket.feemarket.v1.Query/GasPrices";
+    async fn perform(
+        req: tonic::Request<Self>,
+        inner: &Node,
+    ) -> Result<tonic::Response<Self::Response>, tonic::Status> {
+        inner.fee_market_query_client().gas_prices(req).await
+    }
+}
+
+#[async_trait]
+impl GrpcRequest for QueryModuleAccountsRequest {
+    type Response = QueryModuleAccountsResponse;
+    const METHOD: &'static str = "/cosmos.auth.v1beta1.Query/ModuleAccounts";
+    async fn perform(
+        req: tonic::Request<Self>,
+        inner: &Node,
+    ) -> Result<tonic::Response<Self::Response>, tonic::Status> {
+        inner
+            .module_account_query_client()
+            .module_accounts(req)
+            .await
+    }
+}
+
+#[async_trait]
+impl GrpcRequest for QueryModuleAccountByNameRequest {
+    type Response = QueryModuleAccountByNameResponse;
+    const METHOD: &'static str = "/cosmos.auth.v1beta1.Query/ModuleAccountByName";
+    async fn perform(
+        req: tonic::Request<Self>,
+        inner: &Node,
+    ) -> Result<tonic::Response<Self::Response>, tonic::Status> {
+        inner
+            .module_account_query_client()
+            .module_account_by_name(req)
+            .await
+    }
+}
+
+#[async_trait]
+impl GrpcRequest for QueryCommunityPoolRequest {
+    type Response = QueryCommunityPoolResponse;
+    const METHOD: &'static str = "/cosmos.distribution.v1beta1.Query/CommunityPool";
+    async fn perform(
+        req: tonic::Request<Self>,
+        inner: &Node,
+    ) -> Result<tonic::Response<Self::Response>, tonic::Status> {
+        inner.distribution_query_client().community_pool(req).await
+    }
+}
+
+#[async_trait]
+impl GrpcRequest for QueryDenomsFromCreatorRequest {
+    type Response = QueryDenomsFromCreatorResponse;
+    const METHOD: &'static str = "/osmosis.tokenfactory.v1beta1.Query/DenomsFromCreator";
+    async fn perform(
+        req: tonic::Request<Self>,
+        inner: &Node,
+    ) -> Result<tonic::Response<Self::Response>, tonic::Status> {
+        inner
+            .tokenfactory_query_client()
+            .denoms_from_creator(req)
+            .await
+    }
+}
+
+#[async_trait]
+impl GrpcRequest for SeiQueryDenomsFromCreatorRequest {
+    type Response = QueryDenomsFromCreatorResponse;
+    const METHOD: &'static str = "/seiprotocol.seichain.tokenfactory.Query/DenomsFromCreator";
+    async fn perform(
+        req: tonic::Request<Self>,
+        inner: &Node,
+    ) -> Result<tonic::Response<Self::Response>, tonic::Status> {
+        inner
+            .tokenfactory_query_client()
+            .sei_denoms_from_creator(req)
+            .await
+    }
 }
 
 #[async_trait]
 impl GrpcRequest for QueryAllBalancesRequest {
     type Response = QueryAllBalancesResponse;
+    const METHOD: &'static str = "/cosmos.bank.v1beta1.Query/AllBalances";
     async fn perform(
         req: tonic::Request<Self>,
-        inner: &mut Node,
+        inner: &Node,
     ) -> Result<tonic::Response<Self::Response>, tonic::Status> {
         inner.bank_query_client().all_balances(req).await
     }
+
+    async fn perform_lcd(
+        &self,
+        lcd: &Lcd,
+        height: Option<u64>,
+    ) -> Option<Result<Self::Response, String>> {
+        Some(lcd.all_balances(self, height).await)
+    }
 }
 
 #[async_trait]
 impl GrpcRequest for QuerySmartContractStateRequest {
     type Response = QuerySmartContractStateResponse;
+    const METHOD: &'static str = "/cosmwasm.wasm.v1.Query/SmartContractState";
     async fn perform(
         req: tonic::Request<Self>,
-        inner: &mut Node,
+        inner: &Node,
     ) -> Result<tonic::Response<Self::Response>, tonic::Status> {
         inner.wasm_query_client().smart_contract_state(req).await
     }
+
+    async fn perform_lcd(
+        &self,
+        lcd: &Lcd,
+        height: Option<u64>,
+    ) -> Option<Result<Self::Response, String>> {
+        Some(lcd.smart_contract_state(self, height).await)
+    }
 }
 
 #[async_trait]
 impl GrpcRequest for QueryRawContractStateRequest {
     type Response = QueryRawContractStateResponse;
+    const METHOD: &'static str = "/cosmwasm.wasm.v1.Query/RawContractState";
     async fn perform(
         req: tonic::Request<Self>,
-        inner: &mut Node,
+        inner: &Node,
     ) -> Result<tonic::Response<Self::Response>, tonic::Status> {
         inner.wasm_query_client().raw_contract_state(req).await
     }
+
+    async fn perform_lcd(
+        &self,
+        lcd: &Lcd,
+        height: Option<u64>,
+    ) -> Option<Result<Self::Response, String>> {
+        Some(lcd.raw_contract_state(self, height).await)
+    }
+}
+
+#[async_trait]
+impl GrpcRequest for QueryAllContractStateRequest {
+    type Response = QueryAllContractStateResponse;
+    const METHOD: &'static str = "/cosmwasm.wasm.v1.Query/AllContractState";
+    async fn perform(
+        req: tonic::Request<Self>,
+        inner: &Node,
+    ) -> Result<tonic::Response<Self::Response>, tonic::Status> {
+        inner.wasm_query_client().all_contract_state(req).await
+    }
+}
+
+#[async_trait]
+impl GrpcRequest for QueryCodesRequest {
+    type Response = QueryCodesResponse;
+    const METHOD: &'static str = "/cosmwasm.wasm.v1.Query/Codes";
+    async fn perform(
+        req: tonic::Request<Self>,
+        inner: &Node,
+    ) -> Result<tonic::Response<Self::Response>, tonic::Status> {
+        inner.wasm_query_client().codes(req).await
+    }
+}
+
+#[async_trait]
+impl GrpcRequest for QueryContractsByCodeRequest {
+    type Response = QueryContractsByCodeResponse;
+    const METHOD: &'static str = "/cosmwasm.wasm.v1.Query/ContractsByCode";
+    async fn perform(
+        req: tonic::Request<Self>,
+        inner: &Node,
+    ) -> Result<tonic::Response<Self::Response>, tonic::Status> {
+        inner.wasm_query_client().contracts_by_code(req).await
+    }
+}
+
+#[async_trait]
+impl GrpcRequest for QueryContractsByCreatorRequest {
+    type Response = QueryContractsByCreatorResponse;
+    const METHOD: &'static str = "/cosmwasm.wasm.v1.Query/ContractsByCreator";
+    async fn perform(
+        req: tonic::Request<Self>,
+        inner: &Node,
+    ) -> Result<tonic::Response<Self::Response>, tonic::Status> {
+        inner
+            .wasm_creator_query_client()
+            .contracts_by_creator(req)
+            .await
+    }
+}
+
+#[async_trait]
+impl GrpcRequest for WasmQueryParamsRequest {
+    type Response = WasmQueryParamsResponse;
+    const METHOD: &'static str = "/cosmwasm.wasm.v1.Query/Params";
+    async fn perform(
+        req: tonic::Request<Self>,
+        inner: &Node,
+    ) -> Result<tonic::Response<Self::Response>, tonic::Status> {
+        inner.wasm_query_client().params(req).await
+    }
 }
 
 #[async_trait]
 impl GrpcRequest for QueryCodeRequest {
     type Response = QueryCodeResponse;
+    const METHOD: &'static str = "/cosmwasm.wasm.v1.Query/Code";
     async fn perform(
         req: tonic::Request<Self>,
-        inner: &mut Node,
+        inner: &Node,
     ) -> Result<tonic::Response<Self::Response>, tonic::Status> {
         inner.wasm_query_client().code(req).await
     }
+
+    fn cache_lifetime(_config: &QueryCacheConfig) -> Option<CacheLifetime> {
+        Some(CacheLifetime::Forever)
+    }
 }
 
 #[async_trait]
 impl GrpcRequest for GetTxRequest {
     type Response = GetTxResponse;
+    const METHOD: &'static str = "/cosmos.tx.v1beta1.Service/GetTx";
     async fn perform(
         req: tonic::Request<Self>,
-        inner: &mut Node,
+        inner: &Node,
     ) -> Result<tonic::Response<Self::Response>, tonic::Status> {
         inner.tx_service_client().get_tx(req).await
     }
@@ -107,9 +416,10 @@ impl GrpcRequest for GetTxRequest {
 #[async_trait]
 impl GrpcRequest for GetTxsEventRequest {
     type Response = GetTxsEventResponse;
+    const METHOD: &'static str = "/cosmos.tx.v1beta1.Service/GetTxsEvent";
     async fn perform(
         req: tonic::Request<Self>,
-        inner: &mut Node,
+        inner: &Node,
     ) -> Result<tonic::Response<Self::Response>, tonic::Status> {
         inner.tx_service_client().get_txs_event(req).await
     }
@@ -118,20 +428,26 @@ impl GrpcRequest for GetTxsEventRequest {
 #[async_trait]
 impl GrpcRequest for QueryContractInfoRequest {
     type Response = QueryContractInfoResponse;
+    const METHOD: &'static str = "/cosmwasm.wasm.v1.Query/ContractInfo";
     async fn perform(
         req: tonic::Request<Self>,
-        inner: &mut Node,
+        inner: &Node,
     ) -> Result<tonic::Response<Self::Response>, tonic::Status> {
         inner.wasm_query_client().contract_info(req).await
     }
+
+    fn cache_lifetime(config: &QueryCacheConfig) -> Option<CacheLifetime> {
+        config.contract_info_ttl.map(CacheLifetime::Ttl)
+    }
 }
 
 #[async_trait]
 impl GrpcRequest for QueryContractHistoryRequest {
     type Response = QueryContractHistoryResponse;
+    const METHOD: &'static str = "/cosmwasm.wasm.v1.Query/ContractHistory";
     async fn perform(
         req: tonic::Request<Self>,
-        inner: &mut Node,
+        inner: &Node,
     ) -> Result<tonic::Response<Self::Response>, tonic::Status> {
         inner.wasm_query_client().contract_history(req).await
     }
@@ -140,20 +456,26 @@ impl GrpcRequest for QueryContractHistoryRequest {
 #[async_trait]
 impl GrpcRequest for GetBlockByHeightRequest {
     type Response = GetBlockByHeightResponse;
+    const METHOD: &'static str = "/cosmos.base.tendermint.v1beta1.Service/GetBlockByHeight";
     async fn perform(
         req: tonic::Request<Self>,
-        inner: &mut Node,
+        inner: &Node,
     ) -> Result<tonic::Response<Self::Response>, tonic::Status> {
         inner.tendermint_client().get_block_by_height(req).await
     }
+
+    fn cache_lifetime(_config: &QueryCacheConfig) -> Option<CacheLifetime> {
+        Some(CacheLifetime::Forever)
+    }
 }
 
 #[async_trait]
 impl GrpcRequest for GetLatestBlockRequest {
     type Response = GetLatestBlockResponse;
+    const METHOD: &'static str = "/cosmos.base.tendermint.v1beta1.Service/GetLatestBlock";
     async fn perform(
         req: tonic::Request<Self>,
-        inner: &mut Node,
+        inner: &Node,
     ) -> Result<tonic::Response<Self::Response>, tonic::Status> {
         inner.tendermint_client().get_latest_block(req).await
     }
@@ -162,9 +484,10 @@ impl GrpcRequest for GetLatestBlockRequest {
 #[async_trait]
 impl GrpcRequest for SimulateRequest {
     type Response = SimulateResponse;
+    const METHOD: &'static str = "/cosmos.tx.v1beta1.Service/Simulate";
     async fn perform(
         req: tonic::Request<Self>,
-        inner: &mut Node,
+        inner: &Node,
     ) -> Result<tonic::Response<Self::Response>, tonic::Status> {
         inner.tx_service_client().simulate(req).await
     }
@@ -173,20 +496,31 @@ impl GrpcRequest for SimulateRequest {
 #[async_trait]
 impl GrpcRequest for BroadcastTxRequest {
     type Response = BroadcastTxResponse;
+    const METHOD: &'static str = "/cosmos.tx.v1beta1.Service/BroadcastTx";
+    const IS_BROADCAST: bool = true;
     async fn perform(
         req: tonic::Request<Self>,
-        inner: &mut Node,
+        inner: &Node,
     ) -> Result<tonic::Response<Self::Response>, tonic::Status> {
         inner.tx_service_client().broadcast_tx(req).await
     }
+
+    async fn perform_lcd(
+        &self,
+        lcd: &Lcd,
+        _height: Option<u64>,
+    ) -> Option<Result<Self::Response, String>> {
+        Some(lcd.broadcast_tx(self).await)
+    }
 }
 
 #[async_trait]
 impl GrpcRequest for QueryGranterGrantsRequest {
     type Response = QueryGranterGrantsResponse;
+    const METHOD: &'static str = "/cosmos.authz.v1beta1.Query/GranterGrants";
     async fn perform(
         req: tonic::Request<Self>,
-        inner: &mut Node,
+        inner: &Node,
     ) -> Result<tonic::Response<Self::Response>, tonic::Status> {
         inner.authz_query_client().granter_grants(req).await
     }
@@ -195,21 +529,316 @@ impl GrpcRequest for QueryGranterGrantsRequest {
 #[async_trait]
 impl GrpcRequest for QueryGranteeGrantsRequest {
     type Response = QueryGranteeGrantsResponse;
+    const METHOD: &'static str = "/cosmos.authz.v1beta1.Query/GranteeGrants";
     async fn perform(
         req: tonic::Request<Self>,
-        inner: &mut Node,
+        inner: &Node,
     ) -> Result<tonic::Response<Self::Response>, tonic::Status> {
         inner.authz_query_client().grantee_grants(req).await
     }
 }
 
+#[async_trait]
+impl GrpcRequest for QueryGrantsRequest {
+    type Response = QueryGrantsResponse;
+    const METHOD: &'static str = "/cosmos.authz.v1beta1.Query/Grants";
+    async fn perform(
+        req: tonic::Request<Self>,
+        inner: &Node,
+    ) -> Result<tonic::Response<Self::Response>, tonic::Status> {
+        inner.authz_query_client().grants(req).await
+    }
+}
+
 #[async_trait]
 impl GrpcRequest for QueryEpochsInfoRequest {
     type Response = QueryEpochsInfoResponse;
+    const METHOD: &'static str = "/osmosis.epochs.v1beta1.Query/EpochInfos";
     async fn perform(
         req: tonic::Request<Self>,
-        inner: &mut Node,
+        inner: &Node,
     ) -> Result<tonic::Response<Self::Response>, tonic::Status> {
         inner.epochs_query_client().epoch_infos(req).await
     }
 }
+
+#[cfg(feature = "osmosis")]
+#[async_trait]
+impl GrpcRequest for crate::osmosis::poolmanager::EstimateSwapExactAmountInRequest {
+    type Response = crate::osmosis::poolmanager::EstimateSwapExactAmountInResponse;
+    const METHOD: &'static str = "/osmosis.poolmanager.v1beta1.Query/EstimateSwapExactAmountIn";
+    async fn perform(
+        req: tonic::Request<Self>,
+        inner: &Node,
+    ) -> Result<tonic::Response<Self::Response>, tonic::Status> {
+        inner
+            .poolmanager_query_client()
+            .estimate_swap_exact_amount_in(req)
+            .await
+    }
+}
+
+#[cfg(feature = "osmosis")]
+#[async_trait]
+impl GrpcRequest for crate::osmosis::poolmanager::SpotPriceRequest {
+    type Response = crate::osmosis::poolmanager::SpotPriceResponse;
+    const METHOD: &'static str = "/osmosis.poolmanager.v1beta1.Query/SpotPrice";
+    async fn perform(
+        req: tonic::Request<Self>,
+        inner: &Node,
+    ) -> Result<tonic::Response<Self::Response>, tonic::Status> {
+        inner.poolmanager_query_client().spot_price(req).await
+    }
+}
+
+#[cfg(feature = "osmosis")]
+#[async_trait]
+impl GrpcRequest for crate::osmosis::twap::ArithmeticTwapToNowRequest {
+    type Response = crate::osmosis::twap::ArithmeticTwapToNowResponse;
+    const METHOD: &'static str = "/osmosis.twap.v1beta1.Query/ArithmeticTwapToNow";
+    async fn perform(
+        req: tonic::Request<Self>,
+        inner: &Node,
+    ) -> Result<tonic::Response<Self::Response>, tonic::Status> {
+        inner.twap_query_client().arithmetic_twap_to_now(req).await
+    }
+}
+
+#[cfg(feature = "osmosis")]
+#[async_trait]
+impl GrpcRequest for crate::osmosis::concentrated_liquidity::UserPositionsRequest {
+    type Response = crate::osmosis::concentrated_liquidity::UserPositionsResponse;
+    const METHOD: &'static str = "/osmosis.concentratedliquidity.v1beta1.Query/UserPositions";
+    async fn perform(
+        req: tonic::Request<Self>,
+        inner: &Node,
+    ) -> Result<tonic::Response<Self::Response>, tonic::Status> {
+        inner
+            .concentrated_liquidity_query_client()
+            .user_positions(req)
+            .await
+    }
+}
+
+#[cfg(feature = "osmosis")]
+#[async_trait]
+impl GrpcRequest for crate::osmosis::concentrated_liquidity::LiquidityPerTickRangeRequest {
+    type Response = crate::osmosis::concentrated_liquidity::LiquidityPerTickRangeResponse;
+    const METHOD: &'static str =
+        "/osmosis.concentratedliquidity.v1beta1.Query/LiquidityPerTickRange";
+    async fn perform(
+        req: tonic::Request<Self>,
+        inner: &Node,
+    ) -> Result<tonic::Response<Self::Response>, tonic::Status> {
+        inner
+            .concentrated_liquidity_query_client()
+            .liquidity_per_tick_range(req)
+            .await
+    }
+}
+
+#[async_trait]
+impl GrpcRequest for QueryCurrentEpochRequest {
+    type Response = QueryCurrentEpochResponse;
+    const METHOD: &'static str = "/osmosis.epochs.v1beta1.Query/CurrentEpoch";
+    async fn perform(
+        req: tonic::Request<Self>,
+        inner: &Node,
+    ) -> Result<tonic::Response<Self::Response>, tonic::Status> {
+        inner.epochs_query_client().current_epoch(req).await
+    }
+}
+
+#[cfg(feature = "osmosis")]
+#[async_trait]
+impl GrpcRequest for crate::osmosis::lockup::AccountLockedCoinsRequest {
+    type Response = crate::osmosis::lockup::AccountLockedCoinsResponse;
+    const METHOD: &'static str = "/osmosis.lockup.Query/AccountLockedCoins";
+    async fn perform(
+        req: tonic::Request<Self>,
+        inner: &Node,
+    ) -> Result<tonic::Response<Self::Response>, tonic::Status> {
+        inner.lockup_query_client().account_locked_coins(req).await
+    }
+}
+
+#[cfg(feature = "osmosis")]
+#[async_trait]
+impl GrpcRequest for crate::osmosis::lockup::AccountUnlockingCoinsRequest {
+    type Response = crate::osmosis::lockup::AccountUnlockingCoinsResponse;
+    const METHOD: &'static str = "/osmosis.lockup.Query/AccountUnlockingCoins";
+    async fn perform(
+        req: tonic::Request<Self>,
+        inner: &Node,
+    ) -> Result<tonic::Response<Self::Response>, tonic::Status> {
+        inner
+            .lockup_query_client()
+            .account_unlocking_coins(req)
+            .await
+    }
+}
+
+#[cfg(feature = "osmosis")]
+#[async_trait]
+impl GrpcRequest for crate::osmosis::lockup::AccountUnlockableCoinsRequest {
+    type Response = crate::osmosis::lockup::AccountUnlockableCoinsResponse;
+    const METHOD: &'static str = "/osmosis.lockup.Query/AccountUnlockableCoins";
+    async fn perform(
+        req: tonic::Request<Self>,
+        inner: &Node,
+    ) -> Result<tonic::Response<Self::Response>, tonic::Status> {
+        inner
+            .lockup_query_client()
+            .account_unlockable_coins(req)
+            .await
+    }
+}
+
+#[async_trait]
+impl GrpcRequest for QueryDenomMetadataRequest {
+    type Response = QueryDenomMetadataResponse;
+    const METHOD: &'static str = "/cosmos.bank.v1beta1.Query/DenomMetadata";
+    async fn perform(
+        req: tonic::Request<Self>,
+        inner: &Node,
+    ) -> Result<tonic::Response<Self::Response>, tonic::Status> {
+        inner.bank_query_client().denom_metadata(req).await
+    }
+
+    fn cache_lifetime(config: &QueryCacheConfig) -> Option<CacheLifetime> {
+        config.denom_metadata_ttl.map(CacheLifetime::Ttl)
+    }
+}
+
+#[async_trait]
+impl GrpcRequest for QueryDelegationRewardsRequest {
+    type Response = QueryDelegationRewardsResponse;
+    const METHOD: &'static str = "/cosmos.distribution.v1beta1.Query/DelegationRewards";
+    async fn perform(
+        req: tonic::Request<Self>,
+        inner: &Node,
+    ) -> Result<tonic::Response<Self::Response>, tonic::Status> {
+        inner
+            .distribution_query_client()
+            .delegation_rewards(req)
+            .await
+    }
+}
+
+#[async_trait]
+impl GrpcRequest for QueryDelegationTotalRewardsRequest {
+    type Response = QueryDelegationTotalRewardsResponse;
+    const METHOD: &'static str = "/cosmos.distribution.v1beta1.Query/DelegationTotalRewards";
+    async fn perform(
+        req: tonic::Request<Self>,
+        inner: &Node,
+    ) -> Result<tonic::Response<Self::Response>, tonic::Status> {
+        inner
+            .distribution_query_client()
+            .delegation_total_rewards(req)
+            .await
+    }
+}
+
+#[async_trait]
+impl GrpcRequest for QueryDelegatorWithdrawAddressRequest {
+    type Response = QueryDelegatorWithdrawAddressResponse;
+    const METHOD: &'static str = "/cosmos.distribution.v1beta1.Query/DelegatorWithdrawAddress";
+    async fn perform(
+        req: tonic::Request<Self>,
+        inner: &Node,
+    ) -> Result<tonic::Response<Self::Response>, tonic::Status> {
+        inner
+            .distribution_query_client()
+            .delegator_withdraw_address(req)
+            .await
+    }
+}
+
+#[async_trait]
+impl GrpcRequest for QueryProposalRequest {
+    type Response = QueryProposalResponse;
+    const METHOD: &'static str = "/cosmos.gov.v1beta1.Query/Proposal";
+    async fn perform(
+        req: tonic::Request<Self>,
+        inner: &Node,
+    ) -> Result<tonic::Response<Self::Response>, tonic::Status> {
+        inner.gov_query_client().proposal(req).await
+    }
+}
+
+#[async_trait]
+impl GrpcRequest for QueryProposalsRequest {
+    type Response = QueryProposalsResponse;
+    const METHOD: &'static str = "/cosmos.gov.v1beta1.Query/Proposals";
+    async fn perform(
+        req: tonic::Request<Self>,
+        inner: &Node,
+    ) -> Result<tonic::Response<Self::Response>, tonic::Status> {
+        inner.gov_query_client().proposals(req).await
+    }
+}
+
+#[async_trait]
+impl GrpcRequest for QueryTallyResultRequest {
+    type Response = QueryTallyResultResponse;
+    const METHOD: &'static str = "/cosmos.gov.v1beta1.Query/TallyResult";
+    async fn perform(
+        req: tonic::Request<Self>,
+        inner: &Node,
+    ) -> Result<tonic::Response<Self::Response>, tonic::Status> {
+        inner.gov_query_client().tally_result(req).await
+    }
+}
+
+#[async_trait]
+impl GrpcRequest for QueryVotesRequest {
+    type Response = QueryVotesResponse;
+    const METHOD: &'static str = "/cosmos.gov.v1beta1.Query/Votes";
+    async fn perform(
+        req: tonic::Request<Self>,
+        inner: &Node,
+    ) -> Result<tonic::Response<Self::Response>, tonic::Status> {
+        inner.gov_query_client().votes(req).await
+    }
+}
+
+#[async_trait]
+impl GrpcRequest for QueryAllowanceRequest {
+    type Response = QueryAllowanceResponse;
+    const METHOD: &'static str = "/cosmos.feegrant.v1beta1.Query/Allowance";
+    async fn perform(
+        req: tonic::Request<Self>,
+        inner: &Node,
+    ) -> Result<tonic::Response<Self::Response>, tonic::Status> {
+        inner.feegrant_query_client().allowance(req).await
+    }
+}
+
+#[async_trait]
+impl GrpcRequest for QueryAllowancesRequest {
+    type Response = QueryAllowancesResponse;
+    const METHOD: &'static str = "/cosmos.feegrant.v1beta1.Query/Allowances";
+    async fn perform(
+        req: tonic::Request<Self>,
+        inner: &Node,
+    ) -> Result<tonic::Response<Self::Response>, tonic::Status> {
+        inner.feegrant_query_client().allowances(req).await
+    }
+}
+
+#[async_trait]
+impl GrpcRequest for QueryInterchainAccountRequest {
+    type Response = QueryInterchainAccountResponse;
+    const METHOD: &'static str =
+        "/ibc.applications.interchain_accounts.controller.v1.Query/InterchainAccount";
+    async fn perform(
+        req: tonic::Request<Self>,
+        inner: &Node,
+    ) -> Result<tonic::Response<Self::Response>, tonic::Status> {
+        inner
+            .ica_controller_query_client()
+            .interchain_account(req)
+            .await
+    }
+}
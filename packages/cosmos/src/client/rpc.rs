@@ -0,0 +1,438 @@
+//! Access to a node's Tendermint RPC endpoint.
+//!
+//! Some data, such as block results, is not exposed over gRPC at all. These
+//! methods require [crate::CosmosBuilder::set_rpc_url].
+
+use std::sync::Arc;
+
+use base64::Engine;
+use reqwest::Url;
+use serde::{de::DeserializeOwned, Deserialize};
+
+use crate::{
+    decode::txhash_for_bytes,
+    error::{Action, BuilderError},
+    events::{event_from_raw, TxEvent},
+    Cosmos, CosmosBuilder,
+};
+
+use super::json_number::JsonNumber;
+
+/// Client for a Tendermint RPC endpoint, using the URI over HTTP interface.
+///
+/// Errors are plain strings and converted into [crate::Error::Rpc] by the caller.
+pub(crate) struct Rpc {
+    url: Arc<String>,
+    base: Url,
+    client: reqwest::Client,
+}
+
+impl Rpc {
+    pub(super) fn new(builder: &CosmosBuilder, url: &str) -> Result<Self, BuilderError> {
+        let base = url.parse().map_err(|source| BuilderError::InvalidRpcUrl {
+            rpc_url: url.to_owned(),
+            source,
+        })?;
+        Ok(Rpc {
+            url: Arc::new(url.to_owned()),
+            base,
+            client: builder.make_http_client(url)?,
+        })
+    }
+
+    pub(super) fn url(&self) -> &Arc<String> {
+        &self.url
+    }
+
+    async fn call<T: DeserializeOwned>(
+        &self,
+        method: &str,
+        params: &[(&str, String)],
+    ) -> Result<T, String> {
+        #[derive(Deserialize)]
+        struct Response<T> {
+            result: Option<T>,
+            error: Option<RpcError>,
+        }
+        #[derive(Deserialize)]
+        struct RpcError {
+            message: String,
+            #[serde(default)]
+            data: Option<String>,
+        }
+
+        let mut url = self.base.clone();
+        url.path_segments_mut()
+            .map_err(|()| format!("RPC URL {} cannot be a base", self.url))?
+            .pop_if_empty()
+            .push(method);
+        if !params.is_empty() {
+            url.query_pairs_mut().extend_pairs(params);
+        }
+        let res = self
+            .client
+            .get(url.clone())
+            .send()
+            .await
+            .map_err(|e| format!("Request to {url} failed: {e}"))?;
+        let status = res.status();
+        let body = res
+            .bytes()
+            .await
+            .map_err(|e| format!("Unable to read response from {url}: {e}"))?;
+        let res = serde_json::from_slice::<Response<T>>(&body).map_err(|e| {
+            format!(
+                "Unable to parse response from {url} with HTTP status {status}: {e}. Body: {}",
+                String::from_utf8_lossy(&body)
+            )
+        })?;
+        match (res.result, res.error) {
+            (_, Some(RpcError { message, data })) => Err(match data {
+                Some(data) => format!("{message}: {data}"),
+                None => message,
+            }),
+            (Some(result), None) => Ok(result),
+            (None, None) => Err(format!("No result returned from {url}")),
+        }
+    }
+}
+
+/// The results of executing a block, see [Cosmos::get_block_results].
+#[derive(Clone, Debug)]
+pub struct BlockResults {
+    /// Block height
+    pub height: i64,
+    /// Results of each transaction, in the order they appear in the block
+    pub txs_results: Vec<BlockTxResult>,
+    /// Events emitted during BeginBlock, e.g. minting and distribution
+    pub begin_block_events: Vec<TxEvent>,
+    /// Events emitted during EndBlock, e.g. validator set changes
+    pub end_block_events: Vec<TxEvent>,
+    /// Block level events on CometBFT 0.38 and later, which replace begin and end block events
+    pub finalize_block_events: Vec<TxEvent>,
+}
+
+/// Result of a single transaction within [BlockResults].
+#[derive(Clone, Debug)]
+pub struct BlockTxResult {
+    /// Result code, 0 for success
+    pub code: u32,
+    /// Module namespace for a non-zero code
+    pub codespace: String,
+    /// Raw log, contains the error message for failed transactions
+    pub log: String,
+    /// Gas requested by the transaction
+    pub gas_wanted: i64,
+    /// Gas actually used
+    pub gas_used: i64,
+    /// Events emitted by the transaction
+    pub events: Vec<TxEvent>,
+}
+
+/// Transactions waiting in a node's mempool, see [Cosmos::unconfirmed_txs].
+#[derive(Clone, Debug)]
+pub struct UnconfirmedTxs {
+    /// Total number of transactions in the mempool
+    pub total: u64,
+    /// Total size of all transactions in the mempool, in bytes
+    pub total_bytes: u64,
+    /// Hashes of the returned transactions, in the same format as [crate::TxResponseExt]'s txhash
+    pub txhashes: Vec<String>,
+    /// Raw bytes of the returned transactions
+    pub txs: Vec<Vec<u8>>,
+}
+
+#[derive(Deserialize)]
+struct RawUnconfirmedTxs {
+    total: JsonNumber,
+    total_bytes: JsonNumber,
+    txs: Option<Vec<String>>,
+}
+
+impl TryFrom<RawUnconfirmedTxs> for UnconfirmedTxs {
+    type Error = String;
+
+    fn try_from(raw: RawUnconfirmedTxs) -> Result<Self, String> {
+        let txs = raw
+            .txs
+            .unwrap_or_default()
+            .iter()
+            .map(|tx| {
+                base64::engine::general_purpose::STANDARD
+                    .decode(tx)
+                    .map_err(|e| format!("Invalid base64 transaction {tx:?}: {e}"))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(UnconfirmedTxs {
+            total: raw.total.parse("total")?,
+            total_bytes: raw.total_bytes.parse("total_bytes")?,
+            txhashes: txs.iter().map(|tx| txhash_for_bytes(tx)).collect(),
+            txs,
+        })
+    }
+}
+
+#[derive(Deserialize)]
+struct RawBlockResults {
+    height: JsonNumber,
+    txs_results: Option<Vec<RawTxResult>>,
+    begin_block_events: Option<Vec<RawEvent>>,
+    end_block_events: Option<Vec<RawEvent>>,
+    finalize_block_events: Option<Vec<RawEvent>>,
+}
+
+#[derive(Deserialize)]
+struct RawTxResult {
+    #[serde(default)]
+    code: u32,
+    #[serde(default)]
+    codespace: String,
+    #[serde(default)]
+    log: String,
+    gas_wanted: JsonNumber,
+    gas_used: JsonNumber,
+    events: Option<Vec<RawEvent>>,
+}
+
+#[derive(Deserialize)]
+struct RawEvent {
+    #[serde(rename = "type")]
+    kind: String,
+    attributes: Option<Vec<RawAttribute>>,
+}
+
+#[derive(Deserialize)]
+struct RawAttribute {
+    key: Option<String>,
+    value: Option<String>,
+}
+
+impl RawEvent {
+    fn into_event(self) -> TxEvent {
+        event_from_raw(
+            self.kind,
+            self.attributes
+                .unwrap_or_default()
+                .into_iter()
+                .map(|attr| (attr.key.unwrap_or_default(), attr.value.unwrap_or_default()))
+                .collect(),
+        )
+    }
+}
+
+fn convert_events(events: Option<Vec<RawEvent>>) -> Vec<TxEvent> {
+    events
+        .unwrap_or_default()
+        .into_iter()
+        .map(RawEvent::into_event)
+        .collect()
+}
+
+impl TryFrom<RawBlockResults> for BlockResults {
+    type Error = String;
+
+    fn try_from(raw: RawBlockResults) -> Result<Self, String> {
+        Ok(BlockResults {
+            height: raw.height.parse("height")?,
+            txs_results: raw
+                .txs_results
+                .unwrap_or_default()
+                .into_iter()
+                .map(|tx| {
+                    Ok(BlockTxResult {
+                        code: tx.code,
+                        codespace: tx.codespace,
+                        log: tx.log,
+                        gas_wanted: tx.gas_wanted.parse("gas_wanted")?,
+                        gas_used: tx.gas_used.parse("gas_used")?,
+                        events: convert_events(tx.events),
+                    })
+                })
+                .collect::<Result<_, String>>()?,
+            begin_block_events: convert_events(raw.begin_block_events),
+            end_block_events: convert_events(raw.end_block_events),
+            finalize_block_events: convert_events(raw.finalize_block_events),
+        })
+    }
+}
+
+impl Cosmos {
+    /// Perform a Tendermint RPC call.
+    pub(super) async fn rpc_call<T: DeserializeOwned>(
+        &self,
+        method: &str,
+        params: &[(&str, String)],
+        action: Action,
+    ) -> Result<T, crate::Error> {
+        let rpc = self.pool.rpc.as_ref().ok_or(crate::Error::NoRpcUrl)?;
+        let fixture = self.pool.fixture.as_ref();
+        let res = match fixture.and_then(|fixture| fixture.replay_rpc(method, params)) {
+            Some(res) => res,
+            None => {
+                let res = rpc.call::<serde_json::Value>(method, params).await;
+                if let (Some(fixture), Ok(value)) = (fixture, &res) {
+                    fixture.record_rpc(method, params, value);
+                }
+                res
+            }
+        };
+        res.and_then(|value| serde_json::from_value(value).map_err(|e| e.to_string()))
+            .map_err(|message| crate::Error::Rpc {
+                rpc_url: rpc.url().clone(),
+                message,
+                action,
+            })
+    }
+
+    /// Get the results of executing the block at the given height.
+    ///
+    /// Unlike transaction queries, this includes the events emitted outside
+    /// of any transaction, such as those from epoch processing or other
+    /// begin and end block logic. Requires an RPC endpoint, see
+    /// [crate::CosmosBuilder::set_rpc_url].
+    pub async fn get_block_results(&self, height: i64) -> Result<BlockResults, crate::Error> {
+        let action = Action::GetBlockResults(height);
+        let raw: RawBlockResults = self
+            .rpc_call(
+                "block_results",
+                &[("height", height.to_string())],
+                action.clone(),
+            )
+            .await?;
+        raw.try_into()
+            .map_err(|message| crate::Error::InvalidChainResponse { message, action })
+    }
+
+    /// Get transactions waiting in the RPC node's mempool.
+    ///
+    /// Returns at most `limit` transactions, nodes usually cap this at 100.
+    /// Requires an RPC endpoint, see [crate::CosmosBuilder::set_rpc_url].
+    pub async fn unconfirmed_txs(
+        &self,
+        limit: Option<u32>,
+    ) -> Result<UnconfirmedTxs, crate::Error> {
+        let action = Action::UnconfirmedTxs;
+        let params = limit
+            .map(|limit| ("limit", limit.to_string()))
+            .into_iter()
+            .collect::<Vec<_>>();
+        let raw: RawUnconfirmedTxs = self
+            .rpc_call("unconfirmed_txs", &params, action.clone())
+            .await?;
+        raw.try_into()
+            .map_err(|message| crate::Error::InvalidChainResponse { message, action })
+    }
+
+    /// Is the given transaction waiting in the RPC node's mempool?
+    ///
+    /// Nodes only list the first 100 transactions. If the transaction isn't
+    /// among them and the mempool holds more, this returns [None] since
+    /// absence can't be confirmed. Mempools are per node, so a transaction
+    /// broadcast to a different node may not have propagated yet.
+    pub async fn is_tx_pending(&self, txhash: &str) -> Result<Option<bool>, crate::Error> {
+        let unconfirmed = self.unconfirmed_txs(Some(MEMPOOL_PAGE_SIZE)).await?;
+        Ok(unconfirmed.contains(txhash))
+    }
+}
+
+/// Maximum number of transactions nodes return from the `unconfirmed_txs` RPC call.
+const MEMPOOL_PAGE_SIZE: u32 = 100;
+
+impl UnconfirmedTxs {
+    /// Is the transaction in the mempool?
+    ///
+    /// Returns [None] if it wasn't found, but the node only returned part of
+    /// the mempool.
+    pub fn contains(&self, txhash: &str) -> Option<bool> {
+        if self.txhashes.iter().any(|x| x.eq_ignore_ascii_case(txhash)) {
+            Some(true)
+        } else if self.total > self.txhashes.len() as u64 {
+            None
+        } else {
+            Some(false)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_block_results() {
+        let raw: RawBlockResults = serde_json::from_str(
+            r#"{
+                "height": "12",
+                "txs_results": [{
+                    "code": 0,
+                    "data": null,
+                    "log": "[]",
+                    "gas_wanted": "200000",
+                    "gas_used": "81234",
+                    "events": [{
+                        "type": "message",
+                        "attributes": [{"key": "YWN0aW9u", "value": "c2VuZA==", "index": true}]
+                    }]
+                }],
+                "begin_block_events": [{
+                    "type": "mint",
+                    "attributes": [{"key": "amount", "value": "100", "index": true}]
+                }],
+                "end_block_events": null,
+                "validator_updates": null
+            }"#,
+        )
+        .unwrap();
+        let results = BlockResults::try_from(raw).unwrap();
+        assert_eq!(results.height, 12);
+        assert_eq!(results.txs_results[0].gas_used, 81234);
+        assert_eq!(results.txs_results[0].events[0].get("action"), Some("send"));
+        assert_eq!(results.begin_block_events[0].get("amount"), Some("100"));
+        assert!(results.end_block_events.is_empty());
+        assert!(results.finalize_block_events.is_empty());
+    }
+
+    #[test]
+    fn parse_block_results_numbers() {
+        let raw: RawBlockResults = serde_json::from_str(
+            r#"{
+                "height": 12,
+                "txs_results": [{"gas_wanted": 200000, "gas_used": "81234", "events": null}]
+            }"#,
+        )
+        .unwrap();
+        let results = BlockResults::try_from(raw).unwrap();
+        assert_eq!(results.height, 12);
+        assert_eq!(results.txs_results[0].gas_wanted, 200000);
+        assert_eq!(results.txs_results[0].gas_used, 81234);
+    }
+
+    #[test]
+    fn parse_unconfirmed_txs() {
+        let raw: RawUnconfirmedTxs = serde_json::from_str(
+            r#"{"n_txs": "1", "total": "3", "total_bytes": "1024", "txs": ["aGVsbG8="]}"#,
+        )
+        .unwrap();
+        let txs = UnconfirmedTxs::try_from(raw).unwrap();
+        assert_eq!(txs.total, 3);
+        assert_eq!(txs.txs, vec![b"hello".to_vec()]);
+        // Only one of three pending transactions was returned
+        assert_eq!(
+            txs.contains("2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824"),
+            Some(true)
+        );
+        assert_eq!(txs.contains("ABCD"), None);
+        assert_eq!(
+            txs.txhashes,
+            vec!["2CF24DBA5FB0A30E26E83B2AC5B9E29E1B161E5C1FA7425E73043362938B9824"]
+        );
+
+        let raw: RawUnconfirmedTxs =
+            serde_json::from_str(r#"{"n_txs": 0, "total": 0, "total_bytes": 0, "txs": null}"#)
+                .unwrap();
+        let txs = UnconfirmedTxs::try_from(raw).unwrap();
+        assert_eq!(txs.total, 0);
+        assert!(txs.txs.is_empty());
+        assert_eq!(txs.contains("ABCD"), Some(false));
+    }
+}
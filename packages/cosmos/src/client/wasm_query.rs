@@ -0,0 +1,97 @@
+//! The `cosmwasm.wasm.v1.Query/ContractsByCreator` query, added in wasmd 0.40.
+//!
+//! Not included in our version of the protobuf definitions.
+
+use cosmos_sdk_proto::cosmos::base::query::v1beta1::{PageRequest, PageResponse};
+use futures_util::TryStreamExt;
+
+use crate::{codeid::parse_addresses, error::Action, Address, Cosmos, HasAddress};
+
+impl Cosmos {
+    /// List the addresses of all contracts instantiated by the given address.
+    ///
+    /// Requires a chain running wasmd 0.40 or later.
+    pub async fn contracts_by_creator(
+        &self,
+        creator: impl HasAddress,
+    ) -> Result<Vec<Address>, crate::Error> {
+        let creator = creator.get_address();
+        let action = Action::ContractsByCreator(creator);
+        let contracts = self
+            .paginate(
+                action.clone(),
+                move |pagination| QueryContractsByCreatorRequest {
+                    creator_address: creator.get_address_string(),
+                    pagination,
+                },
+                |res| (res.contract_addresses, res.pagination),
+            )
+            .try_collect()
+            .await?;
+        parse_addresses(contracts, action)
+    }
+}
+
+/// QueryContractsByCreatorRequest is the request type for the
+/// Query/ContractsByCreator RPC method.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct QueryContractsByCreatorRequest {
+    /// CreatorAddress is the address of contract creator
+    #[prost(string, tag = "1")]
+    pub creator_address: ::prost::alloc::string::String,
+    /// Pagination defines an optional pagination for the request.
+    #[prost(message, optional, tag = "2")]
+    pub pagination: ::core::option::Option<PageRequest>,
+}
+/// QueryContractsByCreatorResponse is the response type for the
+/// Query/ContractsByCreator RPC method.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct QueryContractsByCreatorResponse {
+    /// ContractAddresses result set
+    #[prost(string, repeated, tag = "1")]
+    pub contract_addresses: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    /// Pagination defines the pagination in the response.
+    #[prost(message, optional, tag = "2")]
+    pub pagination: ::core::option::Option<PageResponse>,
+}
+/// Generated client implementations.
+pub mod query_client {
+    #![allow(unused_variables, dead_code, missing_docs, clippy::let_unit_value)]
+    use tonic::codegen::*;
+    /// Query provides defines the gRPC querier service
+    #[derive(Debug, Clone)]
+    pub struct QueryClient<T> {
+        inner: tonic::client::Grpc<T>,
+    }
+    impl<T> QueryClient<T>
+    where
+        T: tonic::client::GrpcService<tonic::body::BoxBody>,
+        T::Error: Into<StdError>,
+        T::ResponseBody: Body<Data = Bytes> + Send + 'static,
+        <T::ResponseBody as Body>::Error: Into<StdError> + Send,
+    {
+        pub fn new(inner: T) -> Self {
+            let inner = tonic::client::Grpc::new(inner);
+            Self { inner }
+        }
+        /// ContractsByCreator gets the contracts by creator
+        pub async fn contracts_by_creator(
+            &mut self,
+            request: impl tonic::IntoRequest<super::QueryContractsByCreatorRequest>,
+        ) -> Result<tonic::Response<super::QueryContractsByCreatorResponse>, tonic::Status>
+        {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(
+                    tonic::Code::Unknown,
+                    format!("Service was not ready: {}", e.into()),
+                )
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path =
+                http::uri::PathAndQuery::from_static("/cosmwasm.wasm.v1.Query/ContractsByCreator");
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+    }
+}
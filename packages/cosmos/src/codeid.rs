@@ -1,17 +1,36 @@
 use std::{
     fmt::Display,
+    io::{Read, Write},
     path::{Path, PathBuf},
+    time::Duration,
 };
 
-use cosmos_sdk_proto::cosmos::base::abci::v1beta1::TxResponse;
+use cosmos_sdk_proto::{
+    cosmos::base::{abci::v1beta1::TxResponse, query::v1beta1::PageRequest},
+    cosmwasm::wasm::v1::{
+        AccessType, CodeInfoResponse, Params, QueryCodesRequest, QueryContractsByCodeRequest,
+        QueryParamsRequest as WasmQueryParamsRequest,
+    },
+};
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use futures_util::TryStreamExt;
+use sha2::{Digest, Sha256};
 
 use crate::{
-    error::Action,
-    messages::{MsgExecHelper, MsgStoreCodeHelper},
-    Address, AddressHrp, Cosmos, HasAddress, HasAddressHrp, HasCosmos, TxBuilder, TxMessage,
-    TxResponseExt, Wallet,
+    address::RawAddress,
+    error::{Action, AddressError, ChainParseError, ErrorKind},
+    events::TxEvent,
+    messages::MsgStoreCodeHelper,
+    Address, AddressHrp, Contract, ContractAdmin, Cosmos, HasAddress, HasAddressHrp, HasCosmos,
+    TxBuilder, TxResponseExt, Wallet,
 };
 
+/// How many times to look up newly uploaded code before giving up.
+const NEW_CODE_ATTEMPTS: u32 = 5;
+
+/// Delay between lookups of newly uploaded code.
+const NEW_CODE_DELAY: Duration = Duration::from_secs(2);
+
 /// Represents the uploaded code on a specific blockchain connection.
 #[derive(Clone)]
 pub struct CodeId {
@@ -29,6 +48,155 @@ impl CodeId {
     pub async fn download(&self) -> Result<Vec<u8>, crate::Error> {
         self.client.code_info(self.code_id).await
     }
+
+    /// Get the SHA-256 checksum of the WASM code, as stored on chain.
+    pub async fn checksum(&self) -> Result<Vec<u8>, crate::Error> {
+        self.client.code_checksum(self.code_id).await
+    }
+
+    /// Check that the code on chain has the same checksum as the given WASM code.
+    ///
+    /// Gzip compressed code is decompressed first, since the chain stores the
+    /// checksum of the uncompressed code.
+    pub async fn verify_checksum(&self, wasm_byte_code: &[u8]) -> Result<(), crate::Error> {
+        self.verify_digest(&wasm_checksum(wasm_byte_code)?).await
+    }
+
+    async fn verify_digest(&self, expected: &[u8]) -> Result<(), crate::Error> {
+        let actual = self.checksum().await?;
+        self.compare_digest(expected, &actual)
+    }
+
+    /// Check the checksum of freshly uploaded code.
+    ///
+    /// Uses the checksum from the upload transaction's events when present.
+    /// Otherwise it's queried, retrying while the code is not found since the
+    /// node answering may not have caught up to the upload yet.
+    async fn verify_upload(&self, expected: &[u8], res: &TxResponse) -> Result<(), crate::Error> {
+        if let Some(actual) = stored_code_checksum(&res.tx_events()) {
+            return self.compare_digest(expected, &actual);
+        }
+        let mut attempt = 1;
+        loop {
+            match self.checksum().await {
+                Ok(actual) => break self.compare_digest(expected, &actual),
+                Err(e) if e.kind() == ErrorKind::NotFound && attempt < NEW_CODE_ATTEMPTS => {
+                    attempt += 1;
+                    tokio::time::sleep(NEW_CODE_DELAY).await;
+                }
+                Err(e) => break Err(e),
+            }
+        }
+    }
+
+    fn compare_digest(&self, expected: &[u8], actual: &[u8]) -> Result<(), crate::Error> {
+        if expected == actual {
+            Ok(())
+        } else {
+            Err(crate::Error::ChecksumMismatch {
+                code_id: self.code_id,
+                expected: hex::encode(expected),
+                actual: hex::encode(actual),
+            })
+        }
+    }
+}
+
+/// Compute the address of a contract instantiated with `MsgInstantiateContract2`.
+///
+/// `checksum` is the SHA-256 hash of the WASM code, and `fix_msg` is the
+/// instantiate message if it is included in the address. The address uses
+/// the same HRP as the creator.
+pub fn predict_instantiate2_address(
+    checksum: &[u8],
+    creator: impl HasAddress,
+    salt: &[u8],
+    fix_msg: Option<&[u8]>,
+) -> Result<Address, AddressError> {
+    if checksum.len() != 32 {
+        return Err(AddressError::InvalidInstantiate2Checksum {
+            actual: checksum.len(),
+        });
+    }
+    if salt.is_empty() || salt.len() > 64 {
+        return Err(AddressError::InvalidInstantiate2Salt { actual: salt.len() });
+    }
+    let creator = creator.get_address();
+    let msg = fix_msg.unwrap_or_default();
+
+    // See address.Module in the Cosmos SDK and BuildContractAddressPredictable in wasmd
+    let mut key = b"wasm\0".to_vec();
+    for part in [checksum, creator.raw().as_ref(), salt, msg] {
+        key.extend_from_slice(&(part.len() as u64).to_be_bytes());
+        key.extend_from_slice(part);
+    }
+    let hash: [u8; 32] = Sha256::new()
+        .chain_update(Sha256::digest(b"module"))
+        .chain_update(key)
+        .finalize()
+        .into();
+    Ok(RawAddress::from(hash).with_hrp(creator.get_address_hrp()))
+}
+
+/// Parse addresses returned by a query.
+pub(crate) fn parse_addresses(
+    addresses: Vec<String>,
+    action: Action,
+) -> Result<Vec<Address>, crate::Error> {
+    addresses
+        .into_iter()
+        .map(|address| {
+            address.parse().map_err(|source| crate::Error::ChainParse {
+                source: Box::new(ChainParseError::InvalidAddress { address, source }),
+                action: action.clone(),
+            })
+        })
+        .collect()
+}
+
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// Gzip compress data, which wasmd accepts in place of raw WASM code.
+fn gzip(data: &[u8]) -> Vec<u8> {
+    let mut encoder = GzEncoder::new(vec![], Compression::best());
+    encoder
+        .write_all(data)
+        .expect("writing to a Vec never fails");
+    encoder.finish().expect("writing to a Vec never fails")
+}
+
+/// Decompress gzipped WASM code, reading at most `limit` bytes if given.
+///
+/// Returns the decompressed code, which is longer than `limit` if the code is too large.
+fn gunzip(data: &[u8], limit: Option<u64>) -> std::io::Result<Vec<u8>> {
+    let mut res = vec![];
+    GzDecoder::new(data)
+        .take(limit.map_or(u64::MAX, |limit| limit.saturating_add(1)))
+        .read_to_end(&mut res)?;
+    Ok(res)
+}
+
+/// SHA-256 checksum of WASM code as stored on chain, decompressing gzipped code first.
+fn wasm_checksum(wasm_byte_code: &[u8]) -> Result<Vec<u8>, crate::Error> {
+    Ok(if wasm_byte_code.starts_with(&GZIP_MAGIC) {
+        let uncompressed = gunzip(wasm_byte_code, None)
+            .map_err(|source| crate::Error::InvalidGzipWasm { source })?;
+        Sha256::digest(uncompressed).to_vec()
+    } else {
+        Sha256::digest(wasm_byte_code).to_vec()
+    })
+}
+
+/// The checksum of the first code stored in the transaction, if the chain reports it.
+///
+/// wasmd 0.29 and later include a hex encoded `code_checksum` attribute in the
+/// `store_code` event.
+fn stored_code_checksum(events: &[TxEvent]) -> Option<Vec<u8>> {
+    events
+        .iter()
+        .filter(|event| event.kind == "store_code")
+        .find_map(|event| event.get("code_checksum"))
+        .and_then(|checksum| hex::decode(strip_quotes(checksum)).ok())
 }
 
 pub(crate) fn strip_quotes(s: &str) -> &str {
@@ -39,12 +207,19 @@ pub(crate) fn strip_quotes(s: &str) -> &str {
 
 impl Cosmos {
     /// Convenience helper for uploading code to the blockchain
+    ///
+    /// After uploading, the checksum stored on chain is compared against the
+    /// uploaded code. The checksum is taken from the transaction's events, or
+    /// queried with [CodeId::checksum] on chains which don't include it.
     pub async fn store_code(
         &self,
         wallet: &Wallet,
         wasm_byte_code: Vec<u8>,
         source: Option<PathBuf>,
     ) -> Result<CodeId, crate::Error> {
+        let (wasm_byte_code, digest) = self
+            .prepare_wasm_upload(wallet.get_address(), wasm_byte_code)
+            .await?;
         let msg = MsgStoreCodeHelper {
             sender: wallet.get_address(),
             wasm_byte_code,
@@ -54,14 +229,97 @@ impl Cosmos {
         txbuilder.add_message(msg);
         let res = txbuilder.sign_and_broadcast(self, wallet).await?;
 
-        Ok(
-            self.make_code_id(res.parse_first_stored_code_id().map_err(|source| {
-                crate::Error::ChainParse {
-                    source: source.into(),
-                    action: Action::Broadcast(txbuilder),
-                }
-            })?),
+        let code_id = self.make_code_id(res.parse_first_stored_code_id().map_err(|source| {
+            crate::Error::ChainParse {
+                source: source.into(),
+                action: Action::Broadcast(txbuilder),
+            }
+        })?);
+        code_id.verify_upload(&digest, &res).await?;
+        Ok(code_id)
+    }
+
+    /// Get the wasm module parameters, such as who may upload code.
+    pub async fn wasm_params(&self) -> Result<Params, crate::Error> {
+        let action = Action::WasmParams;
+        self.perform_query(WasmQueryParamsRequest {}, action.clone(), true)
+            .await?
+            .into_inner()
+            .params
+            .ok_or_else(|| crate::Error::InvalidChainResponse {
+                message: "No wasm params returned".to_owned(),
+                action,
+            })
+    }
+
+    /// Check the code against the chain's wasm params and compress it for upload.
+    ///
+    /// Returns the bytes to upload and the checksum of the uncompressed code.
+    /// Code which is already gzip compressed is decompressed for the checks
+    /// and checksum, but uploaded as-is.
+    async fn prepare_wasm_upload(
+        &self,
+        sender: Address,
+        wasm_byte_code: Vec<u8>,
+    ) -> Result<(Vec<u8>, Vec<u8>), crate::Error> {
+        let params = self.wasm_params().await?;
+        // Newer versions of wasmd no longer include the maximum size in the params
+        let max = Some(params.max_wasm_code_size).filter(|max| *max != 0);
+        let (upload, uncompressed) = if wasm_byte_code.starts_with(&GZIP_MAGIC) {
+            let uncompressed = gunzip(&wasm_byte_code, max)
+                .map_err(|source| crate::Error::InvalidGzipWasm { source })?;
+            (wasm_byte_code, uncompressed)
+        } else {
+            (gzip(&wasm_byte_code), wasm_byte_code)
+        };
+        if let Some(max) = max {
+            if uncompressed.len() as u64 > max {
+                return Err(crate::Error::WasmTooLarge {
+                    size: uncompressed.len() as u64,
+                    max,
+                });
+            }
+        }
+        if let Some(access) = params.code_upload_access {
+            let permitted = match AccessType::from_i32(access.permission) {
+                Some(AccessType::Nobody) => false,
+                Some(AccessType::OnlyAddress) => access.address == sender.get_address_string(),
+                // Unspecified, everybody, or newer access types we can't check
+                _ => true,
+            };
+            if !permitted {
+                return Err(crate::Error::UploadNotPermitted { sender });
+            }
+        }
+        Ok((upload, Sha256::digest(&uncompressed).to_vec()))
+    }
+
+    /// List all uploaded codes, including their creator and checksum.
+    pub async fn list_codes(&self) -> Result<Vec<CodeInfoResponse>, crate::Error> {
+        self.paginate(
+            Action::ListCodes,
+            |pagination| QueryCodesRequest { pagination },
+            |res| (res.code_infos, res.pagination),
         )
+        .try_collect()
+        .await
+    }
+
+    /// List the addresses of all contracts instantiated from the given code ID.
+    pub async fn contracts_by_code(&self, code_id: u64) -> Result<Vec<Address>, crate::Error> {
+        let action = Action::ContractsByCode(code_id);
+        let contracts = self
+            .paginate(
+                action.clone(),
+                move |pagination| QueryContractsByCodeRequest {
+                    code_id,
+                    pagination,
+                },
+                |res| (res.contracts, res.pagination),
+            )
+            .try_collect()
+            .await?;
+        parse_addresses(contracts, action)
     }
 
     /// Convenience wrapper for [Cosmos::store_code] that works on file paths
@@ -80,6 +338,73 @@ impl Cosmos {
             .await
     }
 
+    /// Find an already uploaded code with the given SHA-256 checksum.
+    ///
+    /// If the same code was uploaded multiple times, the most recent code ID is
+    /// returned. wasmd has no query by checksum, so codes are listed newest
+    /// first and the search stops at the first match.
+    pub async fn find_code_by_checksum(
+        &self,
+        checksum: &[u8],
+    ) -> Result<Option<CodeId>, crate::Error> {
+        let mut codes = Box::pin(self.paginate(
+            Action::ListCodes,
+            |pagination| QueryCodesRequest {
+                pagination: Some(PageRequest {
+                    reverse: true,
+                    ..pagination.unwrap_or_default()
+                }),
+            },
+            |res| (res.code_infos, res.pagination),
+        ));
+        while let Some(code) = codes.try_next().await? {
+            if code.data_hash == checksum {
+                return Ok(Some(self.make_code_id(code.code_id)));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Store and instantiate a contract in one step.
+    ///
+    /// The WASM code is loaded from `wasm_path`. If `reuse_existing_code` is
+    /// set and code with the same checksum is already on chain, that code ID
+    /// is instantiated instead of uploading a new copy. The new contract's
+    /// address is parsed from the instantiate transaction's events.
+    pub async fn deploy_contract(
+        &self,
+        wallet: &Wallet,
+        wasm_path: impl AsRef<Path>,
+        msg: impl serde::Serialize,
+        label: impl Into<String>,
+        admin: ContractAdmin,
+        reuse_existing_code: bool,
+    ) -> Result<Contract, crate::Error> {
+        let path = wasm_path.as_ref();
+        let wasm_byte_code =
+            fs_err::read(path).map_err(|source| crate::Error::LoadingWasmFromFile {
+                path: path.to_owned(),
+                source,
+            })?;
+        let existing = if reuse_existing_code {
+            self.find_code_by_checksum(&wasm_checksum(&wasm_byte_code)?)
+                .await?
+        } else {
+            None
+        };
+        let code_id = match existing {
+            Some(code_id) => {
+                tracing::info!("Reusing existing code ID {code_id} for {}", path.display());
+                code_id
+            }
+            None => {
+                self.store_code(wallet, wasm_byte_code, Some(path.to_owned()))
+                    .await?
+            }
+        };
+        code_id.instantiate(wallet, label, vec![], msg, admin).await
+    }
+
     /// Like [Self::store_code_path], but uses the authz grant mechanism
     pub async fn store_code_path_authz(
         &self,
@@ -93,6 +418,7 @@ impl Cosmos {
                 path: path.to_owned(),
                 source,
             })?;
+        let (wasm_byte_code, digest) = self.prepare_wasm_upload(granter, wasm_byte_code).await?;
         let store_code = MsgStoreCodeHelper {
             sender: granter.get_address(),
             wasm_byte_code,
@@ -100,11 +426,7 @@ impl Cosmos {
         };
 
         let mut txbuilder = TxBuilder::default();
-        let msg = MsgExecHelper {
-            grantee: wallet.get_address(),
-            msgs: vec![TxMessage::from(store_code)],
-        };
-        txbuilder.add_message(msg);
+        txbuilder.add_message(store_code).wrap_authz(wallet);
         let res = txbuilder.sign_and_broadcast(self, wallet).await?;
         let code_id = self.make_code_id(res.parse_first_stored_code_id().map_err(|source| {
             crate::Error::ChainParse {
@@ -112,6 +434,7 @@ impl Cosmos {
                 action: Action::Broadcast(txbuilder),
             }
         })?);
+        code_id.verify_upload(&digest, &res).await?;
         Ok((res, code_id))
     }
 }
@@ -133,3 +456,113 @@ impl HasAddressHrp for CodeId {
         self.client.get_address_hrp()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gzip_wasm() {
+        let data = b"\0asm not really wasm, but repeated repeated repeated".to_vec();
+        let compressed = gzip(&data);
+        assert!(compressed.starts_with(&GZIP_MAGIC));
+        assert!(compressed.len() < data.len());
+        assert_eq!(gunzip(&compressed, None).unwrap(), data);
+        assert_eq!(gunzip(&compressed, Some(1000)).unwrap(), data);
+        // Stops reading just past the limit
+        assert_eq!(gunzip(&compressed, Some(10)).unwrap(), data[..11]);
+
+        let mut corrupt = compressed.clone();
+        let last = corrupt.len() - 5;
+        corrupt[last] ^= 0xff;
+        gunzip(&corrupt, None).unwrap_err();
+        gunzip(&GZIP_MAGIC, None).unwrap_err();
+    }
+
+    #[test]
+    fn checksum_of_gzipped_wasm() {
+        let data = b"\0asm not really wasm".to_vec();
+        let expected = Sha256::digest(&data).to_vec();
+        assert_eq!(wasm_checksum(&data).unwrap(), expected);
+        assert_eq!(wasm_checksum(&gzip(&data)).unwrap(), expected);
+        wasm_checksum(&GZIP_MAGIC).unwrap_err();
+    }
+
+    #[tokio::test]
+    async fn find_code_newest_first() {
+        let path = std::env::temp_dir().join(format!("cosmos-codes-{}.json", std::process::id()));
+        // Codes 9 and 7 share a checksum, listed in reverse order
+        std::fs::write(
+            &path,
+            r#"{"interactions":[{
+                "method":"/cosmwasm.wasm.v1.Query/Codes",
+                "height":null,
+                "body":"0a022801",
+                "response":"0a2408091a20aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa0a2408071a20aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa0a2408051a20bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb"
+            }]}"#,
+        )
+        .unwrap();
+        let mut builder = crate::CosmosBuilder::new(
+            "osmosis-1",
+            "uosmo",
+            AddressHrp::from_static("osmo"),
+            "http://127.0.0.1:1",
+        );
+        builder.set_fixture(Some(crate::FixtureMode::Replay(path.clone())));
+        let cosmos = builder.build_lazy().unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let found = cosmos.find_code_by_checksum(&[0xaa; 32]).await.unwrap();
+        assert_eq!(found.map(|code_id| code_id.get_code_id()), Some(9));
+        let found = cosmos.find_code_by_checksum(&[0xbb; 32]).await.unwrap();
+        assert_eq!(found.map(|code_id| code_id.get_code_id()), Some(5));
+        let found = cosmos.find_code_by_checksum(&[0xcc; 32]).await.unwrap();
+        assert!(found.is_none());
+    }
+
+    #[test]
+    fn store_code_checksum_event() {
+        let checksum = Sha256::digest(b"\0asm").to_vec();
+        let events = [TxEvent {
+            kind: "store_code".to_owned(),
+            attributes: vec![
+                ("code_checksum".to_owned(), hex::encode(&checksum)),
+                ("code_id".to_owned(), "12".to_owned()),
+            ],
+        }];
+        assert_eq!(stored_code_checksum(&events), Some(checksum));
+        let events = [TxEvent {
+            kind: "store_code".to_owned(),
+            attributes: vec![("code_id".to_owned(), "12".to_owned())],
+        }];
+        assert_eq!(stored_code_checksum(&events), None);
+    }
+
+    #[test]
+    fn instantiate2_address() {
+        // Test vectors from cosmwasm-std
+        let checksum =
+            hex::decode("13a1fc994cc6d1c81b746ee0c0ff6f90043875e0bf1d9be6b7d779fc978dc2a5")
+                .unwrap();
+        let creator = RawAddress::from([
+            0x99, 0x99, 0x99, 0x99, 0x99, 0xaa, 0xaa, 0xaa, 0xaa, 0xaa, 0xbb, 0xbb, 0xbb, 0xbb,
+            0xbb, 0xcc, 0xcc, 0xcc, 0xcc, 0xcc,
+        ])
+        .with_hrp(AddressHrp::from_static("osmo"));
+        let expected = |hex: &str| {
+            let raw: [u8; 32] = hex::decode(hex).unwrap().try_into().unwrap();
+            RawAddress::from(raw).with_hrp(AddressHrp::from_static("osmo"))
+        };
+
+        assert_eq!(
+            predict_instantiate2_address(&checksum, creator, b"a", None).unwrap(),
+            expected("5e865d3e45ad3e961f77fd77d46543417ced44d924dc3e079b5415ff6775f847")
+        );
+        assert_eq!(
+            predict_instantiate2_address(&checksum, creator, b"a", Some(b"{}")).unwrap(),
+            expected("0995499608947a5281e2c7ebd71bdb26a1ad981946dad57f6c4d3ee35de77835")
+        );
+        predict_instantiate2_address(&checksum, creator, b"", None).unwrap_err();
+        predict_instantiate2_address(&checksum[1..], creator, b"a", None).unwrap_err();
+    }
+}
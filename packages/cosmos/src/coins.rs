@@ -0,0 +1,716 @@
+use std::{cmp::Ordering, collections::BTreeMap, fmt::Display, str::FromStr};
+
+use cosmos_sdk_proto::cosmos::bank::v1beta1::{Metadata, QueryDenomMetadataRequest};
+
+use crate::{
+    error::{Action, CoinError},
+    Coin, Cosmos,
+};
+
+/// A single coin with a numeric amount.
+///
+/// Can be parsed from strings like `2500000uosmo`. Amounts in a few well
+/// known display denoms whose base denom and exponent are fixed, like `1osmo`
+/// or `1.5osmo`, are converted to base units, see [DenomDisplay::known].
+/// Decimal amounts in any other denom are rejected. For those use
+/// [DenomDisplay::parse_coin] with the chain's metadata, or
+/// [ParsedCoin::from_decimal] with an explicit exponent.
+#[derive(PartialEq, Eq, Debug, Clone, Hash)]
+pub struct ParsedCoin {
+    denom: String,
+    amount: u128,
+}
+
+impl ParsedCoin {
+    /// Construct a new coin from a denom and base unit amount.
+    pub fn new(denom: impl Into<String>, amount: u128) -> Self {
+        ParsedCoin {
+            denom: denom.into(),
+            amount,
+        }
+    }
+
+    /// Convert a decimal display amount into base units of the given denom.
+    ///
+    /// For example, `1.5` with base denom `uosmo` and exponent `6` gives
+    /// `1500000uosmo`.
+    pub fn from_decimal(
+        amount: &str,
+        base_denom: impl Into<String>,
+        exponent: u32,
+    ) -> Result<Self, CoinError> {
+        let base_denom = base_denom.into();
+        let invalid = || CoinError::InvalidFormat {
+            input: amount.to_owned(),
+        };
+        let (whole, fraction) = match amount.split_once('.') {
+            Some((whole, fraction)) => (whole, fraction),
+            None => (amount, ""),
+        };
+        if whole.is_empty() && fraction.is_empty() {
+            return Err(invalid());
+        }
+        if !whole
+            .bytes()
+            .chain(fraction.bytes())
+            .all(|b| b.is_ascii_digit())
+        {
+            return Err(invalid());
+        }
+        let fraction = fraction.trim_end_matches('0');
+        if fraction.len() > exponent as usize {
+            return Err(CoinError::TooManyDecimals {
+                input: amount.to_owned(),
+                exponent,
+            });
+        }
+        let overflow = || CoinError::Overflow {
+            denom: base_denom.clone(),
+        };
+        let scale = 10u128.checked_pow(exponent).ok_or_else(overflow)?;
+        let whole: u128 = if whole.is_empty() {
+            0
+        } else {
+            whole.parse().map_err(|_| overflow())?
+        };
+        let fraction: u128 = if fraction.is_empty() {
+            0
+        } else {
+            fraction.parse::<u128>().map_err(|_| overflow())?
+                * 10u128.pow(exponent - fraction.len() as u32)
+        };
+        let amount = whole
+            .checked_mul(scale)
+            .and_then(|whole| whole.checked_add(fraction))
+            .ok_or_else(overflow)?;
+        Ok(ParsedCoin {
+            denom: base_denom,
+            amount,
+        })
+    }
+
+    /// The denom of this coin.
+    pub fn denom(&self) -> &str {
+        &self.denom
+    }
+
+    /// The amount of this coin, in base units.
+    pub fn amount(&self) -> u128 {
+        self.amount
+    }
+
+    /// Add two coins of the same denom.
+    pub fn checked_add(&self, rhs: &ParsedCoin) -> Result<ParsedCoin, CoinError> {
+        self.check_denom(rhs)?;
+        self.amount
+            .checked_add(rhs.amount)
+            .map(|amount| ParsedCoin::new(self.denom.clone(), amount))
+            .ok_or_else(|| CoinError::Overflow {
+                denom: self.denom.clone(),
+            })
+    }
+
+    /// Subtract a coin of the same denom from this coin.
+    pub fn checked_sub(&self, rhs: &ParsedCoin) -> Result<ParsedCoin, CoinError> {
+        self.check_denom(rhs)?;
+        self.amount
+            .checked_sub(rhs.amount)
+            .map(|amount| ParsedCoin::new(self.denom.clone(), amount))
+            .ok_or_else(|| CoinError::Insufficient {
+                denom: self.denom.clone(),
+                available: self.amount,
+                requested: rhs.amount,
+            })
+    }
+
+    fn check_denom(&self, rhs: &ParsedCoin) -> Result<(), CoinError> {
+        if self.denom == rhs.denom {
+            Ok(())
+        } else {
+            Err(CoinError::MismatchedDenoms {
+                left: self.denom.clone(),
+                right: rhs.denom.clone(),
+            })
+        }
+    }
+}
+
+/// Coins are only comparable when they share a denom.
+impl PartialOrd for ParsedCoin {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        if self.denom == other.denom {
+            Some(self.amount.cmp(&other.amount))
+        } else {
+            None
+        }
+    }
+}
+
+impl FromStr for ParsedCoin {
+    type Err = CoinError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || CoinError::InvalidFormat {
+            input: s.to_owned(),
+        };
+        let split = s
+            .find(|c: char| !c.is_ascii_digit() && c != '.')
+            .ok_or_else(invalid)?;
+        let (amount, denom) = s.split_at(split);
+        if amount.is_empty() || !is_valid_denom(denom) {
+            return Err(invalid());
+        }
+        match DenomDisplay::known(denom) {
+            Some(display) => display.from_display_amount(amount).map_err(|e| match e {
+                CoinError::InvalidFormat { .. } => invalid(),
+                CoinError::TooManyDecimals { input: _, exponent } => CoinError::TooManyDecimals {
+                    input: s.to_owned(),
+                    exponent,
+                },
+                e => e,
+            }),
+            None if amount.contains('.') => Err(CoinError::UnknownDecimals {
+                input: s.to_owned(),
+            }),
+            None => Ok(ParsedCoin {
+                denom: denom.to_owned(),
+                amount: amount.parse().map_err(|_| CoinError::Overflow {
+                    denom: denom.to_owned(),
+                })?,
+            }),
+        }
+    }
+}
+
+fn is_valid_denom(denom: &str) -> bool {
+    let mut chars = denom.chars();
+    chars.next().map_or(false, |c| c.is_ascii_alphabetic())
+        && chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '/' | ':' | '.' | '_' | '-'))
+}
+
+impl Display for ParsedCoin {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}{}", self.amount, self.denom)
+    }
+}
+
+impl From<ParsedCoin> for Coin {
+    fn from(ParsedCoin { denom, amount }: ParsedCoin) -> Self {
+        Coin {
+            denom,
+            amount: amount.to_string(),
+        }
+    }
+}
+
+impl TryFrom<&Coin> for ParsedCoin {
+    type Error = CoinError;
+
+    fn try_from(Coin { denom, amount }: &Coin) -> Result<Self, CoinError> {
+        Ok(ParsedCoin {
+            denom: denom.clone(),
+            amount: amount.parse().map_err(|_| CoinError::InvalidFormat {
+                input: format!("{amount}{denom}"),
+            })?,
+        })
+    }
+}
+
+impl TryFrom<Coin> for ParsedCoin {
+    type Error = CoinError;
+
+    fn try_from(coin: Coin) -> Result<Self, CoinError> {
+        (&coin).try_into()
+    }
+}
+
+/// A collection of coins with at most one entry per denom.
+///
+/// Adding a coin merges it with any existing amount of the same denom. The
+/// string format is a comma separated list, e.g. `100uatom,2500000uosmo`.
+#[derive(PartialEq, Eq, Debug, Clone, Default)]
+pub struct Coins(BTreeMap<String, u128>);
+
+impl Coins {
+    /// An empty collection.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a coin, merging with any existing amount of the same denom.
+    pub fn add(&mut self, coin: ParsedCoin) -> Result<(), CoinError> {
+        if coin.amount == 0 {
+            return Ok(());
+        }
+        let ParsedCoin { denom, amount } = coin;
+        let entry = self.0.entry(denom.clone()).or_default();
+        *entry = entry
+            .checked_add(amount)
+            .ok_or(CoinError::Overflow { denom })?;
+        Ok(())
+    }
+
+    /// Subtract a coin, failing if there is not enough of that denom.
+    pub fn sub(&mut self, coin: &ParsedCoin) -> Result<(), CoinError> {
+        let available = self.get(&coin.denom);
+        let remaining =
+            available
+                .checked_sub(coin.amount)
+                .ok_or_else(|| CoinError::Insufficient {
+                    denom: coin.denom.clone(),
+                    available,
+                    requested: coin.amount,
+                })?;
+        if remaining == 0 {
+            self.0.remove(&coin.denom);
+        } else {
+            self.0.insert(coin.denom.clone(), remaining);
+        }
+        Ok(())
+    }
+
+    /// Add all coins from another collection.
+    pub fn add_all(&mut self, other: Coins) -> Result<(), CoinError> {
+        other.into_iter().try_for_each(|coin| self.add(coin))
+    }
+
+    /// Amount held of the given denom, zero if absent.
+    pub fn get(&self, denom: &str) -> u128 {
+        self.0.get(denom).copied().unwrap_or_default()
+    }
+
+    /// Does this collection contain at least the amounts in `other`?
+    pub fn contains(&self, other: &Coins) -> bool {
+        other
+            .0
+            .iter()
+            .all(|(denom, amount)| self.get(denom) >= *amount)
+    }
+
+    /// Are there no coins in this collection?
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Iterate over the coins, sorted by denom.
+    pub fn iter(&self) -> impl Iterator<Item = ParsedCoin> + '_ {
+        self.0
+            .iter()
+            .map(|(denom, amount)| ParsedCoin::new(denom.clone(), *amount))
+    }
+
+    /// Convert into protobuf coins, sorted by denom as the chain requires.
+    pub fn into_vec(self) -> Vec<Coin> {
+        self.into_iter().map(Coin::from).collect()
+    }
+}
+
+impl IntoIterator for Coins {
+    type Item = ParsedCoin;
+    type IntoIter = std::vec::IntoIter<ParsedCoin>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0
+            .into_iter()
+            .map(|(denom, amount)| ParsedCoin { denom, amount })
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+}
+
+impl TryFrom<Vec<ParsedCoin>> for Coins {
+    type Error = CoinError;
+
+    fn try_from(coins: Vec<ParsedCoin>) -> Result<Self, CoinError> {
+        let mut res = Coins::new();
+        coins.into_iter().try_for_each(|coin| res.add(coin))?;
+        Ok(res)
+    }
+}
+
+impl TryFrom<&[Coin]> for Coins {
+    type Error = CoinError;
+
+    fn try_from(coins: &[Coin]) -> Result<Self, CoinError> {
+        let mut res = Coins::new();
+        for coin in coins {
+            res.add(coin.try_into()?)?;
+        }
+        Ok(res)
+    }
+}
+
+impl From<Coins> for Vec<Coin> {
+    fn from(coins: Coins) -> Self {
+        coins.into_vec()
+    }
+}
+
+impl FromStr for Coins {
+    type Err = CoinError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut res = Coins::new();
+        for coin in s.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+            res.add(coin.parse()?)?;
+        }
+        Ok(res)
+    }
+}
+
+impl Display for Coins {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        for (idx, coin) in self.iter().enumerate() {
+            if idx > 0 {
+                write!(f, ",")?;
+            }
+            write!(f, "{coin}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Display denoms with a fixed base denom and exponent, used when parsing
+/// decimal amounts without chain metadata.
+const KNOWN_DISPLAY_DENOMS: &[(&str, &str, u32)] = &[
+    ("atom", "uatom", 6),
+    ("osmo", "uosmo", 6),
+    ("juno", "ujuno", 6),
+    ("ntrn", "untrn", 6),
+    ("stars", "ustars", 6),
+    ("kuji", "ukuji", 6),
+    ("sei", "usei", 6),
+    ("evmos", "aevmos", 18),
+];
+
+/// Conversion between base units and display units of a denom.
+///
+/// For example, with base denom `uosmo` and exponent 6, `1500000uosmo` is
+/// displayed as `1.5 OSMO`.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct DenomDisplay {
+    base: String,
+    display: String,
+    symbol: String,
+    exponent: u32,
+}
+
+impl DenomDisplay {
+    /// Construct from explicitly supplied values.
+    ///
+    /// The symbol defaults to the upper-cased display denom.
+    pub fn new(base: impl Into<String>, display: impl Into<String>, exponent: u32) -> Self {
+        let display = display.into();
+        DenomDisplay {
+            base: base.into(),
+            symbol: display.to_uppercase(),
+            display,
+            exponent,
+        }
+    }
+
+    /// Look up one of the well known display denoms, e.g. `osmo` or `evmos`.
+    ///
+    /// Only covers denoms whose exponent is the same on every chain. Use
+    /// [Cosmos::denom_display] for anything else.
+    pub fn known(display: &str) -> Option<Self> {
+        KNOWN_DISPLAY_DENOMS
+            .iter()
+            .find(|(x, _, _)| x.eq_ignore_ascii_case(display))
+            .map(|(display, base, exponent)| DenomDisplay::new(*base, *display, *exponent))
+    }
+
+    /// Construct from on-chain bank metadata.
+    ///
+    /// Returns [None] if the metadata does not contain a unit for its display denom.
+    pub fn from_metadata(metadata: &Metadata) -> Option<Self> {
+        let unit = metadata.denom_units.iter().find(|unit| {
+            unit.denom == metadata.display || unit.aliases.contains(&metadata.display)
+        })?;
+        let mut res = DenomDisplay::new(&metadata.base, &metadata.display, unit.exponent);
+        if !metadata.symbol.is_empty() {
+            res.symbol = metadata.symbol.clone();
+        }
+        Some(res)
+    }
+
+    /// Base denom, e.g. `uosmo`.
+    pub fn base(&self) -> &str {
+        &self.base
+    }
+
+    /// Display denom, e.g. `osmo`.
+    pub fn display(&self) -> &str {
+        &self.display
+    }
+
+    /// Symbol used when formatting, e.g. `OSMO`.
+    pub fn symbol(&self) -> &str {
+        &self.symbol
+    }
+
+    /// Number of decimal places between the base and display denoms.
+    pub fn exponent(&self) -> u32 {
+        self.exponent
+    }
+
+    /// Convert an amount in base units to a decimal string in display units.
+    pub fn to_display_amount(&self, amount: u128) -> String {
+        let exponent = self.exponent as usize;
+        if exponent == 0 {
+            return amount.to_string();
+        }
+        let digits = format!("{amount:0>width$}", width = exponent + 1);
+        let (whole, fraction) = digits.split_at(digits.len() - exponent);
+        let fraction = fraction.trim_end_matches('0');
+        if fraction.is_empty() {
+            whole.to_owned()
+        } else {
+            format!("{whole}.{fraction}")
+        }
+    }
+
+    /// Convert a decimal amount in display units to base units.
+    pub fn from_display_amount(&self, amount: &str) -> Result<ParsedCoin, CoinError> {
+        ParsedCoin::from_decimal(amount, &self.base, self.exponent)
+    }
+
+    /// Format a coin of the base denom in display units, e.g. `1.5 OSMO`.
+    pub fn format_coin(&self, coin: &ParsedCoin) -> Result<String, CoinError> {
+        if coin.denom != self.base {
+            return Err(CoinError::MismatchedDenoms {
+                left: self.base.clone(),
+                right: coin.denom.clone(),
+            });
+        }
+        Ok(format!(
+            "{} {}",
+            self.to_display_amount(coin.amount),
+            self.symbol
+        ))
+    }
+
+    /// Parse a coin given in either base or display units.
+    ///
+    /// Accepts values like `1500000uosmo`, `1.5osmo` and `1.5 OSMO`.
+    pub fn parse_coin(&self, s: &str) -> Result<ParsedCoin, CoinError> {
+        let invalid = || CoinError::InvalidFormat {
+            input: s.to_owned(),
+        };
+        let trimmed = s.trim();
+        let split = trimmed
+            .find(|c: char| !c.is_ascii_digit() && c != '.')
+            .ok_or_else(invalid)?;
+        let (amount, denom) = trimmed.split_at(split);
+        let denom = denom.trim_start();
+        if denom == self.base {
+            ParsedCoin::from_decimal(amount, &self.base, 0)
+        } else if denom.eq_ignore_ascii_case(&self.display)
+            || denom.eq_ignore_ascii_case(&self.symbol)
+        {
+            self.from_display_amount(amount)
+        } else {
+            Err(CoinError::MismatchedDenoms {
+                left: self.base.clone(),
+                right: denom.to_owned(),
+            })
+        }
+    }
+}
+
+impl Cosmos {
+    /// Fetch the display information for the given base denom from bank metadata.
+    pub async fn denom_display(
+        &self,
+        denom: impl Into<String>,
+    ) -> Result<DenomDisplay, crate::Error> {
+        let denom = denom.into();
+        let action = Action::QueryDenomMetadata(denom.clone());
+        let metadata = self
+            .perform_query(QueryDenomMetadataRequest { denom }, action.clone(), true)
+            .await?
+            .into_inner()
+            .metadata
+            .ok_or_else(|| crate::Error::InvalidChainResponse {
+                message: "Missing metadata field".to_owned(),
+                action: action.clone(),
+            })?;
+        DenomDisplay::from_metadata(&metadata).ok_or_else(|| crate::Error::InvalidChainResponse {
+            message: format!("No denom unit found for display denom {}", metadata.display),
+            action,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use quickcheck::quickcheck;
+
+    use super::*;
+
+    #[test]
+    fn parse_coins() {
+        assert_eq!(
+            "2500000uosmo".parse::<ParsedCoin>().unwrap(),
+            ParsedCoin::new("uosmo", 2500000)
+        );
+        assert_eq!(
+            "1.5osmo".parse::<ParsedCoin>().unwrap(),
+            ParsedCoin::new("uosmo", 1500000)
+        );
+        assert_eq!(
+            "1osmo".parse::<ParsedCoin>().unwrap(),
+            ParsedCoin::new("uosmo", 1000000)
+        );
+        assert_eq!(
+            "2ATOM".parse::<ParsedCoin>().unwrap(),
+            ParsedCoin::new("uatom", 2000000)
+        );
+        assert_eq!(
+            "5ibc/27394FB092D2ECCD56123C74F36E4C1F926001CEADA9CA97EA622B25F41E5EB2"
+                .parse::<ParsedCoin>()
+                .unwrap()
+                .amount(),
+            5
+        );
+        assert_eq!(
+            "0.5evmos".parse::<ParsedCoin>().unwrap(),
+            ParsedCoin::new("aevmos", 500_000_000_000_000_000)
+        );
+        assert_eq!(
+            "123456factory/osmo12g96ahplpf78558cv5pyunus2m66guykt96lvc/LvN1"
+                .parse::<ParsedCoin>()
+                .unwrap(),
+            ParsedCoin::new(
+                "factory/osmo12g96ahplpf78558cv5pyunus2m66guykt96lvc/LvN1",
+                123456
+            )
+        );
+        "1.0000001osmo".parse::<ParsedCoin>().unwrap_err();
+        assert!(matches!(
+            "1.5uosmo".parse::<ParsedCoin>(),
+            Err(CoinError::UnknownDecimals { .. })
+        ));
+        "1.5inj".parse::<ParsedCoin>().unwrap_err();
+        "123ujunox!".parse::<ParsedCoin>().unwrap_err();
+        "uosmo".parse::<ParsedCoin>().unwrap_err();
+        "100".parse::<ParsedCoin>().unwrap_err();
+        "1..5osmo".parse::<ParsedCoin>().unwrap_err();
+    }
+
+    #[test]
+    fn from_decimal() {
+        assert_eq!(
+            ParsedCoin::from_decimal("0.000000000000000001", "inj", 18).unwrap(),
+            ParsedCoin::new("inj", 1)
+        );
+        assert_eq!(
+            ParsedCoin::from_decimal("12", "uosmo", 6).unwrap(),
+            ParsedCoin::new("uosmo", 12000000)
+        );
+    }
+
+    #[test]
+    fn coins_merge() {
+        let mut coins: Coins = "5uosmo,3uatom,2uosmo".parse().unwrap();
+        assert_eq!(coins.to_string(), "3uatom,7uosmo");
+        coins.sub(&ParsedCoin::new("uatom", 3)).unwrap();
+        assert_eq!(coins.to_string(), "7uosmo");
+        coins.sub(&ParsedCoin::new("uosmo", 8)).unwrap_err();
+    }
+
+    #[test]
+    fn mismatched_denoms() {
+        let osmo = ParsedCoin::new("uosmo", 1);
+        let atom = ParsedCoin::new("uatom", 1);
+        osmo.checked_add(&atom).unwrap_err();
+        assert_eq!(osmo.partial_cmp(&atom), None);
+    }
+
+    #[test]
+    fn denom_display() {
+        let osmo = DenomDisplay::new("uosmo", "osmo", 6);
+        assert_eq!(osmo.to_display_amount(1500000), "1.5");
+        assert_eq!(osmo.to_display_amount(1), "0.000001");
+        assert_eq!(osmo.to_display_amount(2000000), "2");
+        assert_eq!(
+            osmo.format_coin(&ParsedCoin::new("uosmo", 1500000))
+                .unwrap(),
+            "1.5 OSMO"
+        );
+        for input in ["1500000uosmo", "1.5osmo", "1.5 OSMO"] {
+            assert_eq!(
+                osmo.parse_coin(input).unwrap(),
+                ParsedCoin::new("uosmo", 1500000)
+            );
+        }
+        osmo.parse_coin("1.5atom").unwrap_err();
+    }
+
+    #[test]
+    fn sanity() {
+        assert_eq!(
+            "1ujunox".parse::<ParsedCoin>().unwrap(),
+            ParsedCoin::new("ujunox", 1)
+        );
+        "1.523ujunox".parse::<ParsedCoin>().unwrap_err();
+        "foobar".parse::<ParsedCoin>().unwrap_err();
+        "123ujunox!".parse::<ParsedCoin>().unwrap_err();
+        assert_eq!(
+            "123456uwbtc".parse::<ParsedCoin>().unwrap(),
+            ParsedCoin::new("uwbtc", 123456)
+        );
+        assert_eq!(
+            "123456factory/osmo12g96ahplpf78558cv5pyunus2m66guykt96lvc/lvn1"
+                .parse::<ParsedCoin>()
+                .unwrap(),
+            ParsedCoin::new(
+                "factory/osmo12g96ahplpf78558cv5pyunus2m66guykt96lvc/lvn1",
+                123456
+            )
+        );
+    }
+
+    #[derive(Clone, Debug)]
+    struct DenomString(String);
+
+    impl quickcheck::Arbitrary for DenomString {
+        fn arbitrary(g: &mut quickcheck::Gen) -> Self {
+            // See https://github.com/BurntSushi/quickcheck/issues/279
+            let sizes = (3..20).collect::<Vec<_>>();
+            let letters = ('a'..='z').collect::<Vec<_>>();
+            DenomString(
+                (1..*g.choose(&sizes).unwrap())
+                    .map(|_| *g.choose(&letters).unwrap())
+                    .collect(),
+            )
+        }
+    }
+
+    quickcheck! {
+        fn roundtrip(amount: u128) -> bool {
+            let coin = ParsedCoin::new("uosmo", amount);
+            coin.to_string().parse::<ParsedCoin>().unwrap() == coin
+        }
+
+        fn roundtrip_denom(amount: u128, denom: DenomString) -> quickcheck::TestResult {
+            let denom = denom.0;
+            // Display denoms are converted to their base denom
+            if DenomDisplay::known(&denom).is_some() {
+                return quickcheck::TestResult::discard();
+            }
+            let actual = format!("{amount}{denom}").parse::<ParsedCoin>().unwrap();
+            quickcheck::TestResult::from_bool(actual == ParsedCoin::new(denom, amount))
+        }
+
+        fn display_roundtrip(amount: u128, exponent: u8) -> bool {
+            let display = DenomDisplay::new("ufoo", "foo", (exponent % 30).into());
+            let amount = amount % 10u128.pow(30);
+            display
+                .from_display_amount(&display.to_display_amount(amount))
+                .unwrap()
+                .amount()
+                == amount
+        }
+    }
+}
@@ -1,4 +1,4 @@
-use std::{fmt::Display, str::FromStr};
+use std::{fmt::Display, future::Future, str::FromStr, sync::Arc};
 
 use cosmos_sdk_proto::{
     cosmos::{
@@ -6,15 +6,17 @@ use cosmos_sdk_proto::{
         tx::v1beta1::SimulateResponse,
     },
     cosmwasm::wasm::v1::{
-        ContractInfo, MsgExecuteContract, MsgInstantiateContract, MsgMigrateContract,
-        QueryContractHistoryRequest, QueryContractHistoryResponse, QueryContractInfoRequest,
-        QueryRawContractStateRequest, QuerySmartContractStateRequest,
+        ContractInfo, Model, MsgExecuteContract, MsgInstantiateContract, MsgMigrateContract,
+        QueryAllContractStateRequest, QueryContractHistoryRequest, QueryContractHistoryResponse,
+        QueryContractInfoRequest, QueryRawContractStateRequest, QuerySmartContractStateRequest,
     },
 };
+use futures_util::{Stream, TryStreamExt};
 
 use crate::{
     address::{AddressHrp, HasAddressHrp},
     error::{Action, ContractAdminParseError, QueryError},
+    messages::MsgInstantiateContract2,
     TxResponseExt,
 };
 use crate::{Address, CodeId, Cosmos, HasAddress, HasCosmos, TxBuilder, Wallet};
@@ -56,6 +58,81 @@ impl Cosmos {
         }
     }
 
+    /// Perform a smart contract query and parse the response as JSON.
+    ///
+    /// Shorthand for [Contract::query] without first creating a [Contract].
+    pub async fn wasm_query_typed<Q: serde::Serialize, R: serde::de::DeserializeOwned>(
+        &self,
+        contract: impl HasAddress,
+        query: &Q,
+    ) -> Result<R, crate::Error> {
+        self.make_contract(contract.get_address())
+            .query(query)
+            .await
+    }
+
+    /// Lazily load every raw key/value pair in a contract's storage.
+    ///
+    /// Entries are yielded in key order, one page at a time.
+    pub fn wasm_all_state(
+        &self,
+        contract: impl HasAddress,
+    ) -> impl Stream<Item = Result<(Vec<u8>, Vec<u8>), crate::Error>> + Send + 'static {
+        let contract = contract.get_address();
+        self.paginate(
+            Action::AllContractState(contract),
+            move |pagination| QueryAllContractStateRequest {
+                address: contract.get_address_string(),
+                pagination,
+            },
+            |res| {
+                (
+                    res.models
+                        .into_iter()
+                        .map(|Model { key, value }| (key, value))
+                        .collect(),
+                    res.pagination,
+                )
+            },
+        )
+    }
+
+    /// Like [Self::wasm_all_state], but only include keys starting with the given prefix.
+    ///
+    /// The chain has no server side filtering, so all keys before the prefix
+    /// still need to be loaded. The stream ends as soon as the keys pass the
+    /// prefix.
+    pub fn wasm_all_state_with_prefix(
+        &self,
+        contract: impl HasAddress,
+        prefix: impl Into<Vec<u8>>,
+    ) -> impl Stream<Item = Result<(Vec<u8>, Vec<u8>), crate::Error>> + Send + 'static {
+        let prefix = Arc::new(prefix.into());
+        let prefix_skip = prefix.clone();
+        self.wasm_all_state(contract)
+            .try_skip_while(move |(key, _)| {
+                futures_util::future::ready(Ok(key.as_slice() < prefix_skip.as_slice()))
+            })
+            .try_take_while(move |(key, _)| {
+                futures_util::future::ready(Ok(key.starts_with(&prefix)))
+            })
+    }
+
+    /// Load all entries of a `cw-storage-plus` `Map` with the given namespace.
+    ///
+    /// The namespace prefix is removed from the returned keys, leaving the
+    /// encoded map keys.
+    pub fn wasm_map_state(
+        &self,
+        contract: impl HasAddress,
+        namespace: impl AsRef<[u8]>,
+    ) -> impl Stream<Item = Result<(Vec<u8>, Vec<u8>), crate::Error>> + Send + 'static {
+        let prefix = storage_map_prefix(namespace.as_ref());
+        let len = prefix.len();
+        self.wasm_all_state_with_prefix(contract, prefix)
+            .map_ok(move |(mut key, value)| (key.split_off(len), value))
+    }
+
     /// Make a new [CodeId] for the given numeric ID.
     pub fn make_code_id(&self, code_id: u64) -> CodeId {
         CodeId {
@@ -91,11 +168,7 @@ impl CodeId {
         let msg = msg.into();
         let msg = MsgInstantiateContract {
             sender: wallet.get_address().to_string(),
-            admin: match admin {
-                ContractAdmin::NoAdmin => "".to_owned(),
-                ContractAdmin::Sender => wallet.get_address_string(),
-                ContractAdmin::Addr(addr) => addr.get_address_string(),
-            },
+            admin: admin.render(wallet),
             code_id: self.code_id,
             label: label.into(),
             msg: msg.into_bytes(),
@@ -103,6 +176,44 @@ impl CodeId {
         };
         let mut txbuilder = TxBuilder::default();
         txbuilder.add_message(msg);
+        self.broadcast_instantiate(wallet, txbuilder).await
+    }
+
+    /// Instantiate a new contract at a predictable address using `MsgInstantiateContract2`.
+    ///
+    /// The resulting address can be computed ahead of time with
+    /// [crate::predict_instantiate2_address]. The message is not included in
+    /// the address, to do so add a [MsgInstantiateContract2] with `fix_msg`
+    /// set to a [TxBuilder] directly.
+    pub async fn instantiate2(
+        &self,
+        wallet: &Wallet,
+        label: impl Into<String>,
+        funds: Vec<Coin>,
+        msg: impl serde::Serialize,
+        admin: ContractAdmin,
+        salt: impl Into<Vec<u8>>,
+    ) -> Result<Contract, crate::Error> {
+        let msg = MsgInstantiateContract2 {
+            sender: wallet.get_address().to_string(),
+            admin: admin.render(wallet),
+            code_id: self.code_id,
+            label: label.into(),
+            msg: serde_json::to_vec(&msg)?,
+            funds,
+            salt: salt.into(),
+            fix_msg: false,
+        };
+        let mut txbuilder = TxBuilder::default();
+        txbuilder.add_message(msg);
+        self.broadcast_instantiate(wallet, txbuilder).await
+    }
+
+    async fn broadcast_instantiate(
+        &self,
+        wallet: &Wallet,
+        txbuilder: TxBuilder,
+    ) -> Result<Contract, crate::Error> {
         let res = txbuilder.sign_and_broadcast(&self.client, wallet).await?;
 
         let addr =
@@ -290,8 +401,37 @@ impl Contract {
             )
             .await?
             .into_inner();
-        serde_json::from_slice(&res.data)
-            .map_err(|source| crate::Error::JsonDeserialize { source, action })
+        serde_json::from_slice(&res.data).map_err(|source| crate::Error::JsonDeserialize {
+            source,
+            action,
+            raw: res.data,
+        })
+    }
+
+    /// Perform multiple smart contract queries concurrently, all at the same block height.
+    ///
+    /// If this contract is not already pinned to a height, the latest block
+    /// height is used. Results are returned in the same order as the messages.
+    pub async fn query_many<T, Msg, I>(&self, msgs: I) -> Result<Vec<T>, crate::Error>
+    where
+        T: serde::de::DeserializeOwned,
+        Msg: serde::Serialize,
+        I: IntoIterator<Item = Msg>,
+    {
+        let msgs = msgs
+            .into_iter()
+            .map(|msg| serde_json::to_vec(&msg))
+            .collect::<Result<Vec<_>, _>>()?;
+        let contract = match self.client.get_height() {
+            Some(_) => self.clone(),
+            None => {
+                let height = self.client.get_latest_block_info().await?.height;
+                self.clone()
+                    .at_height(Some(height.try_into().unwrap_or_default()))
+            }
+        };
+        let queries = msgs.into_iter().map(|msg| contract.query_rendered(msg));
+        futures_util::future::try_join_all(queries).await
     }
 
     /// Perform a contract migration with the given message
@@ -394,6 +534,17 @@ pub enum ContractAdmin {
     Addr(Address),
 }
 
+impl ContractAdmin {
+    /// The admin address to put in an instantiate message.
+    fn render(self, wallet: &Wallet) -> String {
+        match self {
+            ContractAdmin::NoAdmin => "".to_owned(),
+            ContractAdmin::Sender => wallet.get_address_string(),
+            ContractAdmin::Addr(addr) => addr.get_address_string(),
+        }
+    }
+}
+
 impl FromStr for ContractAdmin {
     type Err = ContractAdminParseError;
 
@@ -410,3 +561,112 @@ impl FromStr for ContractAdmin {
         }
     }
 }
+
+/// The key prefix `cw-storage-plus` uses for entries of a `Map` with the given namespace.
+///
+/// This is the namespace length as a big endian `u16`, followed by the namespace itself.
+fn storage_map_prefix(namespace: &[u8]) -> Vec<u8> {
+    let len = u16::try_from(namespace.len()).unwrap_or(u16::MAX);
+    let mut prefix = Vec::with_capacity(namespace.len() + 2);
+    prefix.extend_from_slice(&len.to_be_bytes());
+    prefix.extend_from_slice(namespace);
+    prefix
+}
+
+/// Lazily load every item from a smart query paginated with `start_after`, as
+/// used by the cw20, cw721 and cw3 specs.
+///
+/// Each page starts after the key `next_key` gives for the last item of the
+/// previous page. Contracts may return fewer items than requested before
+/// reaching the end, so only an empty page ends the stream.
+pub(crate) fn paginate_start_after<Item, Key, LoadPage, Fut, NextKey>(
+    load_page: LoadPage,
+    next_key: NextKey,
+) -> impl Stream<Item = Result<Item, crate::Error>> + Send + 'static
+where
+    Item: Send + 'static,
+    Key: Send + 'static,
+    LoadPage: FnMut(Option<Key>) -> Fut + Send + 'static,
+    Fut: Future<Output = Result<Vec<Item>, crate::Error>> + Send,
+    NextKey: Fn(&Item) -> Key + Send + 'static,
+{
+    struct State<Item, Key, LoadPage, NextKey> {
+        load_page: LoadPage,
+        next_key: NextKey,
+        buffer: std::collections::VecDeque<Item>,
+        /// Key to start the next page after, or [None] once the last page is loaded
+        next: Option<Option<Key>>,
+    }
+    let state = State {
+        load_page,
+        next_key,
+        buffer: std::collections::VecDeque::new(),
+        next: Some(None),
+    };
+    futures_util::stream::unfold(state, |mut state| async move {
+        loop {
+            if let Some(item) = state.buffer.pop_front() {
+                return Some((Ok(item), state));
+            }
+            let start_after = state.next.take()?;
+            let items = match (state.load_page)(start_after).await {
+                Ok(items) => items,
+                Err(e) => return Some((Err(e), state)),
+            };
+            if let Some(last) = items.last() {
+                state.next = Some(Some((state.next_key)(last)));
+            }
+            state.buffer.extend(items);
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn start_after_continues_after_short_page() {
+        let items = (0..7).map(|i| format!("item{i}")).collect::<Vec<_>>();
+        let requests = Arc::new(parking_lot::Mutex::new(vec![]));
+        let loaded = {
+            let items = items.clone();
+            let requests = requests.clone();
+            paginate_start_after(
+                move |start_after: Option<String>| {
+                    requests.lock().push(start_after.clone());
+                    let start = match &start_after {
+                        None => 0,
+                        Some(after) => items.iter().position(|x| x == after).unwrap() + 1,
+                    };
+                    // The contract caps pages at 3 items, below the requested limit.
+                    let page = items.iter().skip(start).take(3).cloned().collect();
+                    async move { Ok(page) }
+                },
+                String::clone,
+            )
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap()
+        };
+        assert_eq!(loaded, items);
+        assert_eq!(
+            *requests.lock(),
+            vec![
+                None,
+                Some("item2".to_owned()),
+                Some("item5".to_owned()),
+                Some("item6".to_owned())
+            ]
+        );
+    }
+
+    #[test]
+    fn map_prefix() {
+        assert_eq!(
+            storage_map_prefix(b"balances"),
+            b"\x00\x08balances".to_vec()
+        );
+        assert_eq!(storage_map_prefix(b""), vec![0, 0]);
+    }
+}
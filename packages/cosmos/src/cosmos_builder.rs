@@ -1,8 +1,9 @@
-use std::{sync::Arc, time::Duration};
+use std::{path::PathBuf, sync::Arc, time::Duration};
 
 use crate::{
+    error::{Action, BuilderError, QueryError, QueryErrorDetails, RetryReason},
     gas_multiplier::{GasMultiplier, GasMultiplierConfig},
-    gas_price::{CurrentGasPrice, GasPriceMethod, DEFAULT_GAS_PRICE},
+    gas_price::{CurrentGasPrice, GasPriceMethod, GasPriceSource, DEFAULT_GAS_PRICE},
     AddressHrp, DynamicGasMultiplier,
 };
 
@@ -17,6 +18,9 @@ pub(crate) struct OsmosisGasParams {
 pub struct CosmosBuilder {
     grpc_url: Arc<String>,
     grpc_fallback_urls: Vec<Arc<String>>,
+    grpc_archive_urls: Vec<Arc<String>>,
+    lcd_url: Option<String>,
+    rpc_url: Option<String>,
     chain_id: String,
     gas_coin: String,
     hrp: AddressHrp,
@@ -24,13 +28,26 @@ pub struct CosmosBuilder {
     // Values with defaults
     gas_estimate_multiplier: GasMultiplierConfig,
     gas_price_method: Option<GasPriceMethod>,
+    alternative_fee_denoms: Vec<(String, f64, f64)>,
     gas_price_retry_attempts: Option<u64>,
     transaction_attempts: Option<usize>,
     referer_header: Option<String>,
+    grpc_headers: Vec<(String, String)>,
+    request_interceptor: Option<RequestInterceptor>,
+    query_logger: Option<QueryLogger>,
     request_count: Option<usize>,
     connection_timeout: Option<Duration>,
+    http2_keep_alive_interval: Option<Duration>,
+    keep_alive_timeout: Option<Duration>,
+    keep_alive_while_idle: Option<bool>,
+    tcp_keepalive: Option<Duration>,
+    initial_stream_window_size: Option<u32>,
+    initial_connection_window_size: Option<u32>,
+    concurrency_limit: Option<usize>,
+    proxy: Option<String>,
     idle_timeout_seconds: Option<u32>,
     query_timeout_seconds: Option<u32>,
+    broadcast_timeout_seconds: Option<u32>,
     query_retries: Option<u32>,
     block_lag_allowed: Option<u32>,
     latest_block_age_allowed: Option<Duration>,
@@ -42,7 +59,16 @@ pub struct CosmosBuilder {
     osmosis_gas_params: Option<OsmosisGasParams>,
     osmosis_gas_price_too_old_seconds: Option<u64>,
     max_price: Option<f64>,
+    max_fee: Option<u64>,
+    alternative_max_fees: Vec<(String, u64)>,
+    grant_expiry_window: Option<Duration>,
     rate_limit_per_second: Option<u64>,
+    broadcast_to_all_nodes: Option<bool>,
+    verify_chain_params: Option<bool>,
+    retry_policy: Option<RetryPolicy>,
+    message_gas_overrides: Vec<(String, GasOverride)>,
+    query_cache: Option<QueryCacheConfig>,
+    fixture: Option<FixtureMode>,
 }
 
 impl CosmosBuilder {
@@ -56,18 +82,34 @@ impl CosmosBuilder {
         Self {
             grpc_url: Arc::new(grpc_url.into()),
             grpc_fallback_urls: vec![],
+            grpc_archive_urls: vec![],
+            lcd_url: None,
+            rpc_url: None,
             chain_id: chain_id.into(),
             gas_coin: gas_coin.into(),
             hrp,
             gas_estimate_multiplier: GasMultiplierConfig::Default,
             gas_price_method: None,
+            alternative_fee_denoms: vec![],
             gas_price_retry_attempts: None,
             transaction_attempts: None,
             referer_header: None,
+            grpc_headers: vec![],
+            request_interceptor: None,
+            query_logger: None,
             request_count: None,
             connection_timeout: None,
+            http2_keep_alive_interval: None,
+            keep_alive_timeout: None,
+            keep_alive_while_idle: None,
+            tcp_keepalive: None,
+            initial_stream_window_size: None,
+            initial_connection_window_size: None,
+            concurrency_limit: None,
+            proxy: None,
             idle_timeout_seconds: None,
             query_timeout_seconds: None,
+            broadcast_timeout_seconds: None,
             query_retries: None,
             block_lag_allowed: None,
             latest_block_age_allowed: None,
@@ -79,7 +121,16 @@ impl CosmosBuilder {
             osmosis_gas_params: None,
             osmosis_gas_price_too_old_seconds: None,
             max_price: None,
+            max_fee: None,
+            alternative_max_fees: vec![],
+            grant_expiry_window: None,
             rate_limit_per_second: None,
+            broadcast_to_all_nodes: None,
+            verify_chain_params: None,
+            retry_policy: None,
+            message_gas_overrides: vec![],
+            query_cache: None,
+            fixture: None,
         }
     }
 
@@ -108,6 +159,18 @@ impl CosmosBuilder {
         &self.grpc_fallback_urls
     }
 
+    /// Add an archive node gRPC URL
+    ///
+    /// Queries pinned to a specific height, see [crate::Cosmos::at_height],
+    /// are sent to a healthy archive node when one is available.
+    pub fn add_grpc_archive_url(&mut self, url: impl Into<String>) {
+        self.grpc_archive_urls.push(url.into().into());
+    }
+
+    pub(crate) fn grpc_archive_urls(&self) -> &Vec<Arc<String>> {
+        &self.grpc_archive_urls
+    }
+
     /// Chain ID we want to communicate with
     pub fn chain_id(&self) -> &str {
         self.chain_id.as_ref()
@@ -185,6 +248,168 @@ impl CosmosBuilder {
         self.gas_price_method = Some(GasPriceMethod::new_static(low, high));
     }
 
+    /// Additional denoms accepted for fees, with their low and high gas prices.
+    ///
+    /// Some chains, like Kujira, accept fees in denoms other than
+    /// [Self::gas_coin]. See [Self::use_fee_denom].
+    pub fn alternative_fee_denoms(&self) -> &[(String, f64, f64)] {
+        &self.alternative_fee_denoms
+    }
+
+    /// Add an alternative fee denom, replacing any previous gas prices for it.
+    pub fn add_alternative_fee_denom(&mut self, denom: impl Into<String>, low: f64, high: f64) {
+        let denom = denom.into();
+        self.alternative_fee_denoms.retain(|(x, _, _)| *x != denom);
+        self.alternative_fee_denoms.push((denom, low, high));
+    }
+
+    /// Pay fees in one of the [Self::alternative_fee_denoms].
+    ///
+    /// This replaces the gas coin and uses the static gas prices configured
+    /// for the denom. If [Self::max_fee] is set, it is replaced by the
+    /// denom's [Self::alternative_max_fee], which must be configured.
+    pub fn use_fee_denom(&mut self, denom: &str) -> Result<(), BuilderError> {
+        if denom == self.gas_coin {
+            return Ok(());
+        }
+        let (_, low, high) = self
+            .alternative_fee_denoms
+            .iter()
+            .find(|(x, _, _)| x == denom)
+            .ok_or_else(|| BuilderError::UnknownFeeDenom {
+                denom: denom.to_owned(),
+            })?;
+        let (low, high) = (*low, *high);
+        if self.max_fee.is_some() {
+            self.max_fee = Some(self.alternative_max_fee(denom).ok_or_else(|| {
+                BuilderError::NoMaxFeeForDenom {
+                    denom: denom.to_owned(),
+                }
+            })?);
+        }
+        self.set_gas_price(low, high);
+        self.gas_coin = denom.to_owned();
+        Ok(())
+    }
+
+    /// Gas adjustments for specific message types, applied after simulation.
+    ///
+    /// See [Self::add_message_gas_override].
+    pub fn message_gas_overrides(&self) -> &[(String, GasOverride)] {
+        &self.message_gas_overrides
+    }
+
+    /// Adjust the gas requested for transactions containing the given message type URL.
+    ///
+    /// Some chains consistently under-simulate certain messages, such as
+    /// `/cosmwasm.wasm.v1.MsgStoreCode`, and a single gas multiplier is too
+    /// blunt to fix that. Replaces any previous override for the type URL.
+    pub fn add_message_gas_override(
+        &mut self,
+        type_url: impl Into<String>,
+        gas_override: GasOverride,
+    ) {
+        let type_url = type_url.into();
+        self.message_gas_overrides.retain(|(x, _)| *x != type_url);
+        self.message_gas_overrides.push((type_url, gas_override));
+    }
+
+    /// Apply [Self::message_gas_overrides] to the padded gas estimate for a transaction.
+    pub(crate) fn apply_gas_overrides<'a>(
+        &self,
+        type_urls: impl Iterator<Item = &'a str>,
+        gas: u64,
+    ) -> u64 {
+        let mut minimum = 0u64;
+        let mut additional = 0u64;
+        for type_url in type_urls {
+            match self
+                .message_gas_overrides
+                .iter()
+                .find(|(x, _)| x == type_url)
+            {
+                Some((_, GasOverride::Add(amount))) => {
+                    additional = additional.saturating_add(*amount)
+                }
+                Some((_, GasOverride::Minimum(amount))) => {
+                    minimum = minimum.saturating_add(*amount)
+                }
+                None => (),
+            }
+        }
+        gas.max(minimum).saturating_add(additional)
+    }
+
+    /// Maximum fee to pay for a single transaction, in [Self::gas_coin].
+    ///
+    /// Transactions whose fee would exceed this are not broadcast and fail
+    /// with [crate::Error::FeeTooHigh]. This protects against runaway fees
+    /// when gas estimation or gas prices misbehave. Fees paid in an
+    /// alternative fee denom are limited by [Self::alternative_max_fee].
+    ///
+    /// Default: [None], no maximum
+    pub fn max_fee(&self) -> Option<u64> {
+        self.max_fee
+    }
+
+    /// See [Self::max_fee]
+    pub fn set_max_fee(&mut self, max_fee: Option<u64>) {
+        self.max_fee = max_fee;
+    }
+
+    /// Maximum fee to pay for a single transaction in an alternative fee denom.
+    ///
+    /// The [Self::max_fee] in the gas coin can't be applied to other denoms,
+    /// so while it is set, paying fees in an alternative fee denom without
+    /// its own maximum fails with [crate::Error::NoMaxFeeForDenom].
+    pub fn alternative_max_fee(&self, denom: &str) -> Option<u64> {
+        self.alternative_max_fees
+            .iter()
+            .find(|(x, _)| x == denom)
+            .map(|(_, max_fee)| *max_fee)
+    }
+
+    /// See [Self::alternative_max_fee]
+    pub fn set_alternative_max_fee(&mut self, denom: impl Into<String>, max_fee: Option<u64>) {
+        let denom = denom.into();
+        self.alternative_max_fees.retain(|(x, _)| *x != denom);
+        if let Some(max_fee) = max_fee {
+            self.alternative_max_fees.push((denom, max_fee));
+        }
+    }
+
+    /// How far ahead [crate::Cosmos::check_grants] reports expiring authz grants
+    ///
+    /// Default: 7 days
+    pub fn grant_expiry_window(&self) -> Duration {
+        self.grant_expiry_window
+            .unwrap_or_else(|| Duration::from_secs(7 * 24 * 60 * 60))
+    }
+
+    /// See [Self::grant_expiry_window]
+    pub fn set_grant_expiry_window(&mut self, grant_expiry_window: Option<Duration>) {
+        self.grant_expiry_window = grant_expiry_window;
+    }
+
+    /// Load gas prices from the given source, reloading on an interval.
+    ///
+    /// Long running services otherwise keep the gas prices from when the
+    /// builder was created. The current gas price, from [Self::set_gas_price]
+    /// or the network defaults, is used until the first load succeeds.
+    pub fn set_gas_price_source(
+        &mut self,
+        source: impl GasPriceSource,
+        refresh_interval: Duration,
+    ) {
+        let CurrentGasPrice { low, high, base: _ } =
+            self.current_gas_price(self.get_init_max_gas_price());
+        self.gas_price_method = Some(GasPriceMethod::new_source(
+            Arc::new(source),
+            refresh_interval,
+            (low, high),
+        ));
+    }
+
     pub(crate) fn set_gas_price_method(&mut self, method: GasPriceMethod) {
         self.gas_price_method = Some(method);
     }
@@ -233,6 +458,39 @@ impl CosmosBuilder {
         self.referer_header = referer_header;
     }
 
+    /// Add a header sent with every gRPC, RPC and LCD request, e.g. an API key.
+    pub fn add_grpc_header(&mut self, name: impl Into<String>, value: impl Into<String>) {
+        self.grpc_headers.push((name.into(), value.into()));
+    }
+
+    /// Headers sent with every gRPC, RPC and LCD request
+    pub fn grpc_headers(&self) -> &[(String, String)] {
+        &self.grpc_headers
+    }
+
+    /// Function called on every outgoing gRPC request, after all headers are added.
+    pub fn request_interceptor(&self) -> Option<&RequestInterceptor> {
+        self.request_interceptor.as_ref()
+    }
+
+    /// See [Self::request_interceptor]
+    pub fn set_request_interceptor(&mut self, interceptor: Option<RequestInterceptor>) {
+        self.request_interceptor = interceptor;
+    }
+
+    /// Function called after every query with a summary of the request and response.
+    ///
+    /// Summaries are also logged with [tracing] at debug level under the
+    /// `cosmos::query` target, whether or not a logger is set.
+    pub fn query_logger(&self) -> Option<&QueryLogger> {
+        self.query_logger.as_ref()
+    }
+
+    /// See [Self::query_logger]
+    pub fn set_query_logger(&mut self, logger: Option<QueryLogger>) {
+        self.query_logger = logger;
+    }
+
     /// The maximum number of concurrent requests
     ///
     /// This is a global limit for the generated [Cosmos], and will apply across all endpoints.
@@ -276,6 +534,112 @@ impl CosmosBuilder {
         self.connection_timeout = connection_timeout;
     }
 
+    /// Interval between HTTP/2 keepalive pings on each gRPC connection.
+    ///
+    /// Defaults to [None], meaning no pings are sent. Long-lived services
+    /// should set this so that connections dropped by intermediaries are
+    /// detected instead of hanging.
+    pub fn http2_keep_alive_interval(&self) -> Option<Duration> {
+        self.http2_keep_alive_interval
+    }
+
+    /// See [Self::http2_keep_alive_interval]
+    pub fn set_http2_keep_alive_interval(&mut self, interval: Option<Duration>) {
+        self.http2_keep_alive_interval = interval;
+    }
+
+    /// How long to wait for a keepalive ping to be acknowledged before closing the connection.
+    ///
+    /// Only relevant with [Self::http2_keep_alive_interval]. Defaults to 20 seconds.
+    pub fn keep_alive_timeout(&self) -> Duration {
+        self.keep_alive_timeout
+            .unwrap_or_else(|| Duration::from_secs(20))
+    }
+
+    /// See [Self::keep_alive_timeout]
+    pub fn set_keep_alive_timeout(&mut self, timeout: Option<Duration>) {
+        self.keep_alive_timeout = timeout;
+    }
+
+    /// Should keepalive pings be sent when there are no requests in flight?
+    ///
+    /// Only relevant with [Self::http2_keep_alive_interval]. Defaults to [false].
+    pub fn keep_alive_while_idle(&self) -> bool {
+        self.keep_alive_while_idle.unwrap_or(false)
+    }
+
+    /// See [Self::keep_alive_while_idle]
+    pub fn set_keep_alive_while_idle(&mut self, enabled: Option<bool>) {
+        self.keep_alive_while_idle = enabled;
+    }
+
+    /// TCP keepalive interval for gRPC connections.
+    ///
+    /// Defaults to [None], meaning TCP keepalive is disabled.
+    pub fn tcp_keepalive(&self) -> Option<Duration> {
+        self.tcp_keepalive
+    }
+
+    /// See [Self::tcp_keepalive]
+    pub fn set_tcp_keepalive(&mut self, tcp_keepalive: Option<Duration>) {
+        self.tcp_keepalive = tcp_keepalive;
+    }
+
+    /// HTTP/2 stream-level flow control window size, in bytes.
+    ///
+    /// Defaults to [None], using the HTTP/2 default.
+    pub fn initial_stream_window_size(&self) -> Option<u32> {
+        self.initial_stream_window_size
+    }
+
+    /// See [Self::initial_stream_window_size]
+    pub fn set_initial_stream_window_size(&mut self, size: Option<u32>) {
+        self.initial_stream_window_size = size;
+    }
+
+    /// HTTP/2 connection-level flow control window size, in bytes.
+    ///
+    /// Defaults to [None], using the HTTP/2 default.
+    pub fn initial_connection_window_size(&self) -> Option<u32> {
+        self.initial_connection_window_size
+    }
+
+    /// See [Self::initial_connection_window_size]
+    pub fn set_initial_connection_window_size(&mut self, size: Option<u32>) {
+        self.initial_connection_window_size = size;
+    }
+
+    /// Maximum number of requests in flight on each gRPC connection.
+    ///
+    /// Defaults to [None], meaning no limit beyond [Self::request_count].
+    pub fn concurrency_limit(&self) -> Option<usize> {
+        self.concurrency_limit
+    }
+
+    /// See [Self::concurrency_limit]
+    pub fn set_concurrency_limit(&mut self, limit: Option<usize>) {
+        self.concurrency_limit = limit;
+    }
+
+    /// Proxy to open gRPC, RPC and LCD connections through.
+    ///
+    /// Supports `http://` proxies using the CONNECT method, and `socks5://`
+    /// and `socks5h://` proxies, which resolve hostnames locally and on the
+    /// proxy respectively. Credentials may be given as percent-encoded
+    /// `user:password@`. If not set, the
+    /// standard `HTTPS_PROXY`, `HTTP_PROXY`, `ALL_PROXY` and `NO_PROXY`
+    /// environment variables are used, see [Self::proxy_for_url].
+    ///
+    /// Connect and TCP keepalive settings do not apply to proxied gRPC connections.
+    pub fn proxy(&self) -> Option<&str> {
+        self.proxy.as_deref()
+    }
+
+    /// See [Self::proxy]
+    pub fn set_proxy(&mut self, proxy: Option<String>) {
+        self.proxy = proxy;
+    }
+
     /// Sets the number of seconds before an idle connection is reaped
     ///
     /// Defaults to 20 seconds
@@ -283,6 +647,33 @@ impl CosmosBuilder {
         self.idle_timeout_seconds.unwrap_or(20)
     }
 
+    /// LCD (REST API) endpoint used as a fallback when gRPC is unavailable.
+    ///
+    /// When a request fails on every gRPC node due to network issues, it is
+    /// retried once over LCD. Only account, balance, contract state queries
+    /// and transaction broadcasts are supported. Defaults to [None].
+    pub fn lcd_url(&self) -> Option<&str> {
+        self.lcd_url.as_deref()
+    }
+
+    /// See [Self::lcd_url]
+    pub fn set_lcd_url(&mut self, lcd_url: Option<String>) {
+        self.lcd_url = lcd_url;
+    }
+
+    /// Tendermint RPC endpoint, e.g. `https://rpc.osmosis.zone`.
+    ///
+    /// Only used for data which is not available over gRPC, such as
+    /// [crate::Cosmos::get_block_results]. Defaults to [None].
+    pub fn rpc_url(&self) -> Option<&str> {
+        self.rpc_url.as_deref()
+    }
+
+    /// See [Self::rpc_url]
+    pub fn set_rpc_url(&mut self, rpc_url: Option<String>) {
+        self.rpc_url = rpc_url;
+    }
+
     /// See [Self::idle_timeout_seconds]
     pub fn set_idle_timeout_seconds(&mut self, idle_timeout_seconds: Option<u32>) {
         self.idle_timeout_seconds = idle_timeout_seconds;
@@ -290,6 +681,8 @@ impl CosmosBuilder {
 
     /// Sets the number of seconds before timing out a gRPC query
     ///
+    /// Applies to each attempt separately, see [Self::retry_policy].
+    ///
     /// Defaults to 5 seconds
     pub fn query_timeout_seconds(&self) -> u32 {
         self.query_timeout_seconds.unwrap_or(5)
@@ -300,11 +693,26 @@ impl CosmosBuilder {
         self.query_timeout_seconds = query_timeout_seconds;
     }
 
+    /// Sets the number of seconds before timing out a transaction broadcast
+    ///
+    /// This only covers submitting the transaction, not waiting for it to
+    /// land in a block.
+    ///
+    /// Defaults to 10 seconds
+    pub fn broadcast_timeout_seconds(&self) -> u32 {
+        self.broadcast_timeout_seconds.unwrap_or(10)
+    }
+
+    /// See [Self::broadcast_timeout_seconds]
+    pub fn set_broadcast_timeout_seconds(&mut self, broadcast_timeout_seconds: Option<u32>) {
+        self.broadcast_timeout_seconds = broadcast_timeout_seconds;
+    }
+
     /// Number of attempts to make at a query before giving up.
     ///
     /// Only retries if there is a tonic-level error.
     ///
-    /// Defaults to 3
+    /// Defaults to 3. Ignored if a policy is set with [Self::set_retry_policy].
     pub fn query_retries(&self) -> u32 {
         self.query_retries.unwrap_or(3)
     }
@@ -314,6 +722,45 @@ impl CosmosBuilder {
         self.query_retries = query_retries;
     }
 
+    /// How failed queries are retried.
+    ///
+    /// Defaults to [RetryPolicy::default], with the number of retries taken from [Self::query_retries].
+    pub fn retry_policy(&self) -> RetryPolicy {
+        self.retry_policy.clone().unwrap_or_else(|| RetryPolicy {
+            max_attempts: self.query_retries() + 1,
+            ..RetryPolicy::default()
+        })
+    }
+
+    /// See [Self::retry_policy]
+    pub fn set_retry_policy(&mut self, retry_policy: Option<RetryPolicy>) {
+        self.retry_policy = retry_policy;
+    }
+
+    /// Cache settings for read queries.
+    ///
+    /// Defaults to [None], meaning nothing is cached.
+    pub fn query_cache(&self) -> Option<&QueryCacheConfig> {
+        self.query_cache.as_ref()
+    }
+
+    /// See [Self::query_cache]
+    pub fn set_query_cache(&mut self, query_cache: Option<QueryCacheConfig>) {
+        self.query_cache = query_cache;
+    }
+
+    /// Record or replay queries using a fixture file.
+    ///
+    /// Defaults to [None], meaning queries always go to the network.
+    pub fn fixture(&self) -> Option<&FixtureMode> {
+        self.fixture.as_ref()
+    }
+
+    /// See [Self::fixture]
+    pub fn set_fixture(&mut self, fixture: Option<FixtureMode>) {
+        self.fixture = fixture;
+    }
+
     /// How many blocks a response is allowed to lag.
     ///
     /// Defaults to 10
@@ -389,6 +836,37 @@ impl CosmosBuilder {
         self.allowed_error_count = allowed;
     }
 
+    /// Should signed transactions be broadcast to the primary and all fallback nodes concurrently?
+    ///
+    /// The first successful broadcast wins, responses from other nodes are ignored.
+    ///
+    /// Default: [false]
+    pub fn broadcast_to_all_nodes(&self) -> bool {
+        self.broadcast_to_all_nodes.unwrap_or(false)
+    }
+
+    /// See [Self::broadcast_to_all_nodes]
+    pub fn set_broadcast_to_all_nodes(&mut self, broadcast_to_all_nodes: Option<bool>) {
+        self.broadcast_to_all_nodes = broadcast_to_all_nodes;
+    }
+
+    /// Should [Self::build] check the node's chain parameters against this configuration?
+    ///
+    /// In addition to the chain ID, which is always checked, this verifies
+    /// the bech32 prefix of a sample account and that the node accepts
+    /// [Self::gas_coin] at our gas prices. Catches pointing a testnet
+    /// configuration at a mainnet node and similar mistakes.
+    ///
+    /// Default: [false]
+    pub fn verify_chain_params(&self) -> bool {
+        self.verify_chain_params.unwrap_or(false)
+    }
+
+    /// See [Self::verify_chain_params]
+    pub fn set_verify_chain_params(&mut self, verify_chain_params: Option<bool>) {
+        self.verify_chain_params = verify_chain_params;
+    }
+
     /// Set parameters for Osmosis's EIP fee market gas.
     ///
     /// Low and high multiplier indicate how much to multiply the base fee by to get low and high prices, respectively. The max price is a cap on what those results will be.
@@ -401,6 +879,40 @@ impl CosmosBuilder {
         });
     }
 
+    /// Use Osmosis's EIP-1559 base fee, loaded from the given LCD endpoint, for gas prices.
+    ///
+    /// This is set automatically for Osmosis mainnet, and can be used for
+    /// Osmosis testnets or private nodes. The base fee is multiplied by the
+    /// values from [Self::set_osmosis_gas_params], so call that first, and
+    /// capped by [Self::set_max_gas_price]. Before each broadcast, the base
+    /// fee is reloaded if older than [Self::get_osmosis_gas_price_too_old_seconds].
+    /// The LCD is queried through the proxy configured so far, see [Self::set_proxy].
+    pub fn set_osmosis_eip_gas_price(&mut self, lcd_url: impl Into<String>) {
+        let lcd_url = lcd_url.into();
+        let client = self.make_http_client(&lcd_url).unwrap_or_else(|e| {
+            tracing::warn!("Unable to configure HTTP client for {lcd_url}, using defaults: {e}");
+            crate::client::env_http_client().unwrap_or_default()
+        });
+        self.gas_price_method = Some(GasPriceMethod::new_osmosis(
+            client,
+            lcd_url,
+            self.get_osmosis_gas_params(),
+        ));
+    }
+
+    /// Reload a dynamic gas price if it's too old.
+    pub(crate) async fn refresh_gas_price(&self, cosmos: &crate::Cosmos) {
+        if let Some(method) = &self.gas_price_method {
+            method.refresh(cosmos).await;
+        }
+    }
+
+    pub(crate) fn launch_gas_price_refresh(&self, cosmos: &crate::Cosmos) {
+        if let Some(method) = &self.gas_price_method {
+            method.launch_refresh(cosmos);
+        }
+    }
+
     /// Sets the maximum gas price to be used on Osmosis mainnet.
     pub fn set_max_gas_price(&mut self, max_price: f64) {
         self.max_price = Some(max_price);
@@ -430,8 +942,353 @@ impl CosmosBuilder {
     }
 }
 
+/// Which query responses are cached, see [CosmosBuilder::set_query_cache].
+///
+/// Code and block lookups by height never change and are always cached
+/// once caching is enabled. Other data is cached for the given time to
+/// live, or not at all if [None]. The cache is shared by all clones of a
+/// [crate::Cosmos], and can be emptied with [crate::Cosmos::clear_query_cache].
+#[derive(Clone, Debug)]
+pub struct QueryCacheConfig {
+    /// Maximum number of responses to keep.
+    pub max_entries: usize,
+    /// How long to cache contract info, which changes on migration or admin updates.
+    pub contract_info_ttl: Option<Duration>,
+    /// How long to cache denom metadata.
+    pub denom_metadata_ttl: Option<Duration>,
+    /// How long to cache account information.
+    ///
+    /// This includes the sequence number, so caching accounts is only
+    /// appropriate for read-only workloads. Defaults to [None].
+    pub account_ttl: Option<Duration>,
+}
+
+impl Default for QueryCacheConfig {
+    fn default() -> Self {
+        QueryCacheConfig {
+            max_entries: 10_000,
+            contract_info_ttl: Some(Duration::from_secs(60)),
+            denom_metadata_ttl: Some(Duration::from_secs(3600)),
+            account_ttl: None,
+        }
+    }
+}
+
+/// Record/replay mode for deterministic tests, see [CosmosBuilder::set_fixture].
+///
+/// Each query is keyed by its gRPC method path (or RPC method), encoded body
+/// and height. This covers transaction broadcasts, including
+/// [CosmosBuilder::set_broadcast_to_all_nodes], and Tendermint RPC calls.
+/// When a key was recorded several times, replay returns the responses in
+/// order and then keeps returning the last one, so polling loops terminate.
+///
+/// Errors returned by the chain, such as NotFound for a missing account, are
+/// recorded and replayed. Connection problems and timeouts are not. The query
+/// cache is bypassed while a fixture is configured.
+#[derive(Clone, Debug)]
+pub enum FixtureMode {
+    /// Perform queries against the network and save every response to the file,
+    /// overwriting any previous contents.
+    ///
+    /// The file is written when the connection is dropped, or earlier with
+    /// [crate::Cosmos::flush_fixture].
+    Record(PathBuf),
+    /// Answer queries from a previously recorded file without touching the network.
+    Replay(PathBuf),
+}
+
+/// A user-supplied hook run on every gRPC request, see [CosmosBuilder::set_request_interceptor].
+///
+/// The hook can modify the request metadata, e.g. to add tracing headers, or
+/// reject the request by returning an error.
+#[derive(Clone)]
+pub struct RequestInterceptor(
+    Arc<dyn Fn(tonic::Request<()>) -> Result<tonic::Request<()>, tonic::Status> + Send + Sync>,
+);
+
+impl RequestInterceptor {
+    /// Wrap a function as an interceptor.
+    pub fn new(
+        f: impl Fn(tonic::Request<()>) -> Result<tonic::Request<()>, tonic::Status>
+            + Send
+            + Sync
+            + 'static,
+    ) -> Self {
+        RequestInterceptor(Arc::new(f))
+    }
+
+    pub(crate) fn call(
+        &self,
+        request: tonic::Request<()>,
+    ) -> Result<tonic::Request<()>, tonic::Status> {
+        (self.0)(request)
+    }
+}
+
+impl std::fmt::Debug for RequestInterceptor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("RequestInterceptor")
+    }
+}
+
+/// A user-supplied hook run after every query, see [CosmosBuilder::set_query_logger].
+#[derive(Clone)]
+pub struct QueryLogger(Arc<dyn Fn(&QueryLog) + Send + Sync>);
+
+impl QueryLogger {
+    /// Wrap a function as a query logger.
+    pub fn new(f: impl Fn(&QueryLog) + Send + Sync + 'static) -> Self {
+        QueryLogger(Arc::new(f))
+    }
+
+    pub(crate) fn call(&self, log: &QueryLog) {
+        (self.0)(log)
+    }
+}
+
+impl std::fmt::Debug for QueryLogger {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("QueryLogger")
+    }
+}
+
+/// Summary of a completed query, passed to a [QueryLogger].
+#[derive(Debug)]
+pub struct QueryLog<'a> {
+    /// Rust type name of the protobuf request
+    pub request_type: &'static str,
+    /// What the query was performed for
+    pub action: &'a Action,
+    /// Height the query was run at, [None] for the latest block
+    pub height: Option<u64>,
+    /// Size of the encoded request in bytes
+    pub request_size: usize,
+    /// Size of the encoded response in bytes, [None] if the query failed
+    pub response_size: Option<usize>,
+    /// Endpoint which answered the query, or the last one tried on failure
+    pub grpc_url: &'a str,
+    /// Total time spent, including retries
+    pub elapsed: Duration,
+    /// The error, if the query failed
+    pub error: Option<&'a QueryError>,
+}
+
+/// Gas adjustment for a message type, see [CosmosBuilder::add_message_gas_override].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GasOverride {
+    /// Add this much gas for each message of the type, on top of the padded estimate.
+    Add(u64),
+    /// Request at least this much gas for each message of the type, whatever the estimate.
+    Minimum(u64),
+}
+
+/// How failed queries are retried, see [CosmosBuilder::set_retry_policy].
+///
+/// Errors which look like network issues are always retried, typically on a
+/// different node, as is any error with a [RetryReason::Connection]. Errors
+/// with one of the [Self::retryable_codes] are retried as well.
+#[derive(Clone, Debug)]
+pub struct RetryPolicy {
+    /// Total number of attempts, including the first one.
+    pub max_attempts: u32,
+    /// Delay before the first retry.
+    pub initial_backoff: Duration,
+    /// Upper limit on the delay between retries.
+    pub max_backoff: Duration,
+    /// Factor the delay is multiplied by after each retry.
+    pub backoff_multiplier: f64,
+    /// Fraction of each delay to randomize, between 0 and 1.
+    ///
+    /// Avoids many clients retrying in lockstep. Values outside the range are
+    /// clamped, and NaN disables jitter.
+    pub jitter: f64,
+    /// Additional gRPC status codes to retry.
+    pub retryable_codes: Vec<tonic::Code>,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            max_attempts: 4,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(5),
+            backoff_multiplier: 2.0,
+            jitter: 0.2,
+            retryable_codes: vec![
+                tonic::Code::Unavailable,
+                tonic::Code::ResourceExhausted,
+                tonic::Code::DeadlineExceeded,
+                tonic::Code::Aborted,
+            ],
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// A policy which never retries.
+    pub fn no_retries() -> Self {
+        RetryPolicy {
+            max_attempts: 1,
+            ..RetryPolicy::default()
+        }
+    }
+
+    /// Delay before the given retry, starting at 1, without jitter applied.
+    fn base_backoff(&self, retry: u32) -> Duration {
+        let factor = self
+            .backoff_multiplier
+            .max(1.0)
+            .powi(retry.saturating_sub(1).min(i32::MAX as u32) as i32);
+        self.initial_backoff
+            .mul_f64(factor.min(u32::MAX.into()))
+            .min(self.max_backoff)
+    }
+
+    /// Delay before the given retry, starting at 1.
+    pub(crate) fn backoff(&self, retry: u32) -> Duration {
+        let base = self.base_backoff(retry);
+        // clamp passes NaN through, which would make mul_f64 panic
+        let jitter = if self.jitter.is_nan() {
+            0.0
+        } else {
+            self.jitter.clamp(0.0, 1.0)
+        };
+        if jitter == 0.0 {
+            base
+        } else {
+            base.mul_f64(1.0 - jitter * rand::random::<f64>())
+        }
+    }
+
+    /// Should a query which failed with this error be sent again?
+    pub(crate) fn is_retryable(&self, err: &QueryErrorDetails) -> bool {
+        err.retry_reason() == Some(RetryReason::Connection)
+            || err
+                .tonic_code()
+                .map_or(false, |code| self.retryable_codes.contains(&code))
+    }
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub(crate) enum ChainPausedMethod {
     None,
     OsmosisMainnet,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn retry_backoff() {
+        let policy = RetryPolicy {
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(1),
+            backoff_multiplier: 2.0,
+            jitter: 0.0,
+            ..RetryPolicy::default()
+        };
+        assert_eq!(policy.backoff(1), Duration::from_millis(100));
+        assert_eq!(policy.backoff(2), Duration::from_millis(200));
+        assert_eq!(policy.backoff(4), Duration::from_millis(800));
+        assert_eq!(policy.backoff(5), Duration::from_secs(1));
+        assert_eq!(policy.backoff(1000), Duration::from_secs(1));
+
+        let policy = RetryPolicy {
+            jitter: 0.5,
+            ..policy
+        };
+        for _ in 0..100 {
+            let backoff = policy.backoff(2);
+            assert!(backoff <= Duration::from_millis(200));
+            assert!(backoff >= Duration::from_millis(100));
+        }
+
+        let policy = RetryPolicy {
+            jitter: f64::NAN,
+            ..policy
+        };
+        assert_eq!(policy.backoff(2), Duration::from_millis(200));
+        let policy = RetryPolicy {
+            jitter: f64::INFINITY,
+            ..policy
+        };
+        assert!(policy.backoff(2) <= Duration::from_millis(200));
+    }
+
+    #[test]
+    fn message_gas_overrides() {
+        let mut builder = CosmosBuilder::new(
+            "osmosis-1",
+            "uosmo",
+            AddressHrp::from_static("osmo"),
+            "http://localhost:9090",
+        );
+        builder
+            .add_message_gas_override("/cosmwasm.wasm.v1.MsgStoreCode", GasOverride::Add(50_000));
+        builder.add_message_gas_override(
+            "/cosmwasm.wasm.v1.MsgExecuteContract",
+            GasOverride::Minimum(300_000),
+        );
+        let apply = |urls: &[&str], gas| builder.apply_gas_overrides(urls.iter().copied(), gas);
+
+        assert_eq!(apply(&["/cosmos.bank.v1beta1.MsgSend"], 100_000), 100_000);
+        assert_eq!(apply(&["/cosmwasm.wasm.v1.MsgStoreCode"], 100_000), 150_000);
+        assert_eq!(
+            apply(&["/cosmwasm.wasm.v1.MsgExecuteContract"; 2], 400_000),
+            600_000
+        );
+        assert_eq!(
+            apply(
+                &[
+                    "/cosmwasm.wasm.v1.MsgExecuteContract",
+                    "/cosmwasm.wasm.v1.MsgStoreCode"
+                ],
+                100_000
+            ),
+            350_000
+        );
+    }
+
+    #[test]
+    fn custom_grpc_headers() {
+        use tonic::service::Interceptor;
+
+        let mut builder = CosmosBuilder::new(
+            "osmosis-1",
+            "uosmo",
+            AddressHrp::from_static("osmo"),
+            "http://localhost:9090",
+        );
+        builder.add_grpc_header("x-api-key", "secret");
+        builder.set_request_interceptor(Some(RequestInterceptor::new(|mut req| {
+            req.metadata_mut()
+                .insert("x-trace-id", "abc".parse().unwrap());
+            Ok(req)
+        })));
+        let req = builder
+            .make_interceptor()
+            .unwrap()
+            .call(tonic::Request::new(()))
+            .unwrap();
+        assert_eq!(req.metadata().get("x-api-key").unwrap(), "secret");
+        assert_eq!(req.metadata().get("x-trace-id").unwrap(), "abc");
+
+        builder.add_grpc_header("invalid header", "value");
+        builder.make_interceptor().unwrap_err();
+    }
+
+    #[test]
+    fn retry_policy_uses_query_retries() {
+        let mut builder = CosmosBuilder::new(
+            "osmosis-1",
+            "uosmo",
+            AddressHrp::from_static("osmo"),
+            "http://localhost:9090",
+        );
+        builder.set_query_retries(Some(1));
+        assert_eq!(builder.retry_policy().max_attempts, 2);
+        builder.set_retry_policy(Some(RetryPolicy::no_retries()));
+        assert_eq!(builder.retry_policy().max_attempts, 1);
+    }
+}
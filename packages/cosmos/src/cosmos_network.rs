@@ -1,8 +1,13 @@
-use std::{fmt::Display, str::FromStr};
+use std::{fmt::Display, str::FromStr, time::Duration};
 
 use serde::de::Visitor;
 
-use crate::{error::BuilderError, gas_price::GasPriceMethod, Cosmos, CosmosBuilder, HasAddressHrp};
+use crate::{
+    chain_registry,
+    error::BuilderError,
+    gas_price::{GasPriceMethod, UrlGasPrice},
+    Cosmos, CosmosBuilder, HasAddressHrp,
+};
 
 /// A set of known networks.
 ///
@@ -27,6 +32,14 @@ pub enum CosmosNetwork {
     StargazeMainnet,
     InjectiveTestnet,
     InjectiveMainnet,
+    NeutronMainnet,
+    NeutronTestnet,
+    KujiraMainnet,
+    KujiraTestnet,
+    TerraMainnet,
+    TerraTestnet,
+    ArchwayMainnet,
+    ArchwayTestnet,
 }
 
 impl CosmosNetwork {
@@ -41,7 +54,7 @@ impl CosmosNetwork {
     ///
     /// If you have an existing [reqwest::Client], consider using [Self::builder_with].
     pub async fn builder(self) -> Result<CosmosBuilder, BuilderError> {
-        self.builder_with(&reqwest::Client::new()).await
+        self.builder_with(&crate::client::env_http_client()?).await
     }
 
     /// Same as [Self::builder] but takes an existing [reqwest::Client]
@@ -82,6 +95,14 @@ impl CosmosNetwork {
             CosmosNetwork::StargazeMainnet => "stargaze-1",
             CosmosNetwork::InjectiveTestnet => "injective-888",
             CosmosNetwork::InjectiveMainnet => "injective-1",
+            CosmosNetwork::NeutronMainnet => "neutron-1",
+            CosmosNetwork::NeutronTestnet => "pion-1",
+            CosmosNetwork::KujiraMainnet => "kaiyo-1",
+            CosmosNetwork::KujiraTestnet => "harpoon-4",
+            CosmosNetwork::TerraMainnet => "phoenix-1",
+            CosmosNetwork::TerraTestnet => "pisco-1",
+            CosmosNetwork::ArchwayMainnet => "archway-1",
+            CosmosNetwork::ArchwayTestnet => "constantine-3",
         }
     }
 
@@ -97,6 +118,10 @@ impl CosmosNetwork {
             CosmosNetwork::SeiMainnet | CosmosNetwork::SeiTestnet => "usei",
             CosmosNetwork::StargazeTestnet | CosmosNetwork::StargazeMainnet => "ustars",
             CosmosNetwork::InjectiveTestnet | CosmosNetwork::InjectiveMainnet => "inj",
+            CosmosNetwork::NeutronMainnet | CosmosNetwork::NeutronTestnet => "untrn",
+            CosmosNetwork::KujiraMainnet | CosmosNetwork::KujiraTestnet => "ukuji",
+            CosmosNetwork::TerraMainnet | CosmosNetwork::TerraTestnet => "uluna",
+            CosmosNetwork::ArchwayMainnet | CosmosNetwork::ArchwayTestnet => "aarch",
         }
     }
 
@@ -125,6 +150,22 @@ impl CosmosNetwork {
             }
             // https://docs.injective.network/develop/public-endpoints/
             CosmosNetwork::InjectiveMainnet => "https://sentry.chain.grpc.injective.network",
+            // https://github.com/cosmos/chain-registry/blob/master/neutron/chain.json
+            CosmosNetwork::NeutronMainnet => "http://neutron-grpc.polkachu.com:19190",
+            // https://github.com/cosmos/chain-registry/blob/master/testnets/neutrontestnet/chain.json
+            CosmosNetwork::NeutronTestnet => "http://neutron-testnet-grpc.polkachu.com:19190",
+            // https://github.com/cosmos/chain-registry/blob/master/kujira/chain.json
+            CosmosNetwork::KujiraMainnet => "http://kujira-grpc.polkachu.com:11890",
+            // https://github.com/cosmos/chain-registry/blob/master/testnets/kujiratestnet/chain.json
+            CosmosNetwork::KujiraTestnet => "http://kujira-testnet-grpc.polkachu.com:11890",
+            // https://github.com/cosmos/chain-registry/blob/master/terra2/chain.json
+            CosmosNetwork::TerraMainnet => "http://terra-grpc.polkachu.com:11790",
+            // https://github.com/cosmos/chain-registry/blob/master/testnets/terra2testnet/chain.json
+            CosmosNetwork::TerraTestnet => "http://terra-testnet-grpc.polkachu.com:11790",
+            // https://docs.archway.io/resources/networks
+            CosmosNetwork::ArchwayMainnet => "https://grpc.mainnet.archway.io",
+            // https://docs.archway.io/resources/networks
+            CosmosNetwork::ArchwayTestnet => "https://grpc.constantine.archway.io",
         }
     }
 
@@ -165,6 +206,22 @@ impl CosmosNetwork {
                 // https://github.com/cosmos/chain-registry/blob/master/injective/chain.json
                 builder.set_gas_price(500000000.0, 900000000.0);
             }
+            CosmosNetwork::NeutronMainnet | CosmosNetwork::NeutronTestnet => {
+                // https://github.com/cosmos/chain-registry/blob/master/neutron/chain.json
+                builder.set_gas_price(0.0053, 0.0106);
+            }
+            CosmosNetwork::KujiraMainnet | CosmosNetwork::KujiraTestnet => {
+                // https://github.com/cosmos/chain-registry/blob/master/kujira/chain.json
+                builder.set_gas_price(0.0034, 0.0068);
+            }
+            CosmosNetwork::TerraMainnet | CosmosNetwork::TerraTestnet => {
+                // https://github.com/cosmos/chain-registry/blob/master/terra2/chain.json
+                builder.set_gas_price(0.015, 0.03);
+            }
+            CosmosNetwork::ArchwayMainnet | CosmosNetwork::ArchwayTestnet => {
+                // Minimum consensus fee, replaced by the on-chain value in load_settings
+                builder.set_gas_price(140000000000.0, 280000000000.0);
+            }
         }
     }
 
@@ -184,7 +241,23 @@ impl CosmosNetwork {
             | CosmosNetwork::StargazeTestnet
             | CosmosNetwork::StargazeMainnet
             | CosmosNetwork::InjectiveTestnet
-            | CosmosNetwork::InjectiveMainnet => Ok(()),
+            | CosmosNetwork::InjectiveMainnet
+            | CosmosNetwork::NeutronMainnet
+            | CosmosNetwork::NeutronTestnet
+            | CosmosNetwork::TerraMainnet
+            | CosmosNetwork::TerraTestnet => Ok(()),
+            CosmosNetwork::ArchwayMainnet => {
+                load_archway_gas_price("https://api.mainnet.archway.io", client, builder).await
+            }
+            CosmosNetwork::ArchwayTestnet => {
+                load_archway_gas_price("https://api.constantine.archway.io", client, builder).await
+            }
+            CosmosNetwork::KujiraMainnet => {
+                load_registry_fee_tokens("kujira", client, builder).await
+            }
+            CosmosNetwork::KujiraTestnet => {
+                load_registry_fee_tokens("testnets/kujiratestnet", client, builder).await
+            }
             CosmosNetwork::OsmosisMainnet => {
                 builder.set_gas_price_method(
                     GasPriceMethod::new_osmosis_mainnet(client, builder.get_osmosis_gas_params())
@@ -193,56 +266,101 @@ impl CosmosNetwork {
                 Ok(())
             }
             CosmosNetwork::SeiMainnet => {
-                #[derive(serde::Deserialize)]
-                struct SeiGasConfig {
-                    #[serde(rename = "pacific-1")]
-                    pub pacific_1: SeiGasConfigItem,
-                }
-                #[derive(serde::Deserialize)]
-                struct SeiGasConfigItem {
-                    pub min_gas_price: f64,
-                }
-
-                let gas_config = load_json::<SeiGasConfig>(
+                load_sei_gas_price(
                     "https://raw.githubusercontent.com/sei-protocol/chain-registry/master/gas.json",
+                    "pacific-1",
                     client,
+                    builder,
                 )
-                .await?;
-
-                builder.set_gas_price(
-                    gas_config.pacific_1.min_gas_price,
-                    gas_config.pacific_1.min_gas_price * 2.0,
-                );
-                Ok(())
+                .await
             }
-            CosmosNetwork::SeiTestnet => {
-                #[derive(serde::Deserialize)]
-                struct SeiGasConfig {
-                    #[serde(rename = "atlantic-2")]
-                    pub atlantic_2: SeiGasConfigItem,
-                }
-                #[derive(serde::Deserialize)]
-                struct SeiGasConfigItem {
-                    pub min_gas_price: f64,
-                }
-
-                let gas_config = load_json::<SeiGasConfig>(
+            CosmosNetwork::SeiTestnet => load_sei_gas_price(
                 "https://raw.githubusercontent.com/sei-protocol/testnet-registry/master/gas.json",
-                    client,
-                )
-                .await?;
-
-                builder.set_gas_price(
-                    gas_config.atlantic_2.min_gas_price,
-                    gas_config.atlantic_2.min_gas_price * 2.0,
-                );
-                Ok(())
-            }
+                "atlantic-2",
+                client,
+                builder,
+            )
+            .await,
         }
     }
 }
 
-async fn load_json<T>(url: &str, client: &reqwest::Client) -> Result<T, BuilderError>
+/// Load Sei's minimum gas price from its registry `gas.json`.
+///
+/// The price is also reloaded every few minutes, since Sei adjusts it
+/// while services keep running.
+async fn load_sei_gas_price(
+    url: &str,
+    chain_id: &str,
+    client: &reqwest::Client,
+    builder: &mut CosmosBuilder,
+) -> Result<(), BuilderError> {
+    #[derive(serde::Deserialize)]
+    struct SeiGasConfigItem {
+        min_gas_price: f64,
+    }
+
+    let mut gas_config =
+        load_json::<std::collections::HashMap<String, SeiGasConfigItem>>(url, client).await?;
+    let min = gas_config
+        .remove(chain_id)
+        .ok_or_else(|| BuilderError::InvalidChainRegistry {
+            chain_name: chain_id.to_owned(),
+            message: format!("No gas price found in {url}"),
+        })?
+        .min_gas_price;
+    builder.set_gas_price(min, min * 2.0);
+    builder.set_gas_price_source(
+        UrlGasPrice::new(url, format!("/{chain_id}/min_gas_price")).with_client(client.clone()),
+        Duration::from_secs(300),
+    );
+    Ok(())
+}
+
+/// Load gas prices for all fee tokens listed in a chain registry `chain.json`.
+async fn load_registry_fee_tokens(
+    chain_name: &str,
+    client: &reqwest::Client,
+    builder: &mut CosmosBuilder,
+) -> Result<(), BuilderError> {
+    let chain = chain_registry::load_chain(client, chain_name).await?;
+    chain_registry::apply_fee_tokens(builder, &chain.fee_tokens);
+    Ok(())
+}
+
+/// Load Archway's minimum consensus fee from the rewards module.
+///
+/// Archway rejects transactions paying less than this per unit of gas.
+async fn load_archway_gas_price(
+    lcd_url: &str,
+    client: &reqwest::Client,
+    builder: &mut CosmosBuilder,
+) -> Result<(), BuilderError> {
+    #[derive(serde::Deserialize)]
+    struct EstimateTxFees {
+        gas_unit_price: DecCoin,
+    }
+    #[derive(serde::Deserialize)]
+    struct DecCoin {
+        amount: String,
+    }
+
+    let url = format!("{lcd_url}/archway/rewards/v1/estimate_tx_fees?gas_limit=1");
+    let fees = load_json::<EstimateTxFees>(&url, client).await?;
+    let min = fees
+        .gas_unit_price
+        .amount
+        .parse::<f64>()
+        .map_err(|source| BuilderError::InvalidGasPrice {
+            url,
+            value: fees.gas_unit_price.amount.clone(),
+            source,
+        })?;
+    builder.set_gas_price(min, min * 2.0);
+    Ok(())
+}
+
+pub(crate) async fn load_json<T>(url: &str, client: &reqwest::Client) -> Result<T, BuilderError>
 where
     T: serde::de::DeserializeOwned,
 {
@@ -313,6 +431,14 @@ impl CosmosNetwork {
             CosmosNetwork::StargazeMainnet => "stargaze-mainnet",
             CosmosNetwork::InjectiveTestnet => "injective-testnet",
             CosmosNetwork::InjectiveMainnet => "injective-mainnet",
+            CosmosNetwork::NeutronMainnet => "neutron-mainnet",
+            CosmosNetwork::NeutronTestnet => "neutron-testnet",
+            CosmosNetwork::KujiraMainnet => "kujira-mainnet",
+            CosmosNetwork::KujiraTestnet => "kujira-testnet",
+            CosmosNetwork::TerraMainnet => "terra-mainnet",
+            CosmosNetwork::TerraTestnet => "terra-testnet",
+            CosmosNetwork::ArchwayMainnet => "archway-mainnet",
+            CosmosNetwork::ArchwayTestnet => "archway-testnet",
         }
     }
 }
@@ -341,9 +467,59 @@ impl FromStr for CosmosNetwork {
             "stargaze-mainnet" => Ok(CosmosNetwork::StargazeMainnet),
             "injective-testnet" => Ok(CosmosNetwork::InjectiveTestnet),
             "injective-mainnet" => Ok(CosmosNetwork::InjectiveMainnet),
+            "neutron-mainnet" => Ok(CosmosNetwork::NeutronMainnet),
+            "neutron-testnet" => Ok(CosmosNetwork::NeutronTestnet),
+            "kujira-mainnet" => Ok(CosmosNetwork::KujiraMainnet),
+            "kujira-testnet" => Ok(CosmosNetwork::KujiraTestnet),
+            "terra-mainnet" => Ok(CosmosNetwork::TerraMainnet),
+            "terra-testnet" => Ok(CosmosNetwork::TerraTestnet),
+            "archway-mainnet" => Ok(CosmosNetwork::ArchwayMainnet),
+            "archway-testnet" => Ok(CosmosNetwork::ArchwayTestnet),
             _ => Err(BuilderError::UnknownCosmosNetwork {
                 network: s.to_owned(),
             }),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::PublicKeyMethod;
+
+    use super::*;
+
+    #[test]
+    fn injective_presets() {
+        for (network, chain_id) in [
+            (CosmosNetwork::InjectiveMainnet, "injective-1"),
+            (CosmosNetwork::InjectiveTestnet, "injective-888"),
+        ] {
+            let builder = network.builder_local();
+            assert_eq!(builder.chain_id(), chain_id);
+            assert_eq!(builder.gas_coin(), "inj");
+            assert_eq!(
+                network.get_address_hrp().default_public_key_method(),
+                PublicKeyMethod::Ethereum
+            );
+            // Gas is priced in 18 decimal inj
+            let price = builder.current_gas_price(f64::MAX);
+            assert_eq!(price.low, 500000000.0);
+            assert_eq!(
+                network.to_string().parse::<CosmosNetwork>().unwrap(),
+                network
+            );
+        }
+    }
+
+    #[test]
+    fn alternative_fee_denom() {
+        let mut builder = CosmosNetwork::KujiraMainnet.builder_local();
+        assert_eq!(builder.gas_coin(), "ukuji");
+        assert!(builder.use_fee_denom("uusk").is_err());
+
+        builder.add_alternative_fee_denom("uusk", 0.001, 0.002);
+        builder.use_fee_denom("uusk").unwrap();
+        assert_eq!(builder.gas_coin(), "uusk");
+        assert_eq!(builder.current_gas_price(f64::MAX).high, 0.002);
+    }
+}
@@ -0,0 +1,318 @@
+//! Typed interface for CW20 token contracts.
+
+use base64::Engine;
+use chrono::{DateTime, Utc};
+use cosmos_sdk_proto::cosmos::base::abci::v1beta1::TxResponse;
+
+use crate::{Address, Contract, Cosmos, HasAddress, HasContract, HasCosmos, Wallet};
+
+/// A CW20 token contract.
+///
+/// Wraps a [Contract] with typed queries and messages for the CW20 spec.
+#[derive(Clone)]
+pub struct Cw20Contract(Contract);
+
+/// Token metadata, see [Cw20Contract::token_info].
+#[derive(serde::Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct TokenInfoResponse {
+    /// Token name
+    pub name: String,
+    /// Ticker symbol
+    pub symbol: String,
+    /// Number of decimal places used for display
+    pub decimals: u8,
+    /// Total amount of tokens in circulation
+    #[serde(with = "uint128")]
+    pub total_supply: u128,
+}
+
+/// When an allowance or approval expires.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(into = "ExpirationRepr", try_from = "ExpirationRepr")]
+pub enum Expiration {
+    /// Expires once the given block height is reached
+    AtHeight(u64),
+    /// Expires once the given block time is reached
+    AtTime(DateTime<Utc>),
+    /// Never expires
+    Never,
+}
+
+/// Wire format of [Expiration], timestamps are nanoseconds since the epoch in a string.
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum ExpirationRepr {
+    AtHeight(u64),
+    AtTime(String),
+    Never {},
+}
+
+impl From<Expiration> for ExpirationRepr {
+    fn from(expiration: Expiration) -> Self {
+        match expiration {
+            Expiration::AtHeight(height) => ExpirationRepr::AtHeight(height),
+            Expiration::AtTime(time) => ExpirationRepr::AtTime(
+                (i128::from(time.timestamp()) * 1_000_000_000
+                    + i128::from(time.timestamp_subsec_nanos()))
+                .to_string(),
+            ),
+            Expiration::Never => ExpirationRepr::Never {},
+        }
+    }
+}
+
+impl TryFrom<ExpirationRepr> for Expiration {
+    type Error = String;
+
+    fn try_from(repr: ExpirationRepr) -> Result<Self, Self::Error> {
+        match repr {
+            ExpirationRepr::AtHeight(height) => Ok(Expiration::AtHeight(height)),
+            ExpirationRepr::AtTime(nanos) => {
+                let nanos = nanos
+                    .parse::<u64>()
+                    .map_err(|e| format!("Invalid timestamp {nanos}: {e}"))?;
+                DateTime::from_timestamp(
+                    (nanos / 1_000_000_000) as i64,
+                    (nanos % 1_000_000_000) as u32,
+                )
+                .map(Expiration::AtTime)
+                .ok_or_else(|| format!("Timestamp out of range: {nanos}"))
+            }
+            ExpirationRepr::Never {} => Ok(Expiration::Never),
+        }
+    }
+}
+
+impl Cosmos {
+    /// Make a new [Cw20Contract] for the given token contract address.
+    pub fn make_cw20(&self, address: Address) -> Cw20Contract {
+        Cw20Contract(self.make_contract(address))
+    }
+}
+
+impl From<Contract> for Cw20Contract {
+    fn from(contract: Contract) -> Self {
+        Cw20Contract(contract)
+    }
+}
+
+impl Cw20Contract {
+    /// Token balance of the given address.
+    pub async fn balance(&self, address: impl HasAddress) -> Result<u128, crate::Error> {
+        #[derive(serde::Deserialize)]
+        struct BalanceResponse {
+            #[serde(with = "uint128")]
+            balance: u128,
+        }
+        let BalanceResponse { balance } = self
+            .0
+            .query(Query::Balance {
+                address: address.get_address(),
+            })
+            .await?;
+        Ok(balance)
+    }
+
+    /// Token name, symbol, decimals and total supply.
+    pub async fn token_info(&self) -> Result<TokenInfoResponse, crate::Error> {
+        self.0.query(Query::TokenInfo {}).await
+    }
+
+    /// Transfer tokens from the wallet to the recipient.
+    pub async fn transfer(
+        &self,
+        wallet: &Wallet,
+        recipient: impl HasAddress,
+        amount: u128,
+    ) -> Result<TxResponse, crate::Error> {
+        self.0
+            .execute(
+                wallet,
+                vec![],
+                Execute::Transfer {
+                    recipient: recipient.get_address(),
+                    amount: amount.to_string(),
+                },
+            )
+            .await
+    }
+
+    /// Send tokens to a contract, which receives `msg` in its receive hook.
+    pub async fn send(
+        &self,
+        wallet: &Wallet,
+        contract: impl HasAddress,
+        amount: u128,
+        msg: impl serde::Serialize,
+    ) -> Result<TxResponse, crate::Error> {
+        self.0
+            .execute(
+                wallet,
+                vec![],
+                Execute::Send {
+                    contract: contract.get_address(),
+                    amount: amount.to_string(),
+                    msg: to_binary(&msg)?,
+                },
+            )
+            .await
+    }
+
+    /// Allow the spender to transfer up to `amount` more tokens on behalf of the wallet.
+    pub async fn increase_allowance(
+        &self,
+        wallet: &Wallet,
+        spender: impl HasAddress,
+        amount: u128,
+        expires: Option<Expiration>,
+    ) -> Result<TxResponse, crate::Error> {
+        self.0
+            .execute(
+                wallet,
+                vec![],
+                Execute::IncreaseAllowance {
+                    spender: spender.get_address(),
+                    amount: amount.to_string(),
+                    expires,
+                },
+            )
+            .await
+    }
+
+    /// Mint new tokens to the recipient. The wallet must be the token's minter.
+    pub async fn mint(
+        &self,
+        wallet: &Wallet,
+        recipient: impl HasAddress,
+        amount: u128,
+    ) -> Result<TxResponse, crate::Error> {
+        self.0
+            .execute(
+                wallet,
+                vec![],
+                Execute::Mint {
+                    recipient: recipient.get_address(),
+                    amount: amount.to_string(),
+                },
+            )
+            .await
+    }
+}
+
+/// Serialize a message as base64 encoded JSON, the format of CosmWasm's `Binary`.
+pub(crate) fn to_binary(msg: &impl serde::Serialize) -> Result<String, crate::Error> {
+    Ok(base64::engine::general_purpose::STANDARD.encode(serde_json::to_vec(msg)?))
+}
+
+/// Serde support for CosmWasm's `Uint128`, which is sent as a string.
+pub(crate) mod uint128 {
+    use serde::Deserialize;
+
+    pub(crate) fn deserialize<'de, D: serde::Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<u128, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+enum Query {
+    Balance { address: Address },
+    TokenInfo {},
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+enum Execute {
+    Transfer {
+        recipient: Address,
+        amount: String,
+    },
+    Send {
+        contract: Address,
+        amount: String,
+        msg: String,
+    },
+    IncreaseAllowance {
+        spender: Address,
+        amount: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        expires: Option<Expiration>,
+    },
+    Mint {
+        recipient: Address,
+        amount: String,
+    },
+}
+
+impl HasAddress for Cw20Contract {
+    fn get_address(&self) -> Address {
+        self.0.get_address()
+    }
+}
+
+impl HasCosmos for Cw20Contract {
+    fn get_cosmos(&self) -> &Cosmos {
+        self.0.get_cosmos()
+    }
+}
+
+impl HasContract for Cw20Contract {
+    fn get_contract(&self) -> &Contract {
+        &self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn send_message() {
+        let contract: Address = "osmo168gdk6r58jdwfv49kuesq2rs747jawnn4ryvyk"
+            .parse()
+            .unwrap();
+        let msg = Execute::Send {
+            contract,
+            amount: 100u128.to_string(),
+            msg: to_binary(&serde_json::json!({"deposit": {}})).unwrap(),
+        };
+        assert_eq!(
+            serde_json::to_value(msg).unwrap(),
+            serde_json::json!({
+                "send": {
+                    "contract": "osmo168gdk6r58jdwfv49kuesq2rs747jawnn4ryvyk",
+                    "amount": "100",
+                    "msg": "eyJkZXBvc2l0Ijp7fX0=",
+                }
+            })
+        );
+    }
+
+    #[test]
+    fn expiration() {
+        assert_eq!(
+            serde_json::to_value(Expiration::AtHeight(5)).unwrap(),
+            serde_json::json!({"at_height": 5})
+        );
+        let time = DateTime::from_timestamp(1_700_000_000, 5).unwrap();
+        assert_eq!(
+            serde_json::to_value(Expiration::AtTime(time)).unwrap(),
+            serde_json::json!({"at_time": "1700000000000000005"})
+        );
+        assert_eq!(
+            serde_json::to_value(Expiration::Never).unwrap(),
+            serde_json::json!({"never": {}})
+        );
+        assert_eq!(
+            serde_json::from_value::<Expiration>(
+                serde_json::json!({"at_time": "1700000000000000005"})
+            )
+            .unwrap(),
+            Expiration::AtTime(time)
+        );
+    }
+}
@@ -0,0 +1,274 @@
+//! Typed interface for CW3 multisig contracts, such as cw3-fixed-multisig.
+
+use cosmos_sdk_proto::{
+    cosmos::{
+        bank::v1beta1::MsgSend,
+        base::{abci::v1beta1::TxResponse, v1beta1::Coin},
+    },
+    cosmwasm::wasm::v1::{MsgExecuteContract, MsgMigrateContract},
+};
+use prost::Message;
+use serde_json::json;
+
+use crate::{
+    cw20::Expiration, Address, Contract, Cosmos, HasAddress, HasContract, HasCosmos, TxMessage,
+    TxResponseExt, Wallet,
+};
+
+/// A CW3 multisig contract.
+///
+/// Wraps a [Contract] with typed queries and messages for proposing, voting on
+/// and executing multisig proposals.
+#[derive(Clone)]
+pub struct Cw3Contract(Contract);
+
+/// A vote on a multisig proposal.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Vote {
+    /// Vote in favor
+    Yes,
+    /// Vote against
+    No,
+    /// Count towards quorum without voting either way
+    Abstain,
+    /// Vote against, signaling a stronger objection
+    Veto,
+}
+
+/// Status of a multisig proposal.
+#[derive(serde::Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ProposalStatus {
+    /// Not yet open for voting
+    Pending,
+    /// Voting is in progress
+    Open,
+    /// Voting finished without passing
+    Rejected,
+    /// Passed, but not yet executed
+    Passed,
+    /// Passed and executed
+    Executed,
+}
+
+/// A multisig proposal, see [Cw3Contract::proposal].
+#[derive(serde::Deserialize, Clone, Debug, PartialEq)]
+pub struct ProposalResponse {
+    /// Proposal ID
+    pub id: u64,
+    /// Proposal title
+    pub title: String,
+    /// Proposal description
+    pub description: String,
+    /// Messages which will be executed if the proposal passes, as `CosmosMsg` JSON
+    pub msgs: Vec<serde_json::Value>,
+    /// Current status
+    pub status: ProposalStatus,
+    /// When voting ends
+    pub expires: Expiration,
+    /// Address which submitted the proposal, not reported by older contract versions
+    #[serde(default)]
+    pub proposer: Option<Address>,
+}
+
+impl Cosmos {
+    /// Make a new [Cw3Contract] for the given multisig contract address.
+    pub fn make_cw3(&self, address: Address) -> Cw3Contract {
+        Cw3Contract(self.make_contract(address))
+    }
+}
+
+impl From<Contract> for Cw3Contract {
+    fn from(contract: Contract) -> Self {
+        Cw3Contract(contract)
+    }
+}
+
+impl Cw3Contract {
+    /// Propose executing the given messages from the multisig.
+    ///
+    /// The messages should use the multisig's address as their sender. Use
+    /// [Cw3Contract::parse_proposal_id] to get the new proposal's ID.
+    pub async fn propose(
+        &self,
+        wallet: &Wallet,
+        title: impl Into<String>,
+        description: impl Into<String>,
+        msgs: Vec<TxMessage>,
+    ) -> Result<TxResponse, crate::Error> {
+        self.0
+            .execute(
+                wallet,
+                vec![],
+                Execute::Propose {
+                    title: title.into(),
+                    description: description.into(),
+                    msgs: msgs.iter().map(to_cosmos_msg).collect(),
+                },
+            )
+            .await
+    }
+
+    /// Vote on an open proposal.
+    pub async fn vote(
+        &self,
+        wallet: &Wallet,
+        proposal_id: u64,
+        vote: Vote,
+    ) -> Result<TxResponse, crate::Error> {
+        self.0
+            .execute(wallet, vec![], Execute::Vote { proposal_id, vote })
+            .await
+    }
+
+    /// Execute the messages of a passed proposal.
+    pub async fn execute_proposal(
+        &self,
+        wallet: &Wallet,
+        proposal_id: u64,
+    ) -> Result<TxResponse, crate::Error> {
+        self.0
+            .execute(wallet, vec![], Execute::Execute { proposal_id })
+            .await
+    }
+
+    /// Load a proposal, including its current status.
+    pub async fn proposal(&self, proposal_id: u64) -> Result<ProposalResponse, crate::Error> {
+        self.0.query(Query::Proposal { proposal_id }).await
+    }
+
+    /// Find the ID of the proposal created by a [Cw3Contract::propose] transaction.
+    pub fn parse_proposal_id(&self, res: &TxResponse) -> Option<u64> {
+        res.wasm_events_for(self)
+            .into_iter()
+            .find_map(|attrs| attrs.get("proposal_id")?.parse().ok())
+    }
+}
+
+/// Convert a message into the `CosmosMsg` JSON format used by contracts.
+///
+/// Wasm execute and migrate messages and bank sends use their dedicated
+/// variants, anything else is sent as a stargate message.
+fn to_cosmos_msg(msg: &TxMessage) -> serde_json::Value {
+    let any = msg.get_protobuf();
+    let coins = |coins: Vec<Coin>| -> Vec<serde_json::Value> {
+        coins
+            .into_iter()
+            .map(|Coin { denom, amount }| json!({"denom": denom, "amount": amount}))
+            .collect()
+    };
+    let binary = |bytes: &[u8]| {
+        use base64::Engine;
+        base64::engine::general_purpose::STANDARD.encode(bytes)
+    };
+    match any.type_url.as_str() {
+        "/cosmwasm.wasm.v1.MsgExecuteContract" => {
+            if let Ok(msg) = MsgExecuteContract::decode(any.value.as_slice()) {
+                return json!({"wasm": {"execute": {
+                    "contract_addr": msg.contract,
+                    "msg": binary(&msg.msg),
+                    "funds": coins(msg.funds),
+                }}});
+            }
+        }
+        "/cosmwasm.wasm.v1.MsgMigrateContract" => {
+            if let Ok(msg) = MsgMigrateContract::decode(any.value.as_slice()) {
+                return json!({"wasm": {"migrate": {
+                    "contract_addr": msg.contract,
+                    "new_code_id": msg.code_id,
+                    "msg": binary(&msg.msg),
+                }}});
+            }
+        }
+        "/cosmos.bank.v1beta1.MsgSend" => {
+            if let Ok(msg) = MsgSend::decode(any.value.as_slice()) {
+                return json!({"bank": {"send": {
+                    "to_address": msg.to_address,
+                    "amount": coins(msg.amount),
+                }}});
+            }
+        }
+        _ => (),
+    }
+    json!({"stargate": {
+        "type_url": any.type_url,
+        "value": binary(&any.value),
+    }})
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+enum Query {
+    Proposal { proposal_id: u64 },
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+enum Execute {
+    Propose {
+        title: String,
+        description: String,
+        msgs: Vec<serde_json::Value>,
+    },
+    Vote {
+        proposal_id: u64,
+        vote: Vote,
+    },
+    Execute {
+        proposal_id: u64,
+    },
+}
+
+impl HasAddress for Cw3Contract {
+    fn get_address(&self) -> Address {
+        self.0.get_address()
+    }
+}
+
+impl HasCosmos for Cw3Contract {
+    fn get_cosmos(&self) -> &Cosmos {
+        self.0.get_cosmos()
+    }
+}
+
+impl HasContract for Cw3Contract {
+    fn get_contract(&self) -> &Contract {
+        &self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cw20::to_binary;
+
+    #[test]
+    fn cosmos_msgs() {
+        let multisig = "osmo1qg5ega6dykkxc307y25pecuufrjkxkaggkkxh7nad0vhyhtuhw3sqaa3c5";
+        let contract = "osmo168gdk6r58jdwfv49kuesq2rs747jawnn4ryvyk";
+        let execute = MsgExecuteContract {
+            sender: multisig.to_owned(),
+            contract: contract.to_owned(),
+            msg: b"{}".to_vec(),
+            funds: vec![Coin {
+                denom: "uosmo".to_owned(),
+                amount: "5".to_owned(),
+            }],
+        };
+        assert_eq!(
+            to_cosmos_msg(&execute.into()),
+            json!({"wasm": {"execute": {
+                "contract_addr": contract,
+                "msg": to_binary(&json!({})).unwrap(),
+                "funds": [{"denom": "uosmo", "amount": "5"}],
+            }}})
+        );
+
+        let other = TxMessage::new("/some.Msg", vec![1, 2, 3], "other");
+        assert_eq!(
+            to_cosmos_msg(&other),
+            json!({"stargate": {"type_url": "/some.Msg", "value": "AQID"}})
+        );
+    }
+}
@@ -0,0 +1,208 @@
+//! Typed interface for CW721 NFT contracts, such as Stargaze's sg721.
+
+use cosmos_sdk_proto::cosmos::base::abci::v1beta1::TxResponse;
+use futures_util::Stream;
+
+use crate::{
+    contract::paginate_start_after,
+    cw20::{to_binary, Expiration},
+    Address, Contract, Cosmos, HasAddress, HasContract, HasCosmos, Wallet,
+};
+
+/// Number of token IDs to load per page in [Cw721Contract::all_tokens].
+const PAGE_SIZE: u32 = 100;
+
+/// A CW721 NFT contract.
+///
+/// Wraps a [Contract] with typed queries and messages for the CW721 spec.
+#[derive(Clone)]
+pub struct Cw721Contract(Contract);
+
+/// The owner of an NFT, see [Cw721Contract::owner_of].
+#[derive(serde::Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct OwnerOfResponse {
+    /// Current owner of the token
+    pub owner: Address,
+    /// Addresses approved to transfer the token
+    pub approvals: Vec<Approval>,
+}
+
+/// Permission for another address to transfer an NFT.
+#[derive(serde::Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Approval {
+    /// Address which may transfer the token
+    pub spender: Address,
+    /// When the approval expires
+    pub expires: Expiration,
+}
+
+impl Cosmos {
+    /// Make a new [Cw721Contract] for the given NFT contract address.
+    pub fn make_cw721(&self, address: Address) -> Cw721Contract {
+        Cw721Contract(self.make_contract(address))
+    }
+}
+
+impl From<Contract> for Cw721Contract {
+    fn from(contract: Contract) -> Self {
+        Cw721Contract(contract)
+    }
+}
+
+impl Cw721Contract {
+    /// Owner and approvals for the given token.
+    ///
+    /// Expired approvals are excluded unless `include_expired` is set.
+    pub async fn owner_of(
+        &self,
+        token_id: impl Into<String>,
+        include_expired: bool,
+    ) -> Result<OwnerOfResponse, crate::Error> {
+        self.0
+            .query(Query::OwnerOf {
+                token_id: token_id.into(),
+                include_expired: Some(include_expired),
+            })
+            .await
+    }
+
+    /// Lazily load the IDs of all tokens in the collection, one page at a time.
+    pub fn all_tokens(&self) -> impl Stream<Item = Result<String, crate::Error>> + Send + 'static {
+        let contract = self.0.clone();
+        paginate_start_after(
+            move |start_after| {
+                let contract = contract.clone();
+                async move {
+                    contract
+                        .query(Query::AllTokens {
+                            start_after,
+                            limit: Some(PAGE_SIZE),
+                        })
+                        .await
+                        .map(|TokensResponse { tokens }| tokens)
+                }
+            },
+            String::clone,
+        )
+    }
+
+    /// Transfer an NFT from the wallet to the recipient.
+    pub async fn transfer_nft(
+        &self,
+        wallet: &Wallet,
+        recipient: impl HasAddress,
+        token_id: impl Into<String>,
+    ) -> Result<TxResponse, crate::Error> {
+        self.0
+            .execute(
+                wallet,
+                vec![],
+                Execute::TransferNft {
+                    recipient: recipient.get_address(),
+                    token_id: token_id.into(),
+                },
+            )
+            .await
+    }
+
+    /// Send an NFT to a contract, which receives `msg` in its receive hook.
+    pub async fn send_nft(
+        &self,
+        wallet: &Wallet,
+        contract: impl HasAddress,
+        token_id: impl Into<String>,
+        msg: impl serde::Serialize,
+    ) -> Result<TxResponse, crate::Error> {
+        self.0
+            .execute(
+                wallet,
+                vec![],
+                Execute::SendNft {
+                    contract: contract.get_address(),
+                    token_id: token_id.into(),
+                    msg: to_binary(&msg)?,
+                },
+            )
+            .await
+    }
+
+    /// Mint a new NFT without extension metadata. The wallet must be the collection's minter.
+    pub async fn mint(
+        &self,
+        wallet: &Wallet,
+        token_id: impl Into<String>,
+        owner: impl HasAddress,
+        token_uri: Option<String>,
+    ) -> Result<TxResponse, crate::Error> {
+        self.0
+            .execute(
+                wallet,
+                vec![],
+                Execute::Mint {
+                    token_id: token_id.into(),
+                    owner: owner.get_address(),
+                    token_uri,
+                },
+            )
+            .await
+    }
+}
+
+#[derive(serde::Deserialize)]
+struct TokensResponse {
+    tokens: Vec<String>,
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+enum Query {
+    OwnerOf {
+        token_id: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        include_expired: Option<bool>,
+    },
+    AllTokens {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        start_after: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        limit: Option<u32>,
+    },
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+enum Execute {
+    TransferNft {
+        recipient: Address,
+        token_id: String,
+    },
+    SendNft {
+        contract: Address,
+        token_id: String,
+        msg: String,
+    },
+    Mint {
+        token_id: String,
+        owner: Address,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        token_uri: Option<String>,
+    },
+}
+
+impl HasAddress for Cw721Contract {
+    fn get_address(&self) -> Address {
+        self.0.get_address()
+    }
+}
+
+impl HasCosmos for Cw721Contract {
+    fn get_cosmos(&self) -> &Cosmos {
+        self.0.get_cosmos()
+    }
+}
+
+impl HasContract for Cw721Contract {
+    fn get_contract(&self) -> &Contract {
+        &self.0
+    }
+}
@@ -0,0 +1,418 @@
+use base64::Engine;
+use cosmos_sdk_proto::{
+    cosmos::{
+        authz::v1beta1::{MsgExec, MsgGrant},
+        bank::v1beta1::MsgSend,
+        base::v1beta1::Coin,
+        crypto::secp256k1,
+        tx::v1beta1::{SignerInfo, Tx, TxBody},
+    },
+    cosmwasm::wasm::v1::{
+        MsgExecuteContract, MsgInstantiateContract, MsgMigrateContract, MsgStoreCode,
+        MsgUpdateAdmin,
+    },
+    traits::{Message, MessageExt},
+    Any,
+};
+
+use sha2::{Digest, Sha256};
+
+use crate::{
+    address::{AddressHrp, HasAddressHrp, RawAddress},
+    error::TxDecodeError,
+    messages::MsgInstantiateContract2,
+    registry::CustomMessage,
+    wallet::{cosmos_address_from_public_key, eth_address_from_public_key},
+    Address, Cosmos,
+};
+
+/// Hash of raw transaction bytes, upper-case hex encoded as in [DecodedTx::txhash].
+pub(crate) fn txhash_for_bytes(bytes: &[u8]) -> String {
+    hex::encode_upper(Sha256::digest(bytes))
+}
+
+/// A transaction decoded from its raw bytes.
+#[derive(Debug, Clone)]
+pub struct DecodedTx {
+    /// Hash of the raw transaction bytes, upper-case hex encoded.
+    pub txhash: String,
+    /// Memo attached to the transaction.
+    pub memo: String,
+    /// Block height after which this transaction is no longer valid, 0 for none.
+    pub timeout_height: u64,
+    /// The messages contained in the transaction.
+    pub messages: Vec<DecodedMessage>,
+    /// Signers of the transaction, in order.
+    pub signers: Vec<DecodedSigner>,
+    /// Fee paid by the transaction.
+    pub fee: Vec<Coin>,
+    /// Gas limit requested by the transaction.
+    pub gas_limit: u64,
+    /// Explicit fee payer, if set.
+    pub fee_payer: Option<String>,
+    /// Fee granter covering the fees, if set.
+    pub fee_granter: Option<String>,
+    /// The full underlying transaction.
+    pub tx: Tx,
+}
+
+/// Information on a single transaction signer.
+#[derive(Debug, Clone)]
+pub struct DecodedSigner {
+    /// Address derived from the signer's public key.
+    ///
+    /// [None] if the public key type is not recognized, e.g. a multisig key.
+    pub address: Option<Address>,
+    /// Type URL of the signer's public key.
+    pub public_key_type: Option<String>,
+    /// Account sequence number used for signing.
+    pub sequence: u64,
+}
+
+/// A transaction message, decoded into a concrete type where recognized.
+#[derive(Debug, Clone)]
+pub enum DecodedMessage {
+    /// Bank send
+    Send(MsgSend),
+    /// Upload wasm code
+    StoreCode(MsgStoreCode),
+    /// Instantiate a contract
+    InstantiateContract(MsgInstantiateContract),
+    /// Instantiate a contract at a predictable address
+    InstantiateContract2(MsgInstantiateContract2),
+    /// Execute a contract
+    ExecuteContract(MsgExecuteContract),
+    /// Migrate a contract
+    MigrateContract(MsgMigrateContract),
+    /// Change a contract's admin
+    UpdateAdmin(MsgUpdateAdmin),
+    /// Authz grant
+    Grant(MsgGrant),
+    /// Authz exec
+    Exec(MsgExec),
+    /// A message type added with [crate::register_message_type]
+    Custom(CustomMessage),
+    /// Any message type not recognized above, or which failed to decode
+    Unknown(Any),
+}
+
+impl DecodedMessage {
+    /// Decode the message, falling back to [DecodedMessage::Unknown].
+    ///
+    /// Types added with [crate::register_message_type] are decoded as well.
+    pub fn from_any(any: Any) -> Self {
+        fn go<T: Message + Default + cosmos_sdk_proto::traits::TypeUrl>(
+            any: &Any,
+            f: impl FnOnce(T) -> DecodedMessage,
+        ) -> Option<DecodedMessage> {
+            T::from_any(any).ok().map(f)
+        }
+        let decoded = match any.type_url.as_str() {
+            "/cosmos.bank.v1beta1.MsgSend" => go(&any, DecodedMessage::Send),
+            "/cosmwasm.wasm.v1.MsgStoreCode" => go(&any, DecodedMessage::StoreCode),
+            "/cosmwasm.wasm.v1.MsgInstantiateContract" => {
+                go(&any, DecodedMessage::InstantiateContract)
+            }
+            "/cosmwasm.wasm.v1.MsgInstantiateContract2" => {
+                MsgInstantiateContract2::decode(&*any.value)
+                    .ok()
+                    .map(DecodedMessage::InstantiateContract2)
+            }
+            "/cosmwasm.wasm.v1.MsgExecuteContract" => go(&any, DecodedMessage::ExecuteContract),
+            "/cosmwasm.wasm.v1.MsgMigrateContract" => go(&any, DecodedMessage::MigrateContract),
+            "/cosmwasm.wasm.v1.MsgUpdateAdmin" => go(&any, DecodedMessage::UpdateAdmin),
+            "/cosmos.authz.v1beta1.MsgGrant" => MsgGrant::decode(&*any.value)
+                .ok()
+                .map(DecodedMessage::Grant),
+            "/cosmos.authz.v1beta1.MsgExec" => {
+                MsgExec::decode(&*any.value).ok().map(DecodedMessage::Exec)
+            }
+            _ => CustomMessage::decode(&any).map(DecodedMessage::Custom),
+        };
+        decoded.unwrap_or(DecodedMessage::Unknown(any))
+    }
+
+    /// The type URL of this message.
+    pub fn type_url(&self) -> &str {
+        match self {
+            DecodedMessage::Send(_) => "/cosmos.bank.v1beta1.MsgSend",
+            DecodedMessage::StoreCode(_) => "/cosmwasm.wasm.v1.MsgStoreCode",
+            DecodedMessage::InstantiateContract(_) => "/cosmwasm.wasm.v1.MsgInstantiateContract",
+            DecodedMessage::InstantiateContract2(_) => "/cosmwasm.wasm.v1.MsgInstantiateContract2",
+            DecodedMessage::ExecuteContract(_) => "/cosmwasm.wasm.v1.MsgExecuteContract",
+            DecodedMessage::MigrateContract(_) => "/cosmwasm.wasm.v1.MsgMigrateContract",
+            DecodedMessage::UpdateAdmin(_) => "/cosmwasm.wasm.v1.MsgUpdateAdmin",
+            DecodedMessage::Grant(_) => "/cosmos.authz.v1beta1.MsgGrant",
+            DecodedMessage::Exec(_) => "/cosmos.authz.v1beta1.MsgExec",
+            DecodedMessage::Custom(msg) => msg.type_url(),
+            DecodedMessage::Unknown(any) => &any.type_url,
+        }
+    }
+}
+
+/// Extension trait to decode the messages in a [TxBody].
+pub trait TxBodyExt {
+    /// Decode each message, see [DecodedMessage::from_any].
+    fn decode_messages(&self) -> Vec<DecodedMessage>;
+}
+
+impl TxBodyExt for TxBody {
+    fn decode_messages(&self) -> Vec<DecodedMessage> {
+        self.messages
+            .iter()
+            .cloned()
+            .map(DecodedMessage::from_any)
+            .collect()
+    }
+}
+
+impl DecodedTx {
+    /// Decode a transaction from its raw protobuf bytes.
+    ///
+    /// The HRP is used for deriving signer addresses from their public keys.
+    pub fn from_bytes(bytes: &[u8], hrp: AddressHrp) -> Result<Self, TxDecodeError> {
+        let txhash = txhash_for_bytes(bytes);
+        let tx = Tx::decode(bytes).map_err(|source| TxDecodeError::InvalidProtobuf { source })?;
+        Self::from_tx(tx, txhash, hrp)
+    }
+
+    /// Decode a transaction from base64-encoded bytes, as returned by the RPC endpoints.
+    pub fn from_base64(s: &str, hrp: AddressHrp) -> Result<Self, TxDecodeError> {
+        let bytes = base64::engine::general_purpose::STANDARD
+            .decode(s.trim())
+            .map_err(|source| TxDecodeError::InvalidBase64 { source })?;
+        Self::from_bytes(&bytes, hrp)
+    }
+
+    fn from_tx(tx: Tx, txhash: String, hrp: AddressHrp) -> Result<Self, TxDecodeError> {
+        let body = tx
+            .body
+            .clone()
+            .ok_or(TxDecodeError::MissingField { field: "body" })?;
+        let auth_info = tx
+            .auth_info
+            .clone()
+            .ok_or(TxDecodeError::MissingField { field: "auth_info" })?;
+        let fee = auth_info.fee.unwrap_or_default();
+        let non_empty = |s: String| if s.is_empty() { None } else { Some(s) };
+        Ok(DecodedTx {
+            txhash,
+            memo: body.memo,
+            timeout_height: body.timeout_height,
+            messages: body
+                .messages
+                .into_iter()
+                .map(DecodedMessage::from_any)
+                .collect(),
+            signers: auth_info
+                .signer_infos
+                .into_iter()
+                .map(|info| DecodedSigner::new(info, hrp))
+                .collect(),
+            fee: fee.amount,
+            gas_limit: fee.gas_limit,
+            fee_payer: non_empty(fee.payer),
+            fee_granter: non_empty(fee.granter),
+            tx,
+        })
+    }
+}
+
+impl DecodedSigner {
+    fn new(info: SignerInfo, hrp: AddressHrp) -> Self {
+        let address = info.public_key.as_ref().and_then(|public_key| {
+            let key = secp256k1::PubKey::decode(&*public_key.value).ok()?.key;
+            let raw = match public_key.type_url.as_str() {
+                "/cosmos.crypto.secp256k1.PubKey" => cosmos_address_from_public_key(&key),
+                "/injective.crypto.v1beta1.ethsecp256k1.PubKey"
+                | "/ethermint.crypto.v1.ethsecp256k1.PubKey" => {
+                    let key = bitcoin::secp256k1::PublicKey::from_slice(&key).ok()?;
+                    eth_address_from_public_key(&key.serialize_uncompressed())
+                }
+                _ => return None,
+            };
+            Some(RawAddress::from(raw).with_hrp(hrp))
+        });
+        DecodedSigner {
+            address,
+            public_key_type: info.public_key.map(|public_key| public_key.type_url),
+            sequence: info.sequence,
+        }
+    }
+}
+
+impl Cosmos {
+    /// Decode raw transaction bytes, deriving signer addresses for this chain.
+    pub fn decode_tx_bytes(&self, bytes: &[u8]) -> Result<DecodedTx, TxDecodeError> {
+        DecodedTx::from_bytes(bytes, self.get_address_hrp())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cosmos_sdk_proto::cosmos::authz::v1beta1::Grant;
+
+    use super::*;
+
+    fn round_trip<T: Message + PartialEq + std::fmt::Debug>(
+        type_url: &str,
+        msg: T,
+        extract: impl FnOnce(DecodedMessage) -> Option<T>,
+    ) {
+        let decoded = DecodedMessage::from_any(Any {
+            type_url: type_url.to_owned(),
+            value: msg.encode_to_vec(),
+        });
+        assert_eq!(decoded.type_url(), type_url);
+        assert_eq!(extract(decoded), Some(msg));
+    }
+
+    #[test]
+    fn decode_known_messages() {
+        let sender = "osmo1sender".to_owned();
+        let contract = "osmo1contract".to_owned();
+        let funds = vec![Coin {
+            denom: "uosmo".to_owned(),
+            amount: "5".to_owned(),
+        }];
+        round_trip(
+            "/cosmos.bank.v1beta1.MsgSend",
+            MsgSend {
+                from_address: sender.clone(),
+                to_address: contract.clone(),
+                amount: funds.clone(),
+            },
+            |x| match x {
+                DecodedMessage::Send(x) => Some(x),
+                _ => None,
+            },
+        );
+        round_trip(
+            "/cosmwasm.wasm.v1.MsgStoreCode",
+            MsgStoreCode {
+                sender: sender.clone(),
+                wasm_byte_code: b"\0asm".to_vec(),
+                instantiate_permission: None,
+            },
+            |x| match x {
+                DecodedMessage::StoreCode(x) => Some(x),
+                _ => None,
+            },
+        );
+        round_trip(
+            "/cosmwasm.wasm.v1.MsgInstantiateContract",
+            MsgInstantiateContract {
+                sender: sender.clone(),
+                admin: sender.clone(),
+                code_id: 7,
+                label: "label".to_owned(),
+                msg: b"{}".to_vec(),
+                funds: funds.clone(),
+            },
+            |x| match x {
+                DecodedMessage::InstantiateContract(x) => Some(x),
+                _ => None,
+            },
+        );
+        round_trip(
+            "/cosmwasm.wasm.v1.MsgInstantiateContract2",
+            MsgInstantiateContract2 {
+                sender: sender.clone(),
+                code_id: 7,
+                label: "label".to_owned(),
+                msg: b"{}".to_vec(),
+                salt: b"salt".to_vec(),
+                ..Default::default()
+            },
+            |x| match x {
+                DecodedMessage::InstantiateContract2(x) => Some(x),
+                _ => None,
+            },
+        );
+        round_trip(
+            "/cosmwasm.wasm.v1.MsgExecuteContract",
+            MsgExecuteContract {
+                sender: sender.clone(),
+                contract: contract.clone(),
+                msg: br#"{"ping":{}}"#.to_vec(),
+                funds,
+            },
+            |x| match x {
+                DecodedMessage::ExecuteContract(x) => Some(x),
+                _ => None,
+            },
+        );
+        round_trip(
+            "/cosmwasm.wasm.v1.MsgMigrateContract",
+            MsgMigrateContract {
+                sender: sender.clone(),
+                contract: contract.clone(),
+                code_id: 8,
+                msg: b"{}".to_vec(),
+            },
+            |x| match x {
+                DecodedMessage::MigrateContract(x) => Some(x),
+                _ => None,
+            },
+        );
+        round_trip(
+            "/cosmwasm.wasm.v1.MsgUpdateAdmin",
+            MsgUpdateAdmin {
+                sender: sender.clone(),
+                new_admin: "osmo1admin".to_owned(),
+                contract,
+            },
+            |x| match x {
+                DecodedMessage::UpdateAdmin(x) => Some(x),
+                _ => None,
+            },
+        );
+        round_trip(
+            "/cosmos.authz.v1beta1.MsgGrant",
+            MsgGrant {
+                granter: sender.clone(),
+                grantee: "osmo1grantee".to_owned(),
+                grant: Some(Grant::default()),
+            },
+            |x| match x {
+                DecodedMessage::Grant(x) => Some(x),
+                _ => None,
+            },
+        );
+        round_trip(
+            "/cosmos.authz.v1beta1.MsgExec",
+            MsgExec {
+                grantee: sender,
+                msgs: vec![Any {
+                    type_url: "/cosmos.bank.v1beta1.MsgSend".to_owned(),
+                    value: vec![],
+                }],
+            },
+            |x| match x {
+                DecodedMessage::Exec(x) => Some(x),
+                _ => None,
+            },
+        );
+    }
+
+    #[test]
+    fn decode_unknown_messages() {
+        let unknown = Any {
+            type_url: "/test.unknown.v1.MsgFoo".to_owned(),
+            value: vec![1, 2, 3],
+        };
+        let decoded = DecodedMessage::from_any(unknown.clone());
+        assert_eq!(decoded.type_url(), unknown.type_url);
+        assert!(matches!(decoded, DecodedMessage::Unknown(any) if any == unknown));
+
+        // A known type URL which fails to decode is kept as is
+        let invalid = Any {
+            type_url: "/cosmos.bank.v1beta1.MsgSend".to_owned(),
+            value: vec![0xff],
+        };
+        assert!(matches!(
+            DecodedMessage::from_any(invalid.clone()),
+            DecodedMessage::Unknown(any) if any == invalid
+        ));
+    }
+}
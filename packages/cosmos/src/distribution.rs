@@ -0,0 +1,109 @@
+use cosmos_sdk_proto::cosmos::{
+    base::v1beta1::DecCoin,
+    distribution::v1beta1::{
+        MsgSetWithdrawAddress, MsgWithdrawDelegatorReward, QueryCommunityPoolRequest,
+        QueryDelegationRewardsRequest, QueryDelegationTotalRewardsRequest,
+        QueryDelegationTotalRewardsResponse, QueryDelegatorWithdrawAddressRequest,
+    },
+};
+use prost::Message;
+
+use crate::{error::Action, Address, Cosmos, HasAddress, TxMessage};
+
+impl From<MsgWithdrawDelegatorReward> for TxMessage {
+    fn from(msg: MsgWithdrawDelegatorReward) -> Self {
+        TxMessage::new(
+            "/cosmos.distribution.v1beta1.MsgWithdrawDelegatorReward",
+            msg.encode_to_vec(),
+            format!(
+                "{} withdrawing rewards from {}",
+                msg.delegator_address, msg.validator_address
+            ),
+        )
+    }
+}
+
+impl From<MsgSetWithdrawAddress> for TxMessage {
+    fn from(msg: MsgSetWithdrawAddress) -> Self {
+        TxMessage::new(
+            "/cosmos.distribution.v1beta1.MsgSetWithdrawAddress",
+            msg.encode_to_vec(),
+            format!(
+                "{} setting withdraw address to {}",
+                msg.delegator_address, msg.withdraw_address
+            ),
+        )
+    }
+}
+
+impl Cosmos {
+    /// Get the rewards accrued by a delegator from a single validator.
+    pub async fn query_delegation_rewards(
+        &self,
+        delegator: impl HasAddress,
+        validator: impl HasAddress,
+    ) -> Result<Vec<DecCoin>, crate::Error> {
+        let req = QueryDelegationRewardsRequest {
+            delegator_address: delegator.get_address_string(),
+            validator_address: validator.get_address_string(),
+        };
+        let action = Action::QueryDelegationRewards {
+            delegator: delegator.get_address(),
+            validator: validator.get_address(),
+        };
+        Ok(self
+            .perform_query(req, action, true)
+            .await?
+            .into_inner()
+            .rewards)
+    }
+
+    /// Get the rewards accrued by a delegator across all validators.
+    ///
+    /// The response contains the per-validator rewards as well as the total.
+    pub async fn query_delegation_total_rewards(
+        &self,
+        delegator: impl HasAddress,
+    ) -> Result<QueryDelegationTotalRewardsResponse, crate::Error> {
+        let req = QueryDelegationTotalRewardsRequest {
+            delegator_address: delegator.get_address_string(),
+        };
+        let action = Action::QueryDelegationTotalRewards(delegator.get_address());
+        Ok(self.perform_query(req, action, true).await?.into_inner())
+    }
+
+    /// Get the funds held in the community pool.
+    pub async fn community_pool(&self) -> Result<Vec<DecCoin>, crate::Error> {
+        Ok(self
+            .perform_query(
+                QueryCommunityPoolRequest {},
+                Action::QueryCommunityPool,
+                true,
+            )
+            .await?
+            .into_inner()
+            .pool)
+    }
+
+    /// Get the address that a delegator's rewards are withdrawn to.
+    pub async fn query_delegator_withdraw_address(
+        &self,
+        delegator: impl HasAddress,
+    ) -> Result<Address, crate::Error> {
+        let req = QueryDelegatorWithdrawAddressRequest {
+            delegator_address: delegator.get_address_string(),
+        };
+        let action = Action::QueryDelegatorWithdrawAddress(delegator.get_address());
+        let withdraw_address = self
+            .perform_query(req, action.clone(), true)
+            .await?
+            .into_inner()
+            .withdraw_address;
+        withdraw_address
+            .parse()
+            .map_err(|e| crate::Error::InvalidChainResponse {
+                message: format!("Invalid withdraw address {withdraw_address:?}: {e}"),
+                action,
+            })
+    }
+}
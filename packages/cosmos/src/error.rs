@@ -39,6 +39,75 @@ pub enum AddressError {
     InvalidByteCount { address: String, actual: usize },
     #[error("Invalid HRP provided: {hrp:?}")]
     InvalidHrp { hrp: String },
+    #[error("Invalid instantiate2 checksum length, expected 32 bytes, received {actual}")]
+    InvalidInstantiate2Checksum { actual: usize },
+    #[error("Invalid instantiate2 salt length, expected 1 to 64 bytes, received {actual}")]
+    InvalidInstantiate2Salt { actual: usize },
+}
+
+/// Errors that can occur while parsing or combining coins.
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum CoinError {
+    #[error("Could not parse coin value {input:?}")]
+    InvalidFormat { input: String },
+    #[error("Decimal amount in {input:?} needs a known display denom, use base units instead")]
+    UnknownDecimals { input: String },
+    #[error("Amount in {input:?} has more than {exponent} decimal places")]
+    TooManyDecimals { input: String, exponent: u32 },
+    #[error("Cannot combine coins with different denoms {left} and {right}")]
+    MismatchedDenoms { left: String, right: String },
+    #[error("Overflow while adding amounts of {denom}")]
+    Overflow { denom: String },
+    #[error("Insufficient {denom}: have {available}, need {requested}")]
+    Insufficient {
+        denom: String,
+        available: u128,
+        requested: u128,
+    },
+}
+
+/// Errors that can occur while decoding raw transactions.
+#[derive(thiserror::Error, Debug, Clone)]
+pub enum TxDecodeError {
+    #[error("Invalid base64 encoding for transaction: {source}")]
+    InvalidBase64 { source: base64::DecodeError },
+    #[error("Unable to decode transaction protobuf: {source}")]
+    InvalidProtobuf { source: prost::DecodeError },
+    #[error("Transaction is missing its {field} field")]
+    MissingField { field: &'static str },
+}
+
+/// Errors that can occur while verifying signatures.
+#[derive(thiserror::Error, Debug, Clone)]
+pub enum SignatureError {
+    #[error("Invalid signature bytes: {source}")]
+    InvalidSignature { source: bitcoin::secp256k1::Error },
+    #[error("Invalid public key: {source}")]
+    InvalidPublicKey { source: bitcoin::secp256k1::Error },
+    #[error("Signature was not produced by {signer}")]
+    Mismatch { signer: String },
+}
+
+/// Errors that can occur while building and signing multisig transactions.
+#[derive(thiserror::Error, Debug, Clone)]
+pub enum MultisigError {
+    #[error("Invalid multisig threshold {threshold} for {key_count} public keys")]
+    InvalidThreshold { threshold: u32, key_count: usize },
+    #[error("Invalid multisig member public key {public_key}, only compressed secp256k1 keys are supported")]
+    InvalidPublicKey { public_key: String },
+    #[error("Public key {public_key} is not a member of the multisig")]
+    NotAMember { public_key: String },
+    #[error("Public key {public_key} is not one of the signers for this transaction")]
+    NotASigner { public_key: String },
+    #[error("Only {signers} signers provided, but the multisig threshold is {threshold}")]
+    NotEnoughSigners { signers: usize, threshold: u32 },
+    #[error("Missing signature from {public_key}")]
+    MissingSignature { public_key: String },
+    #[error("Invalid signature from {public_key}: {source}")]
+    InvalidSignature {
+        public_key: String,
+        source: SignatureError,
+    },
 }
 
 /// Errors that can occur while working with [crate::Wallet].
@@ -81,16 +150,95 @@ pub enum BuilderError {
     },
     #[error("Error downloading chain information from {url}: {source:?}")]
     DownloadChainInfo { url: String, source: reqwest::Error },
+    #[error("Invalid gas price {value:?} loaded from {url}: {source}")]
+    InvalidGasPrice {
+        url: String,
+        value: String,
+        source: std::num::ParseFloatError,
+    },
+    #[error("Invalid chain registry entry for {chain_name}: {message}")]
+    InvalidChainRegistry { chain_name: String, message: String },
     #[error("Unknown Cosmos network value {network:?}")]
     UnknownCosmosNetwork { network: String },
+    #[error("Fee denom {denom} is not configured as an alternative fee denom")]
+    UnknownFeeDenom { denom: String },
+    #[error("A maximum fee is set, but fee denom {denom} has no maximum fee of its own")]
+    NoMaxFeeForDenom { denom: String },
     #[error("Mismatched chain IDs during sanity check of {grpc_url}. Expected: {expected}. Actual: {actual:?}.")]
     MismatchedChainIds {
         grpc_url: String,
         expected: String,
         actual: Option<String>,
     },
+    #[error("Mismatched bech32 prefixes during sanity check of {grpc_url}. Expected: {expected}. Actual: {actual}.")]
+    MismatchedBech32Prefix {
+        grpc_url: String,
+        expected: String,
+        actual: String,
+    },
+    #[error("Gas coin {gas_coin} is not accepted by {grpc_url}, minimum gas prices: {minimum_gas_price}")]
+    GasCoinNotAccepted {
+        grpc_url: String,
+        gas_coin: String,
+        minimum_gas_price: String,
+    },
+    #[error("Highest gas price {configured}{gas_coin} is below the minimum {minimum}{gas_coin} of {grpc_url}")]
+    GasPriceBelowMinimum {
+        grpc_url: String,
+        gas_coin: String,
+        configured: f64,
+        minimum: f64,
+    },
     #[error(transparent)]
     SanityQueryFailed { source: QueryError },
+    #[error("Invalid proxy URL {proxy}: {message}")]
+    InvalidProxy { proxy: String, message: String },
+    #[error("Invalid gRPC header {name:?}: {message}")]
+    InvalidGrpcHeader { name: String, message: String },
+    #[error("Unable to build HTTP client: {source}")]
+    HttpClient { source: reqwest::Error },
+    #[error("Invalid LCD URL {lcd_url}: {source}")]
+    InvalidLcdUrl {
+        lcd_url: String,
+        source: <reqwest::Url as FromStr>::Err,
+    },
+    #[error("Invalid RPC URL {rpc_url}: {source}")]
+    InvalidRpcUrl {
+        rpc_url: String,
+        source: <reqwest::Url as FromStr>::Err,
+    },
+    #[error("Unable to read query fixture {}: {source}", path.display())]
+    ReadFixture {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[error("Invalid query fixture {}: {source}", path.display())]
+    ParseFixture {
+        path: PathBuf,
+        source: serde_json::Error,
+    },
+}
+
+/// Errors that can occur while starting a [crate::localnet::Localnet].
+#[derive(thiserror::Error, Debug)]
+pub enum LocalnetError {
+    #[error(transparent)]
+    Wallet(#[from] WalletError),
+    #[error(transparent)]
+    Builder(#[from] BuilderError),
+    #[error(transparent)]
+    Chain(#[from] Error),
+    #[error("No local node reachable at {grpc_url} and no Docker container configured")]
+    NotRunning { grpc_url: String },
+    #[error("Unable to run docker: {source}")]
+    DockerUnavailable { source: std::io::Error },
+    #[error("Unable to launch {image} container: {stderr}")]
+    DockerRunFailed { image: String, stderr: String },
+    #[error("Local node did not start within {timeout:?}: {last_error}")]
+    StartupTimeout {
+        timeout: Duration,
+        last_error: String,
+    },
 }
 
 /// Parse errors while interacting with chain data.
@@ -120,6 +268,23 @@ pub enum ChainParseError {
     NoCodeIdFound { txhash: String },
     #[error("No instantiated contract found in transaction {txhash}")]
     NoInstantiatedContractFound { txhash: String },
+    #[error("Invalid proposal ID {proposal_id:?} from transaction {txhash}: {source:?}")]
+    InvalidProposalId {
+        proposal_id: String,
+        txhash: String,
+        source: std::num::ParseIntError,
+    },
+    #[error(
+        "No proposal ID found when expecting a submit proposal response in transaction {txhash}"
+    )]
+    NoProposalIdFound { txhash: String },
+    #[error("Invalid address {address:?} returned from chain: {source}")]
+    InvalidAddress {
+        address: String,
+        source: AddressError,
+    },
+    #[error("Invalid number {value:?} returned from chain")]
+    InvalidNumber { value: String },
 }
 
 /// An error that occurs while connecting to a Cosmos gRPC endpoint.
@@ -173,14 +338,18 @@ pub enum Error {
     #[error("Unable to serialize value to JSON: {0}")]
     JsonSerialize(#[from] serde_json::Error),
     #[error(
-        "Unable to deserialize value from JSON while performing: {action}. Parse error: {source}"
+        "Unable to deserialize value from JSON while performing: {action}. Parse error: {source}. Raw response: {}",
+        String::from_utf8_lossy(.raw)
     )]
     JsonDeserialize {
         source: serde_json::Error,
         action: Action,
+        raw: Vec<u8>,
     },
     #[error(transparent)]
     Query(#[from] QueryError),
+    #[error(transparent)]
+    TokenFactory(#[from] TokenFactoryError),
     #[error("Error parsing data returned from chain: {source}. While performing: {action}")]
     ChainParse {
         source: Box<crate::error::ChainParseError>,
@@ -197,9 +366,21 @@ pub enum Error {
         path: PathBuf,
         source: std::io::Error,
     },
+    #[error("WASM code is {size} bytes, larger than the chain's maximum of {max} bytes")]
+    WasmTooLarge { size: u64, max: u64 },
+    #[error("Invalid gzip compressed WASM code: {source}")]
+    InvalidGzipWasm { source: std::io::Error },
+    #[error("{sender} is not permitted to upload code to this chain")]
+    UploadNotPermitted { sender: Address },
+    #[error("Checksum mismatch for code ID {code_id}: uploaded {expected}, chain has {actual}")]
+    ChecksumMismatch {
+        code_id: u64,
+        expected: String,
+        actual: String,
+    },
     #[error("Transaction failed ({grpc_url}) during {stage} with {code} and log: {raw_log}. Action: {action}.")]
     TransactionFailed {
-        code: CosmosSdkError,
+        code: ChainError,
         raw_log: String,
         action: Arc<Action>,
         grpc_url: Arc<String>,
@@ -207,6 +388,48 @@ pub enum Error {
     },
     #[error(transparent)]
     Connection(#[from] ConnectionError),
+    #[error("Cannot find first block at or after {timestamp}. Earliest block: {earliest_height} at {earliest_timestamp}. Latest block: {latest_height} at {latest_timestamp}.")]
+    TimestampOutOfRange {
+        timestamp: DateTime<Utc>,
+        earliest_height: i64,
+        earliest_timestamp: DateTime<Utc>,
+        latest_height: i64,
+        latest_timestamp: DateTime<Utc>,
+    },
+    #[error("Invalid name {name:?}: {reason}")]
+    InvalidName { name: String, reason: &'static str },
+    #[error(transparent)]
+    Multisig(#[from] MultisigError),
+    #[error("Proof verification failed: {message}. While performing: {action}")]
+    ProofVerification { message: String, action: Action },
+    #[error("Light client verification failed at height {height}: {message}")]
+    LightClientVerification { height: u64, message: String },
+    #[error("Fee denom {denom} is not the gas coin or a configured alternative fee denom")]
+    UnknownFeeDenom { denom: String },
+    #[error("Refusing to broadcast: a maximum fee is set, but fee denom {denom} has no maximum fee of its own")]
+    NoMaxFeeForDenom { denom: String },
+    #[error("Refusing to broadcast: fee of {fee}{denom} for {gas} gas is above the maximum of {max_fee}{denom}")]
+    FeeTooHigh {
+        fee: u64,
+        max_fee: u64,
+        denom: String,
+        gas: u64,
+    },
+    #[error("Unable to load gas price: {message}")]
+    LoadGasPrice { message: String },
+    #[error("Unable to write query fixture {}: {source}", path.display())]
+    WriteFixture {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[error("No Tendermint RPC URL configured, see CosmosBuilder::set_rpc_url")]
+    NoRpcUrl,
+    #[error("Tendermint RPC call to {rpc_url} failed: {message}. While performing: {action}")]
+    Rpc {
+        rpc_url: Arc<String>,
+        message: String,
+        action: Action,
+    },
 }
 
 impl Error {
@@ -219,6 +442,147 @@ impl Error {
             _ => None,
         }
     }
+
+    /// Broad category of this error, for deciding how to handle it.
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            Error::Query(QueryError { query, .. }) => query.kind(),
+            Error::Connection(_)
+            | Error::WaitForTransactionTimedOut { .. }
+            | Error::WaitForTransactionTimedOutWhile { .. }
+            | Error::Rpc { .. } => ErrorKind::Connection,
+            Error::TransactionFailed { .. } | Error::UploadNotPermitted { .. } => {
+                ErrorKind::Rejected
+            }
+            Error::JsonDeserialize { .. }
+            | Error::ChainParse { .. }
+            | Error::InvalidChainResponse { .. } => ErrorKind::Parse,
+            Error::JsonSerialize(_)
+            | Error::TokenFactory(_)
+            | Error::LoadingWasmFromFile { .. }
+            | Error::WasmTooLarge { .. }
+            | Error::InvalidGzipWasm { .. }
+            | Error::ChecksumMismatch { .. }
+            | Error::TimestampOutOfRange { .. }
+            | Error::InvalidName { .. }
+            | Error::Multisig(_)
+            | Error::ProofVerification { .. }
+            | Error::LightClientVerification { .. }
+            | Error::UnknownFeeDenom { .. }
+            | Error::NoMaxFeeForDenom { .. }
+            | Error::FeeTooHigh { .. }
+            | Error::LoadGasPrice { .. }
+            | Error::WriteFixture { .. }
+            | Error::NoRpcUrl => ErrorKind::Other,
+        }
+    }
+
+    /// The action being performed when the error occurred, if known.
+    pub fn action(&self) -> Option<&Action> {
+        match self {
+            Error::Query(QueryError { action, .. })
+            | Error::JsonDeserialize { action, .. }
+            | Error::ChainParse { action, .. }
+            | Error::InvalidChainResponse { action, .. }
+            | Error::WaitForTransactionTimedOutWhile { action, .. }
+            | Error::ProofVerification { action, .. }
+            | Error::Rpc { action, .. } => Some(action),
+            Error::TransactionFailed { action, .. } => Some(action.as_ref()),
+            _ => None,
+        }
+    }
+
+    /// The gRPC status returned by the node, if any.
+    pub fn grpc_status(&self) -> Option<&tonic::Status> {
+        match self {
+            Error::Query(QueryError { query, .. }) => query.grpc_status(),
+            _ => None,
+        }
+    }
+
+    /// The Cosmos SDK error code returned by the chain, if any.
+    pub fn chain_code(&self) -> Option<CosmosSdkError> {
+        match self {
+            Error::TransactionFailed { code, .. } => Some(code.kind),
+            Error::Query(QueryError {
+                query: QueryErrorDetails::CosmosSdk { error_code, .. },
+                ..
+            }) => Some(*error_code),
+            Error::Query(QueryError {
+                query: QueryErrorDetails::AccountSequenceMismatch(_),
+                ..
+            }) => Some(CosmosSdkError::IncorrectAccountSequence),
+            _ => None,
+        }
+    }
+
+    /// The full error code of a failed transaction, including its codespace.
+    pub fn chain_error(&self) -> Option<&ChainError> {
+        match self {
+            Error::TransactionFailed { code, .. } => Some(code),
+            _ => None,
+        }
+    }
+
+    /// The error returned by a CosmWasm contract, if this is a contract failure.
+    ///
+    /// Looks at the `raw_log` of failed transactions and the message of failed
+    /// queries and simulations, see [ContractError::parse].
+    pub fn contract_error(&self) -> Option<ContractError> {
+        match self {
+            Error::TransactionFailed { raw_log, .. } => ContractError::parse(raw_log),
+            _ => self
+                .grpc_status()
+                .and_then(|status| ContractError::parse(status.message())),
+        }
+    }
+
+    /// Whether the same operation may succeed if tried again.
+    ///
+    /// Transport failures and transient chain conditions (account sequence
+    /// mismatch, full mempool, insufficient fee while gas prices move) are
+    /// retryable. Rejections that will recur for the same input, such as
+    /// insufficient funds or a contract error, are not.
+    pub fn is_retryable(&self) -> bool {
+        self.retry_reason().is_some()
+    }
+
+    /// Why the operation may succeed if tried again, see [Self::is_retryable].
+    pub fn retry_reason(&self) -> Option<RetryReason> {
+        match self {
+            Error::Query(QueryError { query, .. }) => query.retry_reason(),
+            Error::TransactionFailed { code, .. } => code.kind.retry_reason(),
+            _ => (self.kind() == ErrorKind::Connection).then_some(RetryReason::Connection),
+        }
+    }
+}
+
+/// Broad category of an [Error], see [Error::kind].
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum ErrorKind {
+    /// Problem reaching the node, or the node couldn't serve the request
+    Connection,
+    /// The chain or a contract rejected the request
+    Rejected,
+    /// The requested data doesn't exist
+    NotFound,
+    /// Data returned from the chain couldn't be parsed
+    Parse,
+    /// Any other error, e.g. invalid input or local configuration
+    Other,
+}
+
+/// What needs to change for a retry to succeed, see [Error::retry_reason].
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum RetryReason {
+    /// Problem reaching the node, sending the same request again may work
+    Connection,
+    /// The transaction must be signed again with the expected sequence number
+    SequenceMismatch,
+    /// The transaction must be signed again with a higher fee
+    InsufficientFee,
+    /// The mempool is full, or the node hasn't seen the latest blocks yet
+    Congestion,
 }
 
 #[derive(Debug)]
@@ -242,11 +606,22 @@ pub enum Action {
     GetBaseAccount(Address),
     QueryAllBalances(Address),
     QueryGranterGrants(Address),
+    QueryGranteeGrants(Address),
+    QueryGrants {
+        granter: Address,
+        grantee: Address,
+    },
     CodeInfo(u64),
     GetTransactionBody(String),
     ListTransactionsFor(Address),
     GetBlock(i64),
     GetLatestBlock,
+    GetBlockResults(i64),
+    AbciQuery(String),
+    LightClientFetch(u64),
+    UnconfirmedTxs,
+    QueryMinimumGasPrices,
+    QueryFeeMarket,
     Simulate(TxBuilder),
     Broadcast(TxBuilder),
     RawQuery {
@@ -259,10 +634,62 @@ pub enum Action {
     },
     ContractInfo(Address),
     ContractHistory(Address),
+    AllContractState(Address),
+    ListCodes,
+    WasmParams,
+    ContractsByCode(u64),
+    ContractsByCreator(Address),
     GetEarliestBlock,
     WaitForTransaction(String),
     SanityCheck,
     OsmosisEpochsInfo,
+    OsmosisEstimateSwap {
+        token_in: String,
+        pools: Vec<u64>,
+    },
+    OsmosisSpotPrice {
+        pool_id: u64,
+        base: String,
+        quote: String,
+    },
+    OsmosisTwap {
+        pool_id: u64,
+        base: String,
+        quote: String,
+        start_time: DateTime<Utc>,
+    },
+    OsmosisClPositions(Address),
+    OsmosisClPoolLiquidity(u64),
+    OsmosisCurrentEpoch(String),
+    OsmosisLockedCoins(Address),
+    OsmosisUnlockingCoins(Address),
+    OsmosisUnlockableCoins(Address),
+    QueryDenomMetadata(String),
+    ResolveName(String),
+    QueryDelegationRewards {
+        delegator: Address,
+        validator: Address,
+    },
+    QueryDelegationTotalRewards(Address),
+    QueryDelegatorWithdrawAddress(Address),
+    QueryCommunityPool,
+    QueryModuleAccounts,
+    QueryModuleAccount(String),
+    DenomsFromCreator(Address),
+    QueryProposal(u64),
+    QueryProposals,
+    QueryProposalTally(u64),
+    QueryVotes(u64),
+    QueryFeeAllowance {
+        granter: Address,
+        grantee: Address,
+    },
+    QueryFeeAllowances(Address),
+    QueryInterchainAccount {
+        owner: Address,
+        connection_id: String,
+    },
+    SearchTransactions(Vec<String>),
 }
 
 impl Display for Action {
@@ -271,11 +698,19 @@ impl Display for Action {
             Action::GetBaseAccount(address) => write!(f, "get base account {address}"),
             Action::QueryAllBalances(address) => write!(f, "query all balances for {address}"),
             Action::QueryGranterGrants(address) => write!(f, "query granter grants for {address}"),
+            Action::QueryGranteeGrants(address) => write!(f, "query grantee grants for {address}"),
+            Action::QueryGrants { granter, grantee } => {
+                write!(f, "query grants from {granter} to {grantee}")
+            }
             Action::CodeInfo(code_id) => write!(f, "get code info for code ID {code_id}"),
             Action::GetTransactionBody(txhash) => write!(f, "get transaction {txhash}"),
             Action::ListTransactionsFor(address) => write!(f, "list transactions for {address}"),
             Action::GetBlock(height) => write!(f, "get block {height}"),
             Action::GetLatestBlock => f.write_str("get latest block"),
+            Action::GetBlockResults(height) => write!(f, "get block results at height {height}"),
+            Action::UnconfirmedTxs => f.write_str("get unconfirmed transactions"),
+            Action::AbciQuery(path) => write!(f, "ABCI query {path}"),
+            Action::LightClientFetch(height) => write!(f, "fetch light block at height {height}"),
             Action::Simulate(txbuilder) => write!(f, "simulating transaction: {txbuilder}"),
             Action::Broadcast(txbuilder) => write!(f, "broadcasting transaction: {txbuilder}"),
             Action::RawQuery { contract, key } => {
@@ -286,10 +721,99 @@ impl Display for Action {
             }
             Action::ContractInfo(address) => write!(f, "contract info for {address}"),
             Action::ContractHistory(address) => write!(f, "contract history for {address}"),
+            Action::AllContractState(address) => write!(f, "all contract state for {address}"),
+            Action::ListCodes => f.write_str("list codes"),
+            Action::WasmParams => f.write_str("query wasm params"),
+            Action::ContractsByCode(code_id) => write!(f, "list contracts for code ID {code_id}"),
+            Action::ContractsByCreator(creator) => {
+                write!(f, "list contracts created by {creator}")
+            }
             Action::GetEarliestBlock => f.write_str("get earliest block"),
             Action::WaitForTransaction(txhash) => write!(f, "wait for transaction {txhash}"),
             Action::SanityCheck => f.write_str("sanity check"),
+            Action::QueryMinimumGasPrices => f.write_str("query node minimum gas prices"),
+            Action::QueryFeeMarket => f.write_str("query fee market gas prices"),
             Action::OsmosisEpochsInfo => f.write_str("get Osmosis epochs info"),
+            Action::OsmosisEstimateSwap { token_in, pools } => {
+                write!(
+                    f,
+                    "estimate Osmosis swap of {token_in} through pools {pools:?}"
+                )
+            }
+            Action::OsmosisSpotPrice {
+                pool_id,
+                base,
+                quote,
+            } => write!(
+                f,
+                "query Osmosis spot price of {base} in {quote} for pool {pool_id}"
+            ),
+            Action::OsmosisTwap {
+                pool_id,
+                base,
+                quote,
+                start_time,
+            } => write!(
+                f,
+                "query Osmosis TWAP of {base} in {quote} for pool {pool_id} since {start_time}"
+            ),
+            Action::OsmosisClPositions(owner) => {
+                write!(
+                    f,
+                    "query Osmosis concentrated liquidity positions for {owner}"
+                )
+            }
+            Action::OsmosisClPoolLiquidity(pool_id) => {
+                write!(f, "query Osmosis concentrated liquidity for pool {pool_id}")
+            }
+            Action::OsmosisCurrentEpoch(identifier) => {
+                write!(f, "query current Osmosis {identifier} epoch")
+            }
+            Action::OsmosisLockedCoins(owner) => {
+                write!(f, "query Osmosis locked coins for {owner}")
+            }
+            Action::OsmosisUnlockingCoins(owner) => {
+                write!(f, "query Osmosis unlocking coins for {owner}")
+            }
+            Action::OsmosisUnlockableCoins(owner) => {
+                write!(f, "query Osmosis unlockable coins for {owner}")
+            }
+            Action::QueryDenomMetadata(denom) => write!(f, "query denom metadata for {denom}"),
+            Action::ResolveName(name) => write!(f, "resolve name {name}"),
+            Action::QueryDelegationRewards {
+                delegator,
+                validator,
+            } => write!(
+                f,
+                "query delegation rewards for {delegator} from {validator}"
+            ),
+            Action::QueryDelegationTotalRewards(delegator) => {
+                write!(f, "query total delegation rewards for {delegator}")
+            }
+            Action::QueryDelegatorWithdrawAddress(delegator) => {
+                write!(f, "query withdraw address for {delegator}")
+            }
+            Action::QueryCommunityPool => f.write_str("query community pool"),
+            Action::QueryModuleAccounts => f.write_str("query module accounts"),
+            Action::QueryModuleAccount(name) => write!(f, "query module account {name}"),
+            Action::DenomsFromCreator(creator) => {
+                write!(f, "query tokenfactory denoms created by {creator}")
+            }
+            Action::QueryProposal(id) => write!(f, "query governance proposal {id}"),
+            Action::QueryProposals => f.write_str("query governance proposals"),
+            Action::QueryProposalTally(id) => write!(f, "query tally for governance proposal {id}"),
+            Action::QueryVotes(id) => write!(f, "query votes for governance proposal {id}"),
+            Action::QueryFeeAllowance { granter, grantee } => {
+                write!(f, "query fee allowance from {granter} to {grantee}")
+            }
+            Action::QueryFeeAllowances(grantee) => write!(f, "query fee allowances for {grantee}"),
+            Action::QueryInterchainAccount {
+                owner,
+                connection_id,
+            } => write!(f, "query interchain account for {owner} on {connection_id}"),
+            Action::SearchTransactions(events) => {
+                write!(f, "search transactions matching {events:?}")
+            }
         }
     }
 }
@@ -336,9 +860,7 @@ pub enum QueryErrorDetails {
     JsonParseError(tonic::Status),
     #[error("{0:?}")]
     FailedToExecute(tonic::Status),
-    #[error(
-        "Requested height not available, lowest height reported: {lowest_height:?}. {source:?}"
-    )]
+    #[error("Requested height not available, state pruned at this node. Lowest height reported: {lowest_height:?}. {source:?}")]
     HeightNotAvailable {
         lowest_height: Option<i64>,
         source: tonic::Status,
@@ -367,6 +889,8 @@ pub enum QueryErrorDetails {
     },
     #[error("Account sequence mismatch: {0}")]
     AccountSequenceMismatch(tonic::Status),
+    #[error("No recorded response for {request} in the replay fixture")]
+    FixtureMissing { request: &'static str },
 }
 
 /// Different known Cosmos SDK error codes
@@ -384,6 +908,8 @@ pub enum CosmosSdkError {
     InsufficientFee,
     /// Code 19
     TxInMempool,
+    /// Code 20
+    MempoolIsFull,
     /// Code 21
     TxTooLarge,
     /// Code 28
@@ -404,6 +930,7 @@ impl Display for CosmosSdkError {
             CosmosSdkError::OutOfGas => f.write_str("out of gas (11)"),
             CosmosSdkError::InsufficientFee => f.write_str("insufficient fee (13)"),
             CosmosSdkError::TxInMempool => f.write_str("tx already in mempool (19)"),
+            CosmosSdkError::MempoolIsFull => f.write_str("mempool is full (20)"),
             CosmosSdkError::TxTooLarge => f.write_str("tx too large (21)"),
             CosmosSdkError::InvalidChainId => f.write_str("invalid chain ID (28)"),
             CosmosSdkError::TxTimeoutHeight => f.write_str("tx timeout height (30)"),
@@ -415,6 +942,22 @@ impl Display for CosmosSdkError {
     }
 }
 
+impl CosmosSdkError {
+    /// See [Error::retry_reason]
+    pub(crate) fn retry_reason(self) -> Option<RetryReason> {
+        match self {
+            CosmosSdkError::IncorrectAccountSequence => Some(RetryReason::SequenceMismatch),
+            CosmosSdkError::InsufficientFee => Some(RetryReason::InsufficientFee),
+            // Tx already in mempool usually means a node which hasn't seen
+            // the latest blocks yet, see QueryErrorDetails::error_category
+            CosmosSdkError::MempoolIsFull | CosmosSdkError::TxInMempool => {
+                Some(RetryReason::Congestion)
+            }
+            _ => None,
+        }
+    }
+}
+
 impl From<u32> for CosmosSdkError {
     fn from(value: u32) -> Self {
         match value {
@@ -423,6 +966,7 @@ impl From<u32> for CosmosSdkError {
             11 => Self::OutOfGas,
             13 => Self::InsufficientFee,
             19 => Self::TxInMempool,
+            20 => Self::MempoolIsFull,
             21 => Self::TxTooLarge,
             28 => Self::InvalidChainId,
             30 => Self::TxTimeoutHeight,
@@ -432,6 +976,113 @@ impl From<u32> for CosmosSdkError {
     }
 }
 
+/// Error code returned by the chain for a failed transaction.
+///
+/// ABCI codes are only unique within a codespace: code 5 in the `sdk`
+/// codespace is insufficient funds, but something else entirely for a
+/// different module. `kind` is only decoded for the `sdk` codespace.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct ChainError {
+    /// Module which defined the error, e.g. `sdk` or `wasm`
+    pub codespace: String,
+    /// Numeric ABCI code within the codespace
+    pub code: u32,
+    /// Well known Cosmos SDK error, [CosmosSdkError::Other] outside the `sdk` codespace
+    pub kind: CosmosSdkError,
+}
+
+impl ChainError {
+    /// Decode the codespace and code from a transaction response.
+    pub fn new(codespace: impl Into<String>, code: u32) -> Self {
+        let codespace = codespace.into();
+        // Older nodes leave the codespace empty for SDK errors
+        let kind = if codespace.is_empty() || codespace == "sdk" {
+            code.into()
+        } else {
+            CosmosSdkError::Other(code)
+        };
+        ChainError {
+            codespace,
+            code,
+            kind,
+        }
+    }
+}
+
+impl Display for ChainError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self.kind {
+            CosmosSdkError::Other(_) => {
+                write!(
+                    f,
+                    "error code {} in codespace {}",
+                    self.code, self.codespace
+                )
+            }
+            kind => kind.fmt(f),
+        }
+    }
+}
+
+/// An error returned by a CosmWasm contract, see [Error::contract_error].
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct ContractError {
+    /// Index of the failing message within the transaction, if reported
+    pub message_index: Option<usize>,
+    /// Error string returned by the contract
+    pub error: String,
+}
+
+impl ContractError {
+    /// Extract the contract error from the wasm module's wrapped log format.
+    ///
+    /// Handles both `failed to execute message; message index: 0: <error>:
+    /// execute wasm contract failed` and `query wasm contract failed: <error>`
+    /// styles, optionally preceded by a `codespace wasm code N: ` prefix.
+    /// Returns [None] if the log doesn't come from a failed contract call.
+    pub fn parse(log: &str) -> Option<Self> {
+        const OPERATIONS: [&str; 4] = ["execute", "query", "instantiate", "migrate"];
+
+        let log = match log.strip_prefix("codespace wasm code ") {
+            Some(rest) => rest.split_once(": ")?.1,
+            None => log,
+        };
+        let (message_index, log) =
+            match log.strip_prefix("failed to execute message; message index: ") {
+                Some(rest) => {
+                    let (index, rest) = rest.split_once(": ")?;
+                    (Some(index.parse().ok()?), rest)
+                }
+                None => (None, log),
+            };
+        let error = OPERATIONS.iter().find_map(|operation| {
+            let marker = format!("{operation} wasm contract failed");
+            match log.strip_prefix(&marker) {
+                Some(rest) => rest.strip_prefix(": "),
+                None => log.find(&format!(": {marker}")).map(|idx| &log[..idx]),
+            }
+        })?;
+        Some(ContractError {
+            message_index,
+            error: error.to_owned(),
+        })
+    }
+
+    /// Deserialize the error, for contracts which return errors as JSON.
+    pub fn deserialize<E: serde::de::DeserializeOwned>(&self) -> Result<E, serde_json::Error> {
+        serde_json::from_str(&self.error)
+    }
+}
+
+impl Display for ContractError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self.message_index {
+            Some(index) => write!(f, "Contract error in message {index}: {}", self.error),
+            None => write!(f, "Contract error: {}", self.error),
+        }
+    }
+}
+
 pub(crate) enum QueryErrorCategory {
     /// Should retry, kill the connection
     NetworkIssue,
@@ -481,6 +1132,68 @@ impl QueryErrorDetails {
             QueryErrorDetails::NoNewBlockFound { .. } => NetworkIssue,
             // Same logic as CosmosSdk IncorrectAccountSequence above
             QueryErrorDetails::AccountSequenceMismatch { .. } => ConnectionIsFine,
+            QueryErrorDetails::FixtureMissing { .. } => ConnectionIsFine,
+        }
+    }
+
+    /// See [Error::kind]
+    pub(crate) fn kind(&self) -> ErrorKind {
+        match self {
+            QueryErrorDetails::QueryTimeout(_)
+            | QueryErrorDetails::ConnectionError(_)
+            | QueryErrorDetails::HeightNotAvailable { .. }
+            | QueryErrorDetails::Unavailable { .. }
+            | QueryErrorDetails::Unimplemented { .. }
+            | QueryErrorDetails::TransportError { .. }
+            | QueryErrorDetails::BlocksLagDetected { .. }
+            | QueryErrorDetails::NoNewBlockFound { .. } => ErrorKind::Connection,
+            QueryErrorDetails::CosmosSdk { .. }
+            | QueryErrorDetails::JsonParseError(_)
+            | QueryErrorDetails::FailedToExecute(_)
+            | QueryErrorDetails::AccountSequenceMismatch(_) => ErrorKind::Rejected,
+            QueryErrorDetails::NotFound(_) => ErrorKind::NotFound,
+            QueryErrorDetails::Unknown(_) | QueryErrorDetails::FixtureMissing { .. } => {
+                ErrorKind::Other
+            }
+        }
+    }
+
+    /// See [Error::retry_reason]
+    pub(crate) fn retry_reason(&self) -> Option<RetryReason> {
+        match self {
+            QueryErrorDetails::CosmosSdk { error_code, .. } => error_code.retry_reason(),
+            QueryErrorDetails::AccountSequenceMismatch(_) => Some(RetryReason::SequenceMismatch),
+            _ => (self.kind() == ErrorKind::Connection).then_some(RetryReason::Connection),
+        }
+    }
+
+    /// The gRPC status returned by the node, if any.
+    pub(crate) fn grpc_status(&self) -> Option<&tonic::Status> {
+        match self {
+            QueryErrorDetails::Unknown(source)
+            | QueryErrorDetails::CosmosSdk { source, .. }
+            | QueryErrorDetails::JsonParseError(source)
+            | QueryErrorDetails::FailedToExecute(source)
+            | QueryErrorDetails::HeightNotAvailable { source, .. }
+            | QueryErrorDetails::Unavailable { source, .. }
+            | QueryErrorDetails::Unimplemented { source }
+            | QueryErrorDetails::TransportError { source }
+            | QueryErrorDetails::AccountSequenceMismatch(source) => Some(source),
+            QueryErrorDetails::QueryTimeout(_)
+            | QueryErrorDetails::ConnectionError(_)
+            | QueryErrorDetails::NotFound(_)
+            | QueryErrorDetails::BlocksLagDetected { .. }
+            | QueryErrorDetails::NoNewBlockFound { .. }
+            | QueryErrorDetails::FixtureMissing { .. } => None,
+        }
+    }
+
+    /// The gRPC status code returned by the node, if any.
+    pub(crate) fn tonic_code(&self) -> Option<tonic::Code> {
+        match self {
+            QueryErrorDetails::NotFound(_) => Some(tonic::Code::NotFound),
+            QueryErrorDetails::QueryTimeout(_) => Some(tonic::Code::DeadlineExceeded),
+            _ => self.grpc_status().map(tonic::Status::code),
         }
     }
 
@@ -578,6 +1291,129 @@ mod tests {
         );
     }
 
+    #[test]
+    fn error_accessors() {
+        let err = Error::TransactionFailed {
+            code: ChainError::new("sdk", 5),
+            raw_log: "insufficient funds".to_owned(),
+            action: Arc::new(Action::SanityCheck),
+            grpc_url: Arc::new("http://localhost:9090".to_owned()),
+            stage: TransactionStage::Broadcast,
+        };
+        assert_eq!(err.kind(), ErrorKind::Rejected);
+        assert_eq!(err.chain_code(), Some(CosmosSdkError::InsufficientFunds));
+        assert!(matches!(err.action(), Some(Action::SanityCheck)));
+        assert!(err.grpc_status().is_none());
+
+        let err = Error::NoRpcUrl;
+        assert_eq!(err.kind(), ErrorKind::Other);
+        assert!(err.action().is_none());
+    }
+
+    #[test]
+    fn chain_error_codespace() {
+        let sdk = ChainError::new("sdk", 11);
+        assert_eq!(sdk.kind, CosmosSdkError::OutOfGas);
+        assert_eq!(sdk.to_string(), "out of gas (11)");
+        assert_eq!(ChainError::new("", 21).kind, CosmosSdkError::TxTooLarge);
+
+        let wasm = ChainError::new("wasm", 5);
+        assert_eq!(wasm.kind, CosmosSdkError::Other(5));
+        assert_eq!(wasm.to_string(), "error code 5 in codespace wasm");
+    }
+
+    #[test]
+    fn contract_errors() {
+        let parsed = ContractError::parse("failed to execute message; message index: 1: Unauthorized: execute wasm contract failed").unwrap();
+        assert_eq!(parsed.message_index, Some(1));
+        assert_eq!(parsed.error, "Unauthorized");
+
+        let parsed = ContractError::parse("failed to execute message; message index: 0: {\"id\":\"insufficient_margin\"}: execute wasm contract failed [CosmWasm/wasmd@v0.45.0/x/wasm/keeper/keeper.go:397] With gas wanted: '0' and gas used: '126433' ").unwrap();
+        #[derive(serde::Deserialize, PartialEq, Debug)]
+        struct Json {
+            id: String,
+        }
+        assert_eq!(
+            parsed.deserialize::<Json>().unwrap(),
+            Json {
+                id: "insufficient_margin".to_owned()
+            }
+        );
+
+        let parsed = ContractError::parse("codespace wasm code 9: query wasm contract failed: Error parsing into type QueryMsg: unknown variant `foo`").unwrap();
+        assert_eq!(parsed.message_index, None);
+        assert_eq!(
+            parsed.error,
+            "Error parsing into type QueryMsg: unknown variant `foo`"
+        );
+
+        assert_eq!(
+            ContractError::parse(
+                "account sequence mismatch, expected 5, got 4: incorrect account sequence"
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn retryable() {
+        let failed = |code: u32| Error::TransactionFailed {
+            code: ChainError::new("sdk", code),
+            raw_log: String::new(),
+            action: Arc::new(Action::SanityCheck),
+            grpc_url: Arc::new("http://localhost:9090".to_owned()),
+            stage: TransactionStage::Broadcast,
+        };
+        assert!(failed(13).is_retryable());
+        assert!(failed(20).is_retryable());
+        assert!(failed(32).is_retryable());
+        assert!(!failed(5).is_retryable());
+        assert!(!failed(4).is_retryable());
+        assert_eq!(
+            failed(32).retry_reason(),
+            Some(RetryReason::SequenceMismatch)
+        );
+        assert_eq!(
+            failed(13).retry_reason(),
+            Some(RetryReason::InsufficientFee)
+        );
+        assert_eq!(failed(19).retry_reason(), Some(RetryReason::Congestion));
+
+        let query = |query| {
+            Error::Query(QueryError {
+                action: Action::SanityCheck,
+                builder: Arc::new(CosmosBuilder::new(
+                    "",
+                    "",
+                    AddressHrp::from_static("osmo"),
+                    "",
+                )),
+                height: None,
+                query,
+                grpc_url: Arc::new("http://localhost:9090".to_owned()),
+                node_health: NodeHealthReport { nodes: vec![] },
+            })
+        };
+        assert_eq!(
+            query(QueryErrorDetails::QueryTimeout(Duration::from_secs(1))).retry_reason(),
+            Some(RetryReason::Connection)
+        );
+        assert_eq!(
+            query(QueryErrorDetails::AccountSequenceMismatch(
+                tonic::Status::unknown("account sequence mismatch")
+            ))
+            .retry_reason(),
+            Some(RetryReason::SequenceMismatch)
+        );
+        assert!(
+            !query(QueryErrorDetails::FailedToExecute(tonic::Status::unknown(
+                "contract error"
+            )))
+            .is_retryable()
+        );
+        assert!(!Error::NoRpcUrl.is_retryable());
+    }
+
     #[test]
     fn test_extract_fail() {
         assert_eq!(
@@ -604,6 +1440,14 @@ pub struct SingleNodeHealthReport {
     pub is_healthy: bool,
     pub last_error: Option<LastNodeError>,
     pub error_count: usize,
+    /// Moving average of successful query latency
+    pub latency: Option<std::time::Duration>,
+    /// Most recent block height reported by this node
+    pub latest_height: Option<i64>,
+    /// How many blocks behind the most up to date node
+    pub block_lag: Option<i64>,
+    /// Remaining time before the node is tried again after repeated errors
+    pub quarantined_for: Option<std::time::Duration>,
 }
 
 #[derive(Clone, Debug)]
@@ -629,6 +1473,15 @@ impl Display for SingleNodeHealthReport {
             "Health report for {}. Fallback: {}. Healthy: {}. ",
             self.grpc_url, self.is_fallback, self.is_healthy
         )?;
+        if let Some(latency) = self.latency {
+            write!(f, "Latency: {latency:?}. ")?;
+        }
+        if let Some(block_lag) = self.block_lag {
+            write!(f, "Block lag: {block_lag}. ")?;
+        }
+        if let Some(quarantined_for) = self.quarantined_for {
+            write!(f, "Quarantined for: {quarantined_for:?}. ")?;
+        }
         match &self.last_error {
             None => write!(f, "No errors")?,
             Some(LastNodeError {
@@ -0,0 +1,282 @@
+//! Typed access to the events emitted by a transaction.
+//!
+//! Start with [crate::TxResponseExt::tx_events], which normalizes events
+//! across Cosmos SDK versions, and then convert individual events into the
+//! typed wrappers in this module.
+
+use std::collections::BTreeMap;
+
+use base64::Engine;
+use cosmos_sdk_proto::cosmos::base::abci::v1beta1::TxResponse;
+
+use crate::{codeid::strip_quotes, Address};
+
+/// A single event with its attributes decoded to strings.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TxEvent {
+    /// Event type, e.g. `wasm` or `transfer`
+    pub kind: String,
+    /// Attributes in the order they were emitted
+    pub attributes: Vec<(String, String)>,
+}
+
+impl TxEvent {
+    /// Get the first value of the given attribute.
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// All attributes as a map. For repeated keys, the last value wins.
+    pub fn attribute_map(&self) -> BTreeMap<String, String> {
+        self.attributes.iter().cloned().collect()
+    }
+}
+
+/// Collect all events from a transaction response.
+///
+/// Older Cosmos SDK versions provide decoded events in the per-message logs,
+/// while newer versions only populate the top level events. Top level
+/// events from Tendermint 0.34 based chains have base64 encoded attributes,
+/// which are decoded here.
+pub(crate) fn collect_events(res: &TxResponse) -> Vec<TxEvent> {
+    let from_logs = res
+        .logs
+        .iter()
+        .flat_map(|log| &log.events)
+        .map(|event| TxEvent {
+            kind: event.r#type.clone(),
+            attributes: event
+                .attributes
+                .iter()
+                .map(|attr| (attr.key.clone(), attr.value.clone()))
+                .collect(),
+        })
+        .collect::<Vec<_>>();
+    if !from_logs.is_empty() {
+        return from_logs;
+    }
+    res.events
+        .iter()
+        .map(|event| {
+            event_from_raw(
+                event.r#type.clone(),
+                event
+                    .attributes
+                    .iter()
+                    .map(|attr| {
+                        (
+                            String::from_utf8_lossy(&attr.key).into_owned(),
+                            String::from_utf8_lossy(&attr.value).into_owned(),
+                        )
+                    })
+                    .collect(),
+            )
+        })
+        .collect()
+}
+
+/// Build an event from raw attributes, decoding them if they are base64 encoded.
+pub(crate) fn event_from_raw(kind: String, attributes: Vec<(String, String)>) -> TxEvent {
+    TxEvent {
+        kind,
+        attributes: decode_base64_attributes(&attributes).unwrap_or(attributes),
+    }
+}
+
+/// Decode attributes if they all look base64 encoded.
+///
+/// Attribute keys are short identifiers, so a key that decodes to printable
+/// ASCII is a reliable sign that the whole event is encoded.
+fn decode_base64_attributes(attributes: &[(String, String)]) -> Option<Vec<(String, String)>> {
+    let decode = |s: &str| {
+        base64::engine::general_purpose::STANDARD
+            .decode(s)
+            .ok()
+            .and_then(|bytes| String::from_utf8(bytes).ok())
+    };
+    if attributes.is_empty() {
+        return None;
+    }
+    attributes
+        .iter()
+        .map(|(key, value)| {
+            let key = decode(key)?;
+            if key.is_empty() || !key.bytes().all(|b| b.is_ascii_graphic()) {
+                return None;
+            }
+            Some((key, decode(value)?))
+        })
+        .collect()
+}
+
+/// A `wasm` event emitted by a contract.
+#[derive(Clone, Debug)]
+pub struct WasmEvent {
+    /// Contract which emitted the event
+    pub contract: Address,
+    /// Attributes added by the contract, excluding the contract address
+    pub attributes: BTreeMap<String, String>,
+}
+
+impl WasmEvent {
+    /// Parse a `wasm` event, returning [None] for other events.
+    pub fn from_event(event: &TxEvent) -> Option<Self> {
+        if event.kind != "wasm" {
+            return None;
+        }
+        let contract = event.get("_contract_address")?.parse().ok()?;
+        let mut attributes = event.attribute_map();
+        attributes.remove("_contract_address");
+        Some(WasmEvent {
+            contract,
+            attributes,
+        })
+    }
+}
+
+/// A contract was instantiated.
+#[derive(Clone, Debug)]
+pub struct InstantiateEvent {
+    /// Address of the new contract
+    pub contract: Address,
+    /// Code ID the contract was instantiated from
+    pub code_id: u64,
+}
+
+impl InstantiateEvent {
+    /// Parse an `instantiate` event, returning [None] for other events.
+    pub fn from_event(event: &TxEvent) -> Option<Self> {
+        if event.kind != "instantiate" && event.kind != "cosmwasm.wasm.v1.EventContractInstantiated"
+        {
+            return None;
+        }
+        let contract = event
+            .get("_contract_address")
+            .or_else(|| event.get("contract_address"))?;
+        Some(InstantiateEvent {
+            contract: strip_quotes(contract).parse().ok()?,
+            code_id: strip_quotes(event.get("code_id")?).parse().ok()?,
+        })
+    }
+}
+
+/// WASM code was uploaded.
+#[derive(Clone, Debug)]
+pub struct StoreCodeEvent {
+    /// The new code ID
+    pub code_id: u64,
+    /// Hex encoded checksum of the code, if provided by the chain
+    pub checksum: Option<String>,
+}
+
+impl StoreCodeEvent {
+    /// Parse a `store_code` event, returning [None] for other events.
+    pub fn from_event(event: &TxEvent) -> Option<Self> {
+        if event.kind != "store_code" {
+            return None;
+        }
+        Some(StoreCodeEvent {
+            code_id: strip_quotes(event.get("code_id")?).parse().ok()?,
+            checksum: event.get("code_checksum").map(str::to_owned),
+        })
+    }
+}
+
+/// Tokens were transferred by the bank module.
+#[derive(Clone, Debug)]
+pub struct TransferEvent {
+    /// Receiving address
+    pub recipient: String,
+    /// Sending address, not included by older Cosmos SDK versions
+    pub sender: Option<String>,
+    /// Transferred coins, e.g. `100uosmo,5uatom`
+    pub amount: String,
+}
+
+impl TransferEvent {
+    /// Parse a `transfer` event, returning [None] for other events.
+    pub fn from_event(event: &TxEvent) -> Option<Self> {
+        if event.kind != "transfer" {
+            return None;
+        }
+        Some(TransferEvent {
+            recipient: event.get("recipient")?.to_owned(),
+            sender: event.get("sender").map(str::to_owned),
+            amount: event.get("amount")?.to_owned(),
+        })
+    }
+}
+
+/// The generic `message` event included with every message.
+#[derive(Clone, Debug)]
+pub struct MessageEvent {
+    /// Type URL or legacy name of the message
+    pub action: Option<String>,
+    /// Signer of the message
+    pub sender: Option<String>,
+    /// Module which handled the message
+    pub module: Option<String>,
+}
+
+impl MessageEvent {
+    /// Parse a `message` event, returning [None] for other events.
+    pub fn from_event(event: &TxEvent) -> Option<Self> {
+        if event.kind != "message" {
+            return None;
+        }
+        Some(MessageEvent {
+            action: event.get("action").map(str::to_owned),
+            sender: event.get("sender").map(str::to_owned),
+            module: event.get("module").map(str::to_owned),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn attrs(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs
+            .iter()
+            .map(|(k, v)| ((*k).to_owned(), (*v).to_owned()))
+            .collect()
+    }
+
+    #[test]
+    fn base64_attributes() {
+        // Tendermint 0.34 style
+        let encoded = attrs(&[("X2NvbnRyYWN0X2FkZHJlc3M=", "b3NtbzE="), ("YWN0aW9u", "")]);
+        assert_eq!(
+            decode_base64_attributes(&encoded).unwrap(),
+            attrs(&[("_contract_address", "osmo1"), ("action", "")])
+        );
+
+        // Already decoded
+        let plain = attrs(&[("_contract_address", "osmo1"), ("action", "swap")]);
+        assert_eq!(decode_base64_attributes(&plain), None);
+    }
+
+    #[test]
+    fn typed_events() {
+        let contract = "osmo168gdk6r58jdwfv49kuesq2rs747jawnn4ryvyk";
+        let event = TxEvent {
+            kind: "wasm".to_owned(),
+            attributes: attrs(&[("_contract_address", contract), ("action", "deposit")]),
+        };
+        let wasm = WasmEvent::from_event(&event).unwrap();
+        assert_eq!(wasm.contract.to_string(), contract);
+        assert_eq!(wasm.attributes.get("action").unwrap(), "deposit");
+        assert!(wasm.attributes.get("_contract_address").is_none());
+        assert!(TransferEvent::from_event(&event).is_none());
+
+        let event = TxEvent {
+            kind: "instantiate".to_owned(),
+            attributes: attrs(&[("_contract_address", contract), ("code_id", "12")]),
+        };
+        assert_eq!(InstantiateEvent::from_event(&event).unwrap().code_id, 12);
+    }
+}
@@ -1,7 +1,14 @@
+use std::collections::BTreeMap;
+
 use chrono::{DateTime, Utc};
 use cosmos_sdk_proto::cosmos::base::abci::v1beta1::TxResponse;
 
-use crate::{codeid::strip_quotes, error::ChainParseError, Address};
+use crate::{
+    codeid::strip_quotes,
+    error::ChainParseError,
+    events::{collect_events, TxEvent, WasmEvent},
+    Address, HasAddress,
+};
 
 /// Extension trait to add some helper methods to [TxResponse].
 pub trait TxResponseExt {
@@ -19,6 +26,18 @@ pub trait TxResponseExt {
 
     /// Return the first code ID stored in this transaction
     fn parse_first_stored_code_id(&self) -> Result<u64, ChainParseError>;
+
+    /// Return the IDs of any governance proposals submitted in this transaction
+    fn parse_proposal_ids(&self) -> Result<Vec<u64>, ChainParseError>;
+
+    /// Return the first governance proposal ID submitted in this transaction
+    fn parse_first_proposal_id(&self) -> Result<u64, ChainParseError>;
+
+    /// All events in this transaction, see [crate::events].
+    fn tx_events(&self) -> Vec<TxEvent>;
+
+    /// Attributes of each `wasm` event emitted by the given contract.
+    fn wasm_events_for(&self, contract: impl HasAddress) -> Vec<BTreeMap<String, String>>;
 }
 
 impl TxResponseExt for TxResponse {
@@ -101,4 +120,53 @@ impl TxResponseExt for TxResponse {
                 txhash: self.txhash.clone(),
             })
     }
+
+    fn parse_proposal_ids(&self) -> Result<Vec<u64>, ChainParseError> {
+        let mut res = vec![];
+
+        for log in &self.logs {
+            for event in &log.events {
+                if event.r#type == "submit_proposal" {
+                    for attr in &event.attributes {
+                        if attr.key == "proposal_id" {
+                            let value = strip_quotes(&attr.value);
+                            let value = value.parse::<u64>().map_err(|source| {
+                                ChainParseError::InvalidProposalId {
+                                    proposal_id: value.to_owned(),
+                                    txhash: self.txhash.clone(),
+                                    source,
+                                }
+                            })?;
+                            res.push(value);
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(res)
+    }
+
+    fn parse_first_proposal_id(&self) -> Result<u64, ChainParseError> {
+        self.parse_proposal_ids()?
+            .into_iter()
+            .next()
+            .ok_or_else(|| ChainParseError::NoProposalIdFound {
+                txhash: self.txhash.clone(),
+            })
+    }
+
+    fn tx_events(&self) -> Vec<TxEvent> {
+        collect_events(self)
+    }
+
+    fn wasm_events_for(&self, contract: impl HasAddress) -> Vec<BTreeMap<String, String>> {
+        let contract = contract.get_address();
+        self.tx_events()
+            .iter()
+            .filter_map(WasmEvent::from_event)
+            .filter(|event| event.contract == contract)
+            .map(|event| event.attributes)
+            .collect()
+    }
 }
@@ -0,0 +1,199 @@
+//! Fee allowances, letting one account pay transaction fees for another.
+//!
+//! Once granted, the grantee uses the allowance by setting the granter as the
+//! fee granter on its transactions.
+
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use cosmos_sdk_proto::{
+    cosmos::{
+        base::v1beta1::Coin,
+        feegrant::v1beta1::{
+            AllowedMsgAllowance, BasicAllowance, Grant, MsgGrantAllowance, MsgRevokeAllowance,
+            PeriodicAllowance, QueryAllowanceRequest, QueryAllowancesRequest,
+        },
+    },
+    Any,
+};
+use futures_util::TryStreamExt;
+use prost::Message;
+
+use crate::{
+    error::Action, messages::datetime_to_timestamp, Address, Cosmos, HasAddress, TxMessage,
+};
+
+/// A fee allowance to grant.
+#[derive(Clone, Debug)]
+pub enum FeeAllowance {
+    /// A one-time allowance of up to the spend limit.
+    Basic {
+        /// Maximum fees that may be spent. Empty for no limit.
+        spend_limit: Vec<Coin>,
+        /// When the allowance expires, if ever.
+        expiration: Option<DateTime<Utc>>,
+    },
+    /// An allowance that resets its limit each period.
+    Periodic {
+        /// Maximum fees that may be spent over the lifetime of the allowance. Empty for no limit.
+        spend_limit: Vec<Coin>,
+        /// When the allowance expires, if ever.
+        expiration: Option<DateTime<Utc>>,
+        /// Length of each period.
+        period: Duration,
+        /// Maximum fees that may be spent in each period.
+        period_spend_limit: Vec<Coin>,
+    },
+    /// Restrict another allowance to only the given message types.
+    AllowedMessages {
+        /// The allowance being restricted
+        allowance: Box<FeeAllowance>,
+        /// Type URLs of the messages which may use this allowance, e.g. `/cosmwasm.wasm.v1.MsgExecuteContract`.
+        allowed_messages: Vec<String>,
+    },
+}
+
+fn basic_allowance(spend_limit: Vec<Coin>, expiration: Option<DateTime<Utc>>) -> BasicAllowance {
+    BasicAllowance {
+        spend_limit,
+        expiration: expiration.map(datetime_to_timestamp),
+    }
+}
+
+impl FeeAllowance {
+    /// Convert into the protobuf representation used in [MsgGrantAllowance].
+    pub fn into_any(self) -> Any {
+        match self {
+            FeeAllowance::Basic {
+                spend_limit,
+                expiration,
+            } => Any {
+                type_url: "/cosmos.feegrant.v1beta1.BasicAllowance".to_owned(),
+                value: basic_allowance(spend_limit, expiration).encode_to_vec(),
+            },
+            FeeAllowance::Periodic {
+                spend_limit,
+                expiration,
+                period,
+                period_spend_limit,
+            } => Any {
+                type_url: "/cosmos.feegrant.v1beta1.PeriodicAllowance".to_owned(),
+                value: PeriodicAllowance {
+                    basic: Some(basic_allowance(spend_limit, expiration)),
+                    period: Some(prost_types::Duration {
+                        seconds: period.as_secs().try_into().unwrap_or(i64::MAX),
+                        nanos: period
+                            .subsec_nanos()
+                            .try_into()
+                            .expect("Duration's nanos is too large"),
+                    }),
+                    // Same as the SDK CLI: the first period starts now.
+                    period_can_spend: period_spend_limit.clone(),
+                    period_spend_limit,
+                    period_reset: chrono::Duration::from_std(period)
+                        .ok()
+                        .and_then(|period| Utc::now().checked_add_signed(period))
+                        .map(datetime_to_timestamp),
+                }
+                .encode_to_vec(),
+            },
+            FeeAllowance::AllowedMessages {
+                allowance,
+                allowed_messages,
+            } => Any {
+                type_url: "/cosmos.feegrant.v1beta1.AllowedMsgAllowance".to_owned(),
+                value: AllowedMsgAllowance {
+                    allowance: Some(allowance.into_any()),
+                    allowed_messages,
+                }
+                .encode_to_vec(),
+            },
+        }
+    }
+}
+
+/// A message granting a fee allowance to another address.
+pub struct MsgGrantAllowanceHelper {
+    /// Address paying the fees
+    pub granter: Address,
+    /// Address allowed to use the granter's funds for fees
+    pub grantee: Address,
+    /// Limits on the allowance
+    pub allowance: FeeAllowance,
+}
+
+impl From<MsgGrantAllowanceHelper> for TxMessage {
+    fn from(
+        MsgGrantAllowanceHelper {
+            granter,
+            grantee,
+            allowance,
+        }: MsgGrantAllowanceHelper,
+    ) -> Self {
+        let desc = format!("{granter} grants {grantee} fee allowance {allowance:?}");
+        TxMessage::new(
+            "/cosmos.feegrant.v1beta1.MsgGrantAllowance",
+            MsgGrantAllowance {
+                granter: granter.get_address_string(),
+                grantee: grantee.get_address_string(),
+                allowance: Some(allowance.into_any()),
+            }
+            .encode_to_vec(),
+            desc,
+        )
+    }
+}
+
+impl From<MsgRevokeAllowance> for TxMessage {
+    fn from(msg: MsgRevokeAllowance) -> Self {
+        TxMessage::new(
+            "/cosmos.feegrant.v1beta1.MsgRevokeAllowance",
+            msg.encode_to_vec(),
+            format!("{} revokes fee allowance for {}", msg.granter, msg.grantee),
+        )
+    }
+}
+
+impl Cosmos {
+    /// Get the fee allowance from the granter to the grantee.
+    pub async fn query_allowance(
+        &self,
+        granter: impl HasAddress,
+        grantee: impl HasAddress,
+    ) -> Result<Grant, crate::Error> {
+        let action = Action::QueryFeeAllowance {
+            granter: granter.get_address(),
+            grantee: grantee.get_address(),
+        };
+        let req = QueryAllowanceRequest {
+            granter: granter.get_address_string(),
+            grantee: grantee.get_address_string(),
+        };
+        self.perform_query(req, action.clone(), true)
+            .await?
+            .into_inner()
+            .allowance
+            .ok_or_else(|| crate::Error::InvalidChainResponse {
+                message: "No allowance returned".to_owned(),
+                action,
+            })
+    }
+
+    /// Get all fee allowances granted to the given address.
+    pub async fn query_allowances(
+        &self,
+        grantee: impl HasAddress,
+    ) -> Result<Vec<Grant>, crate::Error> {
+        let grantee = grantee.get_address();
+        self.paginate(
+            Action::QueryFeeAllowances(grantee),
+            move |pagination| QueryAllowancesRequest {
+                grantee: grantee.get_address_string(),
+                pagination,
+            },
+            |res| (res.allowances, res.pagination),
+        )
+        .try_collect()
+        .await
+    }
+}
@@ -94,7 +94,11 @@ impl GasMultiplier {
             }
             Err(e) => {
                 if let Error::TransactionFailed {
-                    code: crate::error::CosmosSdkError::OutOfGas,
+                    code:
+                        crate::error::ChainError {
+                            kind: crate::error::CosmosSdkError::OutOfGas,
+                            ..
+                        },
                     ..
                 } = e
                 {
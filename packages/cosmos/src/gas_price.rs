@@ -1,10 +1,156 @@
-//! Gas price query for osmosis mainnet from lcd endpoint /osmosis/txfees/v1beta1/cur_eip_base_fee
+//! Gas price sources: static prices, the Osmosis EIP base fee from the LCD
+//! endpoint /osmosis/txfees/v1beta1/cur_eip_base_fee, and refreshable
+//! [GasPriceSource]s.
 
-use std::{num::ParseFloatError, sync::Arc, time::Instant};
+use std::{
+    num::ParseFloatError,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 use parking_lot::RwLock;
 
-use crate::{cosmos_builder::OsmosisGasParams, error::BuilderError, CosmosBuilder};
+use crate::{
+    client::WeakCosmos, cosmos_builder::OsmosisGasParams, error::BuilderError, Cosmos,
+    CosmosBuilder,
+};
+
+/// A source of gas prices which can change while an application runs.
+///
+/// Configured with [CosmosBuilder::set_gas_price_source]. Prices are
+/// reloaded in the background on an interval, and before broadcasting if
+/// they have never been loaded. Until the first successful load, the
+/// previously configured gas price is used.
+#[tonic::async_trait]
+pub trait GasPriceSource: std::fmt::Debug + Send + Sync + 'static {
+    /// Load the current low and high gas prices.
+    async fn load(&self, cosmos: &Cosmos) -> Result<(f64, f64), crate::Error>;
+}
+
+/// Gas prices which never change.
+#[derive(Clone, Copy, Debug)]
+pub struct StaticGasPrice {
+    /// Low gas price
+    pub low: f64,
+    /// High gas price
+    pub high: f64,
+}
+
+#[tonic::async_trait]
+impl GasPriceSource for StaticGasPrice {
+    async fn load(&self, _cosmos: &Cosmos) -> Result<(f64, f64), crate::Error> {
+        Ok((self.low, self.high))
+    }
+}
+
+/// Gas price loaded from a JSON document, such as Sei's `gas.json`.
+#[derive(Clone, Debug)]
+pub struct UrlGasPrice {
+    url: String,
+    pointer: String,
+    high_multiplier: f64,
+    client: reqwest::Client,
+}
+
+impl UrlGasPrice {
+    /// Load the low gas price from the value at a JSON pointer, e.g. `/pacific-1/min_gas_price`.
+    ///
+    /// The value may be a number or a string. The high gas price is twice the low price.
+    /// Requests go through the proxy from the environment, see [Self::with_client]
+    /// to use a different client.
+    pub fn new(url: impl Into<String>, pointer: impl Into<String>) -> Self {
+        UrlGasPrice {
+            url: url.into(),
+            pointer: pointer.into(),
+            high_multiplier: 2.0,
+            client: crate::client::env_http_client().unwrap_or_default(),
+        }
+    }
+
+    /// Use an existing [reqwest::Client] to load the document.
+    pub fn with_client(mut self, client: reqwest::Client) -> Self {
+        self.client = client;
+        self
+    }
+
+    /// Set the multiplier used to derive the high gas price from the low price.
+    pub fn with_high_multiplier(mut self, high_multiplier: f64) -> Self {
+        self.high_multiplier = high_multiplier;
+        self
+    }
+
+    /// Load the low and high gas prices.
+    pub async fn fetch(&self) -> Result<(f64, f64), String> {
+        let value = async {
+            self.client
+                .get(&self.url)
+                .send()
+                .await?
+                .error_for_status()?
+                .json::<serde_json::Value>()
+                .await
+        }
+        .await
+        .map_err(|e| format!("Unable to download {}: {e}", self.url))?;
+        let low = match value.pointer(&self.pointer) {
+            Some(serde_json::Value::Number(n)) => n.as_f64(),
+            Some(serde_json::Value::String(s)) => s.parse().ok(),
+            _ => None,
+        }
+        .ok_or_else(|| format!("No gas price at {} in {}", self.pointer, self.url))?;
+        Ok((low, low * self.high_multiplier))
+    }
+}
+
+#[tonic::async_trait]
+impl GasPriceSource for UrlGasPrice {
+    async fn load(&self, _cosmos: &Cosmos) -> Result<(f64, f64), crate::Error> {
+        self.fetch()
+            .await
+            .map_err(|message| crate::Error::LoadGasPrice { message })
+    }
+}
+
+/// Gas price queried from the chain's minimum gas price for the gas coin.
+///
+/// See [Cosmos::min_gas_prices]. Nodes without a fee market often don't
+/// set a minimum, in which case loading fails and the previous price is kept.
+#[derive(Clone, Copy, Debug)]
+pub struct ChainGasPrice {
+    /// Multiplier applied to the node's minimum for the low gas price
+    pub low_multiplier: f64,
+    /// Multiplier applied to the node's minimum for the high gas price
+    pub high_multiplier: f64,
+}
+
+impl Default for ChainGasPrice {
+    fn default() -> Self {
+        ChainGasPrice {
+            low_multiplier: 1.0,
+            high_multiplier: 2.0,
+        }
+    }
+}
+
+#[tonic::async_trait]
+impl GasPriceSource for ChainGasPrice {
+    async fn load(&self, cosmos: &Cosmos) -> Result<(f64, f64), crate::Error> {
+        let gas_coin = cosmos.get_cosmos_builder().gas_coin();
+        let minimum = cosmos
+            .min_gas_prices()
+            .await?
+            .into_iter()
+            .find_map(|price| (price.denom == gas_coin).then_some(price.price))
+            .filter(|price| *price > 0.0)
+            .ok_or_else(|| crate::Error::LoadGasPrice {
+                message: format!("Node has no minimum gas price for {gas_coin}"),
+            })?;
+        Ok((
+            minimum * self.low_multiplier,
+            minimum * self.high_multiplier,
+        ))
+    }
+}
 
 /// Mechanism used for determining the gas price
 #[derive(Clone, Debug)]
@@ -24,9 +170,17 @@ enum GasPriceMethodInner {
         low: f64,
         high: f64,
     },
+    /// Reloaded from a [GasPriceSource], using the fallback until the first load.
+    Source {
+        source: Arc<dyn GasPriceSource>,
+        refresh_interval: Duration,
+        fallback: (f64, f64),
+        loaded: Arc<RwLock<Option<(f64, f64)>>>,
+    },
     /// Reloads from EIP values regularly, starting with the values below.
-    OsmosisMainnet {
+    Osmosis {
         client: reqwest::Client,
+        lcd_url: Arc<String>,
         price: Arc<RwLock<OsmosisGasPrice>>,
         params: OsmosisGasParams,
     },
@@ -46,8 +200,19 @@ impl GasPriceMethod {
                 high: *high,
                 base: *low,
             },
-            GasPriceMethodInner::OsmosisMainnet {
+            GasPriceMethodInner::Source {
+                fallback, loaded, ..
+            } => {
+                let (low, high) = loaded.read().unwrap_or(*fallback);
+                CurrentGasPrice {
+                    low,
+                    high,
+                    base: low,
+                }
+            }
+            GasPriceMethodInner::Osmosis {
                 client,
+                lcd_url,
                 price,
                 params:
                     OsmosisGasParams {
@@ -85,7 +250,7 @@ impl GasPriceMethod {
                             builder.get_osmosis_gas_price_too_old_seconds(),
                         );
                         if should_trigger {
-                            guard.last_triggered = now;
+                            guard.last_triggered = Some(now);
                         }
                         (guard.reported, should_trigger)
                     } else {
@@ -94,9 +259,10 @@ impl GasPriceMethod {
                 };
                 if should_trigger {
                     let client = client.clone();
+                    let lcd_url = lcd_url.clone();
                     let price = price.clone();
                     tokio::task::spawn(async move {
-                        let reported = load_osmosis_gas_base_fee(&client).await?;
+                        let reported = load_osmosis_gas_base_fee_from(&client, &lcd_url).await?;
                         let mut guard = price.write();
                         guard.reported = reported;
                         Ok::<_, LoadOsmosisGasPriceError>(())
@@ -127,26 +293,155 @@ impl GasPriceMethod {
             }
         };
         let price = OsmosisGasPrice {
-            last_triggered: Instant::now(),
+            last_triggered: Some(Instant::now()),
             reported,
         };
         Ok(GasPriceMethod {
-            inner: GasPriceMethodInner::OsmosisMainnet {
+            inner: GasPriceMethodInner::Osmosis {
                 client: client.clone(),
+                lcd_url: Arc::new(OSMOSIS_MAINNET_LCD.to_owned()),
                 price: Arc::new(RwLock::new(price)),
                 params,
             },
         })
     }
 
+    /// Use the EIP base fee from the given LCD endpoint.
+    ///
+    /// No query is made until the first transaction is broadcast, see [Self::refresh].
+    pub(crate) fn new_osmosis(
+        client: reqwest::Client,
+        lcd_url: String,
+        params: OsmosisGasParams,
+    ) -> Self {
+        GasPriceMethod {
+            inner: GasPriceMethodInner::Osmosis {
+                client,
+                lcd_url: Arc::new(lcd_url),
+                price: Arc::new(RwLock::new(OsmosisGasPrice {
+                    reported: 0.0025,
+                    last_triggered: None,
+                })),
+                params,
+            },
+        }
+    }
+
+    pub(crate) fn new_source(
+        source: Arc<dyn GasPriceSource>,
+        refresh_interval: Duration,
+        fallback: (f64, f64),
+    ) -> Self {
+        GasPriceMethod {
+            inner: GasPriceMethodInner::Source {
+                source,
+                refresh_interval,
+                fallback,
+                loaded: Arc::new(RwLock::new(None)),
+            },
+        }
+    }
+
+    /// Reload a dynamic gas price if it's too old or was never loaded, waiting for the result.
+    ///
+    /// Called before broadcasting so that transactions don't use a stale
+    /// price. Failures are logged and the previous value is kept.
+    pub(crate) async fn refresh(&self, cosmos: &Cosmos) {
+        let (client, lcd_url, price) = match &self.inner {
+            GasPriceMethodInner::Static { .. } => return,
+            GasPriceMethodInner::Source { source, loaded, .. } => {
+                if loaded.read().is_none() {
+                    load_from_source(cosmos, source.as_ref(), loaded).await;
+                }
+                return;
+            }
+            GasPriceMethodInner::Osmosis {
+                client,
+                lcd_url,
+                price,
+                params: _,
+            } => (client, lcd_url, price),
+        };
+        let builder = cosmos.get_cosmos_builder();
+        let now = Instant::now();
+        if !osmosis_too_old(
+            price.read().last_triggered,
+            now,
+            builder.get_osmosis_gas_price_too_old_seconds(),
+        ) {
+            return;
+        }
+        match load_osmosis_gas_base_fee_from(client, lcd_url).await {
+            Ok(reported) => {
+                let mut guard = price.write();
+                guard.reported = reported;
+                guard.last_triggered = Some(now);
+            }
+            Err(e) => tracing::warn!("Unable to refresh Osmosis gas price from {lcd_url}: {e}"),
+        }
+    }
+
     pub(crate) fn new_static(low: f64, high: f64) -> GasPriceMethod {
         GasPriceMethod {
             inner: GasPriceMethodInner::Static { low, high },
         }
     }
+
+    /// Start reloading a [GasPriceSource] in the background.
+    ///
+    /// Does nothing for other methods, or outside of a Tokio runtime.
+    pub(crate) fn launch_refresh(&self, cosmos: &Cosmos) {
+        let GasPriceMethodInner::Source {
+            source,
+            refresh_interval,
+            loaded,
+            ..
+        } = &self.inner
+        else {
+            return;
+        };
+        let Ok(handle) = tokio::runtime::Handle::try_current() else {
+            return;
+        };
+        handle.spawn(refresh_source_loop(
+            WeakCosmos::from(cosmos),
+            source.clone(),
+            *refresh_interval,
+            loaded.clone(),
+        ));
+    }
+}
+
+async fn load_from_source(
+    cosmos: &Cosmos,
+    source: &dyn GasPriceSource,
+    loaded: &RwLock<Option<(f64, f64)>>,
+) {
+    match source.load(cosmos).await {
+        Ok(price) => *loaded.write() = Some(price),
+        Err(e) => tracing::warn!("Unable to load gas price from {source:?}: {e}"),
+    }
 }
 
-fn osmosis_too_old(last_triggered: Instant, now: Instant, too_old_seconds: u64) -> bool {
+async fn refresh_source_loop(
+    weak: WeakCosmos,
+    source: Arc<dyn GasPriceSource>,
+    refresh_interval: Duration,
+    loaded: Arc<RwLock<Option<(f64, f64)>>>,
+) {
+    loop {
+        match weak.upgrade() {
+            Some(cosmos) => load_from_source(&cosmos, source.as_ref(), &loaded).await,
+            None => break,
+        }
+        tokio::time::sleep(refresh_interval).await;
+    }
+}
+
+fn osmosis_too_old(last_triggered: Option<Instant>, now: Instant, too_old_seconds: u64) -> bool {
+    let Some(last_triggered) = last_triggered else {
+        return true;
+    };
     match now.checked_duration_since(last_triggered) {
         Some(age) => age.as_secs() > too_old_seconds,
         None => {
@@ -159,19 +454,33 @@ fn osmosis_too_old(last_triggered: Instant, now: Instant, too_old_seconds: u64)
 #[derive(Debug, Clone, Copy)]
 struct OsmosisGasPrice {
     reported: f64,
-    last_triggered: Instant,
+    /// [None] if the base fee has never been loaded
+    last_triggered: Option<Instant>,
 }
 
+const OSMOSIS_MAINNET_LCD: &str = "https://lcd.osmosis.zone";
+
 /// Loads current eip base fee from a v1beta1 lcd endpoint
 pub async fn load_osmosis_gas_base_fee(
     client: &reqwest::Client,
+) -> Result<f64, LoadOsmosisGasPriceError> {
+    load_osmosis_gas_base_fee_from(client, OSMOSIS_MAINNET_LCD).await
+}
+
+/// Loads current eip base fee from the given LCD endpoint, e.g. for Osmosis testnet
+pub async fn load_osmosis_gas_base_fee_from(
+    client: &reqwest::Client,
+    lcd_url: &str,
 ) -> Result<f64, LoadOsmosisGasPriceError> {
     #[derive(serde::Deserialize)]
     struct BaseFee {
         base_fee: String,
     }
     let BaseFee { base_fee } = client
-        .get("https://lcd.osmosis.zone/osmosis/txfees/v1beta1/cur_eip_base_fee")
+        .get(format!(
+            "{}/osmosis/txfees/v1beta1/cur_eip_base_fee",
+            lcd_url.trim_end_matches('/')
+        ))
         .send()
         .await?
         .error_for_status()?
@@ -196,3 +505,36 @@ pub enum LoadOsmosisGasPriceError {
     /// Parse error
     Parse(#[from] ParseFloatError),
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::AddressHrp;
+
+    #[test]
+    fn source_uses_fallback_until_loaded() {
+        let builder = CosmosBuilder::new(
+            "osmosis-1",
+            "uosmo",
+            AddressHrp::from_static("osmo"),
+            "http://localhost:9090",
+        );
+        let method = GasPriceMethod::new_source(
+            Arc::new(StaticGasPrice {
+                low: 0.5,
+                high: 0.6,
+            }),
+            Duration::from_secs(60),
+            (0.1, 0.2),
+        );
+        let current = method.current(&builder, 1.0);
+        assert_eq!((current.low, current.high), (0.1, 0.2));
+
+        let GasPriceMethodInner::Source { loaded, .. } = &method.inner else {
+            unreachable!()
+        };
+        *loaded.write() = Some((0.5, 0.6));
+        let current = method.current(&builder, 1.0);
+        assert_eq!((current.low, current.high, current.base), (0.5, 0.6, 0.5));
+    }
+}
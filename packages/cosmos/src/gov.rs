@@ -0,0 +1,457 @@
+//! Helpers for governance proposals, votes and deposits.
+
+use cosmos_sdk_proto::{
+    cosmos::{
+        base::{abci::v1beta1::TxResponse, v1beta1::Coin},
+        distribution::v1beta1::CommunityPoolSpendProposal,
+        gov::v1beta1::{
+            MsgDeposit, MsgSubmitProposal, MsgVote, MsgVoteWeighted, Proposal, ProposalStatus,
+            QueryProposalRequest, QueryProposalsRequest, QueryTallyResultRequest,
+            QueryVotesRequest, TallyResult, TextProposal, Vote, VoteOption,
+        },
+        params::v1beta1::{ParamChange, ParameterChangeProposal},
+        upgrade::v1beta1::{CancelSoftwareUpgradeProposal, Plan, SoftwareUpgradeProposal},
+    },
+    cosmwasm::wasm::v1::{MigrateContractProposal, StoreCodeProposal, SudoContractProposal},
+    Any,
+};
+use futures_util::TryStreamExt;
+use prost::Message;
+
+use crate::{
+    address::RawAddress, error::Action, Address, AddressHrp, Cosmos, HasAddress, TxBuilder,
+    TxMessage, TxResponseExt, Wallet,
+};
+
+/// The content of a governance proposal.
+///
+/// Each constructor produces the legacy content type used directly by
+/// `gov/v1beta1`. For chains using `gov/v1`, wrap the content with
+/// [ProposalContent::into_v1_message].
+#[derive(Clone, Debug)]
+pub struct ProposalContent(Any);
+
+/// `MsgExecLegacyContent` from `gov/v1`, not included in our protobuf definitions.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+struct MsgExecLegacyContent {
+    #[prost(message, optional, tag = "1")]
+    content: Option<Any>,
+    #[prost(string, tag = "2")]
+    authority: String,
+}
+
+impl ProposalContent {
+    fn new(type_url: &str, msg: impl Message) -> Self {
+        ProposalContent(Any {
+            type_url: type_url.to_owned(),
+            value: msg.encode_to_vec(),
+        })
+    }
+
+    /// A text-only proposal, with no on-chain effect.
+    pub fn text(title: impl Into<String>, description: impl Into<String>) -> Self {
+        Self::new(
+            "/cosmos.gov.v1beta1.TextProposal",
+            TextProposal {
+                title: title.into(),
+                description: description.into(),
+            },
+        )
+    }
+
+    /// Change one or more module parameters.
+    pub fn parameter_change(
+        title: impl Into<String>,
+        description: impl Into<String>,
+        changes: Vec<ParamChange>,
+    ) -> Self {
+        Self::new(
+            "/cosmos.params.v1beta1.ParameterChangeProposal",
+            ParameterChangeProposal {
+                title: title.into(),
+                description: description.into(),
+                changes,
+            },
+        )
+    }
+
+    /// Spend funds from the community pool.
+    pub fn community_pool_spend(
+        title: impl Into<String>,
+        description: impl Into<String>,
+        recipient: Address,
+        amount: Vec<Coin>,
+    ) -> Self {
+        Self::new(
+            "/cosmos.distribution.v1beta1.CommunityPoolSpendProposal",
+            CommunityPoolSpendProposal {
+                title: title.into(),
+                description: description.into(),
+                recipient: recipient.get_address_string(),
+                amount,
+            },
+        )
+    }
+
+    /// Schedule a software upgrade at the given block height.
+    pub fn software_upgrade(
+        title: impl Into<String>,
+        description: impl Into<String>,
+        name: impl Into<String>,
+        height: i64,
+        info: impl Into<String>,
+    ) -> Self {
+        // Time and upgraded client state are deprecated but must still be provided.
+        #[allow(deprecated)]
+        let plan = Plan {
+            name: name.into(),
+            time: None,
+            height,
+            info: info.into(),
+            upgraded_client_state: None,
+        };
+        Self::new(
+            "/cosmos.upgrade.v1beta1.SoftwareUpgradeProposal",
+            SoftwareUpgradeProposal {
+                title: title.into(),
+                description: description.into(),
+                plan: Some(plan),
+            },
+        )
+    }
+
+    /// Cancel a previously scheduled software upgrade.
+    pub fn cancel_software_upgrade(
+        title: impl Into<String>,
+        description: impl Into<String>,
+    ) -> Self {
+        Self::new(
+            "/cosmos.upgrade.v1beta1.CancelSoftwareUpgradeProposal",
+            CancelSoftwareUpgradeProposal {
+                title: title.into(),
+                description: description.into(),
+            },
+        )
+    }
+
+    /// Call a contract's sudo entry point.
+    pub fn sudo_contract(
+        title: impl Into<String>,
+        description: impl Into<String>,
+        contract: Address,
+        msg: impl serde::Serialize,
+    ) -> Result<Self, crate::Error> {
+        Ok(Self::new(
+            "/cosmwasm.wasm.v1.SudoContractProposal",
+            SudoContractProposal {
+                title: title.into(),
+                description: description.into(),
+                contract: contract.get_address_string(),
+                msg: serde_json::to_vec(&msg)?,
+            },
+        ))
+    }
+
+    /// Migrate a contract to a new code ID.
+    pub fn migrate_contract(
+        title: impl Into<String>,
+        description: impl Into<String>,
+        contract: Address,
+        code_id: u64,
+        msg: impl serde::Serialize,
+    ) -> Result<Self, crate::Error> {
+        Ok(Self::new(
+            "/cosmwasm.wasm.v1.MigrateContractProposal",
+            MigrateContractProposal {
+                title: title.into(),
+                description: description.into(),
+                contract: contract.get_address_string(),
+                code_id,
+                msg: serde_json::to_vec(&msg)?,
+            },
+        ))
+    }
+
+    /// Upload wasm code, for chains where uploads are permissioned.
+    ///
+    /// The `run_as` address is recorded as the creator of the code.
+    pub fn store_code(
+        title: impl Into<String>,
+        description: impl Into<String>,
+        run_as: Address,
+        wasm_byte_code: Vec<u8>,
+    ) -> Self {
+        Self::new(
+            "/cosmwasm.wasm.v1.StoreCodeProposal",
+            StoreCodeProposal {
+                title: title.into(),
+                description: description.into(),
+                run_as: run_as.get_address_string(),
+                wasm_byte_code,
+                instantiate_permission: None,
+            },
+        )
+    }
+
+    /// The type URL of the underlying content.
+    pub fn type_url(&self) -> &str {
+        &self.0.type_url
+    }
+
+    /// Get the content for use in a `gov/v1beta1` proposal.
+    pub fn into_any(self) -> Any {
+        self.0
+    }
+
+    /// Wrap the content in a `MsgExecLegacyContent`, for use as a message in a `gov/v1` proposal.
+    ///
+    /// The authority is normally the gov module account, see [gov_module_address].
+    pub fn into_v1_message(self, authority: Address) -> Any {
+        Any {
+            type_url: "/cosmos.gov.v1.MsgExecLegacyContent".to_owned(),
+            value: MsgExecLegacyContent {
+                content: Some(self.0),
+                authority: authority.get_address_string(),
+            }
+            .encode_to_vec(),
+        }
+    }
+}
+
+/// A message submitting a proposal to `gov/v1beta1`.
+pub struct MsgSubmitProposalHelper {
+    /// Account submitting the proposal
+    pub proposer: Address,
+    /// Proposal content
+    pub content: ProposalContent,
+    /// Deposit paid by the proposer
+    pub initial_deposit: Vec<Coin>,
+}
+
+impl From<MsgSubmitProposalHelper> for TxMessage {
+    fn from(
+        MsgSubmitProposalHelper {
+            proposer,
+            content,
+            initial_deposit,
+        }: MsgSubmitProposalHelper,
+    ) -> Self {
+        let desc = format!(
+            "{proposer} submitting {} proposal with deposit {initial_deposit:?}",
+            content.type_url()
+        );
+        TxMessage::new(
+            "/cosmos.gov.v1beta1.MsgSubmitProposal",
+            MsgSubmitProposal {
+                content: Some(content.into_any()),
+                initial_deposit,
+                proposer: proposer.get_address_string(),
+            }
+            .encode_to_vec(),
+            desc,
+        )
+    }
+}
+
+impl From<ProposalContent> for Any {
+    fn from(content: ProposalContent) -> Self {
+        content.into_any()
+    }
+}
+
+fn vote_option_name(option: i32) -> &'static str {
+    VoteOption::from_i32(option).map_or("unknown option", |option| option.as_str_name())
+}
+
+impl From<MsgVote> for TxMessage {
+    fn from(msg: MsgVote) -> Self {
+        TxMessage::new(
+            "/cosmos.gov.v1beta1.MsgVote",
+            msg.encode_to_vec(),
+            format!(
+                "{} voting {} on proposal {}",
+                msg.voter,
+                vote_option_name(msg.option),
+                msg.proposal_id
+            ),
+        )
+    }
+}
+
+impl From<MsgVoteWeighted> for TxMessage {
+    fn from(msg: MsgVoteWeighted) -> Self {
+        let options = msg
+            .options
+            .iter()
+            .map(|option| format!("{} {}", vote_option_name(option.option), option.weight))
+            .collect::<Vec<_>>();
+        TxMessage::new(
+            "/cosmos.gov.v1beta1.MsgVoteWeighted",
+            msg.encode_to_vec(),
+            format!(
+                "{} voting {options:?} on proposal {}",
+                msg.voter, msg.proposal_id
+            ),
+        )
+    }
+}
+
+impl From<MsgDeposit> for TxMessage {
+    fn from(msg: MsgDeposit) -> Self {
+        TxMessage::new(
+            "/cosmos.gov.v1beta1.MsgDeposit",
+            msg.encode_to_vec(),
+            format!(
+                "{} depositing {:?} on proposal {}",
+                msg.depositor, msg.amount, msg.proposal_id
+            ),
+        )
+    }
+}
+
+impl Cosmos {
+    /// Submit a governance proposal, returning the new proposal's ID.
+    pub async fn submit_proposal(
+        &self,
+        wallet: &Wallet,
+        content: ProposalContent,
+        initial_deposit: Vec<Coin>,
+    ) -> Result<(TxResponse, u64), crate::Error> {
+        let mut txbuilder = TxBuilder::default();
+        txbuilder.add_message(MsgSubmitProposalHelper {
+            proposer: wallet.get_address(),
+            content,
+            initial_deposit,
+        });
+        let res = txbuilder.sign_and_broadcast(self, wallet).await?;
+        let proposal_id =
+            res.parse_first_proposal_id()
+                .map_err(|source| crate::Error::ChainParse {
+                    source: source.into(),
+                    action: Action::Broadcast(txbuilder),
+                })?;
+        Ok((res, proposal_id))
+    }
+
+    /// Propose uploading wasm code, for chains with permissioned uploads such as Sei mainnet.
+    ///
+    /// The code ID is only assigned once the proposal passes. The proposal
+    /// description includes the code's checksum so voters can verify it.
+    pub async fn store_code_via_proposal(
+        &self,
+        wallet: &Wallet,
+        wasm_byte_code: Vec<u8>,
+        initial_deposit: Vec<Coin>,
+    ) -> Result<(TxResponse, u64), crate::Error> {
+        let checksum = {
+            use sha2::{Digest, Sha256};
+            hex::encode(Sha256::digest(&wasm_byte_code))
+        };
+        let content = ProposalContent::store_code(
+            "Store code",
+            format!("Upload wasm code with checksum {checksum}"),
+            wallet.get_address(),
+            wasm_byte_code,
+        );
+        self.submit_proposal(wallet, content, initial_deposit).await
+    }
+
+    /// Get a single governance proposal.
+    pub async fn query_proposal(&self, proposal_id: u64) -> Result<Proposal, crate::Error> {
+        let action = Action::QueryProposal(proposal_id);
+        self.perform_query(QueryProposalRequest { proposal_id }, action.clone(), true)
+            .await?
+            .into_inner()
+            .proposal
+            .ok_or_else(|| crate::Error::InvalidChainResponse {
+                message: "No proposal returned".to_owned(),
+                action,
+            })
+    }
+
+    /// Get all governance proposals, optionally filtered by status.
+    ///
+    /// Use [ProposalStatus::Unspecified] to get proposals in any status.
+    pub async fn query_proposals(
+        &self,
+        status: ProposalStatus,
+    ) -> Result<Vec<Proposal>, crate::Error> {
+        let proposal_status = status as i32;
+        self.paginate(
+            Action::QueryProposals,
+            move |pagination| QueryProposalsRequest {
+                proposal_status,
+                voter: String::new(),
+                depositor: String::new(),
+                pagination,
+            },
+            |res| (res.proposals, res.pagination),
+        )
+        .try_collect()
+        .await
+    }
+
+    /// Get the current tally of votes for a proposal.
+    pub async fn query_proposal_tally(
+        &self,
+        proposal_id: u64,
+    ) -> Result<TallyResult, crate::Error> {
+        let action = Action::QueryProposalTally(proposal_id);
+        self.perform_query(
+            QueryTallyResultRequest { proposal_id },
+            action.clone(),
+            true,
+        )
+        .await?
+        .into_inner()
+        .tally
+        .ok_or_else(|| crate::Error::InvalidChainResponse {
+            message: "No tally returned".to_owned(),
+            action,
+        })
+    }
+
+    /// Get all votes cast on a proposal.
+    ///
+    /// Note that most chains remove votes once the voting period ends.
+    pub async fn query_votes(&self, proposal_id: u64) -> Result<Vec<Vote>, crate::Error> {
+        self.paginate(
+            Action::QueryVotes(proposal_id),
+            move |pagination| QueryVotesRequest {
+                proposal_id,
+                pagination,
+            },
+            |res| (res.votes, res.pagination),
+        )
+        .try_collect()
+        .await
+    }
+}
+
+/// Derive the address of a Cosmos SDK module account, e.g. `gov` or `distribution`.
+pub fn module_address(hrp: AddressHrp, module: &str) -> Address {
+    use sha2::{Digest, Sha256};
+    let hash = Sha256::digest(module.as_bytes());
+    let mut raw = [0u8; 20];
+    raw.copy_from_slice(&hash[..20]);
+    RawAddress::from(raw).with_hrp(hrp)
+}
+
+/// The address of the gov module account, the usual authority for `gov/v1` messages.
+pub fn gov_module_address(hrp: AddressHrp) -> Address {
+    module_address(hrp, "gov")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gov_module_address_osmosis() {
+        assert_eq!(
+            gov_module_address(AddressHrp::from_static("osmo")).to_string(),
+            "osmo10d07y265gmmuvt4z0w9aw880jnsr700jjeq4qp"
+        );
+    }
+}
@@ -0,0 +1,359 @@
+//! Helpers for IBC transfers.
+
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use cosmos_sdk_proto::{
+    cosmos::base::{abci::v1beta1::TxResponse, v1beta1::Coin},
+    ibc::core::client::v1::Height,
+};
+use prost::Message;
+
+use crate::{Address, Cosmos, HasAddress, TxBuilder, TxMessage, Wallet};
+
+/// Timeout used by [MsgTransferHelper::new].
+const DEFAULT_TRANSFER_TIMEOUT: Duration = Duration::from_secs(10 * 60);
+
+/// `MsgTransfer` including the memo field, which is missing from our protobuf definitions.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+struct MsgTransfer {
+    #[prost(string, tag = "1")]
+    source_port: String,
+    #[prost(string, tag = "2")]
+    source_channel: String,
+    #[prost(message, optional, tag = "3")]
+    token: Option<Coin>,
+    #[prost(string, tag = "4")]
+    sender: String,
+    #[prost(string, tag = "5")]
+    receiver: String,
+    #[prost(message, optional, tag = "6")]
+    timeout_height: Option<Height>,
+    #[prost(uint64, tag = "7")]
+    timeout_timestamp: u64,
+    #[prost(string, tag = "8")]
+    memo: String,
+}
+
+/// An ICS-20 token transfer to another chain.
+///
+/// At least one of the timeout height or timestamp must be set, otherwise
+/// the chain rejects the transfer. [MsgTransferHelper::new] sets a timestamp
+/// ten minutes in the future.
+#[derive(Clone, Debug)]
+pub struct MsgTransferHelper {
+    /// Sender on this chain
+    pub sender: Address,
+    /// Receiver on the destination chain
+    pub receiver: String,
+    /// Source port, usually `transfer`
+    pub source_port: String,
+    /// Source channel on this chain, e.g. `channel-0`
+    pub source_channel: String,
+    /// Tokens to transfer
+    pub token: Coin,
+    /// Destination chain height after which the transfer times out
+    pub timeout_height: Option<Height>,
+    /// Time after which the transfer times out
+    pub timeout_timestamp: Option<DateTime<Utc>>,
+    /// Memo attached to the transfer
+    pub memo: String,
+}
+
+impl MsgTransferHelper {
+    /// Transfer via the given channel on the `transfer` port, with a ten minute timeout.
+    pub fn new(
+        sender: impl HasAddress,
+        receiver: impl Into<String>,
+        source_channel: impl Into<String>,
+        token: Coin,
+    ) -> Self {
+        MsgTransferHelper {
+            sender: sender.get_address(),
+            receiver: receiver.into(),
+            source_port: "transfer".to_owned(),
+            source_channel: source_channel.into(),
+            token,
+            timeout_height: None,
+            timeout_timestamp: None,
+            memo: String::new(),
+        }
+        .with_timeout_after(DEFAULT_TRANSFER_TIMEOUT)
+    }
+
+    /// Time out the transfer once the given duration has passed.
+    pub fn with_timeout_after(mut self, timeout: Duration) -> Self {
+        self.timeout_timestamp = chrono::Duration::from_std(timeout)
+            .ok()
+            .and_then(|timeout| Utc::now().checked_add_signed(timeout));
+        self
+    }
+
+    /// Time out the transfer at the given time.
+    pub fn with_timeout_timestamp(mut self, timestamp: DateTime<Utc>) -> Self {
+        self.timeout_timestamp = Some(timestamp);
+        self
+    }
+
+    /// Time out the transfer at the given height on the destination chain.
+    pub fn with_timeout_height(mut self, revision_number: u64, revision_height: u64) -> Self {
+        self.timeout_height = Some(Height {
+            revision_number,
+            revision_height,
+        });
+        self
+    }
+
+    /// Disable the timestamp timeout, relying only on the timeout height.
+    pub fn without_timeout_timestamp(mut self) -> Self {
+        self.timeout_timestamp = None;
+        self
+    }
+
+    /// Attach a raw memo.
+    pub fn with_memo(mut self, memo: impl Into<String>) -> Self {
+        self.memo = memo.into();
+        self
+    }
+
+    /// Attach a typed memo for IBC hooks or packet forwarding.
+    pub fn with_ibc_memo(self, memo: &IbcMemo) -> Result<Self, crate::Error> {
+        Ok(self.with_memo(memo.to_memo_string()?))
+    }
+}
+
+impl From<MsgTransferHelper> for TxMessage {
+    fn from(
+        MsgTransferHelper {
+            sender,
+            receiver,
+            source_port,
+            source_channel,
+            token,
+            timeout_height,
+            timeout_timestamp,
+            memo,
+        }: MsgTransferHelper,
+    ) -> Self {
+        let desc = format!(
+            "{sender} transferring {}{} to {receiver} via {source_port}/{source_channel}",
+            token.amount, token.denom
+        );
+        TxMessage::new(
+            "/ibc.applications.transfer.v1.MsgTransfer",
+            MsgTransfer {
+                source_port,
+                source_channel,
+                token: Some(token),
+                sender: sender.get_address_string(),
+                receiver,
+                timeout_height,
+                timeout_timestamp: timeout_timestamp
+                    .and_then(|timestamp| timestamp.timestamp_nanos_opt())
+                    .and_then(|nanos| nanos.try_into().ok())
+                    .unwrap_or_default(),
+                memo,
+            }
+            .encode_to_vec(),
+            desc,
+        )
+    }
+}
+
+impl Cosmos {
+    /// Transfer tokens to another chain over IBC, with a ten minute timeout.
+    ///
+    /// Use [MsgTransferHelper] directly for more control over timeouts and memos.
+    pub async fn ibc_transfer(
+        &self,
+        wallet: &Wallet,
+        receiver: impl Into<String>,
+        source_channel: impl Into<String>,
+        token: Coin,
+    ) -> Result<TxResponse, crate::Error> {
+        TxBuilder::default()
+            .add_message(MsgTransferHelper::new(
+                wallet,
+                receiver,
+                source_channel,
+                token,
+            ))
+            .sign_and_broadcast(self, wallet)
+            .await
+    }
+}
+
+/// A typed IBC transfer memo.
+///
+/// Supports the formats understood by IBC hooks (executing a contract on the
+/// receiving chain) and packet-forward-middleware (routing a transfer through
+/// multiple hops). Use [IbcMemo::to_memo_string] to render it for a transfer
+/// message.
+#[derive(serde::Serialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum IbcMemo {
+    /// Execute a contract on the receiving chain via IBC hooks.
+    Wasm(WasmHook),
+    /// Forward the received funds on to another chain.
+    Forward(PacketForward),
+}
+
+/// Contract execution performed by IBC hooks when the transfer is received.
+#[derive(serde::Serialize, Clone, Debug, PartialEq)]
+pub struct WasmHook {
+    /// Contract to execute. Must match the receiver of the transfer.
+    pub contract: Address,
+    /// Execute message sent to the contract.
+    pub msg: serde_json::Value,
+}
+
+/// A single hop for packet-forward-middleware.
+#[derive(serde::Serialize, Clone, Debug, PartialEq)]
+pub struct PacketForward {
+    /// Receiver on the next chain.
+    pub receiver: String,
+    /// Port on the intermediate chain, usually `transfer`.
+    pub port: String,
+    /// Channel on the intermediate chain leading to the next chain.
+    pub channel: String,
+    /// Timeout for the forwarded packet, e.g. `10m`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timeout: Option<String>,
+    /// How many times to retry forwarding on timeout.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retries: Option<u8>,
+    /// Memo to use for the next hop.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next: Option<Box<IbcMemo>>,
+}
+
+impl IbcMemo {
+    /// Create an IBC hooks memo executing the given message on the contract.
+    pub fn wasm(contract: Address, msg: impl serde::Serialize) -> Result<Self, crate::Error> {
+        Ok(IbcMemo::Wasm(WasmHook {
+            contract,
+            msg: serde_json::to_value(msg)?,
+        }))
+    }
+
+    /// Create a packet-forward memo sending to the receiver via the given channel.
+    ///
+    /// Use [PacketForward::new] instead to customize the timeout or retries.
+    pub fn forward(receiver: impl Into<String>, channel: impl Into<String>) -> Self {
+        PacketForward::new(receiver, channel).into()
+    }
+
+    /// Set the memo used by the final forwarding hop.
+    ///
+    /// IBC hooks memos are always the final step, so they are returned unchanged.
+    pub fn then(self, next: IbcMemo) -> Self {
+        match self {
+            IbcMemo::Wasm(_) => self,
+            IbcMemo::Forward(mut forward) => {
+                forward.next = Some(Box::new(match forward.next.take() {
+                    None => next,
+                    Some(existing) => existing.then(next),
+                }));
+                IbcMemo::Forward(forward)
+            }
+        }
+    }
+
+    /// Render this memo to the JSON string expected in a transfer memo field.
+    pub fn to_memo_string(&self) -> Result<String, crate::Error> {
+        serde_json::to_string(self).map_err(crate::Error::JsonSerialize)
+    }
+}
+
+impl PacketForward {
+    /// Forward to the receiver via the given channel on the `transfer` port.
+    pub fn new(receiver: impl Into<String>, channel: impl Into<String>) -> Self {
+        PacketForward {
+            receiver: receiver.into(),
+            port: "transfer".to_owned(),
+            channel: channel.into(),
+            timeout: None,
+            retries: None,
+            next: None,
+        }
+    }
+
+    /// Set the timeout for the forwarded packet, e.g. `10m`.
+    pub fn with_timeout(mut self, timeout: impl Into<String>) -> Self {
+        self.timeout = Some(timeout.into());
+        self
+    }
+
+    /// Set the number of retries for the forwarded packet.
+    pub fn with_retries(mut self, retries: u8) -> Self {
+        self.retries = Some(retries);
+        self
+    }
+}
+
+impl From<PacketForward> for IbcMemo {
+    fn from(forward: PacketForward) -> Self {
+        IbcMemo::Forward(forward)
+    }
+}
+
+impl From<WasmHook> for IbcMemo {
+    fn from(hook: WasmHook) -> Self {
+        IbcMemo::Wasm(hook)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn multi_hop_with_wasm() {
+        let contract: Address = "osmo168gdk6r58jdwfv49kuesq2rs747jawnn4ryvyk"
+            .parse()
+            .unwrap();
+        let memo = IbcMemo::forward("cosmos1receiver", "channel-0")
+            .then(IbcMemo::forward("osmo1receiver", "channel-1"))
+            .then(IbcMemo::wasm(contract, serde_json::json!({"swap": {}})).unwrap());
+        let expected = serde_json::json!({
+            "forward": {
+                "receiver": "cosmos1receiver",
+                "port": "transfer",
+                "channel": "channel-0",
+                "next": {
+                    "forward": {
+                        "receiver": "osmo1receiver",
+                        "port": "transfer",
+                        "channel": "channel-1",
+                        "next": {
+                            "wasm": {
+                                "contract": contract.to_string(),
+                                "msg": {"swap": {}}
+                            }
+                        }
+                    }
+                }
+            }
+        });
+        assert_eq!(serde_json::to_value(&memo).unwrap(), expected);
+    }
+
+    #[test]
+    fn transfer_defaults() {
+        let sender: Address = "osmo168gdk6r58jdwfv49kuesq2rs747jawnn4ryvyk"
+            .parse()
+            .unwrap();
+        let coin = Coin {
+            denom: "uosmo".to_owned(),
+            amount: "100".to_owned(),
+        };
+        let helper = MsgTransferHelper::new(sender, "cosmos1receiver", "channel-0", coin);
+        assert_eq!(helper.source_port, "transfer");
+        assert!(helper.timeout_timestamp.unwrap() > Utc::now());
+        let (any, _) = TxMessage::from(helper).into_protobuf();
+        let msg = MsgTransfer::decode(&*any.value).unwrap();
+        assert_ne!(msg.timeout_timestamp, 0);
+        assert_eq!(msg.receiver, "cosmos1receiver");
+    }
+}
@@ -0,0 +1,254 @@
+//! Interchain Accounts (ICS-27) from the controller side.
+//!
+//! An owner account on this chain registers an interchain account on a host
+//! chain over an IBC connection, and then sends it transactions to execute.
+//! The controller messages and query were added in ibc-go v6, and aren't
+//! included in our protobuf definitions.
+
+use std::time::Duration;
+
+use cosmos_sdk_proto::ibc::applications::interchain_accounts::v1::{
+    CosmosTx, InterchainAccountPacketData, Type,
+};
+use prost::Message;
+
+use crate::{error::Action, Address, Cosmos, HasAddress, TxMessage};
+
+/// Timeout used by [MsgSendTxHelper::new].
+const DEFAULT_RELATIVE_TIMEOUT: Duration = Duration::from_secs(10 * 60);
+
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+struct MsgRegisterInterchainAccount {
+    #[prost(string, tag = "1")]
+    owner: String,
+    #[prost(string, tag = "2")]
+    connection_id: String,
+    #[prost(string, tag = "3")]
+    version: String,
+}
+
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+struct MsgSendTx {
+    #[prost(string, tag = "1")]
+    owner: String,
+    #[prost(string, tag = "2")]
+    connection_id: String,
+    #[prost(message, optional, tag = "3")]
+    packet_data: Option<InterchainAccountPacketData>,
+    /// Timeout in nanoseconds, relative to the block time
+    #[prost(uint64, tag = "4")]
+    relative_timeout: u64,
+}
+
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub(crate) struct QueryInterchainAccountRequest {
+    #[prost(string, tag = "1")]
+    pub(crate) owner: String,
+    #[prost(string, tag = "2")]
+    pub(crate) connection_id: String,
+}
+
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub(crate) struct QueryInterchainAccountResponse {
+    #[prost(string, tag = "1")]
+    pub(crate) address: String,
+}
+
+/// Register an interchain account for the owner on the other side of the connection.
+pub struct MsgRegisterInterchainAccountHelper {
+    /// Account on this chain controlling the interchain account
+    pub owner: Address,
+    /// IBC connection to the host chain, e.g. `connection-0`
+    pub connection_id: String,
+    /// Channel version metadata. Leave empty to use the default.
+    pub version: String,
+}
+
+impl From<MsgRegisterInterchainAccountHelper> for TxMessage {
+    fn from(
+        MsgRegisterInterchainAccountHelper {
+            owner,
+            connection_id,
+            version,
+        }: MsgRegisterInterchainAccountHelper,
+    ) -> Self {
+        let desc = format!("{owner} registering interchain account on {connection_id}");
+        TxMessage::new(
+            "/ibc.applications.interchain_accounts.controller.v1.MsgRegisterInterchainAccount",
+            MsgRegisterInterchainAccount {
+                owner: owner.get_address_string(),
+                connection_id,
+                version,
+            }
+            .encode_to_vec(),
+            desc,
+        )
+    }
+}
+
+/// Execute messages on the host chain using the owner's interchain account.
+///
+/// The messages are executed by the interchain account, so any signer
+/// fields must contain the interchain account's address on the host chain,
+/// see [Cosmos::query_interchain_account].
+pub struct MsgSendTxHelper {
+    /// Account on this chain controlling the interchain account
+    pub owner: Address,
+    /// IBC connection to the host chain, e.g. `connection-0`
+    pub connection_id: String,
+    /// Messages to execute on the host chain
+    pub msgs: Vec<TxMessage>,
+    /// Memo for the packet
+    pub memo: String,
+    /// Timeout for the packet, relative to the current block time
+    pub relative_timeout: Duration,
+}
+
+impl MsgSendTxHelper {
+    /// Send the messages with no memo and a ten minute timeout.
+    pub fn new(
+        owner: impl HasAddress,
+        connection_id: impl Into<String>,
+        msgs: Vec<TxMessage>,
+    ) -> Self {
+        MsgSendTxHelper {
+            owner: owner.get_address(),
+            connection_id: connection_id.into(),
+            msgs,
+            memo: String::new(),
+            relative_timeout: DEFAULT_RELATIVE_TIMEOUT,
+        }
+    }
+}
+
+impl From<MsgSendTxHelper> for TxMessage {
+    fn from(
+        MsgSendTxHelper {
+            owner,
+            connection_id,
+            msgs,
+            memo,
+            relative_timeout,
+        }: MsgSendTxHelper,
+    ) -> Self {
+        let mut messages = vec![];
+        let mut descs = vec![];
+        for msg in msgs {
+            let (msg, desc) = msg.into_protobuf();
+            messages.push(msg);
+            descs.push(desc);
+        }
+        TxMessage::new(
+            "/ibc.applications.interchain_accounts.controller.v1.MsgSendTx",
+            MsgSendTx {
+                owner: owner.get_address_string(),
+                connection_id: connection_id.clone(),
+                packet_data: Some(InterchainAccountPacketData {
+                    r#type: Type::ExecuteTx as i32,
+                    data: CosmosTx { messages }.encode_to_vec(),
+                    memo,
+                }),
+                relative_timeout: relative_timeout.as_nanos().try_into().unwrap_or(u64::MAX),
+            }
+            .encode_to_vec(),
+            format!("{owner} executing via interchain account on {connection_id}: {descs:?}"),
+        )
+    }
+}
+
+impl Cosmos {
+    /// Get the address of the owner's interchain account on the host chain.
+    ///
+    /// Fails if no account has been registered yet on this connection.
+    pub async fn query_interchain_account(
+        &self,
+        owner: impl HasAddress,
+        connection_id: impl Into<String>,
+    ) -> Result<Address, crate::Error> {
+        let connection_id = connection_id.into();
+        let action = Action::QueryInterchainAccount {
+            owner: owner.get_address(),
+            connection_id: connection_id.clone(),
+        };
+        let req = QueryInterchainAccountRequest {
+            owner: owner.get_address_string(),
+            connection_id,
+        };
+        let address = self
+            .perform_query(req, action.clone(), true)
+            .await?
+            .into_inner()
+            .address;
+        address
+            .parse()
+            .map_err(|e| crate::Error::InvalidChainResponse {
+                message: format!("Invalid interchain account address {address:?}: {e}"),
+                action,
+            })
+    }
+}
+
+/// Client for the controller query service.
+pub(crate) mod query_client {
+    #![allow(unused_variables, dead_code, missing_docs, clippy::let_unit_value)]
+    use tonic::codegen::*;
+    #[derive(Debug, Clone)]
+    pub(crate) struct QueryClient<T> {
+        inner: tonic::client::Grpc<T>,
+    }
+    impl<T> QueryClient<T>
+    where
+        T: tonic::client::GrpcService<tonic::body::BoxBody>,
+        T::Error: Into<StdError>,
+        T::ResponseBody: Body<Data = Bytes> + Send + 'static,
+        <T::ResponseBody as Body>::Error: Into<StdError> + Send,
+    {
+        pub(crate) fn new(inner: T) -> Self {
+            let inner = tonic::client::Grpc::new(inner);
+            Self { inner }
+        }
+        /// InterchainAccount returns the interchain account address for a given owner address on a given connection
+        pub(crate) async fn interchain_account(
+            &mut self,
+            request: impl tonic::IntoRequest<super::QueryInterchainAccountRequest>,
+        ) -> Result<tonic::Response<super::QueryInterchainAccountResponse>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(
+                    tonic::Code::Unknown,
+                    format!("Service was not ready: {}", e.into()),
+                )
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/ibc.applications.interchain_accounts.controller.v1.Query/InterchainAccount",
+            );
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn send_tx_packet_data() {
+        let owner: Address = "osmo168gdk6r58jdwfv49kuesq2rs747jawnn4ryvyk"
+            .parse()
+            .unwrap();
+        let inner = TxMessage::new("/cosmos.bank.v1beta1.MsgSend", vec![1, 2, 3], "send");
+        let (any, _) = TxMessage::from(MsgSendTxHelper::new(owner, "connection-0", vec![inner]))
+            .into_protobuf();
+        let msg = MsgSendTx::decode(&*any.value).unwrap();
+        assert_eq!(msg.relative_timeout, 600_000_000_000);
+        let packet = msg.packet_data.unwrap();
+        assert_eq!(packet.r#type, Type::ExecuteTx as i32);
+        let tx = CosmosTx::decode(&*packet.data).unwrap();
+        assert_eq!(tx.messages.len(), 1);
+        assert_eq!(tx.messages[0].type_url, "/cosmos.bank.v1beta1.MsgSend");
+    }
+}
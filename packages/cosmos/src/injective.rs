@@ -1,6 +1,9 @@
 #![allow(non_snake_case)]
 use cosmos_sdk_proto::cosmos::auth::v1beta1::BaseAccount;
 
+/// Account type used by Injective, and by Ethermint based chains like Evmos.
+///
+/// Both use the same layout under different type URLs.
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct EthAccount {
@@ -1,42 +1,99 @@
 #![deny(missing_docs)]
 //! Library for communicating with Cosmos blockchains over gRPC
+pub use account::Account;
 pub use address::{Address, AddressHrp, HasAddress, HasAddressHrp, PublicKeyMethod, RawAddress};
-pub use client::{BlockInfo, Cosmos, CosmosTxResponse, HasCosmos};
-pub use codeid::CodeId;
+pub use authz::{
+    Authorization, ContractGrant, ContractGrantFilter, ContractGrantLimit, GrantInfo, GrantStatus,
+    StakeValidators,
+};
+pub use batch::{BatchResult, QueryBatch};
+pub use client::{
+    AbciQueryResponse, BlockInfo, BlockResults, BlockTxResult, Cosmos, CosmosTxResponse, HasCosmos,
+    MinGasPrice, MinGasPriceSource, UnconfirmedTxs, WaitForTransactionConfig,
+    WaitForTransactionOutcome,
+};
+#[cfg(feature = "light-client")]
+pub use client::{LightClient, VerifiedHeader};
+pub use codeid::{predict_instantiate2_address, CodeId};
+pub use coins::{Coins, DenomDisplay, ParsedCoin};
 pub use contract::{Contract, ContractAdmin, HasContract};
-pub use cosmos_builder::CosmosBuilder;
+pub use cosmos_builder::{
+    CosmosBuilder, FixtureMode, GasOverride, QueryCacheConfig, QueryLog, QueryLogger,
+    RequestInterceptor, RetryPolicy,
+};
 pub use cosmos_network::CosmosNetwork;
 pub use cosmos_sdk_proto as proto;
 pub use cosmos_sdk_proto::cosmos::base::v1beta1::Coin;
+pub use decode::{DecodedMessage, DecodedSigner, DecodedTx, TxBodyExt};
 pub use error::Error;
 pub use ext::TxResponseExt;
 pub use gas_multiplier::DynamicGasMultiplier;
+pub use liveness::{LivenessConfig, LivenessEvent};
+pub use registry::{register_message_type, CustomMessage};
+pub use sequence::SequenceManager;
+pub use snapshot::{StateDiff, StateSnapshot};
+pub use stream::TxFilter;
 pub use tokenfactory::TokenFactory;
+pub use tx_cost::TxCostReport;
+pub use tx_search::{TxSearchBuilder, TxSearchPage, TxSearchToken};
 pub use txbuilder::{TxBuilder, TxMessage};
 pub use wallet::{SeedPhrase, Wallet};
+pub use wallet_pool::{PooledWallet, WalletPool};
 
+mod account;
 mod address;
 mod authz;
+mod batch;
 mod client;
 mod codeid;
+mod coins;
 mod contract;
 mod cosmos_builder;
 mod cosmos_network;
+mod decode;
+mod distribution;
 mod ext;
 mod gas_multiplier;
 mod injective;
+mod liveness;
+mod registry;
+mod sequence;
+mod snapshot;
+mod stream;
 mod tokenfactory;
+mod tx_cost;
+mod tx_search;
 mod txbuilder;
 mod wallet;
+mod wallet_pool;
 
 #[cfg(feature = "clap")]
 pub mod clap;
 
+#[cfg(feature = "localnet")]
+pub mod localnet;
+
+#[cfg(feature = "name-service")]
+pub mod name_service;
+
+pub mod chain_registry;
+pub mod cw20;
+pub mod cw3;
+pub mod cw721;
 pub mod error;
+pub mod events;
 
+pub mod feegrant;
 pub mod gas_price;
+pub mod gov;
+pub mod ibc;
+pub mod ica;
 pub mod messages;
+pub mod multisig;
+pub mod offline;
 pub mod osmosis;
+pub mod serializable;
+pub mod signature;
 
 /// A result type with our error type provided as the default.
 pub type Result<T, E = Error> = std::result::Result<T, E>;
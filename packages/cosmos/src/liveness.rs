@@ -0,0 +1,246 @@
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use futures_util::Stream;
+
+use crate::{
+    error::{QueryError, QueryErrorDetails},
+    Cosmos,
+};
+
+/// Settings for [Cosmos::watch_liveness].
+#[derive(Clone, Copy, Debug)]
+pub struct LivenessConfig {
+    /// Expected time between blocks on this chain.
+    pub expected_block_time: Duration,
+    /// How many expected block times may pass without a new block before the chain is considered stalled.
+    pub allowed_missed_blocks: u32,
+    /// How often to check the latest block.
+    pub poll_interval: Duration,
+}
+
+impl Default for LivenessConfig {
+    fn default() -> Self {
+        LivenessConfig {
+            expected_block_time: Duration::from_secs(6),
+            allowed_missed_blocks: 10,
+            poll_interval: Duration::from_secs(5),
+        }
+    }
+}
+
+impl LivenessConfig {
+    fn stall_threshold(&self) -> Duration {
+        self.expected_block_time * self.allowed_missed_blocks
+    }
+}
+
+/// A change in chain liveness reported by [Cosmos::watch_liveness].
+#[derive(Clone, Debug)]
+pub enum LivenessEvent {
+    /// The chain is producing blocks. Only emitted as the first event.
+    Healthy {
+        /// Latest block height
+        height: i64,
+        /// Timestamp of the latest block
+        timestamp: DateTime<Utc>,
+    },
+    /// No new block has been seen within the allowed time.
+    Stalled {
+        /// Latest block height
+        height: i64,
+        /// Timestamp of the latest block
+        timestamp: DateTime<Utc>,
+    },
+    /// Blocks are being produced again after a stall.
+    Recovered {
+        /// Latest block height
+        height: i64,
+        /// Timestamp of the latest block
+        timestamp: DateTime<Utc>,
+        /// How long the chain was considered stalled
+        stalled_for: Duration,
+    },
+}
+
+impl LivenessEvent {
+    /// Is the chain currently producing blocks?
+    pub fn is_live(&self) -> bool {
+        !matches!(self, LivenessEvent::Stalled { .. })
+    }
+}
+
+struct WatchState {
+    cosmos: Cosmos,
+    config: LivenessConfig,
+    first: bool,
+    tracker: LivenessTracker,
+}
+
+/// Tracks transitions between live and stalled.
+#[derive(Default)]
+struct LivenessTracker {
+    reported: bool,
+    stalled_since: Option<tokio::time::Instant>,
+    latest: Option<(i64, DateTime<Utc>)>,
+}
+
+impl LivenessTracker {
+    /// Record the latest block, returning an event if the state changed.
+    fn observe(
+        &mut self,
+        height: i64,
+        timestamp: DateTime<Utc>,
+        stalled: bool,
+    ) -> Option<LivenessEvent> {
+        self.latest = Some((height, timestamp));
+        let event = match (stalled, self.stalled_since) {
+            (true, None) => {
+                self.stalled_since = Some(tokio::time::Instant::now());
+                Some(LivenessEvent::Stalled { height, timestamp })
+            }
+            (false, Some(since)) => {
+                self.stalled_since = None;
+                Some(LivenessEvent::Recovered {
+                    height,
+                    timestamp,
+                    stalled_for: since.elapsed(),
+                })
+            }
+            (false, None) if !self.reported => Some(LivenessEvent::Healthy { height, timestamp }),
+            _ => None,
+        };
+        if event.is_some() {
+            self.reported = true;
+        }
+        event
+    }
+
+    /// The query layer reports a chain which stopped producing blocks as an
+    /// error. Treat that as a stalled observation of the last known block.
+    fn observe_error(&mut self, err: &crate::Error) -> Option<Option<LivenessEvent>> {
+        let (old_height, age) = match err {
+            crate::Error::Query(QueryError {
+                query:
+                    QueryErrorDetails::NoNewBlockFound {
+                        old_height, age, ..
+                    },
+                ..
+            }) => (*old_height, *age),
+            _ => return None,
+        };
+        let timestamp = match self.latest {
+            Some((height, timestamp)) if height == old_height => timestamp,
+            _ => {
+                Utc::now()
+                    - chrono::Duration::from_std(age).unwrap_or_else(|_| chrono::Duration::zero())
+            }
+        };
+        Some(self.observe(old_height, timestamp, true))
+    }
+}
+
+impl Cosmos {
+    /// Watch the age of the latest block, producing a stream of liveness transitions.
+    ///
+    /// The first item reflects the current state of the chain, after which
+    /// items are only produced when the chain stalls or recovers. A query
+    /// failing because no new blocks were found counts as a stall, other
+    /// errors while querying are passed through and do not affect the tracked
+    /// state.
+    pub fn watch_liveness(
+        &self,
+        config: LivenessConfig,
+    ) -> impl Stream<Item = Result<LivenessEvent, crate::Error>> + Send + 'static {
+        let state = WatchState {
+            cosmos: self.clone(),
+            config,
+            first: true,
+            tracker: LivenessTracker::default(),
+        };
+        futures_util::stream::unfold(state, |mut state| async move {
+            loop {
+                if state.first {
+                    state.first = false;
+                } else {
+                    tokio::time::sleep(state.config.poll_interval).await;
+                }
+                let event = match state.cosmos.get_latest_block_info().await {
+                    Ok(block) => {
+                        let age = (Utc::now() - block.timestamp).to_std().unwrap_or_default();
+                        let stalled = age > state.config.stall_threshold();
+                        state
+                            .tracker
+                            .observe(block.height, block.timestamp, stalled)
+                    }
+                    Err(e) => match state.tracker.observe_error(&e) {
+                        Some(event) => event,
+                        None => return Some((Err(e), state)),
+                    },
+                };
+                if let Some(event) = event {
+                    return Some((Ok(event), state));
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::{error::Action, AddressHrp, CosmosBuilder};
+
+    fn no_new_block(old_height: i64) -> crate::Error {
+        crate::Error::Query(QueryError {
+            action: Action::GetLatestBlock,
+            builder: Arc::new(CosmosBuilder::new(
+                "osmosis-1",
+                "uosmo",
+                AddressHrp::from_static("osmo"),
+                "http://localhost:9090",
+            )),
+            height: None,
+            query: QueryErrorDetails::NoNewBlockFound {
+                age: Duration::from_secs(120),
+                age_allowed: Duration::from_secs(60),
+                old_height,
+                new_height: old_height,
+            },
+            grpc_url: Arc::new("http://localhost:9090".to_owned()),
+            node_health: crate::error::NodeHealthReport { nodes: vec![] },
+        })
+    }
+
+    #[test]
+    fn no_new_block_is_stalled() {
+        let mut tracker = LivenessTracker::default();
+        let timestamp = Utc::now();
+        assert!(matches!(
+            tracker.observe(10, timestamp, false),
+            Some(LivenessEvent::Healthy { height: 10, .. })
+        ));
+        match tracker.observe_error(&no_new_block(10)) {
+            Some(Some(LivenessEvent::Stalled {
+                height,
+                timestamp: stalled_at,
+            })) => {
+                assert_eq!(height, 10);
+                assert_eq!(stalled_at, timestamp);
+            }
+            _ => panic!("Expected a stall"),
+        }
+        // Still stalled, nothing new to report
+        assert!(matches!(
+            tracker.observe_error(&no_new_block(10)),
+            Some(None)
+        ));
+        assert!(matches!(
+            tracker.observe(11, Utc::now(), false),
+            Some(LivenessEvent::Recovered { height: 11, .. })
+        ));
+        assert!(tracker.observe_error(&crate::Error::NoRpcUrl).is_none());
+    }
+}
@@ -0,0 +1,198 @@
+//! Harness for end-to-end tests against a local chain.
+//!
+//! [Localnet::start] connects to an already running local node, or launches
+//! one in a Docker container if none is reachable, and provides a [Cosmos]
+//! together with the genesis [Wallet] for funding test wallets.
+
+use std::{
+    process::Command,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    error::LocalnetError, Cosmos, CosmosBuilder, CosmosNetwork, HasAddress, HasAddressHrp,
+    SeedPhrase, Wallet,
+};
+
+/// How to reach or launch the local chain.
+#[derive(Clone)]
+pub struct LocalnetConfig {
+    /// Network providing the chain ID, gas coin, address prefix and gRPC URL
+    pub network: CosmosNetwork,
+    /// Seed phrase for an account funded at genesis
+    pub genesis_phrase: SeedPhrase,
+    /// Container to launch if the node isn't already running, [None] to only connect
+    pub docker: Option<DockerConfig>,
+    /// How long to wait for the node to start producing blocks
+    pub startup_timeout: Duration,
+}
+
+/// Docker container for a local chain.
+#[derive(Clone, Debug)]
+pub struct DockerConfig {
+    /// Image to run
+    pub image: String,
+    /// Extra arguments to `docker run`, such as environment variables
+    pub args: Vec<String>,
+    /// Command and arguments to run inside the container
+    pub command: Vec<String>,
+}
+
+impl LocalnetConfig {
+    /// A wasmd node launched from the `cosmwasm/wasmd` image.
+    ///
+    /// The genesis account is the `osmosis-local` test phrase, funded by the
+    /// image's setup script.
+    pub fn wasmd() -> Result<Self, LocalnetError> {
+        let network = CosmosNetwork::WasmdLocal;
+        let genesis_phrase: SeedPhrase = "osmosis-local".parse()?;
+        let genesis = genesis_phrase.with_hrp(network.get_address_hrp())?;
+        Ok(LocalnetConfig {
+            network,
+            genesis_phrase,
+            docker: Some(DockerConfig {
+                image: "cosmwasm/wasmd:v0.45.0".to_owned(),
+                args: vec![
+                    "-e".to_owned(),
+                    format!("CHAIN_ID={}", network.chain_id()),
+                    "-e".to_owned(),
+                    format!("FEE_TOKEN={}", network.gas_coin()),
+                ],
+                command: vec![
+                    "/opt/setup_and_run.sh".to_owned(),
+                    genesis.get_address_string(),
+                ],
+            }),
+            startup_timeout: Duration::from_secs(120),
+        })
+    }
+
+    /// A LocalOsmosis node, which must already be running.
+    ///
+    /// LocalOsmosis is launched with its own compose setup, which funds the
+    /// `osmosis-local` test phrase.
+    pub fn osmosis() -> Result<Self, LocalnetError> {
+        Ok(LocalnetConfig {
+            network: CosmosNetwork::OsmosisLocal,
+            genesis_phrase: "osmosis-local".parse()?,
+            docker: None,
+            startup_timeout: Duration::from_secs(120),
+        })
+    }
+}
+
+/// A running local chain, see the [module documentation](self).
+///
+/// A container launched by [Localnet::start] is removed when this value is dropped.
+pub struct Localnet {
+    cosmos: Cosmos,
+    genesis: Wallet,
+    container: Option<String>,
+}
+
+impl Localnet {
+    /// Connect to the local chain, launching its container first if needed.
+    ///
+    /// Waits until the node is producing blocks.
+    pub async fn start(config: LocalnetConfig) -> Result<Self, LocalnetError> {
+        let LocalnetConfig {
+            network,
+            genesis_phrase,
+            docker,
+            startup_timeout,
+        } = config;
+        let builder = network.builder_local();
+        let genesis = genesis_phrase.with_hrp(network.get_address_hrp())?;
+
+        if let Ok(cosmos) = Self::connect(&builder).await {
+            return Ok(Localnet {
+                cosmos,
+                genesis,
+                container: None,
+            });
+        }
+        let docker = docker.ok_or_else(|| LocalnetError::NotRunning {
+            grpc_url: builder.grpc_url().to_owned(),
+        })?;
+        let mut localnet = Localnet {
+            cosmos: builder.clone().build_lazy()?,
+            genesis,
+            container: Some(run_container(&docker)?),
+        };
+
+        let started = Instant::now();
+        loop {
+            match Self::connect(&builder).await {
+                Ok(cosmos) => {
+                    localnet.cosmos = cosmos;
+                    break Ok(localnet);
+                }
+                Err(e) if started.elapsed() >= startup_timeout => {
+                    break Err(LocalnetError::StartupTimeout {
+                        timeout: startup_timeout,
+                        last_error: e.to_string(),
+                    })
+                }
+                Err(_) => tokio::time::sleep(Duration::from_millis(500)).await,
+            }
+        }
+    }
+
+    /// Build a [Cosmos] and check that blocks are being produced.
+    async fn connect(builder: &CosmosBuilder) -> Result<Cosmos, LocalnetError> {
+        let cosmos = builder.clone().build().await?;
+        cosmos.get_latest_block_info().await?;
+        Ok(cosmos)
+    }
+
+    /// Connection to the local chain.
+    pub fn cosmos(&self) -> &Cosmos {
+        &self.cosmos
+    }
+
+    /// The account funded at genesis.
+    pub fn genesis_wallet(&self) -> &Wallet {
+        &self.genesis
+    }
+
+    /// Generate a new wallet and fund it with the given amount of gas coin from the genesis wallet.
+    pub async fn funded_wallet(&self, amount: u128) -> Result<Wallet, LocalnetError> {
+        let wallet = Wallet::generate(self.cosmos.get_address_hrp())?;
+        self.genesis
+            .send_gas_coin(&self.cosmos, &wallet, amount)
+            .await?;
+        Ok(wallet)
+    }
+}
+
+impl Drop for Localnet {
+    fn drop(&mut self) {
+        if let Some(container) = &self.container {
+            if let Err(e) = Command::new("docker")
+                .args(["rm", "--force", container.as_str()])
+                .output()
+            {
+                tracing::warn!("Unable to remove localnet container {container}: {e}");
+            }
+        }
+    }
+}
+
+/// Launch a detached container, returning its ID.
+fn run_container(docker: &DockerConfig) -> Result<String, LocalnetError> {
+    let output = Command::new("docker")
+        .args(["run", "--detach", "--rm"])
+        .args(["--publish", "9090:9090", "--publish", "26657:26657"])
+        .args(&docker.args)
+        .arg(&docker.image)
+        .args(&docker.command)
+        .output()
+        .map_err(|source| LocalnetError::DockerUnavailable { source })?;
+    if !output.status.success() {
+        return Err(LocalnetError::DockerRunFailed {
+            image: docker.image.clone(),
+            stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+        });
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_owned())
+}
@@ -5,7 +5,7 @@ use std::{fmt::Display, path::PathBuf};
 use chrono::{DateTime, Utc};
 use cosmos_sdk_proto::{
     cosmos::{
-        authz::v1beta1::{GenericAuthorization, Grant, MsgExec, MsgGrant},
+        authz::v1beta1::{Grant, MsgExec, MsgGrant, MsgRevoke},
         bank::v1beta1::MsgSend,
         base::v1beta1::Coin,
     },
@@ -17,7 +17,7 @@ use cosmos_sdk_proto::{
 use prost::Message;
 use prost_types::Timestamp;
 
-use crate::{error::StringOrBytes, Address, HasAddress, TxMessage};
+use crate::{error::StringOrBytes, Address, Authorization, HasAddress, TxMessage};
 
 /// A local version of [MsgExec] with extra information for nice error messages.
 pub struct MsgExecHelper {
@@ -69,32 +69,90 @@ impl From<MsgGrantHelper> for TxMessage {
             authorization,
             expiration,
         }: MsgGrantHelper,
+    ) -> Self {
+        MsgGrantAuthorizationHelper {
+            granter,
+            grantee,
+            authorization: Authorization::generic(authorization),
+            expiration,
+        }
+        .into()
+    }
+}
+
+/// Like [MsgGrantHelper], but supports typed authorizations such as spend limits.
+pub struct MsgGrantAuthorizationHelper {
+    /// Address granting permissions
+    pub granter: Address,
+    /// Address receiving permissions
+    pub grantee: Address,
+    /// Which features are being authorized
+    pub authorization: Authorization,
+    /// When the authorization expires
+    pub expiration: Option<DateTime<Utc>>,
+}
+
+impl From<MsgGrantAuthorizationHelper> for TxMessage {
+    fn from(
+        MsgGrantAuthorizationHelper {
+            granter,
+            grantee,
+            authorization,
+            expiration,
+        }: MsgGrantAuthorizationHelper,
     ) -> Self {
         let desc = format!(
             "{granter} grants {grantee} authorization for {authorization} until {expiration:?}"
         );
-        let authorization = GenericAuthorization { msg: authorization };
-        let authorization = prost_types::Any {
-            type_url: "/cosmos.authz.v1beta1.GenericAuthorization".to_owned(),
-            value: authorization.encode_to_vec(),
-        };
         let msg_grant = MsgGrant {
             granter: granter.get_address_string(),
             grantee: grantee.get_address_string(),
             grant: Some(Grant {
-                authorization: Some(authorization),
+                authorization: Some(authorization.into()),
                 expiration: expiration.map(datetime_to_timestamp),
             }),
         };
         TxMessage::new(
-            "/cosmos.authz.v1beta1.GenericAuthorization",
+            "/cosmos.authz.v1beta1.MsgGrant",
             msg_grant.encode_to_vec(),
             desc,
         )
     }
 }
 
-fn datetime_to_timestamp(x: DateTime<Utc>) -> Timestamp {
+/// A message for revoking a previously granted authorization.
+pub struct MsgRevokeHelper {
+    /// Address which granted the permissions
+    pub granter: Address,
+    /// Address losing the permissions
+    pub grantee: Address,
+    /// Type URL of the message the grant applies to
+    pub msg_type_url: String,
+}
+
+impl From<MsgRevokeHelper> for TxMessage {
+    fn from(
+        MsgRevokeHelper {
+            granter,
+            grantee,
+            msg_type_url,
+        }: MsgRevokeHelper,
+    ) -> Self {
+        let desc = format!("{granter} revokes {grantee} authorization for {msg_type_url}");
+        TxMessage::new(
+            "/cosmos.authz.v1beta1.MsgRevoke",
+            MsgRevoke {
+                granter: granter.get_address_string(),
+                grantee: grantee.get_address_string(),
+                msg_type_url,
+            }
+            .encode_to_vec(),
+            desc,
+        )
+    }
+}
+
+pub(crate) fn datetime_to_timestamp(x: DateTime<Utc>) -> Timestamp {
     prost_types::Timestamp {
         seconds: x.timestamp(),
         nanos: x
@@ -154,6 +212,23 @@ impl From<MsgInstantiateContract> for TxMessage {
     }
 }
 
+impl From<MsgInstantiateContract2> for TxMessage {
+    fn from(msg: MsgInstantiateContract2) -> Self {
+        TxMessage::new(
+            "/cosmwasm.wasm.v1.MsgInstantiateContract2",
+            msg.encode_to_vec(),
+            format!(
+                "{} instantiating code ID {} with label {}, salt {} and message: {}",
+                msg.sender,
+                msg.code_id,
+                msg.label,
+                hex::encode(&msg.salt),
+                StringOrBytes(msg.msg)
+            ),
+        )
+    }
+}
+
 impl From<MsgMigrateContract> for TxMessage {
     fn from(msg: MsgMigrateContract) -> Self {
         TxMessage::new(
@@ -225,3 +300,68 @@ impl Display for PrettyCoins<'_> {
         Ok(())
     }
 }
+
+//////////// GENERATED, COPY/PASTED, AND PATCHED FROM PROST-BUILD ////////////////
+
+/// MsgInstantiateContract2 create a new smart contract instance for the given
+/// code id with a predicable address.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MsgInstantiateContract2 {
+    /// Sender is the that actor that signed the messages
+    #[prost(string, tag = "1")]
+    pub sender: ::prost::alloc::string::String,
+    /// Admin is an optional address that can execute migrations
+    #[prost(string, tag = "2")]
+    pub admin: ::prost::alloc::string::String,
+    /// CodeID is the reference to the stored WASM code
+    #[prost(uint64, tag = "3")]
+    pub code_id: u64,
+    /// Label is optional metadata to be stored with a contract instance.
+    #[prost(string, tag = "4")]
+    pub label: ::prost::alloc::string::String,
+    /// Msg json encoded message to be passed to the contract on instantiation
+    #[prost(bytes = "vec", tag = "5")]
+    pub msg: ::prost::alloc::vec::Vec<u8>,
+    /// Funds coins that are transferred to the contract on instantiation
+    #[prost(message, repeated, tag = "6")]
+    pub funds: ::prost::alloc::vec::Vec<Coin>,
+    /// Salt is an arbitrary value provided by the sender. Size can be 1 to 64.
+    #[prost(bytes = "vec", tag = "7")]
+    pub salt: ::prost::alloc::vec::Vec<u8>,
+    /// FixMsg include the msg value into the hash for the predictable address.
+    /// Default is false
+    #[prost(bool, tag = "8")]
+    pub fix_msg: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn grant_type_url() {
+        let granter: Address = "osmo168gdk6r58jdwfv49kuesq2rs747jawnn4ryvyk"
+            .parse()
+            .unwrap();
+        let grantee: Address = "osmo1qg5ega6dykkxc307y25pecuufrjkxkaggkkxh7nad0vhyhtuhw3sqaa3c5"
+            .parse()
+            .unwrap();
+        let (any, _) = TxMessage::from(MsgGrantHelper {
+            granter,
+            grantee,
+            authorization: "/cosmwasm.wasm.v1.MsgExecuteContract".to_owned(),
+            expiration: None,
+        })
+        .into_protobuf();
+        assert_eq!(any.type_url, "/cosmos.authz.v1beta1.MsgGrant");
+        let msg = MsgGrant::decode(any.value.as_slice()).unwrap();
+        assert_eq!(msg.granter, granter.get_address_string());
+        assert_eq!(msg.grantee, grantee.get_address_string());
+        let authorization = msg.grant.unwrap().authorization.unwrap();
+        assert_eq!(
+            authorization.type_url,
+            "/cosmos.authz.v1beta1.GenericAuthorization"
+        );
+    }
+}
@@ -0,0 +1,442 @@
+//! Multisig accounts using legacy amino threshold public keys.
+//!
+//! The workflow is:
+//!
+//! 1. Build a [MultisigPublicKey] from the members' public keys and the threshold.
+//! 2. Decide which members will sign and create a [MultisigTx] with [Cosmos::make_multisig_tx].
+//! 3. Each signer signs the [MultisigTx] with [MultisigTx::sign], possibly on a different machine.
+//! 4. Combine the signatures and broadcast with [MultisigTx::broadcast].
+//!
+//! Since transactions are signed in direct mode, the set of signers is part of
+//! the signed data and must be decided before anyone signs.
+
+use bitcoin::hashes::{sha256, Hash};
+use cosmos_sdk_proto::{
+    cosmos::{
+        crypto::{
+            multisig::{
+                v1beta1::{CompactBitArray, MultiSignature},
+                LegacyAminoPubKey,
+            },
+            secp256k1::PubKey,
+        },
+        tx::{
+            signing::v1beta1::SignMode,
+            v1beta1::{
+                mode_info::{Multi, Single, Sum},
+                AuthInfo, Fee, ModeInfo, SignDoc, SignerInfo, Tx, TxBody,
+            },
+        },
+    },
+    Any,
+};
+use prost::Message;
+
+use crate::{
+    address::{AddressHrp, RawAddress},
+    error::MultisigError,
+    signature::{verify_sign_doc, SignatureSigner},
+    Address, Cosmos, CosmosTxResponse, PublicKeyMethod, TxBuilder, TxCostReport, Wallet,
+};
+
+/// Amino prefix for `tendermint/PubKeyMultisigThreshold`
+const AMINO_MULTISIG_PREFIX: [u8; 4] = [0x22, 0xc1, 0xf7, 0xe2];
+/// Amino prefix for `tendermint/PubKeySecp256k1`
+const AMINO_SECP256K1_PREFIX: [u8; 4] = [0xeb, 0x5a, 0xe9, 0x87];
+
+/// A threshold multisig public key, as used by `LegacyAminoPubKey` accounts.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MultisigPublicKey {
+    threshold: u32,
+    public_keys: Vec<[u8; 33]>,
+}
+
+impl MultisigPublicKey {
+    /// Create a multisig key from its members' compressed secp256k1 public keys.
+    ///
+    /// Order matters: the same keys in a different order produce a different
+    /// address.
+    pub fn new<T: AsRef<[u8]>>(
+        threshold: u32,
+        public_keys: impl IntoIterator<Item = T>,
+    ) -> Result<Self, MultisigError> {
+        let public_keys = public_keys
+            .into_iter()
+            .map(|public_key| {
+                public_key
+                    .as_ref()
+                    .try_into()
+                    .map_err(|_| MultisigError::InvalidPublicKey {
+                        public_key: hex::encode(public_key),
+                    })
+            })
+            .collect::<Result<Vec<[u8; 33]>, _>>()?;
+        if threshold == 0 || threshold as usize > public_keys.len() {
+            return Err(MultisigError::InvalidThreshold {
+                threshold,
+                key_count: public_keys.len(),
+            });
+        }
+        Ok(MultisigPublicKey {
+            threshold,
+            public_keys,
+        })
+    }
+
+    /// Number of signatures required
+    pub fn get_threshold(&self) -> u32 {
+        self.threshold
+    }
+
+    /// Public keys of the members, in order
+    pub fn get_public_keys(&self) -> &[[u8; 33]] {
+        &self.public_keys
+    }
+
+    /// The multisig account's address on the chain with the given HRP.
+    pub fn get_address(&self, hrp: AddressHrp) -> Address {
+        let hash = sha256::Hash::hash(&self.amino_bytes()).into_inner();
+        let mut raw = [0; 20];
+        raw.copy_from_slice(&hash[..20]);
+        RawAddress::from(raw).with_hrp(hrp)
+    }
+
+    /// Convert into the protobuf representation used in [SignerInfo].
+    pub fn to_any(&self) -> Any {
+        Any {
+            type_url: "/cosmos.crypto.multisig.LegacyAminoPubKey".to_owned(),
+            value: LegacyAminoPubKey {
+                threshold: self.threshold,
+                public_keys: self
+                    .public_keys
+                    .iter()
+                    .map(|key| Any {
+                        type_url: "/cosmos.crypto.secp256k1.PubKey".to_owned(),
+                        value: PubKey { key: key.to_vec() }.encode_to_vec(),
+                    })
+                    .collect(),
+            }
+            .encode_to_vec(),
+        }
+    }
+
+    /// The amino encoding, which the address is derived from.
+    fn amino_bytes(&self) -> Vec<u8> {
+        let mut bytes = AMINO_MULTISIG_PREFIX.to_vec();
+        bytes.push(0x08);
+        prost::encoding::encode_varint(self.threshold.into(), &mut bytes);
+        for key in &self.public_keys {
+            let mut encoded_key = AMINO_SECP256K1_PREFIX.to_vec();
+            prost::encoding::encode_varint(key.len() as u64, &mut encoded_key);
+            encoded_key.extend_from_slice(key);
+            bytes.push(0x12);
+            prost::encoding::encode_varint(encoded_key.len() as u64, &mut bytes);
+            bytes.append(&mut encoded_key);
+        }
+        bytes
+    }
+
+    fn index_of(&self, public_key: &[u8]) -> Result<usize, MultisigError> {
+        self.public_keys
+            .iter()
+            .position(|key| key == public_key)
+            .ok_or_else(|| MultisigError::NotAMember {
+                public_key: hex::encode(public_key),
+            })
+    }
+}
+
+/// A signature from one member of a multisig.
+#[derive(Clone, Debug)]
+pub struct MultisigSignature {
+    /// Compressed public key of the signer
+    pub public_key: Vec<u8>,
+    /// Compact signature over the [SignDoc]
+    pub signature: Vec<u8>,
+}
+
+/// An unsigned transaction from a multisig account, waiting for signatures.
+#[derive(Clone, Debug)]
+pub struct MultisigTx {
+    txbuilder: TxBuilder,
+    multisig: MultisigPublicKey,
+    /// Indices of the signing members, sorted
+    signers: Vec<usize>,
+    body: TxBody,
+    auth_info: AuthInfo,
+    sign_doc: SignDoc,
+}
+
+impl MultisigTx {
+    fn new<T: AsRef<[u8]>>(
+        txbuilder: &TxBuilder,
+        multisig: &MultisigPublicKey,
+        signers: &[T],
+        sign_doc: SignDoc,
+        fee: Fee,
+        sequence: u64,
+    ) -> Result<Self, MultisigError> {
+        let mut indices = signers
+            .iter()
+            .map(|signer| multisig.index_of(signer.as_ref()))
+            .collect::<Result<Vec<_>, _>>()?;
+        indices.sort_unstable();
+        indices.dedup();
+        if indices.len() < multisig.threshold as usize {
+            return Err(MultisigError::NotEnoughSigners {
+                signers: indices.len(),
+                threshold: multisig.threshold,
+            });
+        }
+
+        let mut bitarray = vec![0u8; (multisig.public_keys.len() + 7) / 8];
+        for index in &indices {
+            bitarray[index / 8] |= 1u8 << (7 - index % 8);
+        }
+        let single = ModeInfo {
+            sum: Some(Sum::Single(Single {
+                mode: SignMode::Direct as i32,
+            })),
+        };
+        let auth_info = AuthInfo {
+            signer_infos: vec![SignerInfo {
+                public_key: Some(multisig.to_any()),
+                mode_info: Some(ModeInfo {
+                    sum: Some(Sum::Multi(Multi {
+                        bitarray: Some(CompactBitArray {
+                            extra_bits_stored: (multisig.public_keys.len() % 8) as u32,
+                            elems: bitarray,
+                        }),
+                        mode_infos: indices.iter().map(|_| single.clone()).collect(),
+                    })),
+                }),
+                sequence,
+            }],
+            fee: Some(fee),
+        };
+        let body = TxBody::decode(&*sign_doc.body_bytes)
+            .expect("TxBody was encoded by us and must decode");
+        Ok(MultisigTx {
+            txbuilder: txbuilder.clone(),
+            multisig: multisig.clone(),
+            signers: indices,
+            body,
+            sign_doc: SignDoc {
+                auth_info_bytes: auth_info.encode_to_vec(),
+                ..sign_doc
+            },
+            auth_info,
+        })
+    }
+
+    /// The sign doc each signer must sign.
+    pub fn get_sign_doc(&self) -> &SignDoc {
+        &self.sign_doc
+    }
+
+    /// The multisig key this transaction is from
+    pub fn get_multisig(&self) -> &MultisigPublicKey {
+        &self.multisig
+    }
+
+    /// Sign the transaction with one of the members' wallets.
+    pub fn sign(&self, wallet: &Wallet) -> Result<MultisigSignature, MultisigError> {
+        let public_key = wallet.public_key_bytes();
+        let index = self.multisig.index_of(public_key)?;
+        if !self.signers.contains(&index) {
+            return Err(MultisigError::NotASigner {
+                public_key: hex::encode(public_key),
+            });
+        }
+        Ok(MultisigSignature {
+            public_key: public_key.to_vec(),
+            signature: wallet
+                .sign_bytes(&self.sign_doc.encode_to_vec())
+                .serialize_compact()
+                .to_vec(),
+        })
+    }
+
+    /// Combine signatures from all signers into a signed transaction.
+    ///
+    /// Each signature is verified before being included.
+    pub fn into_tx(self, signatures: &[MultisigSignature]) -> Result<Tx, MultisigError> {
+        let mut ordered = Vec::with_capacity(self.signers.len());
+        for index in &self.signers {
+            let public_key = &self.multisig.public_keys[*index];
+            let signature = signatures
+                .iter()
+                .find(|signature| signature.public_key == public_key)
+                .ok_or_else(|| MultisigError::MissingSignature {
+                    public_key: hex::encode(public_key),
+                })?;
+            verify_sign_doc(
+                SignatureSigner::PublicKey {
+                    public_key: public_key.to_vec(),
+                    method: PublicKeyMethod::Cosmos,
+                },
+                &self.sign_doc,
+                &signature.signature,
+            )
+            .map_err(|source| MultisigError::InvalidSignature {
+                public_key: hex::encode(public_key),
+                source,
+            })?;
+            ordered.push(signature.signature.clone());
+        }
+        Ok(Tx {
+            body: Some(self.body),
+            auth_info: Some(self.auth_info),
+            signatures: vec![MultiSignature {
+                signatures: ordered,
+            }
+            .encode_to_vec()],
+        })
+    }
+
+    /// Combine the signatures, broadcast, and wait for the transaction to complete.
+    pub async fn broadcast(
+        self,
+        cosmos: &Cosmos,
+        signatures: &[MultisigSignature],
+    ) -> Result<CosmosTxResponse, crate::Error> {
+        let txbuilder = self.txbuilder.clone();
+        let tx = self.into_tx(signatures)?;
+        let response = cosmos
+            .broadcast_tx_and_wait(tx.encode_to_vec(), &txbuilder)
+            .await?;
+        let cost = TxCostReport::new(&tx, &response);
+        Ok(CosmosTxResponse { response, tx, cost })
+    }
+}
+
+impl Cosmos {
+    /// Prepare a transaction from a multisig account for signing.
+    ///
+    /// `signers` are the public keys of the members who will sign, which must
+    /// meet the threshold. Gas is estimated by simulation, and the fee uses
+    /// the highest gas price since the fee cannot be increased later without
+    /// collecting new signatures.
+    pub async fn make_multisig_tx<T: AsRef<[u8]>>(
+        &self,
+        txbuilder: &TxBuilder,
+        multisig: &MultisigPublicKey,
+        signers: &[T],
+    ) -> Result<MultisigTx, crate::Error> {
+        let builder = self.get_cosmos_builder();
+        let address = multisig.get_address(builder.hrp());
+        let base_account = self.get_base_account(address).await?;
+        let simres = txbuilder.simulate(self, &[address]).await?;
+        let gas_limit = self.padded_gas(&simres.body, simres.gas_used);
+        let fee = Fee {
+            amount: vec![self.gas_to_fee(
+                txbuilder,
+                gas_limit,
+                builder.gas_price_retry_attempts(),
+            )?],
+            gas_limit,
+            payer: txbuilder.fee_payer_string(),
+            granter: txbuilder.fee_granter_string(),
+        };
+        let sign_doc = SignDoc {
+            body_bytes: simres.body.encode_to_vec(),
+            auth_info_bytes: vec![],
+            chain_id: builder.chain_id().to_owned(),
+            account_number: base_account.account_number,
+        };
+        Ok(MultisigTx::new(
+            txbuilder,
+            multisig,
+            signers,
+            sign_doc,
+            fee,
+            base_account.sequence,
+        )?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wallets() -> Vec<Wallet> {
+        (0..3)
+            .map(|_| Wallet::generate(AddressHrp::from_static("osmo")).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn invalid_threshold() {
+        let wallets = wallets();
+        let keys = wallets.iter().map(|w| w.public_key_bytes());
+        MultisigPublicKey::new(4, keys.clone()).unwrap_err();
+        MultisigPublicKey::new(0, keys.clone()).unwrap_err();
+        MultisigPublicKey::new(2, keys).unwrap();
+    }
+
+    #[test]
+    fn known_address() {
+        // Public keys for the secp256k1 private keys 1, 2 and 3
+        let keys = [
+            "0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798",
+            "02c6047f9441ed7d6d3045406e95c07cd85c778e4b8cef3ca7abac09b95c709ee5",
+            "02f9308a019258c31049344f85f89d5229b531c845836f99b08601f113bce036f9",
+        ]
+        .map(|key| hex::decode(key).unwrap());
+        let hrp = AddressHrp::from_static("cosmos");
+        let multisig = MultisigPublicKey::new(2, &keys).unwrap();
+        assert_eq!(
+            multisig.get_address(hrp).to_string(),
+            "cosmos16nsuts7ccq7c64tat6sm4uar2ammtwgv667hzc"
+        );
+        let reversed = MultisigPublicKey::new(2, keys.iter().rev()).unwrap();
+        assert_eq!(
+            reversed.get_address(hrp).to_string(),
+            "cosmos1zxk8rzxn2fzf8nvxt7fm883ttucwewq4gx07c2"
+        );
+    }
+
+    #[test]
+    fn sign_and_combine() {
+        let wallets = wallets();
+        let multisig =
+            MultisigPublicKey::new(2, wallets.iter().map(|w| w.public_key_bytes())).unwrap();
+        let sign_doc = SignDoc {
+            body_bytes: TxBody::default().encode_to_vec(),
+            auth_info_bytes: vec![],
+            chain_id: "osmosis-1".to_owned(),
+            account_number: 5,
+        };
+        let signers = [wallets[0].public_key_bytes(), wallets[2].public_key_bytes()];
+        let tx = MultisigTx::new(
+            &TxBuilder::default(),
+            &multisig,
+            &signers,
+            sign_doc,
+            Fee::default(),
+            0,
+        )
+        .unwrap();
+
+        let bitarray = match &tx.auth_info.signer_infos[0].mode_info {
+            Some(ModeInfo {
+                sum: Some(Sum::Multi(multi)),
+            }) => multi.bitarray.clone().unwrap(),
+            _ => panic!("Expected multi mode info"),
+        };
+        assert_eq!(bitarray.extra_bits_stored, 3);
+        assert_eq!(bitarray.elems, vec![0b1010_0000]);
+
+        tx.sign(&wallets[1]).unwrap_err();
+        let sig0 = tx.sign(&wallets[0]).unwrap();
+        let sig2 = tx.sign(&wallets[2]).unwrap();
+        tx.clone().into_tx(&[sig0.clone()]).unwrap_err();
+        let mut bad = sig2.clone();
+        bad.signature = sig0.signature.clone();
+        tx.clone().into_tx(&[sig0.clone(), bad]).unwrap_err();
+
+        let signed = tx.into_tx(&[sig2, sig0]).unwrap();
+        let multi = MultiSignature::decode(&*signed.signatures[0]).unwrap();
+        assert_eq!(multi.signatures.len(), 2);
+    }
+}
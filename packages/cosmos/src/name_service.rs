@@ -0,0 +1,227 @@
+//! Resolve human-readable names, like `alice.osmo`, to addresses.
+//!
+//! Two name services are supported:
+//!
+//! * [ICNS](https://www.icns.xyz/), which lives on Osmosis and maps a name to
+//!   an address on any chain, e.g. `alice.osmo` or `alice.juno`.
+//!
+//! * [Stargaze Names](https://www.stargaze.zone/names), which lives on
+//!   Stargaze and maps names like `alice.stars` to Stargaze addresses.
+//!
+//! The [Cosmos] value passed in must be connected to the chain hosting the
+//! name service contract, not necessarily the chain the address is used on.
+
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{error::Action, Address, Cosmos, HasAddress, HasAddressHrp};
+
+/// ICNS resolver contract on Osmosis mainnet.
+const ICNS_RESOLVER_MAINNET: &str =
+    "osmo1xk0s8xgktn9x5vwcgtjdxqzadg88fgn33p8u9cnpdxwemvxscvast52cdd";
+
+/// Stargaze Names contract on Stargaze mainnet.
+const STARGAZE_NAMES_MAINNET: &str =
+    "stars1fx74nkqkw2748av8j7ew7r3xt9cgjqduwn8m0ur5lhe49uhlsasszc5fhr";
+
+/// A name service contract to resolve names against.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NameService {
+    /// The ICNS resolver contract.
+    Icns {
+        /// Address of the resolver contract
+        resolver: Address,
+    },
+    /// The Stargaze Names contract.
+    StargazeNames {
+        /// Address of the names contract
+        contract: Address,
+    },
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "snake_case")]
+enum IcnsQuery<'a> {
+    Address {
+        name: &'a str,
+        bech32_prefix: &'a str,
+    },
+    PrimaryName {
+        address: String,
+    },
+}
+
+#[derive(Deserialize)]
+struct IcnsAddressResponse {
+    address: String,
+}
+
+#[derive(Deserialize)]
+struct IcnsPrimaryNameResponse {
+    name: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "snake_case")]
+enum StargazeQuery<'a> {
+    AssociatedAddress { name: &'a str },
+    Name { address: String },
+}
+
+impl NameService {
+    /// The ICNS resolver deployed on Osmosis mainnet.
+    pub fn icns_mainnet() -> Self {
+        NameService::Icns {
+            resolver: Address::from_str(ICNS_RESOLVER_MAINNET)
+                .expect("ICNS resolver address is valid"),
+        }
+    }
+
+    /// The Stargaze Names contract deployed on Stargaze mainnet.
+    pub fn stargaze_mainnet() -> Self {
+        NameService::StargazeNames {
+            contract: Address::from_str(STARGAZE_NAMES_MAINNET)
+                .expect("Stargaze Names address is valid"),
+        }
+    }
+
+    fn contract_address(&self) -> Address {
+        match self {
+            NameService::Icns { resolver } => *resolver,
+            NameService::StargazeNames { contract } => *contract,
+        }
+    }
+
+    /// Resolve a name such as `alice.osmo` to its address.
+    pub async fn resolve(&self, cosmos: &Cosmos, name: &str) -> Result<Address, crate::Error> {
+        let (label, suffix) = split_name(name)?;
+        let contract = cosmos.make_contract(self.contract_address());
+        let address = match self {
+            NameService::Icns { .. } => {
+                contract
+                    .query::<IcnsAddressResponse>(IcnsQuery::Address {
+                        name: label,
+                        bech32_prefix: suffix,
+                    })
+                    .await?
+                    .address
+            }
+            NameService::StargazeNames { .. } => {
+                if suffix != "stars" {
+                    return Err(crate::Error::InvalidName {
+                        name: name.to_owned(),
+                        reason: "Stargaze Names only supports the .stars suffix",
+                    });
+                }
+                contract
+                    .query::<String>(StargazeQuery::AssociatedAddress { name: label })
+                    .await?
+            }
+        };
+        address
+            .parse()
+            .map_err(|e| crate::Error::InvalidChainResponse {
+                message: format!("Name service returned invalid address {address:?}: {e}"),
+                action: Action::ResolveName(name.to_owned()),
+            })
+    }
+
+    /// Look up the primary name registered for an address.
+    ///
+    /// Returns [None] if the service reports an empty name. Note that some
+    /// contracts, such as Stargaze Names, instead fail the query when no name
+    /// is registered.
+    pub async fn reverse_lookup(
+        &self,
+        cosmos: &Cosmos,
+        address: impl HasAddress,
+    ) -> Result<Option<String>, crate::Error> {
+        let contract = cosmos.make_contract(self.contract_address());
+        let address = address.get_address();
+        let (name, suffix) = match self {
+            NameService::Icns { .. } => {
+                let res = contract
+                    .query::<IcnsPrimaryNameResponse>(IcnsQuery::PrimaryName {
+                        address: address.get_address_string(),
+                    })
+                    .await?;
+                (res.name, address.get_address_hrp().as_str())
+            }
+            NameService::StargazeNames { .. } => {
+                let name = contract
+                    .query::<String>(StargazeQuery::Name {
+                        address: address.get_address_string(),
+                    })
+                    .await?;
+                (name, "stars")
+            }
+        };
+        Ok(if name.is_empty() {
+            None
+        } else if name.contains('.') {
+            Some(name)
+        } else {
+            Some(format!("{name}.{suffix}"))
+        })
+    }
+
+    /// Accept either a plain address or a name to resolve.
+    ///
+    /// Useful for CLI arguments and payment flows accepting either form.
+    pub async fn resolve_recipient(
+        &self,
+        cosmos: &Cosmos,
+        recipient: &str,
+    ) -> Result<Address, crate::Error> {
+        match recipient.parse() {
+            Ok(address) => Ok(address),
+            Err(_) => self.resolve(cosmos, recipient).await,
+        }
+    }
+}
+
+fn split_name(name: &str) -> Result<(&str, &str), crate::Error> {
+    match name.rsplit_once('.') {
+        Some((label, suffix)) if !label.is_empty() && !suffix.is_empty() => Ok((label, suffix)),
+        _ => Err(crate::Error::InvalidName {
+            name: name.to_owned(),
+            reason: "expected a name of the form name.suffix",
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mainnet_contracts() {
+        NameService::icns_mainnet();
+        NameService::stargaze_mainnet();
+    }
+
+    #[test]
+    fn split_names() {
+        assert_eq!(split_name("alice.osmo").unwrap(), ("alice", "osmo"));
+        assert_eq!(
+            split_name("sub.alice.stars").unwrap(),
+            ("sub.alice", "stars")
+        );
+        split_name("alice").unwrap_err();
+        split_name(".osmo").unwrap_err();
+        split_name("alice.").unwrap_err();
+    }
+
+    #[test]
+    fn icns_query_json() {
+        assert_eq!(
+            serde_json::to_string(&IcnsQuery::Address {
+                name: "alice",
+                bech32_prefix: "osmo"
+            })
+            .unwrap(),
+            r#"{"address":{"name":"alice","bech32_prefix":"osmo"}}"#
+        );
+    }
+}
@@ -0,0 +1,310 @@
+//! Offline signing, for keys which are kept off of networked machines.
+//!
+//! The workflow is split into separate steps:
+//!
+//! 1. On a networked machine, look up the account number and sequence with
+//!    [Cosmos::get_base_account] and build an [UnsignedTx] with
+//!    [TxBuilder::make_unsigned_tx].
+//! 2. Move the [UnsignedTx], e.g. as JSON, to the offline machine and sign it
+//!    with [UnsignedTx::sign].
+//! 3. Move the resulting [SignedTx] back and broadcast it with
+//!    [Cosmos::broadcast_signed_tx].
+
+use bitcoin::secp256k1::PublicKey;
+use cosmos_sdk_proto::cosmos::{
+    base::{abci::v1beta1::TxResponse, v1beta1::Coin},
+    tx::v1beta1::{AuthInfo, Fee, SignDoc, TxBody, TxRaw},
+};
+use prost::Message;
+
+use crate::{
+    error::{SignatureError, TxDecodeError},
+    wallet::WalletPublicKey,
+    AddressHrp, Cosmos, PublicKeyMethod, TxBuilder, TxMessage, Wallet,
+};
+
+/// Values normally looked up from the chain while signing, provided explicitly instead.
+#[derive(Clone, Debug)]
+pub struct OfflineTxParams {
+    /// Chain ID the transaction is for
+    pub chain_id: String,
+    /// Account number of the signer
+    pub account_number: u64,
+    /// Sequence number to sign with
+    pub sequence: u64,
+    /// Gas limit for the transaction
+    pub gas_limit: u64,
+    /// Fee to pay
+    pub fee: Vec<Coin>,
+}
+
+/// A transaction waiting to be signed, equivalent to a direct mode [SignDoc].
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct UnsignedTx {
+    /// Protobuf encoded [TxBody]
+    #[serde(with = "base64_bytes")]
+    pub body_bytes: Vec<u8>,
+    /// Protobuf encoded [AuthInfo]
+    #[serde(with = "base64_bytes")]
+    pub auth_info_bytes: Vec<u8>,
+    /// Chain ID the transaction is for
+    pub chain_id: String,
+    /// Account number of the signer
+    pub account_number: u64,
+}
+
+impl From<SignDoc> for UnsignedTx {
+    fn from(
+        SignDoc {
+            body_bytes,
+            auth_info_bytes,
+            chain_id,
+            account_number,
+        }: SignDoc,
+    ) -> Self {
+        UnsignedTx {
+            body_bytes,
+            auth_info_bytes,
+            chain_id,
+            account_number,
+        }
+    }
+}
+
+impl From<UnsignedTx> for SignDoc {
+    fn from(
+        UnsignedTx {
+            body_bytes,
+            auth_info_bytes,
+            chain_id,
+            account_number,
+        }: UnsignedTx,
+    ) -> Self {
+        SignDoc {
+            body_bytes,
+            auth_info_bytes,
+            chain_id,
+            account_number,
+        }
+    }
+}
+
+impl UnsignedTx {
+    /// Encode as a protobuf [SignDoc].
+    pub fn to_protobuf_bytes(&self) -> Vec<u8> {
+        SignDoc::from(self.clone()).encode_to_vec()
+    }
+
+    /// Decode from a protobuf [SignDoc].
+    pub fn from_protobuf_bytes(bytes: &[u8]) -> Result<Self, TxDecodeError> {
+        SignDoc::decode(bytes)
+            .map(UnsignedTx::from)
+            .map_err(|source| TxDecodeError::InvalidProtobuf { source })
+    }
+
+    /// Sign the transaction.
+    ///
+    /// The wallet must be the one whose public key was used in
+    /// [TxBuilder::make_unsigned_tx], otherwise the chain will reject the
+    /// transaction.
+    pub fn sign(&self, wallet: &Wallet) -> SignedTx {
+        let signature = wallet.sign_bytes(&self.to_protobuf_bytes());
+        SignedTx {
+            body_bytes: self.body_bytes.clone(),
+            auth_info_bytes: self.auth_info_bytes.clone(),
+            signatures: vec![signature.serialize_compact().to_vec()],
+        }
+    }
+}
+
+/// A signed transaction ready to broadcast, equivalent to a [TxRaw].
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct SignedTx {
+    /// Protobuf encoded [TxBody]
+    #[serde(with = "base64_bytes")]
+    pub body_bytes: Vec<u8>,
+    /// Protobuf encoded [AuthInfo]
+    #[serde(with = "base64_bytes")]
+    pub auth_info_bytes: Vec<u8>,
+    /// One signature per signer
+    #[serde(with = "base64_bytes_vec")]
+    pub signatures: Vec<Vec<u8>>,
+}
+
+impl SignedTx {
+    /// Encode as a protobuf [TxRaw], the format broadcast to the chain.
+    pub fn to_protobuf_bytes(&self) -> Vec<u8> {
+        TxRaw {
+            body_bytes: self.body_bytes.clone(),
+            auth_info_bytes: self.auth_info_bytes.clone(),
+            signatures: self.signatures.clone(),
+        }
+        .encode_to_vec()
+    }
+
+    /// Decode from a protobuf [TxRaw].
+    pub fn from_protobuf_bytes(bytes: &[u8]) -> Result<Self, TxDecodeError> {
+        let TxRaw {
+            body_bytes,
+            auth_info_bytes,
+            signatures,
+        } = TxRaw::decode(bytes).map_err(|source| TxDecodeError::InvalidProtobuf { source })?;
+        Ok(SignedTx {
+            body_bytes,
+            auth_info_bytes,
+            signatures,
+        })
+    }
+}
+
+impl TxBuilder {
+    /// Build an unsigned transaction without contacting the chain.
+    ///
+    /// The public key may be in compressed or uncompressed form, and is
+    /// encoded based on the HRP's [AddressHrp::default_public_key_method].
+    pub fn make_unsigned_tx(
+        &self,
+        public_key: &[u8],
+        hrp: AddressHrp,
+        OfflineTxParams {
+            chain_id,
+            account_number,
+            sequence,
+            gas_limit,
+            fee,
+        }: OfflineTxParams,
+    ) -> Result<UnsignedTx, SignatureError> {
+        let public_key = PublicKey::from_slice(public_key)
+            .map_err(|source| SignatureError::InvalidPublicKey { source })?;
+        let public_key = match hrp.default_public_key_method() {
+            PublicKeyMethod::Cosmos => WalletPublicKey::Cosmos(public_key.serialize()),
+            PublicKeyMethod::Ethereum => {
+                WalletPublicKey::Ethereum(public_key.serialize_uncompressed())
+            }
+        };
+        let auth_info = AuthInfo {
+            signer_infos: vec![self.make_signer_info(sequence, Some((&public_key, hrp)))],
+            fee: Some(Fee {
+                amount: fee,
+                gas_limit,
+                payer: self.fee_payer_string(),
+                granter: self.fee_granter_string(),
+            }),
+        };
+        Ok(UnsignedTx {
+            body_bytes: self.make_tx_body().encode_to_vec(),
+            auth_info_bytes: auth_info.encode_to_vec(),
+            chain_id,
+            account_number,
+        })
+    }
+}
+
+impl Cosmos {
+    /// Broadcast a transaction signed elsewhere and wait for it to complete.
+    ///
+    /// The bytes are broadcast exactly as signed.
+    pub async fn broadcast_signed_tx(&self, tx: &SignedTx) -> Result<TxResponse, crate::Error> {
+        // Only used to describe the transaction in errors
+        let mut txbuilder = TxBuilder::default();
+        if let Ok(body) = TxBody::decode(&*tx.body_bytes) {
+            for msg in body.messages {
+                let description = format!("Pre-signed {}", msg.type_url);
+                txbuilder.add_message(TxMessage::new(msg.type_url, msg.value, description));
+            }
+            txbuilder.set_optional_memo(Some(body.memo).filter(|memo| !memo.is_empty()));
+        }
+        self.broadcast_tx_and_wait(tx.to_protobuf_bytes(), &txbuilder)
+            .await
+    }
+}
+
+mod base64_bytes {
+    use base64::Engine;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub(super) fn serialize<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&base64::engine::general_purpose::STANDARD.encode(bytes))
+    }
+
+    pub(super) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<u8>, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        base64::engine::general_purpose::STANDARD
+            .decode(s)
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+mod base64_bytes_vec {
+    use base64::Engine;
+    use serde::{ser::SerializeSeq, Deserialize, Deserializer, Serializer};
+
+    pub(super) fn serialize<S: Serializer>(
+        values: &[Vec<u8>],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let mut seq = serializer.serialize_seq(Some(values.len()))?;
+        for bytes in values {
+            seq.serialize_element(&base64::engine::general_purpose::STANDARD.encode(bytes))?;
+        }
+        seq.end()
+    }
+
+    pub(super) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<Vec<u8>>, D::Error> {
+        Vec::<String>::deserialize(deserializer)?
+            .into_iter()
+            .map(|s| {
+                base64::engine::general_purpose::STANDARD
+                    .decode(s)
+                    .map_err(serde::de::Error::custom)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cosmos_sdk_proto::cosmos::bank::v1beta1::MsgSend;
+
+    use crate::{signature::verify_sign_doc, HasAddress};
+
+    use super::*;
+
+    #[test]
+    fn sign_offline() {
+        let hrp = AddressHrp::from_static("osmo");
+        let wallet = Wallet::generate(hrp).unwrap();
+        let mut txbuilder = TxBuilder::default();
+        txbuilder.add_message(MsgSend {
+            from_address: wallet.get_address_string(),
+            to_address: wallet.get_address_string(),
+            amount: vec![],
+        });
+        let unsigned = txbuilder
+            .make_unsigned_tx(
+                wallet.public_key_bytes(),
+                hrp,
+                OfflineTxParams {
+                    chain_id: "osmosis-1".to_owned(),
+                    account_number: 12,
+                    sequence: 3,
+                    gas_limit: 200_000,
+                    fee: vec![],
+                },
+            )
+            .unwrap();
+
+        let json = serde_json::to_string(&unsigned).unwrap();
+        let unsigned: UnsignedTx = serde_json::from_str(&json).unwrap();
+        let signed = unsigned.sign(&wallet);
+        let signed = SignedTx::from_protobuf_bytes(&signed.to_protobuf_bytes()).unwrap();
+
+        verify_sign_doc(&wallet, &unsigned.into(), &signed.signatures[0]).unwrap();
+        let auth_info = AuthInfo::decode(&*signed.auth_info_bytes).unwrap();
+        assert_eq!(auth_info.signer_infos[0].sequence, 3);
+    }
+}
@@ -8,11 +8,28 @@ use crate::{
     Cosmos,
 };
 
+#[cfg(feature = "osmosis")]
+pub(crate) mod concentrated_liquidity;
 pub(crate) mod epochs;
+#[cfg(feature = "osmosis")]
+pub(crate) mod lockup;
+#[cfg(feature = "osmosis")]
+pub(crate) mod poolmanager;
+#[cfg(feature = "osmosis")]
+pub(crate) mod twap;
 
 use chrono::{DateTime, Utc};
+#[cfg(feature = "osmosis")]
+pub use concentrated_liquidity::{
+    FullPositionBreakdown, LiquidityDepthWithRange, MsgCreatePosition, MsgWithdrawPosition,
+    Position,
+};
 pub use epochs::EpochInfo;
 use parking_lot::RwLock;
+#[cfg(feature = "osmosis")]
+pub use poolmanager::{
+    MsgSplitRouteSwapExactAmountIn, MsgSwapExactAmountIn, SwapAmountInRoute, SwapAmountInSplitRoute,
+};
 use prost_types::Timestamp;
 
 impl Cosmos {
@@ -30,6 +47,29 @@ impl Cosmos {
             epochs: res.into_inner().epochs,
         })
     }
+
+    /// Get all Osmosis epochs, such as `day` and `week`.
+    ///
+    /// Like [Cosmos::get_osmosis_epoch_info], but without the summary helpers.
+    pub async fn epoch_infos(&self) -> Result<Vec<EpochInfo>, crate::Error> {
+        Ok(self.get_osmosis_epoch_info().await?.epochs)
+    }
+
+    /// Get the current epoch number for the given epoch identifier.
+    pub async fn current_epoch(&self, identifier: impl Into<String>) -> Result<i64, crate::Error> {
+        let identifier = identifier.into();
+        Ok(self
+            .perform_query(
+                epochs::QueryCurrentEpochRequest {
+                    identifier: identifier.clone(),
+                },
+                Action::OsmosisCurrentEpoch(identifier),
+                true,
+            )
+            .await?
+            .into_inner()
+            .current_epoch)
+    }
 }
 
 /// Information on epochs from an Osmosis chain.
@@ -194,3 +234,38 @@ impl WeakCosmos {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{AddressHrp, CosmosBuilder, FixtureMode};
+
+    #[tokio::test]
+    async fn epoch_infos_from_fixture() {
+        let path = std::env::temp_dir().join(format!("cosmos-epochs-{}.json", std::process::id()));
+        // EpochInfo { identifier: "day", current_epoch: 5, .. }
+        std::fs::write(
+            &path,
+            r#"{"interactions":[{
+                "method":"/osmosis.epochs.v1beta1.Query/EpochInfos",
+                "height":null,
+                "body":"",
+                "response":"0a070a036461792005"
+            }]}"#,
+        )
+        .unwrap();
+        let mut builder = CosmosBuilder::new(
+            "osmosis-1",
+            "uosmo",
+            AddressHrp::from_static("osmo"),
+            "http://127.0.0.1:1",
+        );
+        builder.set_fixture(Some(FixtureMode::Replay(path.clone())));
+        let cosmos = builder.build_lazy().unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let epochs = cosmos.epoch_infos().await.unwrap();
+        assert_eq!(epochs.len(), 1);
+        assert_eq!(epochs[0].identifier, "day");
+        assert_eq!(epochs[0].current_epoch, 5);
+    }
+}
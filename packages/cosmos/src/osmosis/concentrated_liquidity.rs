@@ -0,0 +1,334 @@
+//! Osmosis concentrated liquidity positions.
+//!
+//! Not included in our version of the protobuf definitions.
+
+use cosmos_sdk_proto::cosmos::base::{
+    query::v1beta1::{PageRequest, PageResponse},
+    v1beta1::Coin,
+};
+use futures_util::TryStreamExt;
+use prost::Message;
+
+use crate::{error::Action, Cosmos, HasAddress, TxMessage};
+
+/// A concentrated liquidity position.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Position {
+    /// Position ID
+    #[prost(uint64, tag = "1")]
+    pub position_id: u64,
+    /// Owner of the position
+    #[prost(string, tag = "2")]
+    pub address: ::prost::alloc::string::String,
+    /// Pool the position belongs to
+    #[prost(uint64, tag = "3")]
+    pub pool_id: u64,
+    /// Lower tick of the position's range
+    #[prost(int64, tag = "4")]
+    pub lower_tick: i64,
+    /// Upper tick of the position's range
+    #[prost(int64, tag = "5")]
+    pub upper_tick: i64,
+    /// When the position was created
+    #[prost(message, optional, tag = "6")]
+    pub join_time: ::core::option::Option<::prost_types::Timestamp>,
+    /// Liquidity of the position, as a decimal
+    #[prost(string, tag = "7")]
+    pub liquidity: ::prost::alloc::string::String,
+}
+/// A position together with its underlying assets and rewards.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct FullPositionBreakdown {
+    /// The position
+    #[prost(message, optional, tag = "1")]
+    pub position: ::core::option::Option<Position>,
+    /// Amount of the pool's first asset held by the position
+    #[prost(message, optional, tag = "2")]
+    pub asset0: ::core::option::Option<Coin>,
+    /// Amount of the pool's second asset held by the position
+    #[prost(message, optional, tag = "3")]
+    pub asset1: ::core::option::Option<Coin>,
+    /// Spread rewards which can be claimed
+    #[prost(message, repeated, tag = "4")]
+    pub claimable_spread_rewards: ::prost::alloc::vec::Vec<Coin>,
+    /// Incentives which can be claimed
+    #[prost(message, repeated, tag = "5")]
+    pub claimable_incentives: ::prost::alloc::vec::Vec<Coin>,
+    /// Incentives which would be forfeited by withdrawing now
+    #[prost(message, repeated, tag = "6")]
+    pub forfeited_incentives: ::prost::alloc::vec::Vec<Coin>,
+}
+/// Liquidity available within a tick range.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct LiquidityDepthWithRange {
+    /// Liquidity in the range, as a decimal
+    #[prost(string, tag = "1")]
+    pub liquidity_amount: ::prost::alloc::string::String,
+    /// Lower tick of the range
+    #[prost(int64, tag = "2")]
+    pub lower_tick: i64,
+    /// Upper tick of the range
+    #[prost(int64, tag = "3")]
+    pub upper_tick: i64,
+}
+/// Open a new position within a tick range.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MsgCreatePosition {
+    /// Pool to provide liquidity to
+    #[prost(uint64, tag = "1")]
+    pub pool_id: u64,
+    /// Owner of the new position
+    #[prost(string, tag = "2")]
+    pub sender: ::prost::alloc::string::String,
+    /// Lower tick of the position's range
+    #[prost(int64, tag = "3")]
+    pub lower_tick: i64,
+    /// Upper tick of the position's range
+    #[prost(int64, tag = "4")]
+    pub upper_tick: i64,
+    /// Maximum amount of each asset to deposit
+    #[prost(message, repeated, tag = "5")]
+    pub tokens_provided: ::prost::alloc::vec::Vec<Coin>,
+    /// Minimum amount of the first asset to deposit
+    #[prost(string, tag = "6")]
+    pub token_min_amount0: ::prost::alloc::string::String,
+    /// Minimum amount of the second asset to deposit
+    #[prost(string, tag = "7")]
+    pub token_min_amount1: ::prost::alloc::string::String,
+}
+/// Withdraw some or all liquidity from a position.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MsgWithdrawPosition {
+    /// Position to withdraw from
+    #[prost(uint64, tag = "1")]
+    pub position_id: u64,
+    /// Owner of the position
+    #[prost(string, tag = "2")]
+    pub sender: ::prost::alloc::string::String,
+    /// Liquidity to withdraw, as a decimal
+    #[prost(string, tag = "3")]
+    pub liquidity_amount: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub(crate) struct UserPositionsRequest {
+    #[prost(string, tag = "1")]
+    pub(crate) address: ::prost::alloc::string::String,
+    #[prost(uint64, tag = "2")]
+    pub(crate) pool_id: u64,
+    #[prost(message, optional, tag = "3")]
+    pub(crate) pagination: ::core::option::Option<PageRequest>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub(crate) struct UserPositionsResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub(crate) positions: ::prost::alloc::vec::Vec<FullPositionBreakdown>,
+    #[prost(message, optional, tag = "2")]
+    pub(crate) pagination: ::core::option::Option<PageResponse>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub(crate) struct LiquidityPerTickRangeRequest {
+    #[prost(uint64, tag = "1")]
+    pub(crate) pool_id: u64,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub(crate) struct LiquidityPerTickRangeResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub(crate) liquidity: ::prost::alloc::vec::Vec<LiquidityDepthWithRange>,
+    #[prost(int64, tag = "2")]
+    pub(crate) bucket_index: i64,
+}
+
+impl From<MsgCreatePosition> for TxMessage {
+    fn from(msg: MsgCreatePosition) -> Self {
+        TxMessage::new(
+            "/osmosis.concentratedliquidity.v1beta1.MsgCreatePosition",
+            msg.encode_to_vec(),
+            format!(
+                "{} creating position in pool {} between ticks {} and {}",
+                msg.sender, msg.pool_id, msg.lower_tick, msg.upper_tick
+            ),
+        )
+    }
+}
+
+impl From<MsgWithdrawPosition> for TxMessage {
+    fn from(msg: MsgWithdrawPosition) -> Self {
+        TxMessage::new(
+            "/osmosis.concentratedliquidity.v1beta1.MsgWithdrawPosition",
+            msg.encode_to_vec(),
+            format!(
+                "{} withdrawing {} liquidity from position {}",
+                msg.sender, msg.liquidity_amount, msg.position_id
+            ),
+        )
+    }
+}
+
+impl Cosmos {
+    /// Concentrated liquidity positions owned by the given address.
+    ///
+    /// Pass a `pool_id` to only include positions in that pool.
+    pub async fn osmosis_cl_positions(
+        &self,
+        owner: impl HasAddress,
+        pool_id: Option<u64>,
+    ) -> Result<Vec<FullPositionBreakdown>, crate::Error> {
+        let owner = owner.get_address();
+        self.paginate(
+            Action::OsmosisClPositions(owner),
+            move |pagination| UserPositionsRequest {
+                address: owner.get_address_string(),
+                pool_id: pool_id.unwrap_or_default(),
+                pagination,
+            },
+            |res| (res.positions, res.pagination),
+        )
+        .try_collect()
+        .await
+    }
+
+    /// Liquidity of a concentrated liquidity pool, broken down by tick range.
+    pub async fn osmosis_cl_pool_liquidity(
+        &self,
+        pool_id: u64,
+    ) -> Result<Vec<LiquidityDepthWithRange>, crate::Error> {
+        Ok(self
+            .perform_query(
+                LiquidityPerTickRangeRequest { pool_id },
+                Action::OsmosisClPoolLiquidity(pool_id),
+                true,
+            )
+            .await?
+            .into_inner()
+            .liquidity)
+    }
+}
+
+/// Client for the concentrated liquidity query service.
+pub(crate) mod query_client {
+    #![allow(unused_variables, dead_code, missing_docs, clippy::let_unit_value)]
+    use tonic::codegen::*;
+    #[derive(Debug, Clone)]
+    pub(crate) struct QueryClient<T> {
+        inner: tonic::client::Grpc<T>,
+    }
+    impl<T> QueryClient<T>
+    where
+        T: tonic::client::GrpcService<tonic::body::BoxBody>,
+        T::Error: Into<StdError>,
+        T::ResponseBody: Body<Data = Bytes> + Send + 'static,
+        <T::ResponseBody as Body>::Error: Into<StdError> + Send,
+    {
+        pub(crate) fn new(inner: T) -> Self {
+            let inner = tonic::client::Grpc::new(inner);
+            Self { inner }
+        }
+        /// Returns the positions owned by an address
+        pub(crate) async fn user_positions(
+            &mut self,
+            request: impl tonic::IntoRequest<super::UserPositionsRequest>,
+        ) -> Result<tonic::Response<super::UserPositionsResponse>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(
+                    tonic::Code::Unknown,
+                    format!("Service was not ready: {}", e.into()),
+                )
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/osmosis.concentratedliquidity.v1beta1.Query/UserPositions",
+            );
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+        /// Returns the liquidity of a pool per tick range
+        pub(crate) async fn liquidity_per_tick_range(
+            &mut self,
+            request: impl tonic::IntoRequest<super::LiquidityPerTickRangeRequest>,
+        ) -> Result<tonic::Response<super::LiquidityPerTickRangeResponse>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(
+                    tonic::Code::Unknown,
+                    format!("Service was not ready: {}", e.into()),
+                )
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/osmosis.concentratedliquidity.v1beta1.Query/LiquidityPerTickRange",
+            );
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn user_positions_decoding() {
+        // Field numbers from osmosis/concentratedliquidity/v1beta1/query.proto
+        let bytes = hex::decode(
+            "0a4f0a28080712066f736d6f31781801209cffffffffffffffff0128c80132060880e2cfaa06\
+             3a0431302e35120c0a05756f736d6f12033130301a090a0475696f6e120135220a0a05756f\
+             736d6f12013112060a046e657874",
+        )
+        .unwrap();
+        let res = UserPositionsResponse::decode(bytes.as_slice()).unwrap();
+        let coin = |denom: &str, amount: &str| Coin {
+            denom: denom.to_owned(),
+            amount: amount.to_owned(),
+        };
+        assert_eq!(
+            res.positions,
+            vec![FullPositionBreakdown {
+                position: Some(Position {
+                    position_id: 7,
+                    address: "osmo1x".to_owned(),
+                    pool_id: 1,
+                    lower_tick: -100,
+                    upper_tick: 200,
+                    join_time: Some(prost_types::Timestamp {
+                        seconds: 1_700_000_000,
+                        nanos: 0,
+                    }),
+                    liquidity: "10.5".to_owned(),
+                }),
+                asset0: Some(coin("uosmo", "100")),
+                asset1: Some(coin("uion", "5")),
+                claimable_spread_rewards: vec![coin("uosmo", "1")],
+                claimable_incentives: vec![],
+                forfeited_incentives: vec![],
+            }]
+        );
+        assert_eq!(res.pagination.unwrap().next_key, b"next");
+    }
+
+    #[test]
+    fn position_messages() {
+        let msg = MsgWithdrawPosition {
+            position_id: 7,
+            sender: "osmo1x".to_owned(),
+            liquidity_amount: "10.5".to_owned(),
+        };
+        let decoded = MsgWithdrawPosition::decode(msg.encode_to_vec().as_slice()).unwrap();
+        assert_eq!(decoded, msg);
+        let (any, description) = TxMessage::from(msg).into_protobuf();
+        assert_eq!(
+            any.type_url,
+            "/osmosis.concentratedliquidity.v1beta1.MsgWithdrawPosition"
+        );
+        assert_eq!(
+            description,
+            "osmo1x withdrawing 10.5 liquidity from position 7"
+        );
+    }
+}
@@ -0,0 +1,324 @@
+//! Osmosis poolmanager swap messages and queries.
+//!
+//! Not included in our version of the protobuf definitions.
+
+use cosmos_sdk_proto::cosmos::base::v1beta1::Coin;
+use prost::Message;
+
+use crate::{
+    error::{Action, ChainParseError},
+    Cosmos, TxMessage,
+};
+
+/// One hop of a swap route: the pool to swap through and the denom received.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SwapAmountInRoute {
+    /// Pool to swap through
+    #[prost(uint64, tag = "1")]
+    pub pool_id: u64,
+    /// Denom received from this pool
+    #[prost(string, tag = "2")]
+    pub token_out_denom: ::prost::alloc::string::String,
+}
+/// Swap an exact input amount through a route of one or more pools.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MsgSwapExactAmountIn {
+    /// Address performing the swap
+    #[prost(string, tag = "1")]
+    pub sender: ::prost::alloc::string::String,
+    /// Pools to swap through, in order
+    #[prost(message, repeated, tag = "2")]
+    pub routes: ::prost::alloc::vec::Vec<SwapAmountInRoute>,
+    /// Tokens to swap
+    #[prost(message, optional, tag = "3")]
+    pub token_in: ::core::option::Option<Coin>,
+    /// Minimum output amount, the swap fails if less would be received
+    #[prost(string, tag = "4")]
+    pub token_out_min_amount: ::prost::alloc::string::String,
+}
+/// A portion of the input amount and the route it is swapped through.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SwapAmountInSplitRoute {
+    /// Pools to swap through, in order
+    #[prost(message, repeated, tag = "1")]
+    pub pools: ::prost::alloc::vec::Vec<SwapAmountInRoute>,
+    /// Amount of the input denom to swap through this route
+    #[prost(string, tag = "2")]
+    pub token_in_amount: ::prost::alloc::string::String,
+}
+/// Swap an exact input amount split across several routes.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MsgSplitRouteSwapExactAmountIn {
+    /// Address performing the swap
+    #[prost(string, tag = "1")]
+    pub sender: ::prost::alloc::string::String,
+    /// Routes to swap through
+    #[prost(message, repeated, tag = "2")]
+    pub routes: ::prost::alloc::vec::Vec<SwapAmountInSplitRoute>,
+    /// Denom being swapped
+    #[prost(string, tag = "3")]
+    pub token_in_denom: ::prost::alloc::string::String,
+    /// Minimum total output amount, the swap fails if less would be received
+    #[prost(string, tag = "4")]
+    pub token_out_min_amount: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub(crate) struct EstimateSwapExactAmountInRequest {
+    #[prost(string, tag = "3")]
+    pub(crate) token_in: ::prost::alloc::string::String,
+    #[prost(message, repeated, tag = "4")]
+    pub(crate) routes: ::prost::alloc::vec::Vec<SwapAmountInRoute>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub(crate) struct EstimateSwapExactAmountInResponse {
+    #[prost(string, tag = "1")]
+    pub(crate) token_out_amount: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub(crate) struct SpotPriceRequest {
+    #[prost(uint64, tag = "1")]
+    pub(crate) pool_id: u64,
+    #[prost(string, tag = "2")]
+    pub(crate) base_asset_denom: ::prost::alloc::string::String,
+    #[prost(string, tag = "3")]
+    pub(crate) quote_asset_denom: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub(crate) struct SpotPriceResponse {
+    #[prost(string, tag = "1")]
+    pub(crate) spot_price: ::prost::alloc::string::String,
+}
+
+impl From<MsgSwapExactAmountIn> for TxMessage {
+    fn from(msg: MsgSwapExactAmountIn) -> Self {
+        TxMessage::new(
+            "/osmosis.poolmanager.v1beta1.MsgSwapExactAmountIn",
+            msg.encode_to_vec(),
+            format!(
+                "{} swapping {} through pools {:?} for at least {}{}",
+                msg.sender,
+                msg.token_in
+                    .as_ref()
+                    .map(|coin| format!("{}{}", coin.amount, coin.denom))
+                    .unwrap_or_default(),
+                msg.routes
+                    .iter()
+                    .map(|route| route.pool_id)
+                    .collect::<Vec<_>>(),
+                msg.token_out_min_amount,
+                msg.routes
+                    .last()
+                    .map_or("", |route| route.token_out_denom.as_str()),
+            ),
+        )
+    }
+}
+
+impl From<MsgSplitRouteSwapExactAmountIn> for TxMessage {
+    fn from(msg: MsgSplitRouteSwapExactAmountIn) -> Self {
+        TxMessage::new(
+            "/osmosis.poolmanager.v1beta1.MsgSplitRouteSwapExactAmountIn",
+            msg.encode_to_vec(),
+            format!(
+                "{} swapping {} across {} routes for at least {}",
+                msg.sender,
+                msg.token_in_denom,
+                msg.routes.len(),
+                msg.token_out_min_amount,
+            ),
+        )
+    }
+}
+
+impl Cosmos {
+    /// Estimate the output of swapping `token_in` through the given route.
+    pub async fn osmosis_estimate_swap_exact_amount_in(
+        &self,
+        token_in: &Coin,
+        routes: Vec<SwapAmountInRoute>,
+    ) -> Result<u128, crate::Error> {
+        let action = Action::OsmosisEstimateSwap {
+            token_in: format!("{}{}", token_in.amount, token_in.denom),
+            pools: routes.iter().map(|route| route.pool_id).collect(),
+        };
+        let amount = self
+            .perform_query(
+                EstimateSwapExactAmountInRequest {
+                    token_in: format!("{}{}", token_in.amount, token_in.denom),
+                    routes,
+                },
+                action.clone(),
+                true,
+            )
+            .await?
+            .into_inner()
+            .token_out_amount;
+        amount.parse().map_err(|_| crate::Error::ChainParse {
+            source: Box::new(ChainParseError::InvalidNumber { value: amount }),
+            action,
+        })
+    }
+
+    /// Spot price of the base denom in terms of the quote denom for a single pool.
+    pub async fn osmosis_spot_price(
+        &self,
+        pool_id: u64,
+        base: impl Into<String>,
+        quote: impl Into<String>,
+    ) -> Result<f64, crate::Error> {
+        let req = SpotPriceRequest {
+            pool_id,
+            base_asset_denom: base.into(),
+            quote_asset_denom: quote.into(),
+        };
+        let action = Action::OsmosisSpotPrice {
+            pool_id,
+            base: req.base_asset_denom.clone(),
+            quote: req.quote_asset_denom.clone(),
+        };
+        let price = self
+            .perform_query(req, action.clone(), true)
+            .await?
+            .into_inner()
+            .spot_price;
+        crate::client::parse_dec(&price).ok_or_else(|| crate::Error::ChainParse {
+            source: Box::new(ChainParseError::InvalidNumber { value: price }),
+            action,
+        })
+    }
+}
+
+/// Client for the poolmanager query service.
+pub(crate) mod query_client {
+    #![allow(unused_variables, dead_code, missing_docs, clippy::let_unit_value)]
+    use tonic::codegen::*;
+    #[derive(Debug, Clone)]
+    pub(crate) struct QueryClient<T> {
+        inner: tonic::client::Grpc<T>,
+    }
+    impl<T> QueryClient<T>
+    where
+        T: tonic::client::GrpcService<tonic::body::BoxBody>,
+        T::Error: Into<StdError>,
+        T::ResponseBody: Body<Data = Bytes> + Send + 'static,
+        <T::ResponseBody as Body>::Error: Into<StdError> + Send,
+    {
+        pub(crate) fn new(inner: T) -> Self {
+            let inner = tonic::client::Grpc::new(inner);
+            Self { inner }
+        }
+        /// Estimates the output of a multi-hop swap
+        pub(crate) async fn estimate_swap_exact_amount_in(
+            &mut self,
+            request: impl tonic::IntoRequest<super::EstimateSwapExactAmountInRequest>,
+        ) -> Result<tonic::Response<super::EstimateSwapExactAmountInResponse>, tonic::Status>
+        {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(
+                    tonic::Code::Unknown,
+                    format!("Service was not ready: {}", e.into()),
+                )
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/osmosis.poolmanager.v1beta1.Query/EstimateSwapExactAmountIn",
+            );
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+        /// Returns the spot price of a pool
+        pub(crate) async fn spot_price(
+            &mut self,
+            request: impl tonic::IntoRequest<super::SpotPriceRequest>,
+        ) -> Result<tonic::Response<super::SpotPriceResponse>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(
+                    tonic::Code::Unknown,
+                    format!("Service was not ready: {}", e.into()),
+                )
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/osmosis.poolmanager.v1beta1.Query/SpotPrice",
+            );
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn swap_message_encoding() {
+        let msg = MsgSwapExactAmountIn {
+            sender: "osmo1x".to_owned(),
+            routes: vec![SwapAmountInRoute {
+                pool_id: 1,
+                token_out_denom: "uion".to_owned(),
+            }],
+            token_in: Some(Coin {
+                denom: "uosmo".to_owned(),
+                amount: "1000".to_owned(),
+            }),
+            token_out_min_amount: "5".to_owned(),
+        };
+        // Field numbers from osmosis/poolmanager/v1beta1/tx.proto
+        let expected =
+            b"\x0a\x06osmo1x\x12\x08\x08\x01\x12\x04uion\x1a\x0d\x0a\x05uosmo\x12\x041000\x22\x015";
+        assert_eq!(msg.encode_to_vec(), expected);
+        assert_eq!(MsgSwapExactAmountIn::decode(&expected[..]).unwrap(), msg);
+
+        let (any, description) = TxMessage::from(msg).into_protobuf();
+        assert_eq!(
+            any.type_url,
+            "/osmosis.poolmanager.v1beta1.MsgSwapExactAmountIn"
+        );
+        assert_eq!(any.value, expected);
+        assert_eq!(
+            description,
+            "osmo1x swapping 1000uosmo through pools [1] for at least 5uion"
+        );
+    }
+
+    #[test]
+    fn split_route_round_trip() {
+        let msg = MsgSplitRouteSwapExactAmountIn {
+            sender: "osmo1x".to_owned(),
+            routes: vec![
+                SwapAmountInSplitRoute {
+                    pools: vec![SwapAmountInRoute {
+                        pool_id: 1,
+                        token_out_denom: "uion".to_owned(),
+                    }],
+                    token_in_amount: "600".to_owned(),
+                },
+                SwapAmountInSplitRoute {
+                    pools: vec![SwapAmountInRoute {
+                        pool_id: 2,
+                        token_out_denom: "uion".to_owned(),
+                    }],
+                    token_in_amount: "400".to_owned(),
+                },
+            ],
+            token_in_denom: "uosmo".to_owned(),
+            token_out_min_amount: "5".to_owned(),
+        };
+        let decoded = MsgSplitRouteSwapExactAmountIn::decode(msg.encode_to_vec().as_slice());
+        assert_eq!(decoded.unwrap(), msg);
+    }
+
+    #[test]
+    fn spot_price_response() {
+        let res = SpotPriceResponse::decode(&b"\x0a\x131500000000000000000"[..]).unwrap();
+        assert_eq!(crate::client::parse_dec(&res.spot_price), Some(1.5));
+    }
+}
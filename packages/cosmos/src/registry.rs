@@ -0,0 +1,117 @@
+//! Process-wide registry of additional message types for decoding.
+
+use std::{any::Any as StdAny, collections::HashMap, fmt::Debug, sync::Arc};
+
+use cosmos_sdk_proto::Any;
+use once_cell::sync::Lazy;
+use parking_lot::RwLock;
+
+use crate::TxMessage;
+
+type Decoder = fn(&[u8]) -> Option<(Arc<dyn StdAny + Send + Sync>, String)>;
+
+static REGISTRY: Lazy<RwLock<HashMap<String, Decoder>>> = Lazy::new(Default::default);
+
+/// Register a chain-specific message type, such as an Osmosis or Injective message.
+///
+/// Afterwards, [crate::DecodedMessage::from_any] decodes messages with this
+/// type URL into [crate::DecodedMessage::Custom]. Message types built into
+/// this library always take precedence. Registering the same type URL again
+/// replaces the previous registration.
+pub fn register_message_type<T>(type_url: impl Into<String>)
+where
+    T: prost::Message + Default + Debug + Send + Sync + 'static,
+{
+    fn decode<T: prost::Message + Default + Debug + Send + Sync + 'static>(
+        value: &[u8],
+    ) -> Option<(Arc<dyn StdAny + Send + Sync>, String)> {
+        let msg = T::decode(value).ok()?;
+        let description = format!("{msg:?}");
+        Some((Arc::new(msg), description))
+    }
+    REGISTRY.write().insert(type_url.into(), decode::<T>);
+}
+
+/// A message decoded using a type from [register_message_type].
+#[derive(Clone)]
+pub struct CustomMessage {
+    any: Any,
+    decoded: Arc<dyn StdAny + Send + Sync>,
+    description: String,
+}
+
+impl CustomMessage {
+    /// Decode with the registered type for this type URL, if any.
+    pub(crate) fn decode(any: &Any) -> Option<Self> {
+        let decoder = *REGISTRY.read().get(&any.type_url)?;
+        let (decoded, description) = decoder(&any.value)?;
+        Some(CustomMessage {
+            any: any.clone(),
+            decoded,
+            description,
+        })
+    }
+
+    /// The type URL of this message.
+    pub fn type_url(&self) -> &str {
+        &self.any.type_url
+    }
+
+    /// Get the decoded message, if it has the given type.
+    pub fn downcast_ref<T: 'static>(&self) -> Option<&T> {
+        self.decoded.downcast_ref()
+    }
+
+    /// The original encoded message.
+    pub fn get_protobuf(&self) -> &Any {
+        &self.any
+    }
+}
+
+impl Debug for CustomMessage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.any.type_url, self.description)
+    }
+}
+
+impl From<CustomMessage> for TxMessage {
+    fn from(msg: CustomMessage) -> Self {
+        TxMessage::new(msg.any.type_url, msg.any.value, msg.description)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cosmos_sdk_proto::cosmos::bank::v1beta1::MsgSend;
+    use prost::Message;
+
+    use super::*;
+    use crate::DecodedMessage;
+
+    #[test]
+    fn custom_message_round_trip() {
+        const TYPE_URL: &str = "/test.registry.v1.MsgCustomSend";
+        let msg = MsgSend {
+            from_address: "osmo1from".to_owned(),
+            to_address: "osmo1to".to_owned(),
+            amount: vec![],
+        };
+        let any = Any {
+            type_url: TYPE_URL.to_owned(),
+            value: msg.encode_to_vec(),
+        };
+        assert!(matches!(
+            DecodedMessage::from_any(any.clone()),
+            DecodedMessage::Unknown(_)
+        ));
+
+        register_message_type::<MsgSend>(TYPE_URL);
+        let custom = match DecodedMessage::from_any(any.clone()) {
+            DecodedMessage::Custom(custom) => custom,
+            decoded => panic!("Unexpected {decoded:?}"),
+        };
+        assert_eq!(custom.type_url(), TYPE_URL);
+        assert_eq!(custom.downcast_ref::<MsgSend>(), Some(&msg));
+        assert_eq!(TxMessage::from(custom).get_protobuf(), any);
+    }
+}
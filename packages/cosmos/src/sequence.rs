@@ -0,0 +1,267 @@
+use std::{collections::BTreeSet, sync::Arc};
+
+use cosmos_sdk_proto::cosmos::auth::v1beta1::BaseAccount;
+use parking_lot::Mutex;
+
+use crate::{
+    client::get_expected_sequence_inner,
+    error::{QueryError, RetryReason, TransactionStage},
+    Cosmos, CosmosTxResponse, HasAddress, TxBuilder, Wallet,
+};
+
+/// Hands out sequence numbers for a single wallet on a single chain.
+///
+/// This allows multiple tasks to broadcast transactions from the same wallet
+/// concurrently. Each transaction is signed with the next sequence number
+/// without waiting for earlier transactions to land. If the chain reports a
+/// sequence mismatch, the local state is resynced from the error and the
+/// transaction is retried once.
+///
+/// Clones share the same state. All transactions for the wallet should go
+/// through the same manager.
+#[derive(Clone)]
+pub struct SequenceManager {
+    cosmos: Cosmos,
+    wallet: Wallet,
+    state: Arc<Mutex<SequenceState>>,
+}
+
+#[derive(Default)]
+struct SequenceState {
+    /// Account number and the next sequence number to hand out. [None] if it
+    /// needs to be loaded from the chain.
+    next: Option<(u64, u64)>,
+    /// Sequence numbers of transactions which have been signed but not yet completed
+    in_flight: BTreeSet<u64>,
+}
+
+impl SequenceState {
+    /// Use the expected sequence from a mismatch error, or reload from the chain if it's missing.
+    fn resync_from(&mut self, message: &str) {
+        let account_number = self.next.map(|(account_number, _)| account_number);
+        self.next = account_number.zip(get_expected_sequence_inner(message));
+    }
+
+    fn try_allocate(&mut self) -> Option<(u64, u64)> {
+        let (account_number, sequence) = self.next?;
+        self.next = Some((account_number, sequence + 1));
+        self.in_flight.insert(sequence);
+        Some((account_number, sequence))
+    }
+
+    /// Record the outcome of a transaction, returning `true` on a sequence mismatch.
+    fn complete(&mut self, sequence: u64, res: &Result<CosmosTxResponse, crate::Error>) -> bool {
+        self.in_flight.remove(&sequence);
+        let Err(e) = res else {
+            return false;
+        };
+        if e.retry_reason() == Some(RetryReason::SequenceMismatch) {
+            let message = match e {
+                crate::Error::TransactionFailed { raw_log, .. } => Some(raw_log.as_str()),
+                crate::Error::Query(QueryError { query, .. }) => {
+                    query.grpc_status().map(tonic::Status::message)
+                }
+                _ => None,
+            };
+            self.resync_from(message.unwrap_or_default());
+            return true;
+        }
+        if !matches!(
+            e,
+            crate::Error::TransactionFailed {
+                stage: TransactionStage::Broadcast,
+                ..
+            }
+        ) {
+            return false;
+        }
+        // Rejected during broadcast, so the sequence number was not used.
+        // Give it back if nothing after it has been handed out.
+        if let Some((account_number, next)) = self.next {
+            if next == sequence + 1 {
+                self.next = Some((account_number, sequence));
+            }
+        }
+        false
+    }
+}
+
+impl SequenceManager {
+    /// Create a manager for the given wallet.
+    pub fn new(cosmos: Cosmos, wallet: Wallet) -> Self {
+        SequenceManager {
+            cosmos,
+            wallet,
+            state: Arc::new(Mutex::new(SequenceState::default())),
+        }
+    }
+
+    /// The wallet whose sequence numbers are managed
+    pub fn get_wallet(&self) -> &Wallet {
+        &self.wallet
+    }
+
+    /// Sequence numbers of transactions currently being broadcast.
+    pub fn get_in_flight(&self) -> Vec<u64> {
+        self.state.lock().in_flight.iter().copied().collect()
+    }
+
+    /// Forget the local sequence number and reload it from the chain on next use.
+    pub fn resync(&self) {
+        self.state.lock().next = None;
+    }
+
+    /// Same as [TxBuilder::sign_and_broadcast_cosmos_tx], using the managed sequence number.
+    pub async fn sign_and_broadcast(
+        &self,
+        txbuilder: &TxBuilder,
+    ) -> Result<CosmosTxResponse, crate::Error> {
+        let address = self.wallet.get_address();
+        let simres = txbuilder.simulate(&self.cosmos, &[address]).await?;
+        let gas_to_request = self.cosmos.padded_gas(&simres.body, simres.gas_used);
+        let mut retried = false;
+        loop {
+            let (account_number, sequence) = self.allocate().await?;
+            let base_account = BaseAccount {
+                address: address.get_address_string(),
+                pub_key: None,
+                account_number,
+                sequence,
+            };
+            let res = txbuilder
+                .sign_and_broadcast_with_inner(
+                    &self.cosmos,
+                    &self.wallet,
+                    &base_account,
+                    sequence,
+                    simres.body.clone(),
+                    gas_to_request,
+                )
+                .await;
+            let mismatch = self.complete(sequence, &res);
+            if mismatch && !retried {
+                retried = true;
+                tracing::warn!("Account sequence mismatch for {address}, retrying");
+                continue;
+            }
+            break res;
+        }
+    }
+
+    async fn allocate(&self) -> Result<(u64, u64), crate::Error> {
+        if let Some(allocated) = self.try_allocate() {
+            return Ok(allocated);
+        }
+        let base_account = self
+            .cosmos
+            .get_base_account(self.wallet.get_address())
+            .await?;
+        let mut state = self.state.lock();
+        // Another task may have loaded the sequence in the meantime, in
+        // which case that value wins.
+        state
+            .next
+            .get_or_insert((base_account.account_number, base_account.sequence));
+        Ok(state
+            .try_allocate()
+            .expect("next sequence was just populated"))
+    }
+
+    fn try_allocate(&self) -> Option<(u64, u64)> {
+        self.state.lock().try_allocate()
+    }
+
+    fn complete(&self, sequence: u64, res: &Result<CosmosTxResponse, crate::Error>) -> bool {
+        self.state.lock().complete(sequence, res)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::{Action, ChainError};
+
+    use super::*;
+
+    fn broadcast_failure(code: u32, raw_log: &str) -> Result<CosmosTxResponse, crate::Error> {
+        Err(crate::Error::TransactionFailed {
+            code: ChainError::new("sdk", code),
+            raw_log: raw_log.to_owned(),
+            action: Arc::new(Action::GetLatestBlock),
+            grpc_url: Arc::new("http://127.0.0.1:1".to_owned()),
+            stage: TransactionStage::Broadcast,
+        })
+    }
+
+    #[test]
+    fn resync_from_mismatch_message() {
+        let mut state = SequenceState {
+            next: Some((7, 3)),
+            ..SequenceState::default()
+        };
+        state.resync_from(
+            "account sequence mismatch, expected 12, got 3: incorrect account sequence",
+        );
+        assert_eq!(state.next, Some((7, 12)));
+
+        state.resync_from("something else went wrong");
+        assert_eq!(state.next, None);
+    }
+
+    #[test]
+    fn mismatch_resyncs_sequence() {
+        let mut state = SequenceState {
+            next: Some((7, 3)),
+            ..SequenceState::default()
+        };
+        assert_eq!(state.try_allocate(), Some((7, 3)));
+        let res = broadcast_failure(
+            32,
+            "account sequence mismatch, expected 5, got 3: incorrect account sequence",
+        );
+        assert!(state.complete(3, &res));
+        assert_eq!(state.next, Some((7, 5)));
+        assert!(state.in_flight.is_empty());
+    }
+
+    #[test]
+    fn rejected_sequence_is_reused() {
+        let mut state = SequenceState {
+            next: Some((7, 3)),
+            ..SequenceState::default()
+        };
+        assert_eq!(state.try_allocate(), Some((7, 3)));
+        assert!(!state.complete(3, &broadcast_failure(13, "insufficient fee")));
+        assert_eq!(state.try_allocate(), Some((7, 3)));
+        assert_eq!(state.try_allocate(), Some((7, 4)));
+        // A later sequence has been handed out, so 4 can't be given back.
+        assert!(!state.complete(3, &broadcast_failure(13, "insufficient fee")));
+        assert_eq!(state.next, Some((7, 5)));
+    }
+
+    #[test]
+    fn concurrent_allocation_is_unique() {
+        let state = Arc::new(Mutex::new(SequenceState {
+            next: Some((7, 0)),
+            ..SequenceState::default()
+        }));
+        let handles = (0..8)
+            .map(|_| {
+                let state = state.clone();
+                std::thread::spawn(move || {
+                    (0..100)
+                        .map(|_| state.lock().try_allocate().unwrap().1)
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect::<Vec<_>>();
+        let mut allocated = handles
+            .into_iter()
+            .flat_map(|handle| handle.join().unwrap())
+            .collect::<Vec<_>>();
+        allocated.sort_unstable();
+        assert_eq!(allocated, (0..800).collect::<Vec<_>>());
+        let state = state.lock();
+        assert_eq!(state.next, Some((7, 800)));
+        assert_eq!(state.in_flight.len(), 800);
+    }
+}
@@ -0,0 +1,218 @@
+//! Serializable versions of common query results.
+//!
+//! The protobuf types returned by the chain do not implement
+//! [serde::Serialize]. The types in this module mirror the most commonly used
+//! ones so that they can be emitted directly as JSON, e.g. from an API server.
+
+use cosmos_sdk_proto::{
+    cosmos::base::{abci::v1beta1::TxResponse, v1beta1::Coin},
+    cosmwasm::wasm::v1::ContractInfo,
+};
+
+use crate::CosmosTxResponse;
+
+/// Serializable version of [Coin].
+#[derive(serde::Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct SerializableCoin {
+    /// Denom of the coin
+    pub denom: String,
+    /// Amount, in the base units of the denom
+    pub amount: String,
+}
+
+impl From<Coin> for SerializableCoin {
+    fn from(Coin { denom, amount }: Coin) -> Self {
+        SerializableCoin { denom, amount }
+    }
+}
+
+impl From<&Coin> for SerializableCoin {
+    fn from(coin: &Coin) -> Self {
+        coin.clone().into()
+    }
+}
+
+/// Serializable version of [ContractInfo].
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct SerializableContractInfo {
+    /// Code ID the contract is currently running
+    pub code_id: u64,
+    /// Address that instantiated the contract
+    pub creator: String,
+    /// Admin of the contract, if any
+    pub admin: Option<String>,
+    /// On-chain label
+    pub label: String,
+    /// Block height the contract was created at, if known
+    pub created_height: Option<u64>,
+    /// IBC port ID, if the contract is IBC enabled
+    pub ibc_port_id: Option<String>,
+}
+
+impl From<ContractInfo> for SerializableContractInfo {
+    fn from(
+        ContractInfo {
+            code_id,
+            creator,
+            admin,
+            label,
+            created,
+            ibc_port_id,
+            extension: _,
+        }: ContractInfo,
+    ) -> Self {
+        SerializableContractInfo {
+            code_id,
+            creator,
+            admin: non_empty(admin),
+            label,
+            created_height: created.map(|created| created.block_height),
+            ibc_port_id: non_empty(ibc_port_id),
+        }
+    }
+}
+
+/// Serializable version of an event emitted by a transaction.
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct SerializableEvent {
+    /// Event type
+    #[serde(rename = "type")]
+    pub r#type: String,
+    /// Key/value attributes
+    pub attributes: Vec<SerializableAttribute>,
+}
+
+/// A single attribute within a [SerializableEvent].
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct SerializableAttribute {
+    /// Attribute key
+    pub key: String,
+    /// Attribute value
+    pub value: String,
+}
+
+/// Serializable version of [TxResponse].
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct SerializableTxResponse {
+    /// Block height the transaction landed in
+    pub height: i64,
+    /// Transaction hash
+    pub txhash: String,
+    /// Codespace of the error, empty on success
+    pub codespace: String,
+    /// Result code, 0 indicates success
+    pub code: u32,
+    /// Raw log output
+    pub raw_log: String,
+    /// Additional info
+    pub info: String,
+    /// Gas requested by the transaction
+    pub gas_wanted: i64,
+    /// Gas actually used by the transaction
+    pub gas_used: i64,
+    /// Timestamp of the block containing this transaction
+    pub timestamp: String,
+    /// All events emitted by the transaction
+    pub events: Vec<SerializableEvent>,
+}
+
+impl From<TxResponse> for SerializableTxResponse {
+    fn from(
+        TxResponse {
+            height,
+            txhash,
+            codespace,
+            code,
+            data: _,
+            raw_log,
+            logs: _,
+            info,
+            gas_wanted,
+            gas_used,
+            tx: _,
+            timestamp,
+            events,
+        }: TxResponse,
+    ) -> Self {
+        SerializableTxResponse {
+            height,
+            txhash,
+            codespace,
+            code,
+            raw_log,
+            info,
+            gas_wanted,
+            gas_used,
+            timestamp,
+            events: events
+                .into_iter()
+                .map(|event| SerializableEvent {
+                    r#type: event.r#type,
+                    attributes: event
+                        .attributes
+                        .into_iter()
+                        .map(|attr| SerializableAttribute {
+                            key: String::from_utf8_lossy(&attr.key).into_owned(),
+                            value: String::from_utf8_lossy(&attr.value).into_owned(),
+                        })
+                        .collect(),
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Serializable version of [CosmosTxResponse].
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct SerializableCosmosTxResponse {
+    /// The transaction response
+    pub response: SerializableTxResponse,
+    /// Memo set on the transaction
+    pub memo: String,
+    /// Type URLs of each message in the transaction
+    pub message_types: Vec<String>,
+    /// Fee paid by the transaction
+    pub fee: Vec<SerializableCoin>,
+    /// Gas limit requested in the fee
+    pub gas_limit: u64,
+    /// Gas used while simulating, if the transaction was simulated
+    pub simulated_gas: Option<u64>,
+    /// Gas price attempt which was accepted, if known
+    pub price_attempt: Option<u64>,
+}
+
+impl From<CosmosTxResponse> for SerializableCosmosTxResponse {
+    fn from(CosmosTxResponse { response, tx, cost }: CosmosTxResponse) -> Self {
+        let (memo, message_types) = match tx.body {
+            Some(body) => (
+                body.memo,
+                body.messages.into_iter().map(|msg| msg.type_url).collect(),
+            ),
+            None => (String::new(), vec![]),
+        };
+        let (fee, gas_limit) = match tx.auth_info.and_then(|auth_info| auth_info.fee) {
+            Some(fee) => (
+                fee.amount.into_iter().map(SerializableCoin::from).collect(),
+                fee.gas_limit,
+            ),
+            None => (vec![], 0),
+        };
+        SerializableCosmosTxResponse {
+            response: response.into(),
+            memo,
+            message_types,
+            fee,
+            gas_limit,
+            simulated_gas: cost.simulated_gas,
+            price_attempt: cost.price_attempt,
+        }
+    }
+}
+
+fn non_empty(s: String) -> Option<String> {
+    if s.is_empty() {
+        None
+    } else {
+        Some(s)
+    }
+}
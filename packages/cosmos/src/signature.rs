@@ -0,0 +1,176 @@
+//! Verification of signatures produced by Cosmos wallets.
+
+use base64::Engine;
+use bitcoin::{
+    hashes::{sha256, Hash},
+    secp256k1::{
+        ecdsa::{RecoverableSignature, RecoveryId, Signature},
+        Message, PublicKey,
+    },
+};
+use cosmos_sdk_proto::{cosmos::tx::v1beta1::SignDoc, traits::Message as _};
+
+use crate::{
+    address::{HasAddressHrp, PublicKeyMethod, RawAddress},
+    error::SignatureError,
+    wallet::{
+        cosmos_address_from_public_key, eth_address_from_public_key, global_secp, keccak,
+        WalletPublicKey,
+    },
+    Address, HasAddress, Wallet,
+};
+
+/// Identifies who is expected to have produced a signature.
+#[derive(Clone, Debug)]
+pub enum SignatureSigner {
+    /// A known public key, in compressed or uncompressed form.
+    PublicKey {
+        /// The serialized public key
+        public_key: Vec<u8>,
+        /// How the signed message is hashed for this key
+        method: PublicKeyMethod,
+    },
+    /// Only the address is known. The public key is recovered from the signature.
+    Address(Address),
+}
+
+impl From<Address> for SignatureSigner {
+    fn from(address: Address) -> Self {
+        SignatureSigner::Address(address)
+    }
+}
+
+impl From<&Wallet> for SignatureSigner {
+    fn from(wallet: &Wallet) -> Self {
+        SignatureSigner::PublicKey {
+            public_key: wallet.public_key_bytes().to_vec(),
+            method: match wallet.public_key {
+                WalletPublicKey::Cosmos(_) => PublicKeyMethod::Cosmos,
+                WalletPublicKey::Ethereum(_) => PublicKeyMethod::Ethereum,
+            },
+        }
+    }
+}
+
+fn hash_message(message: &[u8], method: PublicKeyMethod) -> Message {
+    let hash = match method {
+        PublicKeyMethod::Cosmos => sha256::Hash::hash(message).into_inner(),
+        PublicKeyMethod::Ethereum => keccak(message),
+    };
+    Message::from_slice(&hash).expect("32 byte hash is always a valid message")
+}
+
+/// Verify a 64-byte compact secp256k1 signature over the given message.
+///
+/// The message is hashed the same way as [Wallet::sign_bytes].
+pub fn verify_signature(
+    signer: impl Into<SignatureSigner>,
+    message: &[u8],
+    signature: &[u8],
+) -> Result<(), SignatureError> {
+    match signer.into() {
+        SignatureSigner::PublicKey { public_key, method } => {
+            let public_key = PublicKey::from_slice(&public_key)
+                .map_err(|source| SignatureError::InvalidPublicKey { source })?;
+            let signature = Signature::from_compact(signature)
+                .map_err(|source| SignatureError::InvalidSignature { source })?;
+            global_secp()
+                .verify_ecdsa(&hash_message(message, method), &signature, &public_key)
+                .map_err(|_| SignatureError::Mismatch {
+                    signer: hex::encode(public_key.serialize()),
+                })
+        }
+        SignatureSigner::Address(address) => {
+            let method = address.get_address_hrp().default_public_key_method();
+            let message = hash_message(message, method);
+            // Without a recovery ID, try each candidate public key.
+            let matches = (0..4).any(|id| {
+                let recovered = RecoveryId::from_i32(id)
+                    .and_then(|id| RecoverableSignature::from_compact(signature, id))
+                    .and_then(|signature| global_secp().recover_ecdsa(&message, &signature));
+                let public_key = match recovered {
+                    Ok(public_key) => public_key,
+                    Err(_) => return false,
+                };
+                let raw = match method {
+                    PublicKeyMethod::Cosmos => {
+                        cosmos_address_from_public_key(&public_key.serialize())
+                    }
+                    PublicKeyMethod::Ethereum => {
+                        eth_address_from_public_key(&public_key.serialize_uncompressed())
+                    }
+                };
+                RawAddress::from(raw) == address.raw()
+            });
+            if matches {
+                Ok(())
+            } else {
+                Err(SignatureError::Mismatch {
+                    signer: address.get_address_string(),
+                })
+            }
+        }
+    }
+}
+
+/// Verify a signature over a direct mode [SignDoc], as found in a signed transaction.
+pub fn verify_sign_doc(
+    signer: impl Into<SignatureSigner>,
+    sign_doc: &SignDoc,
+    signature: &[u8],
+) -> Result<(), SignatureError> {
+    verify_signature(signer, &sign_doc.encode_to_vec(), signature)
+}
+
+/// The bytes signed for an ADR-36 arbitrary data signature.
+///
+/// This is the canonical amino JSON of a sign doc containing a single
+/// `sign/MsgSignData` message, as produced by wallets like Keplr.
+pub fn adr36_sign_bytes(signer: Address, data: &[u8]) -> Vec<u8> {
+    let data = base64::engine::general_purpose::STANDARD.encode(data);
+    // Keys must be sorted and the output compact, so build the JSON by hand
+    // rather than depend on serde_json's map ordering.
+    format!(
+        r#"{{"account_number":"0","chain_id":"","fee":{{"amount":[],"gas":"0"}},"memo":"","msgs":[{{"type":"sign/MsgSignData","value":{{"data":"{data}","signer":"{signer}"}}}}],"sequence":"0"}}"#
+    )
+    .into_bytes()
+}
+
+/// Verify an ADR-36 signature of arbitrary data by the given address.
+pub fn verify_adr36(signer: Address, data: &[u8], signature: &[u8]) -> Result<(), SignatureError> {
+    verify_signature(signer, &adr36_sign_bytes(signer, data), signature)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::AddressHrp;
+
+    use super::*;
+
+    #[test]
+    fn verify_wallet_signature() {
+        let wallet = Wallet::generate(AddressHrp::from_static("osmo")).unwrap();
+        let other = Wallet::generate(AddressHrp::from_static("osmo")).unwrap();
+        let message = b"hello world";
+        let signature = wallet.sign_bytes(message).serialize_compact();
+        verify_signature(&wallet, message, &signature).unwrap();
+        verify_signature(wallet.get_address(), message, &signature).unwrap();
+        verify_signature(other.get_address(), message, &signature).unwrap_err();
+        verify_signature(&wallet, b"goodbye", &signature).unwrap_err();
+    }
+
+    #[test]
+    fn verify_adr36_signature() {
+        let wallet = Wallet::generate(AddressHrp::from_static("inj")).unwrap();
+        let data = b"login nonce 1234";
+        let signature = wallet.sign_arbitrary(data).serialize_compact();
+        assert_eq!(
+            signature,
+            wallet
+                .sign_bytes(&adr36_sign_bytes(wallet.get_address(), data))
+                .serialize_compact()
+        );
+        verify_adr36(wallet.get_address(), data, &signature).unwrap();
+        verify_adr36(wallet.get_address(), b"other", &signature).unwrap_err();
+    }
+}
@@ -0,0 +1,169 @@
+use std::collections::BTreeMap;
+
+use futures_util::TryStreamExt;
+
+use crate::{Address, Contract, HasAddress, HasCosmos};
+
+/// All raw key/value pairs in a contract's storage at a specific block height.
+///
+/// Snapshots can be serialized, e.g. to a JSON file, with keys and values
+/// hex encoded. Compare two snapshots with [StateSnapshot::diff], for
+/// example before and after a migration.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct StateSnapshot {
+    /// The contract this snapshot was taken from
+    pub contract: Address,
+    /// Block height of the snapshot
+    pub height: u64,
+    /// Raw storage entries, ordered by key
+    #[serde(with = "hex_map")]
+    pub entries: BTreeMap<Vec<u8>, Vec<u8>>,
+}
+
+/// Differences between two [StateSnapshot]s, see [StateSnapshot::diff].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct StateDiff {
+    /// Entries only present in the newer snapshot
+    pub added: Vec<(Vec<u8>, Vec<u8>)>,
+    /// Entries only present in the older snapshot
+    pub removed: Vec<(Vec<u8>, Vec<u8>)>,
+    /// Keys present in both snapshots with different values: key, old value, new value
+    pub changed: Vec<(Vec<u8>, Vec<u8>, Vec<u8>)>,
+}
+
+impl StateDiff {
+    /// Are the two snapshots identical?
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+impl StateSnapshot {
+    /// Compare against a newer snapshot, usually of the same contract.
+    pub fn diff(&self, newer: &StateSnapshot) -> StateDiff {
+        let mut diff = StateDiff::default();
+        for (key, old) in &self.entries {
+            match newer.entries.get(key) {
+                None => diff.removed.push((key.clone(), old.clone())),
+                Some(new) if new != old => {
+                    diff.changed.push((key.clone(), old.clone(), new.clone()))
+                }
+                Some(_) => (),
+            }
+        }
+        for (key, new) in &newer.entries {
+            if !self.entries.contains_key(key) {
+                diff.added.push((key.clone(), new.clone()));
+            }
+        }
+        diff
+    }
+}
+
+impl Contract {
+    /// Capture all of the contract's storage at the given height.
+    ///
+    /// If no height is given, this contract's pinned height is used, falling
+    /// back to the latest block height.
+    pub async fn export_state(&self, height: Option<u64>) -> Result<StateSnapshot, crate::Error> {
+        let cosmos = self.get_cosmos();
+        let height = match height.or(cosmos.get_height()) {
+            Some(height) => height,
+            None => cosmos
+                .get_latest_block_info()
+                .await?
+                .height
+                .try_into()
+                .unwrap_or_default(),
+        };
+        let entries = cosmos
+            .clone()
+            .at_height(Some(height))
+            .wasm_all_state(self.get_address())
+            .try_collect()
+            .await?;
+        Ok(StateSnapshot {
+            contract: self.get_address(),
+            height,
+            entries,
+        })
+    }
+}
+
+mod hex_map {
+    use std::collections::BTreeMap;
+
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    pub(super) fn serialize<S: Serializer>(
+        entries: &BTreeMap<Vec<u8>, Vec<u8>>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(
+            entries
+                .iter()
+                .map(|(key, value)| (hex::encode(key), hex::encode(value))),
+        )
+    }
+
+    pub(super) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<BTreeMap<Vec<u8>, Vec<u8>>, D::Error> {
+        BTreeMap::<String, String>::deserialize(deserializer)?
+            .into_iter()
+            .map(|(key, value)| {
+                Ok((
+                    hex::decode(key).map_err(D::Error::custom)?,
+                    hex::decode(value).map_err(D::Error::custom)?,
+                ))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn diff_and_serialize() {
+        let contract: Address = "osmo168gdk6r58jdwfv49kuesq2rs747jawnn4ryvyk"
+            .parse()
+            .unwrap();
+        let old = StateSnapshot {
+            contract,
+            height: 10,
+            entries: [
+                (b"a".to_vec(), b"1".to_vec()),
+                (b"b".to_vec(), b"2".to_vec()),
+                (b"c".to_vec(), b"3".to_vec()),
+            ]
+            .into_iter()
+            .collect(),
+        };
+        let new = StateSnapshot {
+            contract,
+            height: 20,
+            entries: [
+                (b"a".to_vec(), b"1".to_vec()),
+                (b"b".to_vec(), b"22".to_vec()),
+                (b"d".to_vec(), b"4".to_vec()),
+            ]
+            .into_iter()
+            .collect(),
+        };
+        assert!(old.diff(&old).is_empty());
+        assert_eq!(
+            old.diff(&new),
+            StateDiff {
+                added: vec![(b"d".to_vec(), b"4".to_vec())],
+                removed: vec![(b"c".to_vec(), b"3".to_vec())],
+                changed: vec![(b"b".to_vec(), b"2".to_vec(), b"22".to_vec())],
+            }
+        );
+
+        let json = serde_json::to_string(&new).unwrap();
+        assert!(json.contains(r#""62":"3232""#));
+        assert_eq!(serde_json::from_str::<StateSnapshot>(&json).unwrap(), new);
+    }
+}
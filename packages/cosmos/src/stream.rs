@@ -0,0 +1,361 @@
+use std::{
+    collections::VecDeque,
+    pin::Pin,
+    time::{Duration, Instant},
+};
+
+use cosmos_sdk_proto::cosmos::{
+    base::{abci::v1beta1::TxResponse, query::v1beta1::PageRequest},
+    tx::v1beta1::{GetTxsEventRequest, OrderBy, TxBody},
+};
+use futures_util::{Stream, StreamExt};
+
+use crate::{
+    error::{Action, ErrorKind},
+    BlockInfo, Cosmos, HasAddress,
+};
+
+/// How long to wait before checking for a new block once caught up, or after an error.
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Number of transactions to load per page when searching historical transactions.
+const TXS_PAGE_SIZE: u64 = 100;
+
+/// How long to wait for a new block's transactions to be indexed before reporting an error.
+const INDEX_LAG_ALLOWED: Duration = Duration::from_secs(30);
+
+struct BlockStreamState {
+    cosmos: Cosmos,
+    next_height: i64,
+    latest_height: i64,
+    delay: bool,
+}
+
+/// Which transactions to include in [Cosmos::stream_txs].
+///
+/// All events must match for a transaction to be included.
+#[derive(Clone, Debug, Default)]
+pub struct TxFilter {
+    events: Vec<String>,
+    start_height: Option<i64>,
+}
+
+impl TxFilter {
+    /// A filter with no conditions.
+    ///
+    /// At least one event must be added before use, the chain will reject
+    /// searches without any events.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Require an event, e.g. `message.sender='osmo1...'`.
+    pub fn event(mut self, event: impl Into<String>) -> Self {
+        self.events.push(event.into());
+        self
+    }
+
+    /// Only include transactions sent by the given address.
+    pub fn sender(self, sender: impl HasAddress) -> Self {
+        self.event(format!("message.sender='{}'", sender.get_address()))
+    }
+
+    /// Only include transactions executing the given contract.
+    pub fn contract_execute(self, contract: impl HasAddress) -> Self {
+        self.event(format!(
+            "execute._contract_address='{}'",
+            contract.get_address()
+        ))
+    }
+
+    /// Ignore transactions before the given height.
+    pub fn start_height(mut self, height: i64) -> Self {
+        self.start_height = Some(height);
+        self
+    }
+
+    /// Get the events which must match.
+    pub fn get_events(&self) -> &[String] {
+        &self.events
+    }
+
+    /// Events for searching historical transactions up to and including the cutoff.
+    fn historical_events(&self, cutoff: i64) -> Vec<String> {
+        let mut events = self.events.clone();
+        if let Some(start_height) = self.start_height {
+            events.push(format!("tx.height>={start_height}"));
+        }
+        events.push(format!("tx.height<={cutoff}"));
+        events
+    }
+}
+
+enum TxStreamPhase {
+    /// Paging through transactions up to and including the cutoff height
+    Historical { offset: u64, cutoff: Option<i64> },
+    /// Following new blocks, searching each block containing transactions
+    Live {
+        blocks: Pin<Box<dyn Stream<Item = Result<BlockInfo, crate::Error>> + Send>>,
+        pending: Option<PendingBlock>,
+    },
+}
+
+/// A new block with transactions which still needs to be searched.
+struct PendingBlock {
+    height: i64,
+    /// Used to check that the block has been indexed before searching it
+    last_txhash: String,
+    first_seen: Instant,
+}
+
+struct TxStreamState {
+    cosmos: Cosmos,
+    filter: TxFilter,
+    phase: TxStreamPhase,
+    buffer: VecDeque<(TxBody, TxResponse)>,
+    delay: bool,
+}
+
+impl Cosmos {
+    /// Stream every block starting from the given height.
+    ///
+    /// Historical blocks are produced as quickly as they can be loaded, after
+    /// which the stream waits for new blocks as they are produced. Each block
+    /// is loaded with [Cosmos::get_block_info_with_fallbacks], so a failing
+    /// node is skipped transparently. Errors are passed through and the same
+    /// height is retried on the next poll, so the stream never skips a block.
+    pub fn stream_blocks(
+        &self,
+        start_height: i64,
+    ) -> impl Stream<Item = Result<BlockInfo, crate::Error>> + Send + 'static {
+        let state = BlockStreamState {
+            cosmos: self.clone(),
+            next_height: start_height,
+            latest_height: 0,
+            delay: false,
+        };
+        futures_util::stream::unfold(state, |mut state| async move {
+            loop {
+                if state.delay {
+                    tokio::time::sleep(POLL_INTERVAL).await;
+                    state.delay = false;
+                }
+                if state.next_height > state.latest_height {
+                    match state.cosmos.get_latest_block_info().await {
+                        Ok(latest) => state.latest_height = latest.height,
+                        Err(e) => {
+                            state.delay = true;
+                            return Some((Err(e), state));
+                        }
+                    }
+                    if state.next_height > state.latest_height {
+                        state.delay = true;
+                        continue;
+                    }
+                }
+                let res = state
+                    .cosmos
+                    .get_block_info_with_fallbacks(state.next_height)
+                    .await;
+                match &res {
+                    Ok(_) => state.next_height += 1,
+                    Err(_) => state.delay = true,
+                }
+                return Some((res, state));
+            }
+        })
+    }
+
+    /// Stream all transactions matching the filter, oldest first.
+    ///
+    /// Existing transactions are found by paging through the transaction
+    /// search, beginning at the filter's start height. Once caught up, each new
+    /// block containing transactions is searched as soon as the node has
+    /// indexed it. Transactions are yielded in order without
+    /// duplicates. As with [Cosmos::stream_blocks], errors are passed through
+    /// and the failed step is retried on the next poll.
+    pub fn stream_txs(
+        &self,
+        filter: TxFilter,
+    ) -> impl Stream<Item = Result<(TxBody, TxResponse), crate::Error>> + Send + 'static {
+        let state = TxStreamState {
+            cosmos: self.clone(),
+            filter,
+            phase: TxStreamPhase::Historical {
+                offset: 0,
+                cutoff: None,
+            },
+            buffer: VecDeque::new(),
+            delay: false,
+        };
+        futures_util::stream::unfold(state, |mut state| async move {
+            loop {
+                if let Some(tx) = state.buffer.pop_front() {
+                    return Some((Ok(tx), state));
+                }
+                if state.delay {
+                    tokio::time::sleep(POLL_INTERVAL).await;
+                    state.delay = false;
+                }
+                if let Err(e) = state.step().await {
+                    state.delay = true;
+                    return Some((Err(e), state));
+                }
+            }
+        })
+    }
+
+    /// Load a single page of transactions matching all of the given events.
+    async fn search_txs_page(
+        &self,
+        events: Vec<String>,
+        offset: u64,
+    ) -> Result<Vec<(TxBody, TxResponse)>, crate::Error> {
+        let action = Action::SearchTransactions(events.clone());
+        let res = self
+            .perform_query(
+                GetTxsEventRequest {
+                    events,
+                    pagination: Some(PageRequest {
+                        key: vec![],
+                        offset,
+                        limit: TXS_PAGE_SIZE,
+                        count_total: false,
+                        reverse: false,
+                    }),
+                    order_by: OrderBy::Asc as i32,
+                },
+                action.clone(),
+                true,
+            )
+            .await?
+            .into_inner();
+        if res.txs.len() != res.tx_responses.len() {
+            return Err(crate::Error::InvalidChainResponse {
+                message: format!(
+                    "Mismatched number of transactions ({}) and responses ({})",
+                    res.txs.len(),
+                    res.tx_responses.len()
+                ),
+                action,
+            });
+        }
+        res.txs
+            .into_iter()
+            .zip(res.tx_responses)
+            .map(|(tx, tx_response)| match tx.body {
+                Some(body) => Ok((body, tx_response)),
+                None => Err(crate::Error::InvalidChainResponse {
+                    message: format!("Missing tx body for {}", tx_response.txhash),
+                    action: action.clone(),
+                }),
+            })
+            .collect()
+    }
+}
+
+impl TxStreamState {
+    /// Make progress, filling up the buffer with any newly found transactions.
+    async fn step(&mut self) -> Result<(), crate::Error> {
+        match &mut self.phase {
+            TxStreamPhase::Historical { offset, cutoff } => {
+                // Pin the end of the historical search before starting, so
+                // that nothing is missed or duplicated when switching over
+                // to following new blocks.
+                let cutoff_height = match cutoff {
+                    Some(cutoff) => *cutoff,
+                    None => {
+                        let height = self.cosmos.get_latest_block_info().await?.height;
+                        *cutoff = Some(height);
+                        height
+                    }
+                };
+                let page = self
+                    .cosmos
+                    .search_txs_page(self.filter.historical_events(cutoff_height), *offset)
+                    .await?;
+                *offset += page.len() as u64;
+                let done = (page.len() as u64) < TXS_PAGE_SIZE;
+                self.buffer.extend(page);
+                if done {
+                    let next_height = match self.filter.start_height {
+                        Some(start_height) => start_height.max(cutoff_height + 1),
+                        None => cutoff_height + 1,
+                    };
+                    self.phase = TxStreamPhase::Live {
+                        blocks: Box::pin(self.cosmos.stream_blocks(next_height)),
+                        pending: None,
+                    };
+                }
+            }
+            TxStreamPhase::Live { blocks, pending } => match pending {
+                Some(block) => {
+                    // The transaction indexer can lag behind the block being
+                    // available. Searching too early would silently miss
+                    // transactions, so wait until the block's last
+                    // transaction can be found.
+                    if let Err(e) = self
+                        .cosmos
+                        .get_transaction_body(block.last_txhash.clone())
+                        .await
+                    {
+                        if e.kind() == ErrorKind::NotFound
+                            && block.first_seen.elapsed() < INDEX_LAG_ALLOWED
+                        {
+                            self.delay = true;
+                            return Ok(());
+                        }
+                        return Err(e);
+                    }
+                    let mut events = self.filter.events.clone();
+                    events.push(format!("tx.height={}", block.height));
+                    let mut offset = 0;
+                    let mut found = vec![];
+                    loop {
+                        let mut page = self.cosmos.search_txs_page(events.clone(), offset).await?;
+                        offset += page.len() as u64;
+                        let done = (page.len() as u64) < TXS_PAGE_SIZE;
+                        found.append(&mut page);
+                        if done {
+                            break;
+                        }
+                    }
+                    *pending = None;
+                    self.buffer.extend(found);
+                }
+                None => match blocks.next().await {
+                    Some(block) => {
+                        let block = block?;
+                        if let Some(last_txhash) = block.txhashes.last() {
+                            *pending = Some(PendingBlock {
+                                height: block.height,
+                                last_txhash: last_txhash.clone(),
+                                first_seen: Instant::now(),
+                            });
+                        }
+                    }
+                    None => unreachable!("stream_blocks never terminates"),
+                },
+            },
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn historical_events_respect_start_height() {
+        let filter = TxFilter::new().event("message.action='foo'");
+        assert_eq!(
+            filter.historical_events(50),
+            ["message.action='foo'", "tx.height<=50"]
+        );
+        assert_eq!(
+            filter.start_height(10).historical_events(50),
+            ["message.action='foo'", "tx.height>=10", "tx.height<=50"]
+        );
+    }
+}
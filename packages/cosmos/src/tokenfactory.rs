@@ -26,7 +26,8 @@ impl TryFrom<AddressHrp> for TokenFactoryKind {
 
     fn try_from(hrp: AddressHrp) -> Result<Self, TokenFactoryError> {
         match hrp.as_str() {
-            "osmo" => Ok(TokenFactoryKind::Osmosis),
+            // Neutron uses Osmosis's tokenfactory module, including the type URLs
+            "osmo" | "neutron" => Ok(TokenFactoryKind::Osmosis),
             "sei" => Ok(TokenFactoryKind::Sei),
             _ => Err(TokenFactoryError::Unsupported { hrp }),
         }
@@ -34,6 +35,17 @@ impl TryFrom<AddressHrp> for TokenFactoryKind {
 }
 
 impl Cosmos {
+    /// List the tokenfactory denoms created by the given address.
+    pub async fn denoms_from_creator(
+        &self,
+        creator: impl HasAddress,
+    ) -> Result<Vec<String>, crate::Error> {
+        self.clone()
+            .token_factory()?
+            .denoms_from_creator(creator)
+            .await
+    }
+
     /// Generate a new [TokenFactory] for this connection, if supported for this chain.
     pub fn token_factory(self) -> Result<TokenFactory, TokenFactoryError> {
         self.get_address_hrp()
@@ -135,6 +147,46 @@ impl TokenFactory {
         .into_typed_message(self.kind);
         wallet.broadcast_message(&self.client, msg).await
     }
+
+    /// Set the bank metadata, such as display units, for the given token.
+    pub async fn set_denom_metadata(
+        &self,
+        wallet: &Wallet,
+        metadata: Metadata,
+    ) -> Result<TxResponse, crate::Error> {
+        let msg = MsgSetDenomMetadata {
+            sender: wallet.get_address_string(),
+            metadata: Some(metadata),
+        }
+        .into_typed_message(self.kind);
+        wallet.broadcast_message(&self.client, msg).await
+    }
+
+    /// List the denoms created by the given address.
+    pub async fn denoms_from_creator(
+        &self,
+        creator: impl HasAddress,
+    ) -> Result<Vec<String>, crate::Error> {
+        let action = Action::DenomsFromCreator(creator.get_address());
+        let creator = creator.get_address_string();
+        let denoms = match self.kind {
+            TokenFactoryKind::Osmosis => {
+                self.client
+                    .perform_query(QueryDenomsFromCreatorRequest { creator }, action, true)
+                    .await?
+                    .into_inner()
+                    .denoms
+            }
+            TokenFactoryKind::Sei => {
+                self.client
+                    .perform_query(SeiQueryDenomsFromCreatorRequest { creator }, action, true)
+                    .await?
+                    .into_inner()
+                    .denoms
+            }
+        };
+        Ok(denoms)
+    }
 }
 
 fn type_url(kind: TokenFactoryKind, s: &str) -> String {
@@ -203,6 +255,21 @@ impl MsgChangeAdmin {
     }
 }
 
+impl MsgSetDenomMetadata {
+    fn into_typed_message(self, kind: TokenFactoryKind) -> TxMessage {
+        into_typed_message(
+            kind,
+            "MsgSetDenomMetadata",
+            format!(
+                "tokenfactory: {} setting metadata for {:?}",
+                self.sender,
+                self.metadata.as_ref().map(|metadata| &metadata.base)
+            ),
+            self,
+        )
+    }
+}
+
 //////////// GENERATED, COPY/PASTED, AND PATCHED FROM PROST-BUILD ////////////////
 
 /// MsgCreateDenom defines the message structure for the CreateDenom gRPC service
@@ -326,3 +393,83 @@ pub struct MsgForceTransfer {
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct MsgForceTransferResponse {}
+/// QueryDenomsFromCreatorRequest defines the request structure for the
+/// DenomsFromCreator gRPC query.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct QueryDenomsFromCreatorRequest {
+    #[prost(string, tag = "1")]
+    pub creator: ::prost::alloc::string::String,
+}
+/// The same request for Sei, which uses a different service path.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SeiQueryDenomsFromCreatorRequest {
+    #[prost(string, tag = "1")]
+    pub creator: ::prost::alloc::string::String,
+}
+/// QueryDenomsFromCreatorResponse defines the response structure for the
+/// DenomsFromCreator gRPC query.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct QueryDenomsFromCreatorResponse {
+    #[prost(string, repeated, tag = "1")]
+    pub denoms: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+}
+
+/// Client for the tokenfactory query services.
+pub(crate) mod query_client {
+    #![allow(unused_variables, dead_code, missing_docs, clippy::let_unit_value)]
+    use tonic::codegen::*;
+    #[derive(Debug, Clone)]
+    pub(crate) struct QueryClient<T> {
+        inner: tonic::client::Grpc<T>,
+    }
+    impl<T> QueryClient<T>
+    where
+        T: tonic::client::GrpcService<tonic::body::BoxBody>,
+        T::Error: Into<StdError>,
+        T::ResponseBody: Body<Data = Bytes> + Send + 'static,
+        <T::ResponseBody as Body>::Error: Into<StdError> + Send,
+    {
+        pub(crate) fn new(inner: T) -> Self {
+            let inner = tonic::client::Grpc::new(inner);
+            Self { inner }
+        }
+        /// DenomsFromCreator defines a gRPC query method for fetching all
+        /// denominations created by a specific admin/creator.
+        pub(crate) async fn denoms_from_creator(
+            &mut self,
+            request: impl tonic::IntoRequest<super::QueryDenomsFromCreatorRequest>,
+        ) -> Result<tonic::Response<super::QueryDenomsFromCreatorResponse>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(
+                    tonic::Code::Unknown,
+                    format!("Service was not ready: {}", e.into()),
+                )
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/osmosis.tokenfactory.v1beta1.Query/DenomsFromCreator",
+            );
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+        /// Same as [Self::denoms_from_creator], for Sei's tokenfactory module.
+        pub(crate) async fn sei_denoms_from_creator(
+            &mut self,
+            request: impl tonic::IntoRequest<super::SeiQueryDenomsFromCreatorRequest>,
+        ) -> Result<tonic::Response<super::QueryDenomsFromCreatorResponse>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(
+                    tonic::Code::Unknown,
+                    format!("Service was not ready: {}", e.into()),
+                )
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/seiprotocol.seichain.tokenfactory.Query/DenomsFromCreator",
+            );
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+    }
+}
@@ -0,0 +1,40 @@
+use cosmos_sdk_proto::cosmos::{
+    base::{abci::v1beta1::TxResponse, v1beta1::Coin},
+    tx::v1beta1::Tx,
+};
+
+/// Gas and fee details for a broadcast transaction.
+///
+/// Available from [crate::CosmosTxResponse::cost], for tracking fee spend
+/// per operation without parsing the transaction again.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TxCostReport {
+    /// Gas used while simulating, [None] if the gas amount was provided explicitly
+    pub simulated_gas: Option<u64>,
+    /// Gas limit requested in the fee
+    pub requested_gas: u64,
+    /// Gas actually used by the transaction
+    pub gas_used: u64,
+    /// Fee paid
+    pub fee: Vec<Coin>,
+    /// Gas price attempt which was accepted, starting at 0
+    ///
+    /// [None] for transactions which were signed elsewhere, such as multisig transactions.
+    pub price_attempt: Option<u64>,
+}
+
+impl TxCostReport {
+    pub(crate) fn new(tx: &Tx, response: &TxResponse) -> Self {
+        let fee = tx
+            .auth_info
+            .as_ref()
+            .and_then(|auth_info| auth_info.fee.as_ref());
+        TxCostReport {
+            simulated_gas: None,
+            requested_gas: fee.map_or(0, |fee| fee.gas_limit),
+            gas_used: response.gas_used.try_into().unwrap_or_default(),
+            fee: fee.map(|fee| fee.amount.clone()).unwrap_or_default(),
+            price_attempt: None,
+        }
+    }
+}
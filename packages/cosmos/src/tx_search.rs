@@ -0,0 +1,147 @@
+use cosmos_sdk_proto::cosmos::{
+    base::{abci::v1beta1::TxResponse, query::v1beta1::PageRequest},
+    tx::v1beta1::{GetTxsEventRequest, OrderBy},
+};
+
+use crate::{
+    error::{Action, QueryError},
+    Cosmos, HasAddress,
+};
+
+/// Search for transactions matching a set of event conditions.
+///
+/// All conditions must match for a transaction to be included. At least one
+/// condition is required, the chain rejects searches without any.
+#[derive(Clone, Debug)]
+pub struct TxSearchBuilder {
+    events: Vec<String>,
+    descending: bool,
+    limit: u64,
+}
+
+/// Position to continue a search from, see [TxSearchPage::next].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TxSearchToken {
+    offset: u64,
+}
+
+impl TxSearchToken {
+    /// Start the search after skipping the given number of results.
+    pub fn from_offset(offset: u64) -> Self {
+        TxSearchToken { offset }
+    }
+
+    /// Number of results skipped when using this token.
+    pub fn get_offset(self) -> u64 {
+        self.offset
+    }
+}
+
+/// A single page of results from [TxSearchBuilder::search].
+#[derive(Clone, Debug)]
+pub struct TxSearchPage {
+    /// Matching transactions
+    pub txs: Vec<TxResponse>,
+    /// Token for loading the next page, [None] if this was the last page
+    pub next: Option<TxSearchToken>,
+}
+
+impl Default for TxSearchBuilder {
+    fn default() -> Self {
+        TxSearchBuilder {
+            events: vec![],
+            descending: false,
+            limit: 100,
+        }
+    }
+}
+
+impl TxSearchBuilder {
+    /// A search with no conditions, oldest transactions first.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Require a raw event condition, e.g. `wasm.action='deposit'`.
+    pub fn event(mut self, event: impl Into<String>) -> Self {
+        self.events.push(event.into());
+        self
+    }
+
+    /// Require an event attribute to have the given value.
+    ///
+    /// The key combines the event type and attribute, e.g. `transfer.recipient`.
+    pub fn condition(self, key: &str, value: impl std::fmt::Display) -> Self {
+        self.event(format!("{key}='{value}'"))
+    }
+
+    /// Only include transactions sent by the given address.
+    pub fn sender(self, sender: impl HasAddress) -> Self {
+        self.condition("message.sender", sender.get_address())
+    }
+
+    /// Only include transactions at or after the given height.
+    pub fn min_height(self, height: i64) -> Self {
+        self.event(format!("tx.height>={height}"))
+    }
+
+    /// Only include transactions at or before the given height.
+    pub fn max_height(self, height: i64) -> Self {
+        self.event(format!("tx.height<={height}"))
+    }
+
+    /// Return the newest transactions first.
+    pub fn descending(mut self) -> Self {
+        self.descending = true;
+        self
+    }
+
+    /// Number of transactions per page, defaults to 100.
+    pub fn limit(mut self, limit: u64) -> Self {
+        self.limit = limit;
+        self
+    }
+
+    /// Get the event conditions.
+    pub fn get_events(&self) -> &[String] {
+        &self.events
+    }
+
+    /// Load a page of results, starting from the given token or the first result.
+    pub async fn search(
+        &self,
+        cosmos: &Cosmos,
+        token: Option<TxSearchToken>,
+    ) -> Result<TxSearchPage, QueryError> {
+        let offset = token.map_or(0, TxSearchToken::get_offset);
+        let txs = cosmos
+            .perform_query(
+                GetTxsEventRequest {
+                    events: self.events.clone(),
+                    pagination: Some(PageRequest {
+                        key: vec![],
+                        offset,
+                        limit: self.limit,
+                        count_total: false,
+                        reverse: false,
+                    }),
+                    order_by: if self.descending {
+                        OrderBy::Desc
+                    } else {
+                        OrderBy::Asc
+                    } as i32,
+                },
+                Action::SearchTransactions(self.events.clone()),
+                true,
+            )
+            .await?
+            .into_inner()
+            .tx_responses;
+        let next = if txs.len() as u64 >= self.limit && self.limit > 0 {
+            Some(TxSearchToken::from_offset(offset + txs.len() as u64))
+        } else {
+            None
+        };
+        Ok(TxSearchPage { txs, next })
+    }
+}
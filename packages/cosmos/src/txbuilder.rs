@@ -5,7 +5,7 @@ use cosmos_sdk_proto::{
     cosmwasm::wasm::v1::{MsgExecuteContract, MsgMigrateContract, MsgUpdateAdmin},
 };
 
-use crate::HasAddress;
+use crate::{messages::MsgExecHelper, Address, HasAddress};
 
 /// Transaction builder
 ///
@@ -15,6 +15,9 @@ pub struct TxBuilder {
     pub(crate) messages: Vec<Arc<TxMessage>>,
     pub(crate) memo: Option<String>,
     pub(crate) skip_code_check: bool,
+    pub(crate) fee_granter: Option<Address>,
+    pub(crate) fee_payer: Option<Address>,
+    pub(crate) fee_denom: Option<String>,
 }
 
 impl Display for TxBuilder {
@@ -22,6 +25,15 @@ impl Display for TxBuilder {
         if let Some(memo) = &self.memo {
             writeln!(f, "Memo: {memo}")?;
         }
+        if let Some(fee_granter) = &self.fee_granter {
+            writeln!(f, "Fee granter: {fee_granter}")?;
+        }
+        if let Some(fee_payer) = &self.fee_payer {
+            writeln!(f, "Fee payer: {fee_payer}")?;
+        }
+        if let Some(fee_denom) = &self.fee_denom {
+            writeln!(f, "Fee denom: {fee_denom}")?;
+        }
         for (idx, msg) in self.messages.iter().enumerate() {
             write!(f, "Message {idx}: {}", msg.description)?;
             if idx + 1 < self.messages.len() {
@@ -115,6 +127,82 @@ impl TxBuilder {
         self
     }
 
+    /// Pay fees from a fee allowance granted by the given address.
+    ///
+    /// See [crate::feegrant] for granting allowances.
+    pub fn set_fee_granter(&mut self, granter: impl HasAddress) -> &mut Self {
+        self.fee_granter = Some(granter.get_address());
+        self
+    }
+
+    /// Either set or clear the fee granter.
+    pub fn set_optional_fee_granter(&mut self, granter: Option<Address>) -> &mut Self {
+        self.fee_granter = granter;
+        self
+    }
+
+    /// Pay fees from the given account instead of the first signer.
+    ///
+    /// The payer must also be a signer of the transaction.
+    pub fn set_fee_payer(&mut self, payer: impl HasAddress) -> &mut Self {
+        self.fee_payer = Some(payer.get_address());
+        self
+    }
+
+    /// Either set or clear the fee payer.
+    pub fn set_optional_fee_payer(&mut self, payer: Option<Address>) -> &mut Self {
+        self.fee_payer = payer;
+        self
+    }
+
+    /// Pay fees in the given denom instead of the gas coin.
+    ///
+    /// The denom must be the gas coin or one of
+    /// [crate::CosmosBuilder::alternative_fee_denoms], whose gas prices are
+    /// used for this transaction.
+    pub fn set_fee_denom(&mut self, denom: impl Into<String>) -> &mut Self {
+        self.fee_denom = Some(denom.into());
+        self
+    }
+
+    /// Either set or clear the fee denom.
+    pub fn set_optional_fee_denom(&mut self, denom: Option<String>) -> &mut Self {
+        self.fee_denom = denom;
+        self
+    }
+
+    pub(crate) fn fee_granter_string(&self) -> String {
+        self.fee_granter
+            .map(|granter| granter.get_address_string())
+            .unwrap_or_default()
+    }
+
+    pub(crate) fn fee_payer_string(&self) -> String {
+        self.fee_payer
+            .map(|payer| payer.get_address_string())
+            .unwrap_or_default()
+    }
+
+    /// Wrap all messages added so far into a single authz `MsgExec` sent by `grantee`.
+    ///
+    /// The existing messages must use the granter as their sender, and the
+    /// transaction must then be signed by the grantee. Does nothing if no
+    /// messages have been added.
+    pub fn wrap_authz(&mut self, grantee: impl HasAddress) -> &mut Self {
+        if !self.messages.is_empty() {
+            let msgs = self
+                .messages
+                .drain(..)
+                .map(|msg| Arc::try_unwrap(msg).unwrap_or_else(|msg| TxMessage::clone(&msg)))
+                .collect();
+            self.add_message(MsgExecHelper {
+                grantee: grantee.get_address(),
+                msgs,
+            });
+        }
+        self
+    }
+
     /// When calling [TxBuilder::sign_and_broadcast], skip the check of whether the code is 0
     pub fn set_skip_code_check(&mut self, skip_code_check: bool) -> &mut Self {
         self.skip_code_check = skip_code_check;
@@ -123,7 +211,7 @@ impl TxBuilder {
 }
 
 /// A message to include in a transaction.
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct TxMessage {
     type_url: String,
     value: Vec<u8>,
@@ -165,3 +253,33 @@ impl TxMessage {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wrap_authz() {
+        let granter: Address = "osmo168gdk6r58jdwfv49kuesq2rs747jawnn4ryvyk"
+            .parse()
+            .unwrap();
+        let grantee: Address = "osmo1qg5ega6dykkxc307y25pecuufrjkxkaggkkxh7nad0vhyhtuhw3sqaa3c5"
+            .parse()
+            .unwrap();
+        let mut txbuilder = TxBuilder::default();
+        txbuilder.wrap_authz(grantee);
+        assert!(txbuilder.messages.is_empty());
+
+        txbuilder
+            .add_execute_message(granter, granter, vec![], "{}")
+            .unwrap()
+            .add_execute_message(granter, granter, vec![], "{}")
+            .unwrap()
+            .wrap_authz(grantee);
+        assert_eq!(txbuilder.messages.len(), 1);
+        assert_eq!(
+            txbuilder.messages[0].type_url,
+            "/cosmos.authz.v1beta1.MsgExec"
+        );
+    }
+}
@@ -195,30 +195,22 @@ pub struct DerivationPathComponent {
 
 impl DerivationPathConfig {
     pub const fn cosmos_numbered(index: u64) -> Self {
-        DerivationPathConfig::Four([
-            DerivationPathComponent {
-                value: 118,
-                hardened: true,
-            },
-            DerivationPathComponent {
-                value: 0,
-                hardened: true,
-            },
-            DerivationPathComponent {
-                value: 0,
-                hardened: false,
-            },
-            DerivationPathComponent {
-                value: index,
-                hardened: false,
-            },
-        ])
+        Self::coin_type_numbered(118, index)
     }
 
     pub const fn ethereum_numbered(index: u64) -> Self {
+        Self::coin_type_numbered(60, index)
+    }
+
+    /// Terra uses its own coin type instead of the Cosmos one.
+    pub const fn terra_numbered(index: u64) -> Self {
+        Self::coin_type_numbered(330, index)
+    }
+
+    const fn coin_type_numbered(coin_type: u64, index: u64) -> Self {
         DerivationPathConfig::Four([
             DerivationPathComponent {
-                value: 60,
+                value: coin_type,
                 hardened: true,
             },
             DerivationPathComponent {
@@ -301,7 +293,7 @@ pub(crate) enum WalletPublicKey {
     Ethereum([u8; 65]),
 }
 
-fn global_secp() -> &'static Secp256k1<All> {
+pub(crate) fn global_secp() -> &'static Secp256k1<All> {
     static CELL: OnceCell<Secp256k1<All>> = OnceCell::new();
     CELL.get_or_init(Secp256k1::new)
 }
@@ -335,6 +327,13 @@ impl Wallet {
         global_secp().sign_ecdsa(&msg, &self.privkey.private_key)
     }
 
+    /// Sign arbitrary data following ADR-36, as done by wallets like Keplr.
+    ///
+    /// The signature can be checked with [crate::signature::verify_adr36].
+    pub fn sign_arbitrary(&self, data: &[u8]) -> Signature {
+        self.sign_bytes(&crate::signature::adr36_sign_bytes(self.address, data))
+    }
+
     // Technically these functions are redundant, but keeping them as
     // convenient/ergonomic helpers.
 
@@ -398,12 +397,38 @@ impl Wallet {
     }
 }
 
-fn cosmos_address_from_public_key(public_key: &[u8]) -> [u8; 20] {
+impl Cosmos {
+    /// Send coins from the wallet to the destination, see [Wallet::send_coins].
+    ///
+    /// Accepts either a `Vec<Coin>` or [crate::Coins] for the amount.
+    pub async fn send_coins(
+        &self,
+        wallet: &Wallet,
+        dest: impl HasAddress,
+        amount: impl Into<Vec<Coin>>,
+    ) -> Result<TxResponse, crate::Error> {
+        wallet
+            .send_coins(self, dest.get_address(), amount.into())
+            .await
+    }
+
+    /// Send the given amount of gas coin from the wallet, see [Wallet::send_gas_coin].
+    pub async fn send_gas_coin(
+        &self,
+        wallet: &Wallet,
+        dest: impl HasAddress,
+        amount: u128,
+    ) -> Result<TxResponse, crate::Error> {
+        wallet.send_gas_coin(self, dest, amount).await
+    }
+}
+
+pub(crate) fn cosmos_address_from_public_key(public_key: &[u8]) -> [u8; 20] {
     let sha = sha256::Hash::hash(public_key);
     ripemd160::Hash::hash(sha.as_ref()).into_inner()
 }
 
-fn eth_address_from_public_key(public_key: &[u8; 65]) -> [u8; 20] {
+pub(crate) fn eth_address_from_public_key(public_key: &[u8; 65]) -> [u8; 20] {
     assert_eq!(public_key[0], 4);
     let hash = keccak(&public_key[1..]);
     let mut output = [0u8; 20];
@@ -429,7 +454,7 @@ impl HasAddress for Wallet {
     }
 }
 
-fn keccak(input: &[u8]) -> [u8; 32] {
+pub(crate) fn keccak(input: &[u8]) -> [u8; 32] {
     let mut sha3 = Keccak::v256();
     sha3.update(input);
     let mut output = [0; 32];
@@ -479,6 +504,33 @@ mod tests {
         assert_eq!(expected_injective, injective.get_address());
     }
 
+    #[test]
+    fn test_terra_address() {
+        // LocalTerra's test1 account
+        let expected: Address = "terra1x46rqay4d3cssq8gxxvqz8xt6nwlz4td20k38v"
+            .parse()
+            .unwrap();
+        let terra = SeedPhrase::from_str(OSMO_LOCAL_PHRASE)
+            .unwrap()
+            .with_hrp(AddressHrp::from_static("terra"))
+            .unwrap();
+        assert_eq!(expected, terra.get_address());
+    }
+
+    #[test]
+    fn test_evmos_matches_injective() {
+        const PHRASE: &str =
+            "dilemma flavor noise circle voyage vacant amateur mass morning tunnel unhappy entire";
+        let seed_phrase = SeedPhrase::from_str(PHRASE).unwrap();
+        let injective = seed_phrase
+            .with_hrp(AddressHrp::from_static("inj"))
+            .unwrap();
+        let evmos = seed_phrase
+            .with_hrp(AddressHrp::from_static("evmos"))
+            .unwrap();
+        assert_eq!(injective.get_address().raw(), evmos.get_address().raw());
+    }
+
     // https://www.geeksforgeeks.org/how-to-create-an-ethereum-wallet-address-from-a-private-key/
     #[test]
     fn test_ethereum_address() {
@@ -545,4 +597,42 @@ mod tests {
             hex::encode(hash)
         );
     }
+
+    #[tokio::test]
+    async fn cosmos_send_coins_signs_with_wallet() {
+        let path = std::env::temp_dir().join(format!("cosmos-send-{}.json", std::process::id()));
+        std::fs::write(&path, r#"{"interactions":[]}"#).unwrap();
+        let mut builder = crate::CosmosBuilder::new(
+            "osmosis-1",
+            "uosmo",
+            AddressHrp::from_static("osmo"),
+            "http://127.0.0.1:1",
+        );
+        builder.set_fixture(Some(crate::FixtureMode::Replay(path.clone())));
+        let cosmos = builder.build_lazy().unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let wallet = SeedPhrase::from_str("osmo-local")
+            .unwrap()
+            .with_hrp(cosmos.get_address_hrp())
+            .unwrap();
+        let dest = Wallet::generate(cosmos.get_address_hrp()).unwrap();
+        // Nothing is recorded, so the wrappers stop at the wallet's account lookup.
+        for res in [
+            cosmos
+                .send_coins(&wallet, &dest, crate::Coins::default())
+                .await,
+            cosmos.send_gas_coin(&wallet, &dest, 5).await,
+        ] {
+            assert!(matches!(
+                res,
+                Err(crate::Error::Query(crate::error::QueryError {
+                    query: crate::error::QueryErrorDetails::FixtureMissing {
+                        request: "/cosmos.auth.v1beta1.Query/Account"
+                    },
+                    ..
+                }))
+            ));
+        }
+    }
 }
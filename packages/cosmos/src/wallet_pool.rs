@@ -0,0 +1,303 @@
+use std::{collections::VecDeque, sync::Arc};
+
+use chrono::{DateTime, Utc};
+use cosmos_sdk_proto::cosmos::{bank::v1beta1::MsgSend, base::v1beta1::Coin};
+use parking_lot::Mutex;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+use crate::{
+    error::WalletError, messages::MsgGrantHelper, Address, Cosmos, CosmosTxResponse, HasAddress,
+    HasAddressHrp, SeedPhrase, SequenceManager, TxBuilder, Wallet,
+};
+
+/// Distributes transactions across several worker wallets for higher throughput.
+///
+/// Each worker has its own [SequenceManager] and handles one transaction at a
+/// time. A transaction checks out an idle worker, and returns it once
+/// complete. When every worker is busy, callers wait for one to be returned.
+/// Optionally, workers can act on behalf of
+/// a main wallet via authz: set the granter with [WalletPool::with_granter]
+/// and grant permissions with [WalletPool::grant_from].
+#[derive(Clone)]
+pub struct WalletPool {
+    cosmos: Cosmos,
+    workers: Arc<[SequenceManager]>,
+    /// Indices of workers not currently checked out
+    idle: Arc<Mutex<VecDeque<usize>>>,
+    /// One permit per idle worker
+    available: Arc<Semaphore>,
+    granter: Option<Address>,
+}
+
+/// A worker wallet checked out of a [WalletPool].
+///
+/// The worker is returned to the pool when this is dropped.
+pub struct PooledWallet {
+    pool: WalletPool,
+    index: usize,
+    _permit: OwnedSemaphorePermit,
+}
+
+impl WalletPool {
+    /// Create a pool from the given worker wallets.
+    ///
+    /// Panics if no wallets are provided.
+    pub fn new(cosmos: &Cosmos, wallets: impl IntoIterator<Item = Wallet>) -> Self {
+        let workers = wallets
+            .into_iter()
+            .map(|wallet| SequenceManager::new(cosmos.clone(), wallet))
+            .collect::<Arc<[_]>>();
+        assert!(
+            !workers.is_empty(),
+            "WalletPool requires at least one wallet"
+        );
+        WalletPool {
+            cosmos: cosmos.clone(),
+            idle: Arc::new(Mutex::new((0..workers.len()).collect())),
+            available: Arc::new(Semaphore::new(workers.len())),
+            workers,
+            granter: None,
+        }
+    }
+
+    /// Derive `count` worker wallets from a seed phrase.
+    ///
+    /// Wallets use the chain's default derivation path with indices `0..count`.
+    pub fn from_seed_phrase(
+        cosmos: &Cosmos,
+        seed_phrase: &SeedPhrase,
+        count: u64,
+    ) -> Result<Self, WalletError> {
+        let hrp = cosmos.get_address_hrp();
+        let wallets = (0..count)
+            .map(|index| {
+                SeedPhrase {
+                    derivation_path: Some(hrp.default_derivation_path_with_index(index)),
+                    ..seed_phrase.clone()
+                }
+                .with_hrp(hrp)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self::new(cosmos, wallets))
+    }
+
+    /// Execute all transactions on behalf of the granter using authz.
+    ///
+    /// Messages must use the granter as their sender.
+    pub fn with_granter(mut self, granter: impl HasAddress) -> Self {
+        self.granter = Some(granter.get_address());
+        self
+    }
+
+    /// The worker wallets in this pool
+    pub fn get_wallets(&self) -> impl Iterator<Item = &Wallet> {
+        self.workers.iter().map(SequenceManager::get_wallet)
+    }
+
+    /// Check out an idle worker, waiting for one to be returned if all are busy.
+    pub async fn checkout(&self) -> PooledWallet {
+        let permit = self
+            .available
+            .clone()
+            .acquire_owned()
+            .await
+            .expect("WalletPool semaphore is never closed");
+        self.checkout_with(permit)
+    }
+
+    /// Check out an idle worker, or return [None] if all are busy.
+    pub fn try_checkout(&self) -> Option<PooledWallet> {
+        let permit = self.available.clone().try_acquire_owned().ok()?;
+        Some(self.checkout_with(permit))
+    }
+
+    fn checkout_with(&self, permit: OwnedSemaphorePermit) -> PooledWallet {
+        let index = self
+            .idle
+            .lock()
+            .pop_front()
+            .expect("WalletPool has an idle worker for each permit");
+        PooledWallet {
+            pool: self.clone(),
+            index,
+            _permit: permit,
+        }
+    }
+
+    /// Number of workers not currently checked out.
+    pub fn idle_count(&self) -> usize {
+        self.available.available_permits()
+    }
+
+    /// Sign and broadcast with the next idle worker wallet.
+    pub async fn sign_and_broadcast(
+        &self,
+        txbuilder: &TxBuilder,
+    ) -> Result<CosmosTxResponse, crate::Error> {
+        self.checkout().await.sign_and_broadcast(txbuilder).await
+    }
+
+    /// Grant every worker authz permission to send the given message types on behalf of `main`.
+    pub async fn grant_from(
+        &self,
+        main: &Wallet,
+        msg_type_urls: &[impl AsRef<str>],
+        expiration: Option<DateTime<Utc>>,
+    ) -> Result<CosmosTxResponse, crate::Error> {
+        let mut txbuilder = TxBuilder::default();
+        for wallet in self.get_wallets() {
+            for msg_type_url in msg_type_urls {
+                txbuilder.add_message(MsgGrantHelper {
+                    granter: main.get_address(),
+                    grantee: wallet.get_address(),
+                    authorization: msg_type_url.as_ref().to_owned(),
+                    expiration,
+                });
+            }
+        }
+        txbuilder
+            .sign_and_broadcast_cosmos_tx(&self.cosmos, main)
+            .await
+    }
+
+    /// Send each worker the given amount of gas coin from `main`, in a single transaction.
+    pub async fn fund_from(
+        &self,
+        main: &Wallet,
+        amount: u128,
+    ) -> Result<CosmosTxResponse, crate::Error> {
+        let denom = self.cosmos.get_cosmos_builder().gas_coin().to_owned();
+        let mut txbuilder = TxBuilder::default();
+        for wallet in self.get_wallets() {
+            txbuilder.add_message(MsgSend {
+                from_address: main.get_address_string(),
+                to_address: wallet.get_address_string(),
+                amount: vec![Coin {
+                    denom: denom.clone(),
+                    amount: amount.to_string(),
+                }],
+            });
+        }
+        txbuilder
+            .sign_and_broadcast_cosmos_tx(&self.cosmos, main)
+            .await
+    }
+}
+
+impl PooledWallet {
+    /// The checked out worker wallet
+    pub fn get_wallet(&self) -> &Wallet {
+        self.pool.workers[self.index].get_wallet()
+    }
+
+    /// Sign and broadcast with this worker, using the pool's granter if set.
+    pub async fn sign_and_broadcast(
+        &self,
+        txbuilder: &TxBuilder,
+    ) -> Result<CosmosTxResponse, crate::Error> {
+        let worker = &self.pool.workers[self.index];
+        match self.pool.granter {
+            None => worker.sign_and_broadcast(txbuilder).await,
+            Some(_) => {
+                let mut wrapped = txbuilder.clone();
+                wrapped.wrap_authz(worker.get_wallet());
+                worker.sign_and_broadcast(&wrapped).await
+            }
+        }
+    }
+}
+
+impl Drop for PooledWallet {
+    fn drop(&mut self) {
+        // The permit is released after this runs, so the index is always
+        // back in the queue before another task can claim it.
+        self.pool.idle.lock().push_back(self.index);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashSet, str::FromStr, time::Duration};
+
+    use crate::{AddressHrp, CosmosBuilder};
+
+    use super::*;
+
+    const PHRASE: &str =
+        "dilemma flavor noise circle voyage vacant amateur mass morning tunnel unhappy entire";
+
+    fn make_pool(count: u64) -> WalletPool {
+        let cosmos = CosmosBuilder::new(
+            "osmosis-1",
+            "uosmo",
+            AddressHrp::from_static("osmo"),
+            "http://127.0.0.1:1",
+        )
+        .build_lazy()
+        .unwrap();
+        let seed_phrase = SeedPhrase::from_str(PHRASE).unwrap();
+        WalletPool::from_seed_phrase(&cosmos, &seed_phrase, count).unwrap()
+    }
+
+    #[tokio::test]
+    async fn checkout_and_return() {
+        let pool = make_pool(2);
+        assert_eq!(pool.idle_count(), 2);
+
+        let first = pool.checkout().await;
+        let second = pool.checkout().await;
+        assert_ne!(
+            first.get_wallet().get_address(),
+            second.get_wallet().get_address()
+        );
+        assert_eq!(pool.idle_count(), 0);
+        assert!(pool.try_checkout().is_none());
+
+        let returned = first.get_wallet().get_address();
+        drop(first);
+        assert_eq!(pool.idle_count(), 1);
+        let third = pool.try_checkout().unwrap();
+        assert_eq!(third.get_wallet().get_address(), returned);
+    }
+
+    #[tokio::test]
+    async fn checkout_waits_when_exhausted() {
+        let pool = make_pool(1);
+        let held = pool.checkout().await;
+        let waiting = tokio::spawn({
+            let pool = pool.clone();
+            async move { pool.checkout().await.get_wallet().get_address() }
+        });
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(!waiting.is_finished());
+
+        let address = held.get_wallet().get_address();
+        drop(held);
+        assert_eq!(waiting.await.unwrap(), address);
+        assert_eq!(pool.idle_count(), 1);
+    }
+
+    #[tokio::test]
+    async fn concurrent_checkouts_never_share_a_worker() {
+        let pool = make_pool(3);
+        let in_use = Arc::new(Mutex::new(HashSet::new()));
+        let tasks = (0..30)
+            .map(|_| {
+                let pool = pool.clone();
+                let in_use = in_use.clone();
+                tokio::spawn(async move {
+                    let worker = pool.checkout().await;
+                    let address = worker.get_wallet().get_address();
+                    assert!(in_use.lock().insert(address));
+                    assert!(in_use.lock().len() <= 3);
+                    tokio::time::sleep(Duration::from_millis(5)).await;
+                    assert!(in_use.lock().remove(&address));
+                })
+            })
+            .collect::<Vec<_>>();
+        for task in tasks {
+            task.await.unwrap();
+        }
+        assert_eq!(pool.idle_count(), 3);
+    }
+}